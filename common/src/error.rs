@@ -52,6 +52,90 @@ pub enum ErrorType {
         token: String,
     },
 
+    /// Occurs due to a numeric or character literal that is malformed or out of range.
+    InvalidLiteral {
+        /// The literal as written in the source code.
+        literal: String,
+        /// Describes what is wrong with the literal.
+        reason: String,
+    },
+
+    /// Occurs due to a serialized token stream that is truncated, corrupt, or written by an unsupported version.
+    MalformedTokenStream {
+        /// Describes what could not be decoded.
+        message: String,
+    },
+
+    /// Occurs due to a file that could not be read or written.
+    IoError {
+        /// The path of the file.
+        path: String,
+        /// The error reported by the operating system.
+        message: String,
+    },
+
+    /// Occurs due to input bytes that are not valid in the encoding they are read as.
+    InvalidEncoding {
+        /// The name of the encoding.
+        encoding: String,
+        /// The byte offset of the first invalid sequence.
+        offset: usize,
+    },
+
+    /// Occurs due to a custom token pattern that is not a valid regular expression.
+    InvalidTokenPattern {
+        /// The pattern as given.
+        pattern: String,
+        /// Describes why the pattern is invalid.
+        message: String,
+    },
+
+    /// Occurs due to a character that cannot be allowed in identifiers because it already has a meaning.
+    InvalidIdentifierCharacter {
+        /// The character as given.
+        character: char,
+        /// Describes what the character conflicts with.
+        reason: String,
+    },
+
+    /// Occurs due to constructs nested more deeply than the configured limit allows.
+    NestingTooDeep {
+        /// What is nested, such as `block comment` or `bracket`.
+        construct: String,
+        /// The deepest nesting allowed.
+        limit: usize,
+    },
+
+    /// Occurs due to a token longer than the configured limit allows.
+    TokenTooLong {
+        /// The name of the token's kind, such as `IDENTIFIER`.
+        kind: String,
+        /// How many characters the token has.
+        length: usize,
+        /// The most characters a token may have.
+        limit: usize,
+    },
+
+    /// Occurs due to a run of control characters, such as NUL, outside any comment or literal.
+    ControlCharacter {
+        /// The first control character of the run.
+        character: char,
+        /// How many control characters the run holds.
+        count: usize,
+    },
+
+    /// Occurs due to a lexer specification that cannot be read or is inconsistent.
+    InvalidLexerSpec {
+        /// Describes what is wrong with the specification.
+        message: String,
+    },
+
+    /// Occurs due to a project configuration file that is not valid TOML or does not match the expected settings.
+    InvalidConfigFile {
+        /// Describes what is wrong with the file.
+        message: String,
+    },
+
     /// A placeholder error for development use.
     DevError {
         /// A message describing what needs to be addressed.
@@ -0,0 +1,138 @@
+//! This file lexes into tokens whose payloads borrow from an arena, rather than each owning a heap allocation.
+//!
+//! A `LexSession` owns a bump arena. Lexing copies the source into the arena once, and identifiers, numbers, and
+//! the lexemes of custom tokens are slices of that copy, so identifier-heavy code no longer costs an allocation
+//! per token. Everything allocated by a session is freed together when the session is dropped or reset.
+
+use std::cell::RefCell;
+
+use crate::{
+    config::LexerConfig,
+    core::Lexer,
+    error::LexError,
+    span::Span,
+    token::Token,
+};
+
+/// The smallest block of memory the arena requests at a time.
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// A token whose text borrows from a `LexSession`'s arena.
+#[derive(PartialEq, Debug, Clone)]
+pub enum ArenaToken<'a> {
+    /// Number.
+    Number(&'a str),
+    /// Identifier.
+    Identifier(&'a str),
+    /// String literal, without its quotes.
+    StringLiteral(&'a str),
+    /// A token produced by a custom rule, holding the name of its kind and its lexeme.
+    Custom(&'a str, &'a str),
+    /// Preprocessor line, from the `#`.
+    HashLine(&'a str),
+    /// Any token without a text payload.
+    Token(Token),
+}
+
+impl ArenaToken<'_> {
+    /// Converts the token into an owned `Token`.
+    pub fn to_token(&self) -> Token {
+        match self {
+            ArenaToken::Number(text) => Token::NUMBER(text.chars().collect()),
+            ArenaToken::Identifier(text) => Token::IDENTIFIER(text.chars().collect()),
+            ArenaToken::StringLiteral(text) => Token::STRINGLITERAL(text.chars().collect()),
+            ArenaToken::Custom(kind, text) => Token::CUSTOM(kind.to_string(), text.chars().collect()),
+            ArenaToken::HashLine(text) => Token::HASHLINE(text.chars().collect()),
+            ArenaToken::Token(token) => token.clone(),
+        }
+    }
+}
+
+/// An arena token paired with the span of source code it was lexed from.
+///
+/// # Fields
+/// * `token` - The lexed token.
+/// * `span` - Where the token appears in the source code.
+#[derive(PartialEq, Debug, Clone)]
+pub struct ArenaSpannedToken<'a> {
+    pub token: ArenaToken<'a>,
+    pub span: Span,
+}
+
+/// Owns the memory that arena tokens borrow from.
+///
+/// # Fields
+/// * `chunks` - The blocks of text allocated so far. A chunk is never grown past its capacity, so text stored in it
+///   never moves.
+/// * `config` - The settings to lex with.
+#[derive(Default)]
+pub struct LexSession {
+    chunks: RefCell<Vec<String>>,
+    config: LexerConfig,
+}
+
+impl LexSession {
+    /// Creates a session that lexes with the default settings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a session that lexes with the given settings.
+    pub fn with_config(config: LexerConfig) -> Self {
+        Self { chunks: RefCell::new(Vec::new()), config }
+    }
+
+    /// Lexes `input`, allocating the text of the tokens in the session's arena.
+    ///
+    /// # Returns
+    /// * `Ok(Vec<ArenaSpannedToken>)` - The tokens and their spans, borrowing from the session.
+    /// * `Err(Vec<LexError>)` - Every error and the span of source it was found at.
+    pub fn lex(&self, input: &str) -> Result<Vec<ArenaSpannedToken<'_>>, Vec<LexError>> {
+        let tokens = Lexer::lex_without_payloads(input, &self.config)?;
+        let source: &str = self.alloc_str(input);
+
+        Ok(tokens
+            .into_iter()
+            .map(|spanned| {
+                let text = &source[spanned.span.start..spanned.span.end];
+                let token = match spanned.token {
+                    Token::NUMBER(_) => ArenaToken::Number(text),
+                    Token::IDENTIFIER(_) => ArenaToken::Identifier(text),
+                    Token::STRINGLITERAL(_) => ArenaToken::StringLiteral(&text[1..text.len() - 1]),
+                    Token::CUSTOM(kind, _) => ArenaToken::Custom(self.alloc_str(&kind), text),
+                    Token::HASHLINE(_) => ArenaToken::HashLine(text),
+                    token => ArenaToken::Token(token),
+                };
+                ArenaSpannedToken { token, span: spanned.span }
+            })
+            .collect())
+    }
+
+    /// Gives the number of bytes of text held by the arena.
+    pub fn allocated_bytes(&self) -> usize {
+        self.chunks.borrow().iter().map(String::len).sum()
+    }
+
+    /// Frees everything the arena holds. Requiring `&mut self` guarantees no tokens still borrow from it.
+    pub fn reset(&mut self) {
+        self.chunks.get_mut().clear();
+    }
+
+    /// Copies `text` into the arena, starting a new chunk if the current one is too full.
+    fn alloc_str(&self, text: &str) -> &str {
+        let mut chunks = self.chunks.borrow_mut();
+        if chunks.last().is_none_or(|chunk| chunk.capacity() - chunk.len() < text.len()) {
+            chunks.push(String::with_capacity(text.len().max(CHUNK_SIZE)));
+        }
+        let chunk: &mut String = chunks.last_mut().expect("a chunk was just ensured");
+        let start: usize = chunk.len();
+        chunk.push_str(text);
+        let stored: *const str = &chunk[start..];
+
+        // SAFETY: `push_str` stayed within the chunk's capacity, so the chunk's buffer was not reallocated and will
+        // not be while the session is borrowed: chunks are only ever appended to within capacity, and are freed only
+        // by `reset`, which takes `&mut self`, or by dropping the session. Moving a chunk within `chunks` does not
+        // move its buffer.
+        unsafe { &*stored }
+    }
+}
@@ -0,0 +1,135 @@
+//! This file measures the lexer against a corpus of real source files, so that performance work can be compared
+//! on realistic input from within the crate.
+//!
+//! Only the time spent lexing is measured; reading the files from disk is excluded. Allocation counts are only
+//! available when the program installs `CountingAllocator` as its global allocator:
+//!
+//! ```ignore
+//! #[global_allocator]
+//! static ALLOCATOR: lexer::bench::CountingAllocator = lexer::bench::CountingAllocator;
+//! ```
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    fs,
+    path::Path,
+    sync::atomic::{AtomicU64, Ordering},
+    time::{Duration, Instant},
+};
+
+use common::error::ErrorType;
+
+use crate::{
+    core::Lexer,
+    driver::{discover_files, DriverConfig},
+    encoding::Encoding,
+};
+
+/// The number of allocations made through `CountingAllocator`.
+static ALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+
+/// A global allocator that counts allocations before passing them on to the system allocator.
+pub struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+/// The totals from lexing a corpus.
+///
+/// # Fields
+/// * `files` - The number of files lexed.
+/// * `bytes` - The total size of the files in bytes.
+/// * `tokens` - The number of tokens produced by files that lexed without errors, including their `EOF` tokens.
+/// * `files_with_errors` - The number of files that failed to lex.
+/// * `errors` - The total number of errors across all files.
+/// * `unreadable` - The number of files that could not be read, which are otherwise left out of the totals.
+/// * `elapsed` - The time spent lexing, excluding reading the files.
+/// * `allocations` - The number of allocations made while lexing, or `None` if `CountingAllocator` is not the
+///   global allocator. Allocations made by other threads at the same time are counted too.
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct CorpusReport {
+    pub files: usize,
+    pub bytes: u64,
+    pub tokens: u64,
+    pub files_with_errors: usize,
+    pub errors: u64,
+    pub unreadable: usize,
+    pub elapsed: Duration,
+    pub allocations: Option<u64>,
+}
+
+impl CorpusReport {
+    /// Gives the lexing throughput in megabytes (10^6 bytes) per second.
+    pub fn throughput_mb_per_s(&self) -> f64 {
+        let seconds = self.elapsed.as_secs_f64();
+        if seconds == 0.0 {
+            return 0.0;
+        }
+        self.bytes as f64 / 1_000_000.0 / seconds
+    }
+
+    /// Gives the fraction of files that failed to lex, between 0 and 1.
+    pub fn error_rate(&self) -> f64 {
+        if self.files == 0 {
+            return 0.0;
+        }
+        self.files_with_errors as f64 / self.files as f64
+    }
+}
+
+/// Lexes every C source file under a directory, recursively, and totals the results.
+///
+/// Files are found as `driver::discover_files` finds them with the default settings, so hidden files and
+/// directories and files without a `.c` or `.h` extension are skipped. They are lexed one at a time in path order,
+/// detecting the encoding of each as `Lexer::lex_bytes_with_encoding` does.
+///
+/// # Parameters
+/// * `dir` - The root of the corpus.
+///
+/// # Returns
+/// * `Ok(CorpusReport)` - The totals for the corpus.
+/// * `Err(ErrorType)` - `ErrorType::IoError` if a directory cannot be read.
+pub fn run_corpus(dir: &Path) -> Result<CorpusReport, ErrorType> {
+    let paths = discover_files(dir, &DriverConfig::default())?;
+
+    let mut report = CorpusReport::default();
+    let mut allocations: u64 = 0;
+    for path in paths {
+        let Ok(bytes) = fs::read(&path) else {
+            report.unreadable += 1;
+            continue;
+        };
+
+        let allocations_before = ALLOCATIONS.load(Ordering::Relaxed);
+        let start = Instant::now();
+        let result = Lexer::lex_bytes_with_encoding(&bytes, Encoding::Auto);
+        report.elapsed += start.elapsed();
+        allocations += ALLOCATIONS.load(Ordering::Relaxed) - allocations_before;
+
+        report.files += 1;
+        report.bytes += bytes.len() as u64;
+        match result {
+            Ok(tokens) => report.tokens += tokens.len() as u64,
+            Err(errors) => {
+                report.files_with_errors += 1;
+                report.errors += errors.len() as u64;
+            }
+        }
+    }
+    // Lexing always allocates, so a counter that never moved means the allocator is not installed
+    report.allocations = (allocations > 0).then_some(allocations);
+    Ok(report)
+}
@@ -0,0 +1,652 @@
+//! Command line interface to the lexer.
+//!
+//! - `lex tokens <file>`: Lexes a C source file and prints one token per line with its span.
+//! - `lex gen <spec> <file>`: Lexes a file with a lexer generated from a TOML or JSON specification.
+//! - `lex explain <file> --at <offset>`: Explains why the lexer placed the token boundaries around a byte offset.
+//! - `lex check <files>`: Reports every diagnostic in the files, as text, or as JSON or SARIF with `--format`.
+//!   Settings are read from `--config`, or else from the nearest `lexer.toml` above the current directory.
+//!   `--max-file-tokens`, `--max-block-tokens`, and `--max-block-lines` override its budgets, and
+//!   `--deny-warnings` fails on warnings as well as errors, so that budgets can gate CI.
+//! - `lex watch <paths>`: Checks files and directories, then checks each file again whenever it changes.
+//! - `lex tree <file>`: Prints a tree-sitter-style tree of a file's tokens, as an S-expression or as JSON with
+//!   `--format json`.
+//! - `lex repl`: Lexes each entry typed at a prompt and prints its tokens, continuing an entry onto the next line
+//!   while a bracket or comment is open or the line ends with a backslash.
+//! - `lex replay <recording>`: Prints the steps of a session recorded by `lex tokens --record <path>`, one at a
+//!   time with `--step`.
+//! - `lex teach <file>`: Prints a file with its tokens marked under each line and a legend of the marks, for
+//!   demonstrating how lexing proceeds. `--step` goes through the tokens one at a time, waiting for Enter before
+//!   each, and `--color` chooses whether tokens are colored, by default only when printing to a terminal.
+//! - `lex --stdin [--stream]`: Lexes standard input and prints one JSON object per token. With `--stream`, tokens
+//!   are printed as soon as each line arrives rather than once the input ends.
+//!
+//! The first two commands accept `--format csv` or `--format tsv` to print a table of tokens instead, and
+//! `lex tokens --trace <path>` saves a trace of the lexer's decisions that `lex explain --trace <path>` can reuse.
+//! `lex tokens --record <path>` saves a recording of the whole session instead, for attaching to bug reports, and
+//! `lex tokens --redact` replaces identifiers and literals with placeholders, in the printed tokens and in the
+//! recording, so that neither gives the source away.
+//!
+//! Every command that lexes C source, from a file or standard input, uses the settings of the nearest `lexer.toml`
+//! above the current directory, so that it gives the same tokens as `lex check` and the project driver.
+
+use std::{fs, io::{self, BufRead, IsTerminal, Write}, path::{Path, PathBuf}, process::ExitCode};
+
+use clap::{Parser, Subcommand, ValueEnum};
+use common::error::ErrorType;
+use lexer::{
+    budget::Budgets,
+    check::{self, FileReport},
+    config::LexerConfig,
+    core::Lexer,
+    diagnostic::Severity,
+    export,
+    lexgen::{GeneratedLexer, LexerSpec},
+    project::ProjectConfig,
+    redact,
+    repl::{Repl, ReplEntry},
+    replay::Recording,
+    sarif,
+    span::SpannedToken,
+    stream::{StreamItem, StreamLexer},
+    teach,
+    token::Token,
+    trace::{Decision, Trace},
+    tree::Tree,
+    watch::{self, FileUpdate, WatchSession},
+};
+
+#[derive(Parser)]
+#[command(name = "lex", about = "Lexes source files and prints their tokens")]
+#[command(args_conflicts_with_subcommands = true)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+    /// Lexes standard input instead of running a command, printing one JSON object per token.
+    #[arg(long)]
+    stdin: bool,
+    /// Prints the tokens of each line of standard input as soon as it arrives.
+    #[arg(long, requires = "stdin")]
+    stream: bool,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Lexes a C source file.
+    Tokens {
+        /// The file to lex.
+        file: PathBuf,
+        /// How to print the tokens.
+        #[arg(long, value_enum, default_value_t = Format::Text)]
+        format: Format,
+        /// Saves a trace of the lexer's decisions to this file.
+        #[arg(long)]
+        trace: Option<PathBuf>,
+        /// Saves a recording of the session to this file, for `lex replay`.
+        #[arg(long)]
+        record: Option<PathBuf>,
+        /// Replaces identifiers and literals with placeholders in the printed tokens, and hides them and the
+        /// contents of comments in the recording. Only the text format can be redacted.
+        #[arg(long)]
+        redact: bool,
+    },
+    /// Lexes a file with a lexer generated from a specification.
+    Gen {
+        /// The TOML or JSON specification of the lexer.
+        spec: PathBuf,
+        /// The file to lex.
+        file: PathBuf,
+        /// How to print the tokens.
+        #[arg(long, value_enum, default_value_t = Format::Text)]
+        format: Format,
+    },
+    /// Explains why the lexer placed the token boundaries around a byte offset of a C source file.
+    Explain {
+        /// The file that was lexed.
+        file: PathBuf,
+        /// The byte offset to explain.
+        #[arg(long)]
+        at: usize,
+        /// A trace saved by `lex tokens --trace`, used instead of lexing the file again.
+        #[arg(long)]
+        trace: Option<PathBuf>,
+    },
+    /// Reports the lex errors, lints, and other diagnostics in C source files.
+    Check {
+        /// The files to check.
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+        /// How to print the diagnostics.
+        #[arg(long, value_enum, default_value_t = CheckFormat::Text)]
+        format: CheckFormat,
+        /// The project configuration file to use instead of the nearest `lexer.toml`.
+        #[arg(long)]
+        config: Option<PathBuf>,
+        /// The most tokens a file may hold, overriding the project's budget.
+        #[arg(long)]
+        max_file_tokens: Option<usize>,
+        /// The most tokens a top-level block may hold, overriding the project's budget.
+        #[arg(long)]
+        max_block_tokens: Option<usize>,
+        /// The most lines a top-level block may span, overriding the project's budget.
+        #[arg(long)]
+        max_block_lines: Option<usize>,
+        /// Fails if any file has warnings, not only errors.
+        #[arg(long)]
+        deny_warnings: bool,
+    },
+    /// Watches files and directories, printing the diagnostics of each file whenever it changes. Under a directory,
+    /// only C sources that lexer.toml does not ignore are checked.
+    Watch {
+        /// The files and directories to watch.
+        #[arg(required = true)]
+        paths: Vec<PathBuf>,
+    },
+    /// Prints a tree-sitter-style tree of a C source file's tokens.
+    Tree {
+        /// The file to lex.
+        file: PathBuf,
+        /// How to print the tree.
+        #[arg(long, value_enum, default_value_t = TreeFormat::Sexp)]
+        format: TreeFormat,
+    },
+    /// Lexes each entry typed at a prompt and prints its tokens.
+    Repl,
+    /// Prints the steps of a recorded lexer session.
+    Replay {
+        /// The recording saved by `lex tokens --record`.
+        recording: PathBuf,
+        /// Waits for Enter before printing each step.
+        #[arg(long)]
+        step: bool,
+    },
+    /// Prints a file with its tokens marked, for demonstrating how lexing proceeds.
+    Teach {
+        /// The file to lex.
+        file: PathBuf,
+        /// Goes through the tokens one at a time, waiting for Enter before each.
+        #[arg(long)]
+        step: bool,
+        /// When to color tokens.
+        #[arg(long, value_enum, default_value_t = ColorChoice::Auto)]
+        color: ColorChoice,
+    },
+}
+
+/// How tokens are printed.
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    /// One token per line, preceded by its span.
+    Text,
+    /// Comma-separated values with a header row.
+    Csv,
+    /// Tab-separated values with a header row.
+    Tsv,
+}
+
+/// How diagnostics are printed.
+#[derive(Clone, Copy, ValueEnum)]
+enum CheckFormat {
+    /// One diagnostic per line, as `file:line:column: severity[code]: message`.
+    Text,
+    /// A JSON document in the schema described by the `check` module.
+    Json,
+    /// A SARIF 2.1.0 log, for code scanning tools.
+    Sarif,
+}
+
+/// When teach output is colored.
+#[derive(Clone, Copy, ValueEnum)]
+enum ColorChoice {
+    /// Colors when standard output is a terminal.
+    Auto,
+    /// Always colors.
+    Always,
+    /// Never colors.
+    Never,
+}
+
+/// How trees are printed.
+#[derive(Clone, Copy, ValueEnum)]
+enum TreeFormat {
+    /// An S-expression of the named nodes.
+    Sexp,
+    /// A JSON array of every node, in the schema described by the `tree` module.
+    Json,
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let Some(command) = cli.command else {
+        if cli.stdin {
+            return lex_stdin(cli.stream);
+        }
+        eprintln!("error: a command or --stdin is required; see `lex --help`");
+        return ExitCode::FAILURE;
+    };
+    let (result, format) = match command {
+        Command::Tokens { file, format, trace, record, redact } => {
+            if redact && !matches!(format, Format::Text) {
+                eprintln!("error: --redact only applies to the text format");
+                return ExitCode::FAILURE;
+            }
+            let result = read(&file).and_then(|source| {
+                let config = lexer_config()?;
+                if let Some(record_path) = &record {
+                    write(record_path, &Recording::record(&source, &config, redact).encode())?;
+                }
+                let tokens = match &trace {
+                    None => Lexer::lex_spanned_with_config(&source, &config)?,
+                    Some(trace_path) => {
+                        let (result, trace) = Lexer::lex_traced(&source, &config);
+                        write(trace_path, &trace.encode())?;
+                        result.map_err(|errors| errors.into_iter().map(ErrorType::from).collect::<Vec<_>>())?
+                    }
+                };
+                Ok((source, if redact { redact::redact_tokens(&tokens) } else { tokens }))
+            });
+            (result, format)
+        }
+        Command::Explain { file, at, trace } => return explain(&file, at, trace.as_ref()),
+        Command::Check { files, format, config, max_file_tokens, max_block_tokens, max_block_lines, deny_warnings } => {
+            let budgets = Budgets { max_file_tokens, max_block_tokens, max_block_lines };
+            return check(&files, format, config.as_deref(), budgets, deny_warnings);
+        }
+        Command::Watch { paths } => {
+            let settings =
+                project_config(None).and_then(|project| project.driver_config()).map(WatchSession::with_config);
+            if let Err(error) = settings.and_then(|session| watch::watch_with_session(&paths, session, print_update)) {
+                eprintln!("error: {:?}", error);
+            }
+            return ExitCode::FAILURE;
+        }
+        Command::Tree { file, format } => return tree(&file, format),
+        Command::Repl => return repl(),
+        Command::Replay { recording, step } => return replay(&recording, step),
+        Command::Teach { file, step, color } => return teach(&file, step, color),
+        Command::Gen { spec, file, format } => {
+            let result = LexerSpec::load(&spec)
+                .and_then(GeneratedLexer::new)
+                .map_err(|error| vec![error])
+                .and_then(|lexer| read(&file).and_then(|source| lexer.lex(&source).map(|tokens| (source, tokens))));
+            (result, format)
+        }
+    };
+
+    match result {
+        Ok((source, tokens)) => match print_tokens(&source, &tokens, format) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(error) => {
+                eprintln!("error: {}", error);
+                ExitCode::FAILURE
+            }
+        },
+        Err(errors) => {
+            for error in errors {
+                eprintln!("error: {:?}", error);
+            }
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Prints the explanation of a byte offset.
+fn explain(file: &PathBuf, offset: usize, trace_path: Option<&PathBuf>) -> ExitCode {
+    let explanation = read(file).and_then(|source| {
+        let trace = match trace_path {
+            Some(path) => Trace::decode(&fs::read(path).map_err(|error| io_error(path, error))?)?,
+            None => Lexer::lex_traced(&source, &lexer_config()?).1,
+        };
+        Ok(trace.explain(&source, offset))
+    });
+
+    match explanation {
+        Ok(Some(explanation)) => {
+            println!("{}", explanation);
+            ExitCode::SUCCESS
+        }
+        Ok(None) => {
+            eprintln!("error: offset {} is past the end of the trace", offset);
+            ExitCode::FAILURE
+        }
+        Err(errors) => {
+            for error in errors {
+                eprintln!("error: {:?}", error);
+            }
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Prints the diagnostics of every file, failing if any file cannot be read or has errors, or warnings too if
+/// `deny_warnings` is set. Budgets set in `overrides` replace the project's.
+fn check(
+    files: &[PathBuf],
+    format: CheckFormat,
+    config_path: Option<&Path>,
+    overrides: Budgets,
+    deny_warnings: bool,
+) -> ExitCode {
+    let project = project_config(config_path);
+    let settings = project.and_then(|project| Ok((project.lexer_builder()?.build(), project.lint_config())));
+    let (config, mut lints) = match settings {
+        Ok(settings) => settings,
+        Err(error) => {
+            eprintln!("error: {:?}", error);
+            return ExitCode::FAILURE;
+        }
+    };
+    let budgets = &mut lints.budgets;
+    budgets.max_file_tokens = overrides.max_file_tokens.or(budgets.max_file_tokens);
+    budgets.max_block_tokens = overrides.max_block_tokens.or(budgets.max_block_tokens);
+    budgets.max_block_lines = overrides.max_block_lines.or(budgets.max_block_lines);
+
+    let mut reports: Vec<FileReport> = Vec::new();
+    let mut failed = false;
+    for file in files {
+        match read(file) {
+            Ok(source) => {
+                reports.push(FileReport::check_with_config(&file.display().to_string(), source, &config, &lints));
+            }
+            Err(errors) => {
+                for error in errors {
+                    eprintln!("error: {:?}", error);
+                }
+                failed = true;
+            }
+        }
+    }
+
+    let mut stdout = io::stdout().lock();
+    let written = match format {
+        CheckFormat::Text => check::write_text(&mut stdout, &reports),
+        CheckFormat::Json => check::write_json(&mut stdout, &reports),
+        CheckFormat::Sarif => sarif::write_sarif(&mut stdout, &reports),
+    };
+    if let Err(error) = written {
+        eprintln!("error: {}", error);
+        return ExitCode::FAILURE;
+    }
+    let failing = if deny_warnings { FileReport::has_warnings } else { FileReport::has_errors };
+    if failed || reports.iter().any(failing) {
+        return ExitCode::FAILURE;
+    }
+    ExitCode::SUCCESS
+}
+
+/// Lexes standard input, printing every token and error as one line of JSON. When streaming, the output is flushed
+/// after every line of input so that it can be read while the input is still being written.
+fn lex_stdin(stream: bool) -> ExitCode {
+    let config = match lexer_config() {
+        Ok(config) => config,
+        Err(error) => {
+            eprintln!("error: {:?}", error);
+            return ExitCode::FAILURE;
+        }
+    };
+    let mut lexer = StreamLexer::new(config);
+    let mut stdin = io::stdin().lock();
+    let mut stdout = io::stdout().lock();
+    let mut failed = false;
+    let mut print = |items: Vec<StreamItem>, stdout: &mut io::StdoutLock| -> io::Result<()> {
+        for item in items {
+            failed |= matches!(item, StreamItem::Error(_));
+            writeln!(stdout, "{}", item.to_json())?;
+        }
+        stdout.flush()
+    };
+
+    let mut line = String::new();
+    loop {
+        line.clear();
+        let read = if stream { stdin.read_line(&mut line) } else { io::Read::read_to_string(&mut stdin, &mut line) };
+        let written = match read {
+            Ok(0) => break,
+            Ok(_) => print(lexer.push(&line), &mut stdout),
+            Err(error) => Err(error),
+        };
+        if let Err(error) = written {
+            eprintln!("error: {}", error);
+            return ExitCode::FAILURE;
+        }
+    }
+    if let Err(error) = print(lexer.finish(), &mut stdout) {
+        eprintln!("error: {}", error);
+        return ExitCode::FAILURE;
+    }
+    if failed {
+        return ExitCode::FAILURE;
+    }
+    ExitCode::SUCCESS
+}
+
+/// Prints the tree of a file's tokens.
+fn tree(file: &PathBuf, format: TreeFormat) -> ExitCode {
+    let tree = read(file).and_then(|source| {
+        let tokens = Lexer::lex_spanned_with_config(&source, &lexer_config()?)?;
+        Ok(Tree::build(&source, &tokens))
+    });
+    match tree {
+        Ok(tree) => {
+            match format {
+                TreeFormat::Sexp => println!("{}", tree.to_sexp()),
+                TreeFormat::Json => println!("{}", tree.to_json()),
+            }
+            ExitCode::SUCCESS
+        }
+        Err(errors) => {
+            for error in errors {
+                eprintln!("error: {:?}", error);
+            }
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Runs an interactive session on standard input until it ends, printing the tokens and errors of each entry.
+fn repl() -> ExitCode {
+    let config = match lexer_config() {
+        Ok(config) => config,
+        Err(error) => {
+            eprintln!("error: {:?}", error);
+            return ExitCode::FAILURE;
+        }
+    };
+    let mut repl = Repl::new(config);
+    let mut stdin = io::stdin().lock();
+    let mut stdout = io::stdout().lock();
+    let mut line = String::new();
+    loop {
+        let written = write!(stdout, "{}", repl.prompt()).and_then(|()| stdout.flush());
+        line.clear();
+        let entry = match written.and_then(|()| stdin.read_line(&mut line)) {
+            Ok(0) if repl.is_continuing() => repl.finish(),
+            Ok(0) => return ExitCode::SUCCESS,
+            Ok(_) => repl.feed(&line),
+            Err(error) => {
+                eprintln!("error: {}", error);
+                return ExitCode::FAILURE;
+            }
+        };
+        if let Err(error) = print_entry(&mut stdout, &entry) {
+            eprintln!("error: {}", error);
+            return ExitCode::FAILURE;
+        }
+    }
+}
+
+/// Prints a recorded session, waiting for Enter before each step if stepping.
+fn replay(path: &PathBuf, step: bool) -> ExitCode {
+    let recording = fs::read(path).map_err(|error| io_error(path, error)).and_then(|bytes| Recording::decode(&bytes));
+    let recording = match recording {
+        Ok(recording) => recording,
+        Err(error) => {
+            eprintln!("error: {:?}", error);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let mut stdin = io::stdin().lock();
+    let mut stdout = io::stdout().lock();
+    let mut print = |stdout: &mut io::StdoutLock| -> io::Result<()> {
+        writeln!(stdout, "input\t{} bytes, hash {:016x}", recording.input_len, recording.input_hash)?;
+        if recording.redacted {
+            writeln!(stdout, "redacted")?;
+        }
+        for (name, value) in recording.settings.iter() {
+            writeln!(stdout, "setting\t{} = {}", name, value)?;
+        }
+        for replayed in recording.replay() {
+            if step {
+                stdout.flush()?;
+                if stdin.read_line(&mut String::new())? == 0 {
+                    return Ok(());
+                }
+            }
+            let decision = match &replayed.step.decision {
+                Decision::Trivia => "trivia".to_string(),
+                Decision::Token { rule, kind } => format!("{} ({:?})", kind, rule),
+                Decision::Error { rule } => format!("error ({:?})", rule),
+            };
+            let span = replayed.span();
+            writeln!(
+                stdout,
+                "{}\t{}..{}\t{:?}\t{}\t{:?}",
+                replayed.index, span.start, span.end, replayed.step.mode, decision, replayed.lexeme
+            )?;
+        }
+        stdout.flush()
+    };
+    if let Err(error) = print(&mut stdout) {
+        eprintln!("error: {}", error);
+        return ExitCode::FAILURE;
+    }
+    ExitCode::SUCCESS
+}
+
+/// Prints the tokens of a complete entry, leaving out `EOF`, followed by its errors.
+fn print_entry(stdout: &mut io::StdoutLock, entry: &ReplEntry) -> io::Result<()> {
+    let ReplEntry::Complete { source, tokens, errors } = entry else {
+        return Ok(());
+    };
+    for spanned in tokens.iter().filter(|spanned| spanned.token != Token::EOF) {
+        let lexeme = &source[spanned.span.start..spanned.span.end];
+        writeln!(stdout, "{}..{}\t{}\t{}", spanned.span.start, spanned.span.end, spanned.token.name(), lexeme)?;
+    }
+    for error in errors {
+        let diagnostic = check::error_diagnostic(error);
+        let span = error.span();
+        writeln!(stdout, "{}..{}\terror[{}]: {}", span.start, span.end, diagnostic.code, diagnostic.message)?;
+    }
+    stdout.flush()
+}
+
+/// Prints a summary line for a file that was checked, followed by its diagnostics.
+fn print_update(update: &FileUpdate) {
+    let count = |severity: Severity| {
+        update.report.diagnostics.iter().filter(|d| d.severity == severity && !d.suppressed).count()
+    };
+    println!(
+        "{}: {} errors, {} warnings ({} bytes in {:.2} ms)",
+        update.report.path,
+        count(Severity::Error),
+        count(Severity::Warning),
+        update.bytes,
+        update.elapsed.as_secs_f64() * 1000.0,
+    );
+    let mut stdout = io::stdout().lock();
+    let written = check::write_text(&mut stdout, std::slice::from_ref(&update.report)).and_then(|()| stdout.flush());
+    if let Err(error) = written {
+        eprintln!("error: {}", error);
+    }
+}
+
+/// Writes a file, reporting failure the same way as lex errors.
+fn write(path: &PathBuf, bytes: &[u8]) -> Result<(), Vec<ErrorType>> {
+    fs::write(path, bytes).map_err(|error| vec![io_error(path, error)])
+}
+
+/// Converts a failed file operation into an error.
+fn io_error(path: &Path, error: io::Error) -> ErrorType {
+    ErrorType::IoError { path: path.display().to_string(), message: error.to_string() }
+}
+
+/// Prints a file with its tokens marked, or each token in turn, waiting for Enter before each, if stepping.
+fn teach(file: &PathBuf, step: bool, color: ColorChoice) -> ExitCode {
+    let (source, config) = match read(file).and_then(|source| Ok((source, lexer_config()?))) {
+        Ok(settings) => settings,
+        Err(errors) => {
+            for error in errors {
+                eprintln!("error: {:?}", error);
+            }
+            return ExitCode::FAILURE;
+        }
+    };
+    let color = match color {
+        ColorChoice::Auto => io::stdout().is_terminal(),
+        ColorChoice::Always => true,
+        ColorChoice::Never => false,
+    };
+
+    let (tokens, errors) = Lexer::lex_recovering(&source, &config);
+    let mut stdin = io::stdin().lock();
+    let mut stdout = io::stdout().lock();
+    let mut print = |stdout: &mut io::StdoutLock| -> io::Result<()> {
+        if !step {
+            write!(stdout, "{}", teach::annotate(&source, &tokens, &errors, color))?;
+            return stdout.flush();
+        }
+        let steps = teach::teach_steps(&tokens, &errors);
+        for (index, taught) in steps.iter().enumerate() {
+            if index > 0 {
+                stdout.flush()?;
+                if stdin.read_line(&mut String::new())? == 0 {
+                    return Ok(());
+                }
+            }
+            write!(stdout, "{}", taught.render(&source, index + 1, steps.len(), color))?;
+        }
+        stdout.flush()
+    };
+    if let Err(error) = print(&mut stdout) {
+        eprintln!("error: {}", error);
+        return ExitCode::FAILURE;
+    }
+    ExitCode::SUCCESS
+}
+
+/// Reads the project's settings from the `lexer.toml` at `config_path`, or else the nearest one above the current
+/// directory, falling back to the defaults if there is none, so that every command lexes the same way.
+fn project_config(config_path: Option<&Path>) -> Result<ProjectConfig, ErrorType> {
+    match config_path {
+        Some(path) => ProjectConfig::load(path),
+        None => std::env::current_dir()
+            .map_err(|error| io_error(Path::new("."), error))
+            .and_then(|dir| ProjectConfig::discover(&dir))
+            .map(|found| found.map(|(_, project)| project).unwrap_or_default()),
+    }
+}
+
+/// Builds the lexer settings of the project found by `project_config`.
+fn lexer_config() -> Result<LexerConfig, ErrorType> {
+    Ok(project_config(None)?.lexer_builder()?.build())
+}
+
+/// Reads a source file, reporting failure the same way as lex errors.
+fn read(path: &PathBuf) -> Result<String, Vec<ErrorType>> {
+    fs::read_to_string(path).map_err(|error| vec![io_error(path, error)])
+}
+
+/// Prints the tokens to standard output in the chosen format.
+fn print_tokens(source: &str, tokens: &[SpannedToken], format: Format) -> io::Result<()> {
+    let mut stdout = io::stdout().lock();
+    match format {
+        Format::Text => {
+            for spanned in tokens {
+                writeln!(stdout, "{}..{}\t{:?}", spanned.span.start, spanned.span.end, spanned.token)?;
+            }
+            Ok(())
+        }
+        Format::Csv => export::write_csv(&mut stdout, source, tokens),
+        Format::Tsv => export::write_tsv(&mut stdout, source, tokens),
+    }
+}
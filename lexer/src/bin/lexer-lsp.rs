@@ -0,0 +1,35 @@
+//! Language server built on the lexer, speaking the Language Server Protocol over standard input and output.
+//!
+//! Build it with `cargo build --features lsp` and point an editor's language client at the `lexer-lsp` binary.
+
+use std::{io, process::ExitCode};
+
+use lexer::{
+    config::LexerConfig,
+    lsp::{read_message, write_message, Server},
+};
+
+fn main() -> ExitCode {
+    let mut server = Server::new(LexerConfig::default());
+    let mut stdin = io::stdin().lock();
+    let mut stdout = io::stdout().lock();
+    loop {
+        let message = match read_message(&mut stdin) {
+            Ok(Some(message)) => message,
+            Ok(None) => return ExitCode::FAILURE,
+            Err(error) => {
+                eprintln!("error: {}", error);
+                return ExitCode::FAILURE;
+            }
+        };
+        for reply in server.handle(&message) {
+            if let Err(error) = write_message(&mut stdout, &reply) {
+                eprintln!("error: {}", error);
+                return ExitCode::FAILURE;
+            }
+        }
+        if let Some(code) = server.exit_code() {
+            return ExitCode::from(code);
+        }
+    }
+}
@@ -0,0 +1,138 @@
+//! This file finds every `{ ... }` block in a token stream, with what introduces it and how deeply it is nested, for
+//! quick lexical tools such as counting functions or finding the longest one.
+//!
+//! The introducer of a block is the nearest keyword or identifier before its `{`, looking past a parenthesized
+//! list: the name in `int main(void) {` and `struct point {`, the keyword in `if (x) {`, `else {`, and `struct {`.
+//! A block after anything else, such as `=` in an initializer or `;` before a plain compound statement, has none.
+
+use std::ops::Range;
+
+use crate::{
+    span::{Span, SpannedToken},
+    token::{Token, TokenCategory},
+};
+
+/// What a block is the body of, judged from the tokens before it.
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Hash)]
+pub enum BlockKind {
+    /// The body of a function: a name and a parenthesized list, outside any other function.
+    Function,
+    /// The body of a struct.
+    Struct,
+    /// The body of an enum.
+    Enum,
+    /// The body of `if`, `else`, `for`, `while`, `do`, or `switch`.
+    Control,
+    /// Any other block, such as a compound statement or an initializer.
+    Other,
+}
+
+/// A `{ ... }` block.
+///
+/// # Fields
+/// * `tokens` - The indices of the block's tokens, from its `{` to its `}`, in the stream it was found in.
+/// * `span` - The source code from the `{` to the `}`, or to the last token if the block is never closed.
+/// * `level` - How many blocks enclose it, 0 for a block at the top level.
+/// * `kind` - What the block is the body of.
+/// * `introducer` - The keyword or identifier that introduces the block, if any.
+#[derive(PartialEq, Debug, Clone)]
+pub struct Block {
+    pub tokens: Range<usize>,
+    pub span: Span,
+    pub level: usize,
+    pub kind: BlockKind,
+    pub introducer: Option<SpannedToken>,
+}
+
+/// Finds every block in a stream of spanned tokens.
+///
+/// # Parameters
+/// * `tokens` - The tokens to search, as produced by `Lexer::lex_spanned`. A `}` without a matching `{` is ignored.
+///
+/// # Returns
+/// * `Vec<Block>` - Every block in the order its `{` appears, so each block comes before the blocks inside it.
+pub fn extract_blocks(tokens: &[SpannedToken]) -> Vec<Block> {
+    let mut blocks: Vec<Block> = Vec::new();
+    let mut open: Vec<usize> = Vec::new();
+
+    for (index, spanned) in tokens.iter().enumerate() {
+        match spanned.token {
+            Token::LBRACKET => {
+                let (kind, introducer) = introduce(tokens, index, open.is_empty());
+                open.push(blocks.len());
+                blocks.push(Block {
+                    tokens: index..tokens.len(),
+                    span: spanned.span,
+                    level: open.len() - 1,
+                    kind,
+                    introducer: introducer.cloned(),
+                });
+            }
+            Token::RBRACKET => {
+                if let Some(block) = open.pop() {
+                    blocks[block].tokens.end = index + 1;
+                    blocks[block].span.end = spanned.span.end;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // Blocks that are never closed run to the last token
+    let end = tokens.iter().rev().find(|spanned| spanned.token != Token::EOF).map(|last| last.span.end);
+    for block in open {
+        let block = &mut blocks[block];
+        block.tokens.end = tokens.len() - usize::from(tokens.last().is_some_and(|last| last.token == Token::EOF));
+        block.span.end = end.unwrap_or(block.span.end);
+    }
+    blocks
+}
+
+/// Finds what introduces the block opened at `index`
+fn introduce(tokens: &[SpannedToken], index: usize, top_level: bool) -> (BlockKind, Option<&SpannedToken>) {
+    let Some(mut before) = index.checked_sub(1) else {
+        return (BlockKind::Other, None);
+    };
+    let after_list: bool = tokens[before].token == Token::RPAREN;
+    if after_list {
+        match matching_open_paren(tokens, before).and_then(|paren| paren.checked_sub(1)) {
+            Some(previous) => before = previous,
+            None => return (BlockKind::Other, None),
+        }
+    }
+
+    let introducer = &tokens[before];
+    let kind = match introducer.token {
+        Token::IF | Token::ELSE | Token::FOR | Token::WHILE | Token::DO | Token::SWITCH => BlockKind::Control,
+        Token::IDENTIFIER(_) if after_list && top_level => BlockKind::Function,
+        Token::IDENTIFIER(_) => match tokens.get(before.wrapping_sub(1)).map(|previous| &previous.token) {
+            Some(Token::STRUCT) if !after_list => BlockKind::Struct,
+            Some(Token::ENUM) if !after_list => BlockKind::Enum,
+            _ => BlockKind::Other,
+        },
+        Token::STRUCT if !after_list => BlockKind::Struct,
+        Token::ENUM if !after_list => BlockKind::Enum,
+        _ => BlockKind::Other,
+    };
+    let is_word = matches!(introducer.token, Token::IDENTIFIER(_))
+        || matches!(introducer.token.category(), TokenCategory::Keyword | TokenCategory::Type);
+    (kind, is_word.then_some(introducer))
+}
+
+/// Finds the `(` matching the `)` at `close`
+fn matching_open_paren(tokens: &[SpannedToken], close: usize) -> Option<usize> {
+    let mut depth: usize = 0;
+    for index in (0..=close).rev() {
+        match tokens[index].token {
+            Token::RPAREN => depth += 1,
+            Token::LPAREN => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(index);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
@@ -0,0 +1,81 @@
+//! This file checks files and their blocks against size budgets, a cheap stand-in for complexity metrics that
+//! teams can enforce in CI with `lex check --deny-warnings`.
+//!
+//! A file is measured in tokens, not counting the final `Token::EOF`. Blocks are found with
+//! `block::extract_blocks` and measured in tokens, from `{` to `}`, and in lines, counting the lines of the `{` and
+//! the `}`. Only top-level blocks are measured, since the blocks inside them count towards their size already: the
+//! body of a function, a struct, or an initializer at file scope, but not a loop within a function.
+
+use serde::Deserialize;
+
+use crate::{
+    block::extract_blocks,
+    diagnostic::Diagnostic,
+    span::SpannedToken,
+    token::Token,
+};
+
+/// The largest a file and its blocks may be. Unset budgets are not checked.
+///
+/// # Fields
+/// * `max_file_tokens` - The most tokens a file may hold.
+/// * `max_block_tokens` - The most tokens a top-level block may hold.
+/// * `max_block_lines` - The most lines a top-level block may span.
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Budgets {
+    pub max_file_tokens: Option<usize>,
+    pub max_block_tokens: Option<usize>,
+    pub max_block_lines: Option<usize>,
+}
+
+/// Checks a file and its top-level blocks against budgets.
+///
+/// # Parameters
+/// * `source` - The source code, used to count the lines of blocks.
+/// * `tokens` - The tokens lexed from `source`.
+/// * `budgets` - The budgets to check.
+///
+/// # Returns
+/// * `Vec<Diagnostic>` - A warning for every budget exceeded: `file-token-budget` at the first token past the
+///   budget, and `block-token-budget` or `block-line-budget` at what introduces the block, or its `{` if nothing
+///   does, in source order.
+pub fn check_budgets(source: &str, tokens: &[SpannedToken], budgets: &Budgets) -> Vec<Diagnostic> {
+    let mut diagnostics: Vec<Diagnostic> = Vec::new();
+    let counted = tokens.iter().filter(|spanned| spanned.token != Token::EOF).count();
+    if let Some(max) = budgets.max_file_tokens.filter(|max| counted > *max) {
+        diagnostics.push(Diagnostic::warning(
+            "file-token-budget",
+            format!("file has {} tokens, the budget is {}", counted, max),
+            tokens[max].span,
+        ));
+    }
+    if budgets.max_block_tokens.is_none() && budgets.max_block_lines.is_none() {
+        return diagnostics;
+    }
+
+    for block in extract_blocks(tokens).into_iter().filter(|block| block.level == 0) {
+        let (name, span) = match &block.introducer {
+            Some(introducer) => (format!("`{}`", &source[introducer.span.start..introducer.span.end]), introducer.span),
+            None => ("block".to_string(), tokens[block.tokens.start].span),
+        };
+        let length = block.tokens.len();
+        if let Some(max) = budgets.max_block_tokens.filter(|max| length > *max) {
+            diagnostics.push(Diagnostic::warning(
+                "block-token-budget",
+                format!("{} has {} tokens, the budget is {}", name, length, max),
+                span,
+            ));
+        }
+        let lines = source[block.span.start..block.span.end].matches('\n').count() + 1;
+        if let Some(max) = budgets.max_block_lines.filter(|max| lines > *max) {
+            diagnostics.push(Diagnostic::warning(
+                "block-line-budget",
+                format!("{} spans {} lines, the budget is {}", name, lines, max),
+                span,
+            ));
+        }
+    }
+    diagnostics.sort_by_key(|diagnostic| diagnostic.span.start);
+    diagnostics
+}
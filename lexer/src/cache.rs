@@ -0,0 +1,171 @@
+//! This file caches lex results by the hash of the source they came from, so that unchanged files are decoded
+//! from the cache instead of being lexed again.
+//!
+//! Entries are stored in the binary format from the `codec` module. Where they are kept is up to a
+//! `CacheStorage` implementation; an in-memory map and a directory on disk are provided.
+//!
+//! Entries are keyed by the source, the codec format version, and the version of this crate, so that a lexer that
+//! tokenizes differently never reads the entries of an older one. Each entry also records the length of its source
+//! and a second hash of it, which are checked on load, so that two sources whose keys collide are never confused.
+
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use common::error::ErrorType;
+
+use crate::{
+    codec::{self, FORMAT_VERSION},
+    core::Lexer,
+    fingerprint::fnv1a,
+    span::SpannedToken,
+};
+
+/// Somewhere encoded token streams can be kept between lexes.
+pub trait CacheStorage {
+    /// Gives the bytes stored under `key`, if any.
+    fn load(&self, key: u64) -> Option<Vec<u8>>;
+
+    /// Stores `bytes` under `key`, replacing any previous entry. Failures are ignored, since a cache that cannot
+    /// be written to only costs time.
+    fn store(&mut self, key: u64, bytes: Vec<u8>);
+}
+
+/// Keeps cache entries in memory for the lifetime of the cache.
+#[derive(Debug, Default)]
+pub struct MemoryStorage {
+    entries: HashMap<u64, Vec<u8>>,
+}
+
+impl MemoryStorage {
+    /// Creates an empty in-memory storage.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl CacheStorage for MemoryStorage {
+    fn load(&self, key: u64) -> Option<Vec<u8>> {
+        self.entries.get(&key).cloned()
+    }
+
+    fn store(&mut self, key: u64, bytes: Vec<u8>) {
+        self.entries.insert(key, bytes);
+    }
+}
+
+/// Keeps cache entries as files in a directory, so they survive between runs.
+#[derive(Debug, Clone)]
+pub struct DirectoryStorage {
+    root: PathBuf,
+}
+
+impl DirectoryStorage {
+    /// Creates a storage that keeps entries under `root`. The directory is created when the first entry is stored.
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// Gives the path of the file holding the entry for `key`.
+    fn entry_path(&self, key: u64) -> PathBuf {
+        self.root.join(format!("{:016x}.lxt", key))
+    }
+}
+
+impl CacheStorage for DirectoryStorage {
+    fn load(&self, key: u64) -> Option<Vec<u8>> {
+        fs::read(self.entry_path(key)).ok()
+    }
+
+    fn store(&mut self, key: u64, bytes: Vec<u8>) {
+        if fs::create_dir_all(&self.root).is_ok() {
+            let _ = fs::write(self.entry_path(key), bytes);
+        }
+    }
+}
+
+/// A lex cache keyed by the content of the source.
+///
+/// # Fields
+/// * `storage` - Where entries are kept.
+/// * `hits` - How many lookups were answered from the cache.
+/// * `misses` - How many lookups had to lex the source.
+pub struct LexCache<S: CacheStorage> {
+    storage: S,
+    hits: usize,
+    misses: usize,
+}
+
+impl<S: CacheStorage> LexCache<S> {
+    /// Creates a cache backed by `storage`.
+    pub fn new(storage: S) -> Self {
+        Self { storage, hits: 0, misses: 0 }
+    }
+
+    /// Reads the file at `path` and returns its tokens, from the cache if its content has been lexed before.
+    ///
+    /// # Returns
+    /// * `Ok(Vec<SpannedToken>)` - The tokens of the file.
+    /// * `Err(Vec<ErrorType>)` - `ErrorType::IoError` if the file cannot be read, or the lex errors otherwise.
+    ///   Failed lexes are not cached.
+    pub fn lex_or_load(&mut self, path: &Path) -> Result<Vec<SpannedToken>, Vec<ErrorType>> {
+        let source = fs::read_to_string(path).map_err(|error| ErrorType::IoError {
+            path: path.display().to_string(),
+            message: error.to_string(),
+        })?;
+        self.lex_or_load_source(&source)
+    }
+
+    /// Returns the tokens of `source`, from the cache if it has been lexed before.
+    pub fn lex_or_load_source(&mut self, source: &str) -> Result<Vec<SpannedToken>, Vec<ErrorType>> {
+        let key = cache_key(source);
+        let header = entry_header(source);
+        let entry = self.storage.load(key).filter(|bytes| bytes.starts_with(&header));
+        if let Some(tokens) = entry.and_then(|bytes| codec::decode(&bytes[ENTRY_HEADER_LEN..]).ok()) {
+            self.hits += 1;
+            return Ok(tokens);
+        }
+
+        self.misses += 1;
+        let tokens = Lexer::lex_spanned(source)?;
+        let mut bytes = header.to_vec();
+        bytes.extend(codec::encode(&tokens));
+        self.storage.store(key, bytes);
+        Ok(tokens)
+    }
+
+    /// Gives the number of lookups answered from the cache.
+    pub fn hits(&self) -> usize {
+        self.hits
+    }
+
+    /// Gives the number of lookups that had to lex the source.
+    pub fn misses(&self) -> usize {
+        self.misses
+    }
+}
+
+/// The version of the lexer, mixed into cache keys so that entries written by a release that lexed differently
+/// are never looked up.
+const LEXER_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// The length of the header before the encoded tokens of an entry: the length of its source and a second hash of
+/// it, as little-endian 64-bit integers.
+const ENTRY_HEADER_LEN: usize = 16;
+
+/// Gives the key a source's entry is stored under
+fn cache_key(source: &str) -> u64 {
+    let version = LEXER_VERSION.bytes().chain(std::iter::once(0));
+    fnv1a(std::iter::once(FORMAT_VERSION).chain(version).chain(source.bytes()))
+}
+
+/// Gives the header of a source's entry: its length, and a hash of its bytes in reverse, which is unrelated to the
+/// key
+fn entry_header(source: &str) -> [u8; ENTRY_HEADER_LEN] {
+    let mut header = [0; ENTRY_HEADER_LEN];
+    header[..8].copy_from_slice(&(source.len() as u64).to_le_bytes());
+    header[8..].copy_from_slice(&fnv1a(source.bytes().rev()).to_le_bytes());
+    header
+}
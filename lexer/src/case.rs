@@ -0,0 +1,89 @@
+//! This file rewrites source code so that every keyword is spelled in one case, for dialects whose keywords are
+//! case-insensitive, such as code written for compilers that accept `IF` and `If` as `if`.
+//!
+//! The rewrite works from the raw token stream of `raw::lex_raw_with_config`, which tiles the source exactly, so
+//! whitespace, comments, and every other token are copied through untouched. Only words are rewritten: keywords,
+//! contextual keywords registered with `LexerBuilder::contextual_keyword`, and identifiers spelled like either in
+//! another case. Changing the case of an ASCII word keeps its length, so every span into the original source is
+//! still valid in the rewritten one.
+
+use crate::{
+    config::LexerConfig,
+    error::LexError,
+    raw::{lex_raw_with_config, with_spans},
+    span::Span,
+    text::TextEdit,
+    token::{Token, TokenKind},
+};
+
+/// The case to spell keywords in.
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub enum KeywordCase {
+    /// Lowercase, as standard C spells them.
+    #[default]
+    Lower,
+    /// Uppercase.
+    Upper,
+}
+
+/// One keyword whose spelling was changed.
+///
+/// # Fields
+/// * `span` - Where the keyword appears, in both the original and the rewritten source.
+/// * `original` - The keyword as it was spelled.
+/// * `replacement` - The keyword as it is now spelled.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct KeywordEdit {
+    pub span: Span,
+    pub original: String,
+    pub replacement: String,
+}
+
+impl From<KeywordEdit> for TextEdit {
+    fn from(edit: KeywordEdit) -> Self {
+        TextEdit { span: edit.span, text: edit.replacement }
+    }
+}
+
+/// Rewrites every keyword in `source` to be spelled in one case.
+///
+/// # Parameters
+/// * `source` - The source code to rewrite.
+/// * `config` - The settings to lex with. Its contextual keywords are normalized along with the built-in ones.
+/// * `case` - The case to spell keywords in.
+///
+/// # Returns
+/// * `Ok((String, Vec<KeywordEdit>))` - The rewritten source and every keyword that changed, in source order.
+/// * `Err(Vec<LexError>)` - Every error and the span of source it was found at, if `source` does not lex.
+pub fn normalize_keyword_case(
+    source: &str,
+    config: &LexerConfig,
+    case: KeywordCase,
+) -> Result<(String, Vec<KeywordEdit>), Vec<LexError>> {
+    let tokens = lex_raw_with_config(source, config)?;
+    let mut rewritten = String::with_capacity(source.len());
+    let mut edits: Vec<KeywordEdit> = Vec::new();
+
+    for (token, span) in with_spans(&tokens) {
+        let text = &source[span.start..span.end];
+        let is_word = token.kind == TokenKind::IDENTIFIER
+            || Token::keywords().any(|(_, keyword)| keyword.kind() == token.kind);
+        let replacement = match case {
+            KeywordCase::Lower => text.to_ascii_lowercase(),
+            KeywordCase::Upper => text.to_ascii_uppercase(),
+        };
+        if is_word && replacement != text && is_keyword(&text.to_ascii_lowercase(), config) {
+            rewritten.push_str(&replacement);
+            edits.push(KeywordEdit { span, original: text.to_string(), replacement });
+        } else {
+            rewritten.push_str(text);
+        }
+    }
+    Ok((rewritten, edits))
+}
+
+/// Checks whether a lowercase word is a keyword or a contextual keyword
+fn is_keyword(word: &str, config: &LexerConfig) -> bool {
+    Token::keywords().any(|(spelling, _)| spelling == word)
+        || config.keyword_table().contextual_keywords().any(|keyword| keyword.to_ascii_lowercase() == word)
+}
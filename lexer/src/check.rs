@@ -0,0 +1,273 @@
+//! This file gathers every diagnostic the lexer and its optional passes report for a file, and writes them in a
+//! stable JSON schema for editors and CI annotation tools.
+//!
+//! ## JSON schema
+//!
+//! The output is one object, `{"version": 1, "diagnostics": [...]}`, where each diagnostic is:
+//!
+//! ```json
+//! {
+//!   "file": "src/main.c",
+//!   "range": {
+//!     "start": {"line": 1, "column": 5, "offset": 4},
+//!     "end": {"line": 1, "column": 6, "offset": 5}
+//!   },
+//!   "severity": "error",
+//!   "code": "unrecognized-token",
+//!   "message": "unrecognized token `$`",
+//!   "suppressed": false
+//! }
+//! ```
+//!
+//! Lines and columns count from 1, columns count bytes from the start of the line, and offsets count bytes from
+//! the start of the file. `severity` is one of `error`, `warning`, or `info`. `suppressed` is true for diagnostics
+//! muted by a suppression comment, which do not make `lex check` fail. Fields are only ever added, and
+//! `version` is bumped if any existing field changes meaning.
+
+use std::io::{self, Write};
+
+use common::error::ErrorType;
+use serde::Serialize;
+
+use crate::{
+    budget::check_budgets,
+    column::DEFAULT_TAB_WIDTH,
+    config::LexerConfig,
+    confusable::check_confusables,
+    core::{describe_control_chars, Lexer, UNTERMINATED_STRING},
+    diagnostic::{Annotations, Diagnostic, Severity},
+    directive::check_directives,
+    error::LexError,
+    lint::{lint_identifiers, LintConfig},
+    literal::check_literal_suffixes,
+    span::{Location, Span, SpannedToken},
+    suppress::apply_suppressions,
+};
+
+/// The version of the JSON schema written by `write_json`.
+pub const JSON_SCHEMA_VERSION: u32 = 1;
+
+/// The diagnostics found in one file.
+///
+/// # Fields
+/// * `path` - The file, as it should be shown to the user.
+/// * `source` - The contents of the file, used to turn spans into lines and columns.
+/// * `diagnostics` - Every diagnostic, in source order.
+#[derive(PartialEq, Debug, Clone)]
+pub struct FileReport {
+    pub path: String,
+    pub source: String,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl FileReport {
+    /// Checks a file's contents with `check_source`.
+    pub fn check(path: &str, source: String) -> Self {
+        let diagnostics = check_source(&source);
+        Self { path: path.to_string(), source, diagnostics }
+    }
+
+    /// Checks a file's contents with the given lexer and lint settings, such as those of a project's `lexer.toml`.
+    pub fn check_with_config(path: &str, source: String, config: &LexerConfig, lints: &LintConfig) -> Self {
+        let (tokens, errors, reported) = Lexer::lex_with_diagnostics(&source, config);
+        let diagnostics = check_lexed_with(&source, &tokens, &errors, reported, lints);
+        Self { path: path.to_string(), source, diagnostics }
+    }
+
+    /// Returns true if any diagnostic that is not suppressed is an error.
+    pub fn has_errors(&self) -> bool {
+        self.diagnostics.iter().any(|diagnostic| diagnostic.severity == Severity::Error && !diagnostic.suppressed)
+    }
+
+    /// Returns true if any diagnostic that is not suppressed is an error or a warning.
+    pub fn has_warnings(&self) -> bool {
+        self.diagnostics.iter().any(|diagnostic| diagnostic.severity <= Severity::Warning && !diagnostic.suppressed)
+    }
+}
+
+/// Lexes source code and runs every check over it: lex errors, identifier lints with the default settings,
+/// confusable characters, preprocessor lines, numbers written directly before words, and size budgets, of which
+/// the default settings set none.
+///
+/// The lex recovers from errors, so the other checks still see every token that could be lexed.
+///
+/// # Returns
+/// * `Vec<Diagnostic>` - Every diagnostic, sorted by where it starts and then by severity.
+pub fn check_source(source: &str) -> Vec<Diagnostic> {
+    let (tokens, errors) = Lexer::lex_recovering(source, &LexerConfig::default());
+    check_lexed(source, &tokens, &errors, &LintConfig::default())
+}
+
+/// Runs every check over source code that has already been lexed with `Lexer::lex_recovering`, for callers that
+/// lex with their own settings or also need the tokens.
+///
+/// # Parameters
+/// * `source` - The source code.
+/// * `tokens` - The tokens lexed from `source`.
+/// * `errors` - The errors reported while lexing `source`.
+/// * `lints` - Which identifier lints to run.
+///
+/// # Returns
+/// * `Vec<Diagnostic>` - Every diagnostic, sorted by where it starts and then by severity.
+pub fn check_lexed(source: &str, tokens: &[SpannedToken], errors: &[LexError], lints: &LintConfig) -> Vec<Diagnostic> {
+    check_lexed_with(source, tokens, errors, Vec::new(), lints)
+}
+
+/// Runs every check over source code lexed with `Lexer::lex_with_diagnostics`, adding the warnings the lexer
+/// reported, such as whitespace lints, so that they are suppressed and sorted with the rest.
+///
+/// # Parameters
+/// * `source` - The source code.
+/// * `tokens` - The tokens lexed from `source`.
+/// * `errors` - The errors reported while lexing `source`.
+/// * `reported` - The warnings reported while lexing `source`.
+/// * `lints` - Which identifier lints to run.
+///
+/// # Returns
+/// * `Vec<Diagnostic>` - Every diagnostic, sorted by where it starts and then by severity.
+pub fn check_lexed_with(
+    source: &str,
+    tokens: &[SpannedToken],
+    errors: &[LexError],
+    reported: Vec<Diagnostic>,
+    lints: &LintConfig,
+) -> Vec<Diagnostic> {
+    let mut diagnostics: Vec<Diagnostic> = errors.iter().map(error_diagnostic).collect();
+    diagnostics.extend(reported);
+    diagnostics.extend(lint_identifiers(tokens, lints));
+    diagnostics.extend(check_confusables(source, tokens));
+    diagnostics.extend(check_directives(tokens));
+    diagnostics.extend(check_literal_suffixes(source, tokens));
+    diagnostics.extend(check_budgets(source, tokens, &lints.budgets));
+    apply_suppressions(source, tokens, errors, &mut diagnostics);
+    diagnostics.sort_by_key(|diagnostic| (diagnostic.span.start, diagnostic.severity));
+    diagnostics
+}
+
+/// Converts a lex error into an error-severity diagnostic with a stable code.
+pub fn error_diagnostic(error: &LexError) -> Diagnostic {
+    let (code, message) = match error.error() {
+        ErrorType::UnrecognizedToken { token } => ("unrecognized-token", format!("unrecognized token `{}`", token)),
+        ErrorType::SyntaxError { message } => ("syntax-error", message.clone()),
+        ErrorType::InvalidLiteral { literal, reason } => {
+            ("invalid-literal", format!("invalid literal `{}`: {}", literal, reason))
+        }
+        ErrorType::NestingTooDeep { construct, limit } => {
+            ("nesting-too-deep", format!("{} nested more than {} deep", construct, limit))
+        }
+        ErrorType::TokenTooLong { kind, length, limit } => {
+            ("token-too-long", format!("{} of {} characters is longer than the limit of {}", kind, length, limit))
+        }
+        ErrorType::ControlCharacter { character, count } => {
+            ("control-character", format!("unexpected {}", describe_control_chars(*character, *count)))
+        }
+        other => ("lex-error", format!("{:?}", other)),
+    };
+    Diagnostic::error(code, message, error.span())
+}
+
+/// Gives the labels to render a lex error with, as `Diagnostic::render_annotated` takes them.
+///
+/// An unterminated string literal is labelled twice: at its opening quote, and at the newline or end of input that
+/// ends it before a closing quote. Every other error has one label covering its span.
+///
+/// # Parameters
+/// * `error` - The error to label.
+/// * `source` - The source code the error was found in.
+pub fn error_annotations(error: &LexError, source: &str) -> Annotations {
+    let span = error.span();
+    let mut annotations = Annotations::new();
+    let unterminated = matches!(error.error(), ErrorType::SyntaxError { message } if message == UNTERMINATED_STRING);
+    if !unterminated || !source.get(span.start..).is_some_and(|rest| rest.starts_with('"')) {
+        annotations.label(span, "");
+        return annotations;
+    }
+    annotations.label(Span::new(span.start, span.start + 1), "this string is never closed");
+    if source[span.end..].starts_with('\n') {
+        annotations.secondary(Span::new(span.end, span.end + 1), "the line ends before a closing quote");
+    } else {
+        annotations.secondary(Span::new(span.end, span.end), "the input ends before a closing quote");
+    }
+    annotations
+}
+
+/// Writes the diagnostics of every file as one JSON document in the schema described above.
+///
+/// # Parameters
+/// * `writer` - Where to write the document.
+/// * `reports` - The files and their diagnostics, written in the order given.
+pub fn write_json<W: Write>(writer: &mut W, reports: &[FileReport]) -> io::Result<()> {
+    let diagnostics: Vec<JsonDiagnostic> = reports
+        .iter()
+        .flat_map(|report| report.diagnostics.iter().map(move |diagnostic| JsonDiagnostic::new(report, diagnostic)))
+        .collect();
+    serde_json::to_writer(&mut *writer, &JsonDocument { version: JSON_SCHEMA_VERSION, diagnostics })?;
+    writeln!(writer)
+}
+
+/// Writes the diagnostics of every file one per line as `file:line:column: severity[code]: message`, the format
+/// compilers use and most problem matchers expect. Suppressed diagnostics are left out.
+pub fn write_text<W: Write>(writer: &mut W, reports: &[FileReport]) -> io::Result<()> {
+    for report in reports {
+        for diagnostic in report.diagnostics.iter().filter(|diagnostic| !diagnostic.suppressed) {
+            let start = diagnostic.span.start_location(&report.source, DEFAULT_TAB_WIDTH);
+            writeln!(
+                writer,
+                "{}:{}:{}: {}[{}]: {}",
+                report.path, start.line, start.byte_column, diagnostic.severity, diagnostic.code, diagnostic.message,
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// The document written by `write_json`.
+#[derive(Serialize)]
+struct JsonDocument<'a> {
+    version: u32,
+    diagnostics: Vec<JsonDiagnostic<'a>>,
+}
+
+/// One diagnostic in the JSON schema.
+#[derive(Serialize)]
+struct JsonDiagnostic<'a> {
+    file: &'a str,
+    range: JsonRange,
+    severity: String,
+    code: &'a str,
+    message: &'a str,
+    suppressed: bool,
+}
+
+/// A range of source in the JSON schema.
+#[derive(Serialize)]
+struct JsonRange {
+    start: JsonPosition,
+    end: JsonPosition,
+}
+
+/// A position in source in the JSON schema.
+#[derive(Serialize)]
+struct JsonPosition {
+    line: usize,
+    column: usize,
+    offset: usize,
+}
+
+impl<'a> JsonDiagnostic<'a> {
+    /// Converts a diagnostic, locating its span within the file.
+    fn new(report: &'a FileReport, diagnostic: &'a Diagnostic) -> Self {
+        let position = |offset: usize| {
+            let location = Location::of(&report.source, offset, DEFAULT_TAB_WIDTH);
+            JsonPosition { line: location.line, column: location.byte_column, offset }
+        };
+        Self {
+            file: &report.path,
+            range: JsonRange { start: position(diagnostic.span.start), end: position(diagnostic.span.end) },
+            severity: diagnostic.severity.to_string(),
+            code: diagnostic.code,
+            message: &diagnostic.message,
+            suppressed: diagnostic.suppressed,
+        }
+    }
+}
@@ -0,0 +1,248 @@
+//! This file encodes token streams into a compact binary form and decodes them back, so that build systems can
+//! cache the result of lexing a file and skip re-lexing it when it has not changed.
+//!
+//! ## Format
+//!
+//! * A header of the magic bytes `LXT` followed by a one byte format version.
+//! * A symbol table: the number of symbols, then each symbol as a length-prefixed UTF-8 string. Identifier,
+//!   number, string literal, and custom token payloads are stored here once and referenced by index.
+//! * The tokens: the number of tokens, then for each token the one byte stable id of its kind, as `TokenKind::id`
+//!   gives it, its payload (one or two symbol indices, or a character), a one byte set of flags, where it starts,
+//!   and its length. The flags are `FLAG_SYNTHETIC` and `FLAG_CONTEXTUAL_KEYWORD`, copied from the token, and
+//!   `FLAG_ABSOLUTE_START`. A token starts at the gap given after the end of the previous token, or at the offset
+//!   given when `FLAG_ABSOLUTE_START` is set, which it is for a token that starts before the previous one ends.
+//!
+//! All integers after the header are LEB128 varints, so small offsets and indices take a single byte.
+
+use std::collections::HashMap;
+
+use common::error::ErrorType;
+
+use crate::{
+    span::{Span, SpannedToken},
+    token::{Token, TokenData, TokenKind},
+};
+
+/// Magic bytes identifying an encoded token stream.
+const MAGIC: &[u8; 3] = b"LXT";
+
+/// The version of the format written by `encode`. Bumped whenever the layout changes. Kinds are written as their
+/// stable ids, so adding a kind does not change the format.
+pub const FORMAT_VERSION: u8 = 2;
+
+/// The flag set for a token synthesized during recovery.
+const FLAG_SYNTHETIC: u8 = 1;
+
+/// The flag set for an identifier spelled like a contextual keyword.
+const FLAG_CONTEXTUAL_KEYWORD: u8 = 2;
+
+/// The flag set for a token whose start is stored as an offset rather than a gap after the previous token.
+const FLAG_ABSOLUTE_START: u8 = 4;
+
+/// Encodes a token stream into bytes.
+///
+/// # Parameters
+/// * `tokens` - The tokens to encode, as produced by `Lexer::lex_spanned`.
+///
+/// # Returns
+/// * `Vec<u8>` - The encoded stream, which `decode` turns back into the same tokens.
+pub fn encode(tokens: &[SpannedToken]) -> Vec<u8> {
+    let mut symbols: Vec<String> = Vec::new();
+    let mut symbol_ids: HashMap<String, usize> = HashMap::new();
+    let mut body: Vec<u8> = Vec::new();
+    let mut previous_end: usize = 0;
+    let mut intern = |text: String, body: &mut Vec<u8>| {
+        let next_id = symbols.len();
+        let id = *symbol_ids.entry(text.clone()).or_insert_with(|| {
+            symbols.push(text);
+            next_id
+        });
+        write_varint(body, id as u64);
+    };
+
+    write_varint(&mut body, tokens.len() as u64);
+    for spanned in tokens {
+        body.push(spanned.token.kind().id());
+        match &spanned.token {
+            Token::NUMBER(chars)
+            | Token::IDENTIFIER(chars)
+            | Token::STRINGLITERAL(chars)
+            | Token::HASHLINE(chars)
+            | Token::TYPENAME(chars)
+            | Token::UNKNOWN(chars) => {
+                intern(chars.iter().collect(), &mut body);
+            }
+            Token::CUSTOM(kind, chars) => {
+                intern(kind.clone(), &mut body);
+                intern(chars.iter().collect(), &mut body);
+            }
+            Token::CHAR(c) => write_varint(&mut body, *c as u64),
+            _ => {}
+        }
+        let mut flags: u8 = 0;
+        if spanned.synthetic {
+            flags |= FLAG_SYNTHETIC;
+        }
+        if spanned.contextual_keyword {
+            flags |= FLAG_CONTEXTUAL_KEYWORD;
+        }
+        match spanned.span.start.checked_sub(previous_end) {
+            Some(gap) => {
+                body.push(flags);
+                write_varint(&mut body, gap as u64);
+            }
+            None => {
+                body.push(flags | FLAG_ABSOLUTE_START);
+                write_varint(&mut body, spanned.span.start as u64);
+            }
+        }
+        write_varint(&mut body, spanned.span.len() as u64);
+        previous_end = spanned.span.end;
+    }
+
+    let mut bytes: Vec<u8> = Vec::with_capacity(body.len() + 4);
+    bytes.extend_from_slice(MAGIC);
+    bytes.push(FORMAT_VERSION);
+    write_varint(&mut bytes, symbols.len() as u64);
+    for symbol in &symbols {
+        write_varint(&mut bytes, symbol.len() as u64);
+        bytes.extend_from_slice(symbol.as_bytes());
+    }
+    bytes.extend_from_slice(&body);
+    bytes
+}
+
+/// Decodes a token stream previously produced by `encode`.
+///
+/// # Parameters
+/// * `bytes` - The encoded stream.
+///
+/// # Returns
+/// * `Ok(Vec<SpannedToken>)` - The decoded tokens.
+/// * `Err(ErrorType)` - `ErrorType::MalformedTokenStream` if the bytes are truncated, corrupt, or were written by a
+///   different format version.
+pub fn decode(bytes: &[u8]) -> Result<Vec<SpannedToken>, ErrorType> {
+    let mut reader = Reader { bytes, position: 0 };
+
+    if reader.take(MAGIC.len())? != MAGIC {
+        return Err(malformed("missing header"));
+    }
+    let version = reader.take(1)?[0];
+    if version != FORMAT_VERSION {
+        return Err(malformed(&format!("unsupported format version {}", version)));
+    }
+
+    let symbol_count = reader.varint()? as usize;
+    let mut symbols: Vec<Vec<char>> = Vec::with_capacity(symbol_count.min(bytes.len()));
+    for _ in 0..symbol_count {
+        let length = reader.varint()? as usize;
+        let text = std::str::from_utf8(reader.take(length)?).map_err(|_| malformed("symbol is not UTF-8"))?;
+        symbols.push(text.chars().collect());
+    }
+
+    let token_count = reader.varint()? as usize;
+    let mut tokens: Vec<SpannedToken> = Vec::with_capacity(token_count.min(bytes.len()));
+    let mut previous_end: usize = 0;
+    for _ in 0..token_count {
+        let id = reader.take(1)?[0];
+        let kind = TokenKind::from_id(id)
+            .filter(|kind| *kind != TokenKind::TRIVIA)
+            .ok_or_else(|| malformed(&format!("unknown token kind {}", id)))?;
+        let mut symbol = || {
+            let index = reader.varint()? as usize;
+            symbols.get(index).cloned().ok_or_else(|| malformed("symbol index out of range"))
+        };
+        let data = match kind {
+            TokenKind::CUSTOM => {
+                let name: String = symbol()?.into_iter().collect();
+                TokenData::Custom(name, symbol()?)
+            }
+            TokenKind::CHAR => {
+                let value = reader.varint()?;
+                let c = u32::try_from(value)
+                    .ok()
+                    .and_then(char::from_u32)
+                    .ok_or_else(|| malformed("invalid character"))?;
+                TokenData::Char(c)
+            }
+            kind if kind.has_payload() => TokenData::Text(symbol()?),
+            _ => TokenData::None,
+        };
+        let token = Token::from_parts(kind, data).ok_or_else(|| malformed("payload does not match the kind"))?;
+        let flags = reader.take(1)?[0];
+        if flags & !(FLAG_SYNTHETIC | FLAG_CONTEXTUAL_KEYWORD | FLAG_ABSOLUTE_START) != 0 {
+            return Err(malformed(&format!("unknown token flags {}", flags)));
+        }
+        let start = if flags & FLAG_ABSOLUTE_START != 0 { 0 } else { previous_end };
+        let start = offset_after(start, reader.varint()?)?;
+        let end = offset_after(start, reader.varint()?)?;
+        let mut spanned = SpannedToken::new(token, Span::new(start, end));
+        spanned.synthetic = flags & FLAG_SYNTHETIC != 0;
+        spanned.contextual_keyword = flags & FLAG_CONTEXTUAL_KEYWORD != 0;
+        tokens.push(spanned);
+        previous_end = end;
+    }
+
+    if reader.position != bytes.len() {
+        return Err(malformed("trailing bytes after the last token"));
+    }
+    Ok(tokens)
+}
+
+/// Adds a decoded distance to an offset, failing if the result does not fit
+fn offset_after(offset: usize, distance: u64) -> Result<usize, ErrorType> {
+    usize::try_from(distance)
+        .ok()
+        .and_then(|distance| offset.checked_add(distance))
+        .ok_or_else(|| malformed("span out of range"))
+}
+
+/// Appends `value` as an LEB128 varint.
+pub(crate) fn write_varint(bytes: &mut Vec<u8>, mut value: u64) {
+    loop {
+        let byte = (value & 0x7F) as u8;
+        value >>= 7;
+        if value == 0 {
+            bytes.push(byte);
+            return;
+        }
+        bytes.push(byte | 0x80);
+    }
+}
+
+/// Reads through an encoded stream, failing cleanly on truncation.
+pub(crate) struct Reader<'a> {
+    pub(crate) bytes: &'a [u8],
+    pub(crate) position: usize,
+}
+
+impl<'a> Reader<'a> {
+    /// Takes the next `n` bytes.
+    pub(crate) fn take(&mut self, n: usize) -> Result<&'a [u8], ErrorType> {
+        let end = self.position
+            .checked_add(n)
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or_else(|| malformed("unexpected end of input"))?;
+        let slice = &self.bytes[self.position..end];
+        self.position = end;
+        Ok(slice)
+    }
+
+    /// Reads an LEB128 varint.
+    pub(crate) fn varint(&mut self) -> Result<u64, ErrorType> {
+        let mut value: u64 = 0;
+        for shift in (0..64).step_by(7) {
+            let byte = self.take(1)?[0];
+            value |= ((byte & 0x7F) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(malformed("varint is too long"))
+    }
+}
+
+/// Helper function to create a malformed token stream error
+pub(crate) fn malformed(message: &str) -> ErrorType {
+    ErrorType::MalformedTokenStream { message: message.to_string() }
+}
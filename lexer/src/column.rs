@@ -0,0 +1,86 @@
+//! This file computes how wide characters are when displayed in a terminal, so that columns reported in
+//! diagnostics and the carets drawn under source lines line up with what the user sees.
+//!
+//! Tabs advance to the next tab stop, East Asian wide characters take two cells, and combining marks and
+//! zero-width characters take none.
+
+/// The tab width used when none is configured.
+pub const DEFAULT_TAB_WIDTH: usize = 4;
+
+/// Ranges of characters that occupy two cells.
+const WIDE: [(u32, u32); 12] = [
+    (0x1100, 0x115F),
+    (0x2E80, 0x303E),
+    (0x3041, 0x33FF),
+    (0x3400, 0x4DBF),
+    (0x4E00, 0x9FFF),
+    (0xA000, 0xA4CF),
+    (0xAC00, 0xD7A3),
+    (0xF900, 0xFAFF),
+    (0xFE30, 0xFE4F),
+    (0xFF00, 0xFF60),
+    (0xFFE0, 0xFFE6),
+    (0x1F300, 0x3FFFD),
+];
+
+/// Ranges of characters that occupy no cells: combining marks and zero-width formatting characters.
+const ZERO_WIDTH: [(u32, u32); 8] = [
+    (0x0300, 0x036F),
+    (0x1AB0, 0x1AFF),
+    (0x1DC0, 0x1DFF),
+    (0x200B, 0x200F),
+    (0x202A, 0x202E),
+    (0x2060, 0x2069),
+    (0x20D0, 0x20FF),
+    (0xFE20, 0xFE2F),
+];
+
+/// Gives the number of cells a character occupies, not counting tabs, whose width depends on where they appear.
+pub fn char_width(c: char) -> usize {
+    let code = c as u32;
+    let in_ranges = |ranges: &[(u32, u32)]| ranges.iter().any(|(low, high)| (*low..=*high).contains(&code));
+
+    if c.is_control() || c == '\u{FEFF}' || in_ranges(&ZERO_WIDTH) {
+        0
+    } else if in_ranges(&WIDE) {
+        2
+    } else {
+        1
+    }
+}
+
+/// Gives the number of cells `text` occupies when it starts at the beginning of a line.
+///
+/// # Parameters
+/// * `text` - Text from a single line.
+/// * `tab_width` - The distance between tab stops.
+pub fn display_width(text: &str, tab_width: usize) -> usize {
+    text.chars().fold(0, |column, c| advance(column, c, tab_width))
+}
+
+/// Expands the tabs in `text` to spaces, so that the result occupies the same cells as `text` but can be
+/// measured by counting.
+pub fn expand_tabs(text: &str, tab_width: usize) -> String {
+    let mut expanded = String::with_capacity(text.len());
+    let mut column = 0;
+    for c in text.chars() {
+        let next = advance(column, c, tab_width);
+        if c == '\t' {
+            expanded.extend(std::iter::repeat_n(' ', next - column));
+        } else {
+            expanded.push(c);
+        }
+        column = next;
+    }
+    expanded
+}
+
+/// Gives the column after `c` when it is displayed starting at `column`.
+fn advance(column: usize, c: char, tab_width: usize) -> usize {
+    if c == '\t' {
+        let tab_width = tab_width.max(1);
+        (column / tab_width + 1) * tab_width
+    } else {
+        column + char_width(c)
+    }
+}
@@ -0,0 +1,314 @@
+//! This file compares the lexer against a reference tokenizer for C, so that divergences from the language can be
+//! found on real code as operators and literals are added.
+//!
+//! The reference follows the C11 rules for preprocessing tokens: maximal munch over the full set of punctuators,
+//! preprocessing numbers, string and character literals with their encoding prefixes, and preprocessor lines, with
+//! identifiers that spell a C11 keyword classified as keywords. It is deliberately written independently of `core`
+//! so that the two do not share mistakes. Tokens are compared by class and span only, since the reference does not
+//! compute values.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use common::error::ErrorType;
+
+use crate::{
+    config::LexerConfig,
+    core::Lexer,
+    driver::{discover_files, DriverConfig},
+    span::Span,
+    token::{Token, TokenCategory},
+};
+
+/// Every C11 keyword.
+const C_KEYWORDS: [&str; 44] = [
+    "auto", "break", "case", "char", "const", "continue", "default", "do", "double", "else", "enum", "extern",
+    "float", "for", "goto", "if", "inline", "int", "long", "register", "restrict", "return", "short", "signed",
+    "sizeof", "static", "struct", "switch", "typedef", "union", "unsigned", "void", "volatile", "while",
+    "_Alignas", "_Alignof", "_Atomic", "_Bool", "_Complex", "_Generic", "_Imaginary", "_Noreturn", "_Static_assert",
+    "_Thread_local",
+];
+
+/// Every C11 punctuator, longest spellings first.
+const C_PUNCTUATORS: [&str; 54] = [
+    "%:%:", "...", "<<=", ">>=", "->", "++", "--", "<<", ">>", "<=", ">=", "==", "!=", "&&", "||", "*=", "/=", "%=",
+    "+=", "-=", "&=", "^=", "|=", "##", "<:", ":>", "<%", "%>", "%:", "[", "]", "(", ")", "{", "}", ".", "&", "*",
+    "+", "-", "~", "!", "/", "%", "<", ">", "^", "|", "?", ":", ";", "=", ",", "#",
+];
+
+/// The broad class of a token, the level at which the lexer and the reference are compared.
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Hash)]
+pub enum TokenClass {
+    /// A reserved word, including type names.
+    Keyword,
+    /// An identifier.
+    Identifier,
+    /// A number.
+    Number,
+    /// A string literal.
+    String,
+    /// A character literal.
+    Char,
+    /// An operator or punctuator.
+    Punctuator,
+    /// A preprocessor line.
+    Directive,
+    /// Source that is not a valid token, reported by the lexer as an error.
+    Invalid,
+}
+
+/// A token reduced to its class and span.
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Hash)]
+pub struct ClassifiedToken {
+    pub class: TokenClass,
+    pub span: Span,
+}
+
+/// A stretch of source where the lexer and the reference disagree, either on where tokens start and end or on
+/// what they are.
+///
+/// # Fields
+/// * `expected` - The reference's tokens over the stretch.
+/// * `actual` - The lexer's tokens over the stretch. Lex errors appear as `TokenClass::Invalid`.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Divergence {
+    pub expected: Vec<ClassifiedToken>,
+    pub actual: Vec<ClassifiedToken>,
+}
+
+impl Divergence {
+    /// Gives the stretch of source the divergence covers.
+    pub fn span(&self) -> Span {
+        let tokens = || self.expected.iter().chain(&self.actual);
+        let start = tokens().map(|token| token.span.start).min().unwrap_or(0);
+        let end = tokens().map(|token| token.span.end).max().unwrap_or(0);
+        Span::new(start, end)
+    }
+}
+
+/// Lexes source code with both the lexer, using the default settings, and the reference tokenizer, and compares
+/// the results.
+///
+/// # Returns
+/// * `Vec<Divergence>` - Every stretch where the two disagree, in source order. Empty if they agree.
+pub fn compare_with_reference(input: &str) -> Vec<Divergence> {
+    diverge(&reference_tokens(input), &lexer_tokens(input))
+}
+
+/// Compares every C source file under a directory against the reference, recursively.
+///
+/// Files are found as `driver::discover_files` finds them with the default settings, so hidden files and
+/// directories and files without a `.c` or `.h` extension are skipped. Files that cannot be read as UTF-8 are
+/// skipped too, since neither tokenizer can take them.
+///
+/// # Returns
+/// * `Ok(Vec<(PathBuf, Vec<Divergence>)>)` - The divergences of every file that has any, in path order.
+/// * `Err(ErrorType)` - `ErrorType::IoError` if a directory cannot be read.
+pub fn compare_corpus(dir: &Path) -> Result<Vec<(PathBuf, Vec<Divergence>)>, ErrorType> {
+    let mut results: Vec<(PathBuf, Vec<Divergence>)> = Vec::new();
+    for path in discover_files(dir, &DriverConfig::default())? {
+        let Ok(source) = fs::read_to_string(&path) else {
+            continue;
+        };
+        let divergences = compare_with_reference(&source);
+        if !divergences.is_empty() {
+            results.push((path, divergences));
+        }
+    }
+    Ok(results)
+}
+
+/// Tokenizes source code by the C11 rules, skipping whitespace and comments.
+///
+/// # Returns
+/// * `Vec<ClassifiedToken>` - Every token in source order. A character that cannot start a token is
+///   `TokenClass::Invalid`, as is an unterminated literal, which runs to the end of its line.
+pub fn reference_tokens(input: &str) -> Vec<ClassifiedToken> {
+    let bytes = input.as_bytes();
+    let mut tokens: Vec<ClassifiedToken> = Vec::new();
+    let mut position = 0;
+    let mut line_start = true;
+
+    while position < bytes.len() {
+        let rest = &input[position..];
+        let c = bytes[position];
+        if c == b'\n' {
+            line_start = true;
+            position += 1;
+            continue;
+        }
+        if c.is_ascii_whitespace() {
+            position += 1;
+            continue;
+        }
+        if rest.starts_with("//") {
+            position += rest.find('\n').unwrap_or(rest.len());
+            continue;
+        }
+        if let Some(comment) = rest.strip_prefix("/*") {
+            position += comment.find("*/").map_or(rest.len(), |end| end + 4);
+            continue;
+        }
+
+        let (class, len) = if c == b'#' && line_start {
+            (TokenClass::Directive, directive_len(rest))
+        } else {
+            reference_token(rest)
+        };
+        line_start = false;
+        tokens.push(ClassifiedToken { class, span: Span::new(position, position + len) });
+        position += len;
+    }
+    tokens
+}
+
+/// Lexes source code with the default settings, keeping errors in place as invalid tokens
+fn lexer_tokens(input: &str) -> Vec<ClassifiedToken> {
+    let (tokens, errors) = Lexer::lex_recovering(input, &LexerConfig::default());
+    let mut classified: Vec<ClassifiedToken> = tokens
+        .iter()
+        .filter(|spanned| spanned.token != Token::EOF)
+        .map(|spanned| ClassifiedToken { class: class_of(&spanned.token), span: spanned.span })
+        .chain(errors.iter().map(|error| ClassifiedToken { class: TokenClass::Invalid, span: error.span() }))
+        .collect();
+    classified.sort_by_key(|token| (token.span.start, token.span.end));
+    classified
+}
+
+/// Gives the class of one of the lexer's tokens
+fn class_of(token: &Token) -> TokenClass {
+    match token {
+        Token::IDENTIFIER(_) | Token::TYPENAME(_) => TokenClass::Identifier,
+        Token::NUMBER(_) => TokenClass::Number,
+        Token::STRINGLITERAL(_) => TokenClass::String,
+        Token::CHAR(_) => TokenClass::Char,
+        Token::HASHLINE(_) => TokenClass::Directive,
+        token => match token.category() {
+            TokenCategory::Keyword | TokenCategory::Type => TokenClass::Keyword,
+            TokenCategory::Operator | TokenCategory::Punctuation => TokenClass::Punctuator,
+            _ => TokenClass::Invalid,
+        },
+    }
+}
+
+/// Tokenizes the token at the start of `rest`, which is not whitespace, a comment, or a preprocessor line
+fn reference_token(rest: &str) -> (TokenClass, usize) {
+    let bytes = rest.as_bytes();
+    let c = bytes[0];
+
+    let prefixed = ["u8", "u", "U", "L"].into_iter().find_map(|prefix| {
+        rest.strip_prefix(prefix).filter(|quoted| quoted.starts_with(['"', '\'']))
+    });
+    if let Some(quoted) = prefixed {
+        let (class, len) = quoted_len(quoted);
+        return (class, rest.len() - quoted.len() + len);
+    }
+    if c == b'"' || c == b'\'' {
+        return quoted_len(rest);
+    }
+    if c.is_ascii_alphabetic() || c == b'_' {
+        let len = bytes.iter().position(|b| !b.is_ascii_alphanumeric() && *b != b'_').unwrap_or(bytes.len());
+        let class = if C_KEYWORDS.contains(&&rest[..len]) { TokenClass::Keyword } else { TokenClass::Identifier };
+        return (class, len);
+    }
+    if c.is_ascii_digit() || (c == b'.' && bytes.get(1).is_some_and(u8::is_ascii_digit)) {
+        return (TokenClass::Number, pp_number_len(bytes));
+    }
+    if let Some(punctuator) = C_PUNCTUATORS.iter().find(|punctuator| rest.starts_with(*punctuator)) {
+        return (TokenClass::Punctuator, punctuator.len());
+    }
+    (TokenClass::Invalid, rest.chars().next().map_or(1, char::len_utf8))
+}
+
+/// Gives the length of a preprocessing number: a digit, optionally after a `.`, followed by letters, digits, `_`,
+/// `.`, and signs directly after an exponent letter
+fn pp_number_len(bytes: &[u8]) -> usize {
+    let mut len = 1;
+    while let Some(&b) = bytes.get(len) {
+        let sign = matches!(b, b'+' | b'-') && matches!(bytes[len - 1], b'e' | b'E' | b'p' | b'P');
+        if !(sign || b.is_ascii_alphanumeric() || b == b'_' || b == b'.') {
+            break;
+        }
+        len += 1;
+    }
+    len
+}
+
+/// Gives the class and length of a string or character literal starting with its quote. A literal left open at the
+/// end of its line is invalid.
+fn quoted_len(rest: &str) -> (TokenClass, usize) {
+    let bytes = rest.as_bytes();
+    let quote = bytes[0];
+    let class = if quote == b'"' { TokenClass::String } else { TokenClass::Char };
+    let mut len = 1;
+    while let Some(&b) = bytes.get(len) {
+        match b {
+            b'\\' if bytes.get(len + 1).is_some_and(|next| *next != b'\n') => len += 2,
+            b'\n' => break,
+            b if b == quote => return (class, len + 1),
+            _ => len += 1,
+        }
+    }
+    (TokenClass::Invalid, len.min(bytes.len()))
+}
+
+/// Gives the length of a preprocessor line, including lines joined to it by a backslash before the newline
+fn directive_len(rest: &str) -> usize {
+    let mut len = 0;
+    for line in rest.split_inclusive('\n') {
+        let content = line.strip_suffix('\n').unwrap_or(line);
+        let content = content.strip_suffix('\r').unwrap_or(content);
+        if !content.ends_with('\\') || content.len() == line.len() {
+            return len + content.len();
+        }
+        len += line.len();
+    }
+    len
+}
+
+/// Pairs up two token lists, collecting every stretch where they disagree
+fn diverge(expected: &[ClassifiedToken], actual: &[ClassifiedToken]) -> Vec<Divergence> {
+    let mut divergences: Vec<Divergence> = Vec::new();
+    let (mut i, mut j) = (0, 0);
+
+    while i < expected.len() || j < actual.len() {
+        if i < expected.len() && j < actual.len() && expected[i] == actual[j] {
+            i += 1;
+            j += 1;
+            continue;
+        }
+
+        // Grow the stretch until neither side has a token starting inside it
+        let mut divergence = Divergence { expected: Vec::new(), actual: Vec::new() };
+        let mut end = 0;
+        loop {
+            let next_expected = expected.get(i).map(|token| token.span.start);
+            let next_actual = actual.get(j).map(|token| token.span.start);
+            let take_expected = match (next_expected, next_actual) {
+                (Some(e), Some(a)) => e <= a,
+                (Some(_), None) => true,
+                (None, _) => false,
+            };
+            let token = if take_expected { expected[i] } else { actual[j] };
+            if take_expected {
+                divergence.expected.push(token);
+                i += 1;
+            } else {
+                divergence.actual.push(token);
+                j += 1;
+            }
+            end = end.max(token.span.end);
+
+            let inside = |start: Option<usize>| start.is_some_and(|start| start < end);
+            if !inside(expected.get(i).map(|token| token.span.start))
+                && !inside(actual.get(j).map(|token| token.span.start))
+            {
+                break;
+            }
+        }
+        divergences.push(divergence);
+    }
+    divergences
+}
@@ -0,0 +1,472 @@
+//! This file defines how a lexer is configured and the builder used to configure one.
+//!
+//! A `LexerConfig` is built once and can then be used for any number of lexes. `LexerConfig::default()` is the
+//! configuration `Lexer::lex` uses.
+
+use std::sync::Arc;
+
+use common::error::ErrorType;
+
+use crate::{
+    rule::{RegexRule, TokenRule},
+    tables::KeywordTable,
+    token::OPERATORS,
+};
+
+/// The settings a lexer runs with.
+///
+/// # Fields
+/// * `rules` - Custom token rules, in the order they are tried.
+/// * `regex_rules` - Token kinds defined by regular expressions, in the order they were registered.
+/// * `split_angle_brackets` - Whether `>` is always lexed on its own rather than combined into `>=`.
+/// * `keywords` - The keywords, the contextual keywords: words that lex as identifiers but are flagged as keywords a
+///   parser may recognize in some positions, and any operator aliases. `None` shares `KeywordTable::standard`.
+/// * `identifier_start` - Characters besides ASCII letters and `_` that may start an identifier.
+/// * `identifier_continue` - Characters besides ASCII letters, digits, and `_` that may continue an identifier.
+///   Always includes every character in `identifier_start`.
+/// * `max_comment_depth` - How deeply block comments may nest, or `None` for `DEFAULT_MAX_DEPTH`.
+/// * `max_bracket_depth` - How deeply brackets may nest, or `None` for `DEFAULT_MAX_DEPTH`.
+/// * `nested_comments` - Whether a `/*` inside a block comment opens a nested comment, rather than being ignored
+///   as standard C does.
+/// * `whitespace_lints` - Whether to warn about trailing whitespace, mixed indentation, and a missing final
+///   newline while skipping whitespace.
+/// * `final_newline_lint` - Whether to warn about a missing final newline, or `None` to follow `whitespace_lints`.
+/// * `omit_eof` - Whether the token lists the `lex` functions collect leave out the final `Token::EOF`.
+/// * `nul_ends_input` - Whether the input ends at its first NUL character, with anything after it reported as
+///   garbage.
+/// * `control_chars` - What to do with control characters outside comments and literals.
+/// * `leading_zeros` - How a number written with a leading zero, such as `0123`, is checked.
+/// * `max_number_digits` - How many digits a number may have, or `None` for no limit.
+/// * `reject_repeated_zeros` - Whether a number may start with more than one zero, as in `007`.
+/// * `multiline_strings` - Whether a string literal may run over a newline, rather than being ended by it with an
+///   error.
+/// * `soft_fail` - Whether text the lexer does not support becomes a `Token::UNKNOWN` and a warning rather than an
+///   error.
+/// * `max_token_length` - How many characters an identifier, number, string literal, preprocessor line, or custom
+///   token may have, or `None` for no limit.
+/// * `long_tokens` - What to do with a token longer than `max_token_length`.
+#[derive(Clone, Default)]
+pub struct LexerConfig {
+    pub(crate) rules: Vec<Arc<dyn TokenRule>>,
+    pub(crate) regex_rules: Vec<RegexRule>,
+    pub(crate) split_angle_brackets: bool,
+    pub(crate) keywords: Option<Arc<KeywordTable>>,
+    pub(crate) identifier_start: Vec<char>,
+    pub(crate) identifier_continue: Vec<char>,
+    pub(crate) max_comment_depth: Option<usize>,
+    pub(crate) max_bracket_depth: Option<usize>,
+    pub(crate) nested_comments: bool,
+    pub(crate) whitespace_lints: bool,
+    pub(crate) final_newline_lint: Option<bool>,
+    pub(crate) omit_eof: bool,
+    pub(crate) nul_ends_input: bool,
+    pub(crate) control_chars: ControlCharPolicy,
+    pub(crate) leading_zeros: LeadingZeros,
+    pub(crate) max_number_digits: Option<usize>,
+    pub(crate) reject_repeated_zeros: bool,
+    pub(crate) multiline_strings: bool,
+    pub(crate) soft_fail: bool,
+    pub(crate) max_token_length: Option<usize>,
+    pub(crate) long_tokens: LongTokenPolicy,
+}
+
+/// What the lexer does with a C0 control character, U+0000 to U+001F other than whitespace such as tab and newline,
+/// found outside a comment or literal. A run of consecutive control characters is handled as one.
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub enum ControlCharPolicy {
+    /// Report the run as an `ErrorType::ControlCharacter`.
+    #[default]
+    Error,
+    /// Skip the run like whitespace, reporting it as a `control-character` warning.
+    Warn,
+    /// Skip the run like whitespace.
+    Skip,
+}
+
+/// How the lexer checks a number of more than one digit that starts with `0`, such as `0123`, which C reads as
+/// octal but readers of other languages often take for decimal.
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub enum LeadingZeros {
+    /// Lex the number as written, without checking it.
+    #[default]
+    Allow,
+    /// Read the number as octal, as C does, reporting a digit `8` or `9` as an `ErrorType::InvalidLiteral`.
+    Octal,
+    /// Read the number as decimal, reporting it as a `leading-zero` warning since C would read it as octal.
+    DecimalWithWarning,
+}
+
+/// What the lexer does with an identifier, number, string literal, preprocessor line, or custom token longer than
+/// the limit set with `LexerBuilder::max_token_length`.
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub enum LongTokenPolicy {
+    /// Report the token as an `ErrorType::TokenTooLong`, skipping all of it.
+    #[default]
+    Error,
+    /// Keep the token with its characters cut to the limit, reporting it as a `token-too-long` warning. Its span
+    /// still covers all of it.
+    Truncate,
+}
+
+/// The deepest nesting of block comments or brackets allowed unless a `LexerBuilder` sets another limit.
+pub const DEFAULT_MAX_DEPTH: usize = 256;
+
+impl LexerConfig {
+    /// Returns true if block comments nest.
+    pub fn nested_comments(&self) -> bool {
+        self.nested_comments
+    }
+
+    /// Gives what the lexer does with control characters.
+    pub fn control_chars(&self) -> ControlCharPolicy {
+        self.control_chars
+    }
+
+    /// Gives how numbers with a leading zero are checked.
+    pub fn leading_zeros(&self) -> LeadingZeros {
+        self.leading_zeros
+    }
+
+    /// Returns true if the token lists the `lex` functions collect end with `Token::EOF`.
+    pub fn emits_eof(&self) -> bool {
+        !self.omit_eof
+    }
+
+    /// Returns true if a missing final newline is reported.
+    pub fn reports_missing_final_newline(&self) -> bool {
+        self.final_newline_lint.unwrap_or(self.whitespace_lints)
+    }
+
+    /// Gives the keyword table the lexer looks words up in, shared with every clone of the configuration.
+    pub fn keyword_table(&self) -> &KeywordTable {
+        self.keywords.as_deref().unwrap_or_else(|| KeywordTable::standard())
+    }
+
+    /// Gives how deeply block comments may nest.
+    pub fn max_comment_depth(&self) -> usize {
+        self.max_comment_depth.unwrap_or(DEFAULT_MAX_DEPTH)
+    }
+
+    /// Gives how many characters a token may have, if that is limited.
+    pub fn max_token_length(&self) -> Option<usize> {
+        self.max_token_length
+    }
+
+    /// Gives how deeply `(`, `[`, and `{` may nest.
+    pub fn max_bracket_depth(&self) -> usize {
+        self.max_bracket_depth.unwrap_or(DEFAULT_MAX_DEPTH)
+    }
+
+    /// Checks whether a character may start an identifier.
+    pub(crate) fn is_identifier_start(&self, c: char) -> bool {
+        c.is_ascii_alphabetic() || c == '_' || self.identifier_start.contains(&c)
+    }
+
+    /// Checks whether a character may continue an identifier.
+    pub(crate) fn is_identifier_continue(&self, c: char) -> bool {
+        c.is_ascii_alphanumeric() || c == '_' || self.identifier_continue.contains(&c)
+    }
+}
+
+/// Builds a `LexerConfig`.
+///
+/// # Examples
+///
+/// ```
+/// use lexer::{config::LexerBuilder, core::Lexer};
+/// let config = LexerBuilder::new().build();
+/// let tokens = Lexer::lex_with_config("int x;", &config);
+/// ```
+#[derive(Default)]
+pub struct LexerBuilder {
+    rules: Vec<(i32, Arc<dyn TokenRule>)>,
+    regex_rules: Vec<RegexRule>,
+    split_angle_brackets: bool,
+    contextual_keywords: Vec<String>,
+    operator_aliases: bool,
+    identifier_start: Vec<char>,
+    identifier_continue: Vec<char>,
+    max_comment_depth: Option<usize>,
+    max_bracket_depth: Option<usize>,
+    nested_comments: bool,
+    whitespace_lints: bool,
+    final_newline_lint: Option<bool>,
+    omit_eof: bool,
+    nul_ends_input: bool,
+    control_chars: ControlCharPolicy,
+    leading_zeros: LeadingZeros,
+    max_number_digits: Option<usize>,
+    reject_repeated_zeros: bool,
+    multiline_strings: bool,
+    soft_fail: bool,
+    max_token_length: Option<usize>,
+    long_tokens: LongTokenPolicy,
+}
+
+impl LexerBuilder {
+    /// Creates a builder for the default configuration.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a custom token rule.
+    ///
+    /// Custom rules are tried before the built-in rules, from the highest priority to the lowest. Rules with the
+    /// same priority are tried in the order they were registered.
+    pub fn rule(mut self, priority: i32, rule: impl TokenRule + 'static) -> Self {
+        self.rules.push((priority, Arc::new(rule)));
+        self
+    }
+
+    /// Defines a token kind by a regular expression.
+    ///
+    /// Matching text becomes `Token::CUSTOM(kind, lexeme)`. Regex tokens compete with the built-in rules by
+    /// longest match: a regex token is produced only when it is strictly longer than the built-in token at the
+    /// same position, so keywords and operators keep their meaning when a pattern would match them exactly. When
+    /// several patterns match, the longest wins, and among equally long matches the first registered wins.
+    ///
+    /// # Errors
+    /// * `ErrorType::InvalidTokenPattern` if `pattern` is not a valid regular expression.
+    pub fn token_regex(mut self, kind: &str, pattern: &str) -> Result<Self, ErrorType> {
+        self.regex_rules.push(RegexRule::new(kind, pattern)?);
+        Ok(self)
+    }
+
+    /// Lexes `>` on its own even when it is followed by `=`, for languages where `>` can close an angle-bracketed
+    /// list, as in `List<int>=x`. Parsers that only sometimes need this can instead split a token on demand with
+    /// `Token::split_greater`.
+    pub fn split_angle_brackets(mut self, split: bool) -> Self {
+        self.split_angle_brackets = split;
+        self
+    }
+
+    /// Registers a contextual keyword: a word that still lexes as `Token::IDENTIFIER`, so existing code using it as
+    /// a name keeps working, but whose tokens have `SpannedToken::contextual_keyword` set so a parser can treat it
+    /// as a keyword where one is expected.
+    pub fn contextual_keyword(mut self, word: &str) -> Self {
+        if !self.contextual_keywords.iter().any(|existing| existing == word) {
+            self.contextual_keywords.push(word.to_string());
+        }
+        self
+    }
+
+    /// Allows each of `chars` to start and continue identifiers, as `$` does in JavaScript-like dialects.
+    ///
+    /// # Errors
+    /// * `ErrorType::InvalidIdentifierCharacter` if a character is whitespace or a digit, or begins an operator,
+    ///   punctuation, a literal, a comment, or a preprocessor line.
+    pub fn identifier_start(mut self, chars: &str) -> Result<Self, ErrorType> {
+        for c in chars.chars() {
+            if c.is_ascii_digit() {
+                return Err(invalid_identifier_char(c, "digits start numbers"));
+            }
+            if matches!(c, '"' | '\'' | '#') {
+                return Err(invalid_identifier_char(c, "it starts a literal or a preprocessor line"));
+            }
+            check_identifier_char(c)?;
+            push_new(&mut self.identifier_start, c);
+            push_new(&mut self.identifier_continue, c);
+        }
+        Ok(self)
+    }
+
+    /// Allows each of `chars` to continue identifiers, though not to start them, as `'` does for primes in
+    /// ML-like dialects.
+    ///
+    /// # Errors
+    /// * `ErrorType::InvalidIdentifierCharacter` if a character is whitespace, or begins an operator,
+    ///   punctuation, or a comment.
+    pub fn identifier_continue(mut self, chars: &str) -> Result<Self, ErrorType> {
+        for c in chars.chars() {
+            check_identifier_char(c)?;
+            push_new(&mut self.identifier_continue, c);
+        }
+        Ok(self)
+    }
+
+    /// Makes a `/*` inside a block comment open a nested comment that needs its own `*/`, as in languages such as
+    /// Swift and Rust, so that `/* /* */ x */` is one comment. Standard C does not nest comments, so by default the
+    /// first `*/` ends the comment and `x */` is lexed.
+    pub fn nested_comments(mut self, enabled: bool) -> Self {
+        self.nested_comments = enabled;
+        self
+    }
+
+    /// Limits how deeply block comments may nest when `nested_comments` is on, `DEFAULT_MAX_DEPTH` unless set. A
+    /// comment opened beyond the limit is reported as `ErrorType::NestingTooDeep` and the rest of the outermost
+    /// comment is still skipped.
+    pub fn max_comment_depth(mut self, depth: usize) -> Self {
+        self.max_comment_depth = Some(depth);
+        self
+    }
+
+    /// Limits how deeply `(`, `[`, and `{` may nest, `DEFAULT_MAX_DEPTH` unless set, so that recursive consumers of
+    /// the tokens cannot be driven into a stack overflow. A bracket opened beyond the limit is reported as
+    /// `ErrorType::NestingTooDeep` instead of being returned as a token.
+    pub fn max_bracket_depth(mut self, depth: usize) -> Self {
+        self.max_bracket_depth = Some(depth);
+        self
+    }
+
+    /// Warns about whitespace problems as it is skipped, without an extra pass over the source: trailing
+    /// whitespace, including at the end of a line comment, as `trailing-whitespace`; indentation with a tab after a
+    /// space as `mixed-indentation`; and a last line without a newline as `missing-final-newline`. The warnings are
+    /// returned by `Lexer::lex_with_diagnostics` and `Lexer::take_diagnostics`.
+    pub fn whitespace_lints(mut self, enabled: bool) -> Self {
+        self.whitespace_lints = enabled;
+        self
+    }
+
+    /// Turns the `missing-final-newline` warning on or off on its own, whether or not the other whitespace lints
+    /// are on. Unless set, it is reported exactly when `whitespace_lints` is on.
+    pub fn final_newline_lint(mut self, enabled: bool) -> Self {
+        self.final_newline_lint = Some(enabled);
+        self
+    }
+
+    /// Chooses whether the token lists collected by `Lexer::lex` and the other `lex` functions end with
+    /// `Token::EOF`, as they do by default. `Lexer::next_token` still returns `Token::EOF` at the end of the input,
+    /// since that is how a caller driving it learns the input is done.
+    pub fn emit_eof(mut self, enabled: bool) -> Self {
+        self.omit_eof = !enabled;
+        self
+    }
+
+    /// Makes the input end at its first NUL character, as it does for C strings and zero-padded buffers. `EOF` is
+    /// placed at the NUL, and anything other than more NULs after it is reported as a `trailing-garbage` warning
+    /// by `Lexer::lex_with_diagnostics` and `Lexer::take_diagnostics`. By default a NUL is handled like any other
+    /// control character, as `control_chars` chooses.
+    pub fn nul_ends_input(mut self, enabled: bool) -> Self {
+        self.nul_ends_input = enabled;
+        self
+    }
+
+    /// Chooses what to do with control characters outside comments and literals, `ControlCharPolicy::Error` unless
+    /// set. Warnings are returned by `Lexer::lex_with_diagnostics` and `Lexer::take_diagnostics`.
+    pub fn control_chars(mut self, policy: ControlCharPolicy) -> Self {
+        self.control_chars = policy;
+        self
+    }
+
+    /// Chooses how numbers with a leading zero, such as `0123`, are checked, `LeadingZeros::Allow` unless set.
+    /// Warnings are returned by `Lexer::lex_with_diagnostics` and `Lexer::take_diagnostics`.
+    pub fn leading_zeros(mut self, policy: LeadingZeros) -> Self {
+        self.leading_zeros = policy;
+        self
+    }
+
+    /// Limits how many digits a number may have, counting leading zeros. A longer number is reported as an
+    /// `ErrorType::InvalidLiteral`, for targets whose literals must fit a fixed width.
+    pub fn max_number_digits(mut self, digits: usize) -> Self {
+        self.max_number_digits = Some(digits);
+        self
+    }
+
+    /// Reports a number starting with more than one zero, such as `00` or `007`, as an `ErrorType::InvalidLiteral`.
+    pub fn reject_repeated_zeros(mut self, enabled: bool) -> Self {
+        self.reject_repeated_zeros = enabled;
+        self
+    }
+
+    /// Lets string literals run over newlines, keeping each newline in the string, as dialects with multiline
+    /// strings do. By default a newline before the closing quote ends the string with an error, as in C.
+    /// Character literals are always ended by a newline.
+    pub fn multiline_strings(mut self, enabled: bool) -> Self {
+        self.multiline_strings = enabled;
+        self
+    }
+
+    /// Lexes the C++ alternative operator spellings, such as `and`, `or`, and `not`, to the same tokens as `&&`,
+    /// `||`, and `!`. The spelling a token was written with stays in the source its span covers. Off by default, as
+    /// in C, where these words are identifiers.
+    pub fn operator_aliases(mut self, enabled: bool) -> Self {
+        self.operator_aliases = enabled;
+        self
+    }
+
+    /// Turns on soft-fail mode, for running the lexer across real codebases to find what it does not support yet.
+    /// Text it cannot lex, a character no rule accepts or a literal it cannot read, becomes a `Token::UNKNOWN`
+    /// holding the text, and is reported as an `unknown-token` warning by `Lexer::lex_with_diagnostics` and
+    /// `Lexer::take_diagnostics` instead of as an error. Errors in constructs the lexer does support, such as an
+    /// unterminated string or brackets nested too deeply, are still errors.
+    pub fn soft_fail(mut self, enabled: bool) -> Self {
+        self.soft_fail = enabled;
+        self
+    }
+
+    /// Limits how many characters an identifier, number, string literal, preprocessor line, or custom token may
+    /// have, counting its quotes or other delimiters, so that services lexing untrusted input can bound the memory
+    /// each token takes. A longer token is handled as `long_tokens` chooses, by default reported as an
+    /// `ErrorType::TokenTooLong`. Either way, no token carries more than `length` characters.
+    pub fn max_token_length(mut self, length: usize) -> Self {
+        self.max_token_length = Some(length);
+        self
+    }
+
+    /// Chooses what to do with a token longer than `max_token_length` allows, `LongTokenPolicy::Error` unless set.
+    /// Warnings are returned by `Lexer::lex_with_diagnostics` and `Lexer::take_diagnostics`.
+    pub fn long_tokens(mut self, policy: LongTokenPolicy) -> Self {
+        self.long_tokens = policy;
+        self
+    }
+
+    /// Registers the keywords C99 added that older code may still use as names, `inline` and `restrict`, as
+    /// contextual keywords.
+    pub fn c99_contextual_keywords(self) -> Self {
+        self.contextual_keyword("inline").contextual_keyword("restrict")
+    }
+
+    /// Finishes building the configuration.
+    pub fn build(mut self) -> LexerConfig {
+        self.rules.sort_by_key(|(priority, _)| std::cmp::Reverse(*priority));
+        LexerConfig {
+            rules: self.rules.into_iter().map(|(_, rule)| rule).collect(),
+            regex_rules: self.regex_rules,
+            split_angle_brackets: self.split_angle_brackets,
+            keywords: (!self.contextual_keywords.is_empty() || self.operator_aliases).then(|| {
+                let table = KeywordTable::new(&self.contextual_keywords);
+                Arc::new(if self.operator_aliases { table.with_operator_aliases() } else { table })
+            }),
+            identifier_start: self.identifier_start,
+            identifier_continue: self.identifier_continue,
+            max_comment_depth: self.max_comment_depth,
+            max_bracket_depth: self.max_bracket_depth,
+            nested_comments: self.nested_comments,
+            whitespace_lints: self.whitespace_lints,
+            final_newline_lint: self.final_newline_lint,
+            omit_eof: self.omit_eof,
+            nul_ends_input: self.nul_ends_input,
+            control_chars: self.control_chars,
+            leading_zeros: self.leading_zeros,
+            max_number_digits: self.max_number_digits,
+            reject_repeated_zeros: self.reject_repeated_zeros,
+            multiline_strings: self.multiline_strings,
+            soft_fail: self.soft_fail,
+            max_token_length: self.max_token_length,
+            long_tokens: self.long_tokens,
+        }
+    }
+}
+
+/// Rejects a character for identifiers if it would change how existing tokens lex
+fn check_identifier_char(c: char) -> Result<(), ErrorType> {
+    if c.is_whitespace() {
+        return Err(invalid_identifier_char(c, "whitespace separates tokens"));
+    }
+    if let Some((spelling, _)) = OPERATORS.iter().find(|(spelling, _)| spelling.starts_with(c)) {
+        return Err(invalid_identifier_char(c, &format!("it starts the operator `{}`", spelling)));
+    }
+    Ok(())
+}
+
+/// Helper function to create an invalid identifier character error
+fn invalid_identifier_char(c: char, reason: &str) -> ErrorType {
+    ErrorType::InvalidIdentifierCharacter { character: c, reason: reason.to_string() }
+}
+
+/// Adds a character to a set kept as a vector, unless it is already there
+fn push_new(chars: &mut Vec<char>, c: char) {
+    if !chars.contains(&c) {
+        chars.push(c);
+    }
+}
@@ -0,0 +1,133 @@
+//! This file detects characters that look like ASCII but are not, and characters that are invisible, both of
+//! which can make source code read differently than it compiles.
+//!
+//! Invisible characters are looked for in the whole source, string literals and comments included, since a
+//! directional override in a comment can reorder the code shown around it. Lookalikes are only looked for in
+//! identifiers, which can only hold them when Unicode identifier characters are enabled, and only in identifiers
+//! that could pass for ASCII, as rustc does: text written in another script is not reported.
+
+use crate::{
+    diagnostic::Diagnostic,
+    span::{Span, SpannedToken},
+    token::Token,
+};
+
+/// Non-ASCII characters that are commonly mistaken for ASCII ones, with the character they imitate and their
+/// Unicode name.
+const CONFUSABLES: [(char, char, &str); 30] = [
+    ('\u{0430}', 'a', "CYRILLIC SMALL LETTER A"),
+    ('\u{0435}', 'e', "CYRILLIC SMALL LETTER IE"),
+    ('\u{043E}', 'o', "CYRILLIC SMALL LETTER O"),
+    ('\u{0440}', 'p', "CYRILLIC SMALL LETTER ER"),
+    ('\u{0441}', 'c', "CYRILLIC SMALL LETTER ES"),
+    ('\u{0443}', 'y', "CYRILLIC SMALL LETTER U"),
+    ('\u{0445}', 'x', "CYRILLIC SMALL LETTER HA"),
+    ('\u{0456}', 'i', "CYRILLIC SMALL LETTER BYELORUSSIAN-UKRAINIAN I"),
+    ('\u{0458}', 'j', "CYRILLIC SMALL LETTER JE"),
+    ('\u{0455}', 's', "CYRILLIC SMALL LETTER DZE"),
+    ('\u{0410}', 'A', "CYRILLIC CAPITAL LETTER A"),
+    ('\u{0412}', 'B', "CYRILLIC CAPITAL LETTER VE"),
+    ('\u{0415}', 'E', "CYRILLIC CAPITAL LETTER IE"),
+    ('\u{041A}', 'K', "CYRILLIC CAPITAL LETTER KA"),
+    ('\u{041C}', 'M', "CYRILLIC CAPITAL LETTER EM"),
+    ('\u{041D}', 'H', "CYRILLIC CAPITAL LETTER EN"),
+    ('\u{041E}', 'O', "CYRILLIC CAPITAL LETTER O"),
+    ('\u{0420}', 'P', "CYRILLIC CAPITAL LETTER ER"),
+    ('\u{0421}', 'C', "CYRILLIC CAPITAL LETTER ES"),
+    ('\u{0422}', 'T', "CYRILLIC CAPITAL LETTER TE"),
+    ('\u{0425}', 'X', "CYRILLIC CAPITAL LETTER HA"),
+    ('\u{03BF}', 'o', "GREEK SMALL LETTER OMICRON"),
+    ('\u{03B1}', 'a', "GREEK SMALL LETTER ALPHA"),
+    ('\u{0391}', 'A', "GREEK CAPITAL LETTER ALPHA"),
+    ('\u{0392}', 'B', "GREEK CAPITAL LETTER BETA"),
+    ('\u{0395}', 'E', "GREEK CAPITAL LETTER EPSILON"),
+    ('\u{039F}', 'O', "GREEK CAPITAL LETTER OMICRON"),
+    ('\u{FF1B}', ';', "FULLWIDTH SEMICOLON"),
+    ('\u{037E}', ';', "GREEK QUESTION MARK"),
+    ('\u{2212}', '-', "MINUS SIGN"),
+];
+
+/// Characters that render as nothing (or reorder surrounding text) but still change what the compiler sees.
+const INVISIBLES: [(char, &str); 15] = [
+    ('\u{200B}', "ZERO WIDTH SPACE"),
+    ('\u{200C}', "ZERO WIDTH NON-JOINER"),
+    ('\u{200D}', "ZERO WIDTH JOINER"),
+    ('\u{2060}', "WORD JOINER"),
+    ('\u{FEFF}', "ZERO WIDTH NO-BREAK SPACE"),
+    ('\u{200E}', "LEFT-TO-RIGHT MARK"),
+    ('\u{200F}', "RIGHT-TO-LEFT MARK"),
+    ('\u{202A}', "LEFT-TO-RIGHT EMBEDDING"),
+    ('\u{202B}', "RIGHT-TO-LEFT EMBEDDING"),
+    ('\u{202C}', "POP DIRECTIONAL FORMATTING"),
+    ('\u{202D}', "LEFT-TO-RIGHT OVERRIDE"),
+    ('\u{202E}', "RIGHT-TO-LEFT OVERRIDE"),
+    ('\u{2066}', "LEFT-TO-RIGHT ISOLATE"),
+    ('\u{2067}', "RIGHT-TO-LEFT ISOLATE"),
+    ('\u{2069}', "POP DIRECTIONAL ISOLATE"),
+];
+
+/// Scans source code for invisible characters, and its identifiers for confusable ones.
+///
+/// A byte order mark at the very start of the input is allowed, since editors commonly write one. A confusable
+/// character is reported when its identifier also holds ASCII letters, or when every non-ASCII character of the
+/// identifier is confusable, so that the identifier reads as ASCII.
+///
+/// # Parameters
+/// * `input` - The source code to check.
+/// * `tokens` - The tokens lexed from `input`.
+///
+/// # Returns
+/// * `Vec<Diagnostic>` - A warning for every suspicious character, in source order.
+pub fn check_confusables(input: &str, tokens: &[SpannedToken]) -> Vec<Diagnostic> {
+    let mut diagnostics: Vec<Diagnostic> = Vec::new();
+
+    for (offset, c) in input.char_indices() {
+        let Some((_, name)) = INVISIBLES.iter().find(|(invisible, _)| *invisible == c) else {
+            continue;
+        };
+        if c == '\u{FEFF}' && offset == 0 {
+            continue;
+        }
+        diagnostics.push(Diagnostic::warning(
+            "invisible-character",
+            format!("invisible character U+{:04X} {} in source", c as u32, name),
+            Span::new(offset, offset + c.len_utf8()),
+        ));
+    }
+
+    for spanned in tokens.iter().filter(|spanned| matches!(spanned.token, Token::IDENTIFIER(_))) {
+        let Some(identifier) = input.get(spanned.span.start..spanned.span.end) else {
+            continue;
+        };
+        if !reads_as_ascii(identifier) {
+            continue;
+        }
+        for (offset, c) in identifier.char_indices() {
+            if let Some((_, lookalike, name)) = confusable(c) {
+                let start: usize = spanned.span.start + offset;
+                diagnostics.push(Diagnostic::warning(
+                    "confusable-character",
+                    format!("U+{:04X} {} looks like `{}` but is a different character", c as u32, name, lookalike),
+                    Span::new(start, start + c.len_utf8()),
+                ));
+            }
+        }
+    }
+    diagnostics.sort_by_key(|diagnostic| diagnostic.span.start);
+    diagnostics
+}
+
+/// Finds a character in the table of confusables
+fn confusable(c: char) -> Option<&'static (char, char, &'static str)> {
+    CONFUSABLES.iter().find(|(confusable, _, _)| *confusable == c)
+}
+
+/// Checks whether an identifier with confusable characters could pass for ASCII: it mixes them with ASCII letters,
+/// or all of its non-ASCII characters are confusable
+fn reads_as_ascii(identifier: &str) -> bool {
+    let mut non_ascii = identifier.chars().filter(|c| !c.is_ascii()).peekable();
+    if non_ascii.peek().is_none() {
+        return false;
+    }
+    identifier.chars().any(|c| c.is_ascii_alphabetic()) || non_ascii.all(|c| confusable(c).is_some())
+}
@@ -1,8 +1,29 @@
 //! This file drives the lexing process, which takes an input string and breaks it up into lexemes (tokens).
 
-use crate::token::Token;
+use std::{ops::Range, time::Instant};
+
+use crate::{
+    config::{ControlCharPolicy, LeadingZeros, LexerConfig, LongTokenPolicy},
+    diagnostic::Diagnostic,
+    encoding::{self, Encoding, Transcoded},
+    error::{self, LexError},
+    literal,
+    mode::LexMode,
+    output::LexOutput,
+    progress::{Progress, ProgressReporter},
+    rule::Cursor,
+    span::{Span, SpannedToken},
+    token::Token,
+    trace::{Decision, Rule, Trace, TraceStep},
+};
 use common::error::ErrorType;
 
+/// The message of the error reported for a string literal without a closing quote.
+pub(crate) const UNTERMINATED_STRING: &str = "unterminated string literal";
+
+/// The maximum number of characters the lexer may look ahead of the current one.
+const MAX_LOOKAHEAD: usize = 8;
+
 /// The `Lexer` struct models the process of lexical analysis.
 /// 
 /// At initialization, it takes a string input, a starting position, and the current character.
@@ -10,23 +31,98 @@ use common::error::ErrorType;
 /// # Fields
 /// * `input` - A vector of characters representing the source code to be lexed.
 /// * `position` - The current position within the input vector.
-/// * `current` - The current character being analyzed by the lexer.
+/// * `current` - The current character being analyzed by the lexer, or `None` at the end of the input.
+/// * `offsets` - The byte offset of each character in `input`, followed by the total length in bytes.
+/// * `token_start` - The position at which the token currently being lexed begins.
+/// * `modes` - The stack of modes the lexer is in. The lexer is in `LexMode::Normal` when the stack is empty.
+/// * `config` - The settings the lexer runs with.
+/// * `source` - The input as a string, kept only when regex rules need to match against it.
+/// * `trace` - The decisions made so far, recorded only when tracing is requested.
+/// * `keep_payloads` - Whether identifiers, numbers, and strings carry their characters. Callers that recover
+///   lexemes from spans turn this off to avoid allocating for every token.
+/// * `recovering` - Whether unterminated literals are closed with a synthetic token rather than failing.
+/// * `synthetic` - Whether the token most recently returned by `next_token` was synthesized during recovery.
+/// * `recovered_errors` - The errors that recovery has worked around so far, and those found inside comments.
+/// * `unclosed_comment` - Whether a block comment ran to the end of the input.
+/// * `bracket_depth` - How many `(`, `[`, and `{` are open.
+/// * `diagnostics` - The warnings reported while skipping whitespace and comments, when whitespace lints are on.
 pub struct Lexer {
     input: Vec<char>,
     position: usize,
-    current: char,
+    current: Option<char>,
+    offsets: Vec<usize>,
+    token_start: usize,
+    modes: Vec<LexMode>,
+    config: LexerConfig,
+    source: String,
+    trace: Option<Vec<TraceStep>>,
+    keep_payloads: bool,
+    recovering: bool,
+    synthetic: bool,
+    recovered_errors: Vec<LexError>,
+    unclosed_comment: bool,
+    bracket_depth: usize,
+    diagnostics: Vec<Diagnostic>,
 }
 
 impl Lexer {
+    /// Creates a lexer positioned at the start of `input`, for callers that drive it one token at a time with
+    /// `next_token`.
+    ///
+    /// Spans are byte offsets into `input`. To lex a snippet embedded in a larger buffer, pass the whole buffer
+    /// and `seek` to the snippet, and the spans will be relative to the buffer.
+    ///
+    /// # Parameters
+    /// * `input` - The source code to be lexed.
+    /// * `config` - The settings to lex with, as built by a `LexerBuilder`.
+    pub fn new(input: &str, config: LexerConfig) -> Self {
+        Lexer::from_chars(input.chars().collect(), config)
+    }
+
     /// Initializes the lexer. 
     /// 
     /// # Parameters
     /// * `input` - A vector of characters that represents the source code to be lexed. 
-    fn new(input: Vec<char>) -> Self {
+    /// * `config` - The settings to lex with.
+    fn from_chars(mut input: Vec<char>, config: LexerConfig) -> Self {
+        let mut offsets: Vec<usize> = Vec::with_capacity(input.len() + 1);
+        let mut offset: usize = 0;
+        for c in input.iter() {
+            offsets.push(offset);
+            offset += c.len_utf8();
+        }
+        offsets.push(offset);
+
+        let mut diagnostics: Vec<Diagnostic> = Vec::new();
+        if let Some(nul) = input.iter().position(|c| *c == '\0').filter(|_| config.nul_ends_input) {
+            if input[nul..].iter().any(|c| *c != '\0') {
+                let span: Span = Span::new(offsets[nul], offset);
+                let message: String = "content after a NUL character is ignored".to_string();
+                diagnostics.push(Diagnostic::warning("trailing-garbage", message, span));
+            }
+            input.truncate(nul);
+            offsets.truncate(nul + 1);
+        }
+        let source: String = if config.regex_rules.is_empty() { String::new() } else { input.iter().collect() };
+        let current: Option<char> = input.first().copied();
+
         Self {
             input,
             position: 0,
-            current: '@', // EOF token
+            current,
+            offsets,
+            token_start: 0,
+            source,
+            modes: Vec::new(),
+            config,
+            trace: None,
+            keep_payloads: true,
+            recovering: false,
+            synthetic: false,
+            recovered_errors: Vec::new(),
+            unclosed_comment: false,
+            bracket_depth: 0,
+            diagnostics,
         }
     }
 
@@ -76,43 +172,459 @@ impl Lexer {
     // }
 
     pub fn lex(input: &str) -> Result<Vec<Token>, Vec<ErrorType>> {
-        let mut lexer: Lexer = Lexer::new(input.chars().collect());
-        let mut errors: Vec<ErrorType> = Vec::new();
-        let mut tokens: Vec<Token> = Vec::new();
-        lexer.current = lexer.input[0];
+        let tokens: Vec<SpannedToken> = Lexer::lex_spanned(input)?;
+        Ok(tokens.into_iter().map(|spanned| spanned.token).collect())
+    }
+
+    /// Lexically analyzes the given input string, pairing every token with the span of source it came from.
+    ///
+    /// # Parameters
+    /// * `input` - A string slice representing the source code to be lexed.
+    ///
+    /// # Returns
+    /// * `Ok(Vec<SpannedToken>)` - The tokens and their byte spans if the input is lexed without errors.
+    /// * `Err(Vec<ErrorType>)` - A vector of error types if any issues occur during lexing.
+    pub fn lex_spanned(input: &str) -> Result<Vec<SpannedToken>, Vec<ErrorType>> {
+        Lexer::lex_spanned_with_config(input, &LexerConfig::default())
+    }
+
+    /// Lexically analyzes the given input string with a custom configuration.
+    ///
+    /// # Parameters
+    /// * `input` - A string slice representing the source code to be lexed.
+    /// * `config` - The settings to lex with, as built by a `LexerBuilder`.
+    ///
+    /// # Returns
+    /// * `Ok(Vec<Token>)` - A vector of tokens if the input is successfully lexed without errors.
+    /// * `Err(Vec<ErrorType>)` - A vector of error types if any issues occur during lexing.
+    pub fn lex_with_config(input: &str, config: &LexerConfig) -> Result<Vec<Token>, Vec<ErrorType>> {
+        let tokens: Vec<SpannedToken> = Lexer::lex_spanned_with_config(input, config)?;
+        Ok(tokens.into_iter().map(|spanned| spanned.token).collect())
+    }
+
+    /// Lexically analyzes the given input string with a custom configuration, pairing every token with its span.
+    ///
+    /// # Parameters
+    /// * `input` - A string slice representing the source code to be lexed.
+    /// * `config` - The settings to lex with, as built by a `LexerBuilder`.
+    ///
+    /// # Returns
+    /// * `Ok(Vec<SpannedToken>)` - The tokens and their byte spans if the input is lexed without errors.
+    /// * `Err(Vec<ErrorType>)` - A vector of error types if any issues occur during lexing.
+    pub fn lex_spanned_with_config(input: &str, config: &LexerConfig) -> Result<Vec<SpannedToken>, Vec<ErrorType>> {
+        Lexer::lex_located(input, config)
+            .map_err(|errors| errors.into_iter().map(LexError::into_error).collect())
+    }
+
+    /// Lexically analyzes the given input string with a custom configuration, pairing every token and every error
+    /// with its span.
+    ///
+    /// Errors are reported in source order. Errors with the same span keep the order in which they were found, so
+    /// the output is identical across runs and platforms.
+    ///
+    /// # Parameters
+    /// * `input` - A string slice representing the source code to be lexed.
+    /// * `config` - The settings to lex with, as built by a `LexerBuilder`.
+    ///
+    /// # Returns
+    /// * `Ok(Vec<SpannedToken>)` - The tokens and their byte spans if the input is lexed without errors.
+    /// * `Err(Vec<LexError>)` - Every error and the span of source it was found at.
+    pub fn lex_located(input: &str, config: &LexerConfig) -> Result<Vec<SpannedToken>, Vec<LexError>> {
+        Lexer::from_chars(input.chars().collect(), config.clone()).run()
+    }
+
+    /// Lexically analyzes the given input string while recording every decision the lexer makes, so that token
+    /// boundaries can be explained afterwards.
+    ///
+    /// # Parameters
+    /// * `input` - A string slice representing the source code to be lexed.
+    /// * `config` - The settings to lex with, as built by a `LexerBuilder`.
+    ///
+    /// # Returns
+    /// * `(Result<Vec<SpannedToken>, Vec<LexError>>, Trace)` - The result of lexing, as from `lex_located`, and
+    ///   the trace of how it was reached. The trace is returned whether or not lexing succeeds.
+    pub fn lex_traced(input: &str, config: &LexerConfig) -> (Result<Vec<SpannedToken>, Vec<LexError>>, Trace) {
+        let mut lexer: Lexer = Lexer::from_chars(input.chars().collect(), config.clone());
+        lexer.trace = Some(Vec::new());
+        let result = lexer.run();
+        (result, Trace { steps: lexer.trace.unwrap_or_default() })
+    }
+
+    /// Lexically analyzes the given input string without copying the characters of identifiers and numbers into
+    /// their tokens, leaving their payloads empty. The characters can be recovered from the spans.
+    pub(crate) fn lex_without_payloads(input: &str, config: &LexerConfig) -> Result<Vec<SpannedToken>, Vec<LexError>> {
+        let mut lexer: Lexer = Lexer::from_chars(input.chars().collect(), config.clone());
+        lexer.keep_payloads = false;
+        lexer.run()
+    }
+
+    /// Lexically analyzes the given input string, recovering from errors so that a parser always receives a complete
+    /// token stream.
+    ///
+    /// An unterminated string or character literal is closed at the end of its line, and its token is marked
+    /// `synthetic`. Unrecognized characters are left out of the stream. Every error is still reported, so the
+    /// parser can report the one precise error rather than a cascade of failures caused by it.
+    ///
+    /// # Parameters
+    /// * `input` - A string slice representing the source code to be lexed.
+    /// * `config` - The settings to lex with, as built by a `LexerBuilder`.
+    ///
+    /// # Returns
+    /// * `(Vec<SpannedToken>, Vec<LexError>)` - The tokens, ending with `Token::EOF` unless `LexerBuilder::emit_eof`
+    ///   turned it off, and every error in source order.
+    pub fn lex_recovering(input: &str, config: &LexerConfig) -> (Vec<SpannedToken>, Vec<LexError>) {
+        let mut lexer: Lexer = Lexer::from_chars(input.chars().collect(), config.clone());
+        lexer.recovering = true;
+        lexer.run_to_end()
+    }
+
+    /// Lexically analyzes the given input string as `lex_recovering` does, reporting progress as it goes, for inputs
+    /// large enough that a host should show a progress bar.
+    ///
+    /// # Parameters
+    /// * `input` - A string slice representing the source code to be lexed.
+    /// * `config` - The settings to lex with, as built by a `LexerBuilder`.
+    /// * `interval` - How many bytes to lex between reports. Progress is reported after the first token that
+    ///   crosses each multiple of `interval`, and once more when the input is done.
+    /// * `reporter` - What to report progress to.
+    ///
+    /// # Returns
+    /// * `(Vec<SpannedToken>, Vec<LexError>)` - The tokens and errors, as from `lex_recovering`.
+    ///
+    /// # Panics
+    /// Panics if `interval` is 0.
+    pub fn lex_with_progress(
+        input: &str,
+        config: &LexerConfig,
+        interval: usize,
+        reporter: &dyn ProgressReporter,
+    ) -> (Vec<SpannedToken>, Vec<LexError>) {
+        assert!(interval > 0, "the progress interval must be at least one byte");
+        let progress = |bytes_processed: usize| Progress {
+            bytes_processed,
+            bytes_total: input.len(),
+            files_completed: 0,
+            files_total: 0,
+            current_file: None,
+        };
+        let mut lexer: Lexer = Lexer::from_chars(input.chars().collect(), config.clone());
+        lexer.recovering = true;
+        let mut next_report = interval;
+        let lexed = lexer.run_to_end_with(|offset| {
+            if offset >= next_report && offset < input.len() {
+                reporter.report(&progress(offset));
+                next_report = (offset / interval + 1) * interval;
+            }
+        });
+        reporter.report(&progress(input.len()));
+        lexed
+    }
+
+    /// Lexically analyzes the given input string as `lex_recovering` does, also giving the warnings found while
+    /// skipping whitespace and comments, such as those of the whitespace lints `LexerBuilder::whitespace_lints`
+    /// turns on, and the `trailing-garbage` warning of `LexerBuilder::nul_ends_input`.
+    ///
+    /// # Returns
+    /// * `(Vec<SpannedToken>, Vec<LexError>, Vec<Diagnostic>)` - The tokens, as from `lex_recovering`, every error
+    ///   in source order, and the warnings in source order.
+    pub fn lex_with_diagnostics(
+        input: &str,
+        config: &LexerConfig,
+    ) -> (Vec<SpannedToken>, Vec<LexError>, Vec<Diagnostic>) {
+        let mut lexer: Lexer = Lexer::from_chars(input.chars().collect(), config.clone());
+        lexer.recovering = true;
+        let (tokens, errors) = lexer.run_to_end();
+        (tokens, errors, lexer.take_diagnostics())
+    }
+
+    /// Lexically analyzes the given input string, giving everything the lex produces in one `LexOutput`.
+    ///
+    /// Unlike `lex_located`, the tokens are kept when there are errors, leaving out only the text that failed to
+    /// lex. Unterminated literals are not recovered from; use `lex_recovering` for a stream a parser can always
+    /// consume.
+    ///
+    /// # Parameters
+    /// * `input` - A string slice representing the source code to be lexed.
+    /// * `config` - The settings to lex with, as built by a `LexerBuilder`.
+    pub fn lex_output(input: &str, config: &LexerConfig) -> LexOutput {
+        let start: Instant = Instant::now();
+        let mut lexer: Lexer = Lexer::from_chars(input.chars().collect(), config.clone());
+        let (tokens, errors) = lexer.run_to_end();
+        let diagnostics: Vec<Diagnostic> = lexer.take_diagnostics();
+        LexOutput::new(input, tokens, errors, diagnostics, start.elapsed())
+    }
+
+    /// Lexically analyzes one byte range of the given input string, such as a function body that was edited,
+    /// reporting spans relative to the whole input.
+    ///
+    /// # Parameters
+    /// * `input` - A string slice representing the whole source file.
+    /// * `range` - The byte range to lex.
+    ///
+    /// # Returns
+    /// * `Ok(Vec<SpannedToken>)` - The tokens in the range, ending with a `Token::EOF` at `range.end` unless
+    ///   `LexerBuilder::emit_eof` turned it off.
+    /// * `Err(Vec<LexError>)` - Every error in the range and its span.
+    ///
+    /// # Panics
+    /// Panics if either end of `range` is out of bounds or inside a multi-byte character, as slicing would.
+    pub fn lex_range(input: &str, range: Range<usize>) -> Result<Vec<SpannedToken>, Vec<LexError>> {
+        Lexer::lex_range_with_config(input, range, &LexerConfig::default())
+    }
+
+    /// Lexically analyzes one byte range of the given input string with a custom configuration, as `lex_range`
+    /// does.
+    ///
+    /// Text outside the range is never read, so a token that continues past `range.end` is cut off there, and the
+    /// range should start outside any comment or literal.
+    pub fn lex_range_with_config(
+        input: &str,
+        range: Range<usize>,
+        config: &LexerConfig,
+    ) -> Result<Vec<SpannedToken>, Vec<LexError>> {
+        // Check the range the same way slicing does, so a bad range panics here rather than being lexed
+        let _ = &input[range.clone()];
+        let mut lexer: Lexer = Lexer::new(&input[..range.end], config.clone());
+        lexer.seek(range.start);
+        lexer.run()
+    }
+
+    /// Lexes the whole input, collecting the tokens or every error
+    fn run(&mut self) -> Result<Vec<SpannedToken>, Vec<LexError>> {
+        let (tokens, errors) = self.run_to_end();
+        if errors.is_empty() {
+            return Ok(tokens);
+        }
+        Err(errors)
+    }
+
+    /// Lexes the whole input, collecting the tokens that could be lexed and every error
+    fn run_to_end(&mut self) -> (Vec<SpannedToken>, Vec<LexError>) {
+        self.run_to_end_with(|_| {})
+    }
+
+    /// Runs the lexer to the end of its input as `run_to_end` does, passing the byte offset reached to `step` after
+    /// every token or error
+    fn run_to_end_with(&mut self, mut step: impl FnMut(usize)) -> (Vec<SpannedToken>, Vec<LexError>) {
+        let mut errors: Vec<LexError> = Vec::new();
+        let mut tokens: Vec<SpannedToken> = Vec::new();
 
         loop {
-            let token: Result<Token, ErrorType> = lexer.next_token();
-            match token {
-                Ok(token) => {
-                    if token == Token::EOF {
-                        tokens.push(token);
+            let next = self.next_token();
+            step(self.offset());
+            match next {
+                Ok(spanned) => {
+                    if spanned.token == Token::EOF {
+                        if self.config.emits_eof() {
+                            tokens.push(spanned);
+                        }
                         break;
                     }
-                    tokens.push(token);
+                    tokens.push(spanned);
                 }
-                Err(error) => {
-                    errors.push(error);
-                    lexer.read_char();
+                Err(error) => errors.push(error),
+            }
+        }
+        errors.append(&mut self.recovered_errors);
+        error::sort_by_span(&mut errors);
+        (tokens, errors)
+    }
+
+    /// Lexes the next token, skipping any whitespace and comments before it.
+    ///
+    /// Every call makes progress: after an error, the lexer has moved past the text it could not lex, so calling
+    /// again continues with the rest of the input. Once `Token::EOF` has been returned, every further call returns
+    /// `Token::EOF` again.
+    ///
+    /// # Returns
+    /// * `Ok(SpannedToken)` - The token and its span.
+    /// * `Err(LexError)` - The error and the span of the text that was skipped.
+    pub fn next_token(&mut self) -> Result<SpannedToken, LexError> {
+        let scanned = self.scan_token().and_then(|token| self.track_brackets(token));
+        match scanned.and_then(|token| self.limit_length(token)) {
+            Ok(token) => {
+                let mut spanned: SpannedToken = SpannedToken::new(token, self.current_span());
+                spanned.synthetic = std::mem::take(&mut self.synthetic);
+                spanned.contextual_keyword = self.is_contextual_keyword(&spanned.token);
+                Ok(spanned)
+            }
+            Err(error) => {
+                // Step over the offending character if the rule that failed did not consume it
+                if self.position == self.token_start {
+                    self.read_char();
+                }
+                let span = self.current_span();
+                if self.config.soft_fail
+                    && matches!(error, ErrorType::UnrecognizedToken { .. } | ErrorType::InvalidLiteral { .. })
+                {
+                    let text: Vec<char> = self.input[self.token_start..self.position].to_vec();
+                    let lexeme: String = text.iter().collect();
+                    let message = format!("`{}` is not supported and was kept as an unknown token", lexeme);
+                    self.diagnostics.push(Diagnostic::warning("unknown-token", message, span));
+                    return Ok(SpannedToken::new(Token::UNKNOWN(text), span));
                 }
+                Err(LexError::new(error, span))
             }
         }
-        if errors.is_empty() {
-            return Ok(tokens);
+    }
+
+    /// Takes the warnings found so far while skipping whitespace and comments, for callers that drive the lexer
+    /// with `next_token`.
+    pub fn take_diagnostics(&mut self) -> Vec<Diagnostic> {
+        std::mem::take(&mut self.diagnostics)
+    }
+
+    /// Gives the byte offset of the next character the lexer will read.
+    pub fn offset(&self) -> usize {
+        self.byte_offset(self.position)
+    }
+
+    /// Returns true once the lexer has read all of its input. The next token will be `Token::EOF`, unless
+    /// it was already returned.
+    pub fn is_at_end(&self) -> bool {
+        self.position >= self.input.len()
+    }
+
+    /// Moves the lexer to a byte offset, so that the next token is lexed from there, and leaves any comment or
+    /// other mode it was in.
+    ///
+    /// An offset inside a multi-byte character moves to the start of that character, and an offset past the end
+    /// of the input moves to the end.
+    pub fn seek(&mut self, offset: usize) {
+        let offset = offset.min(self.offsets[self.input.len()]);
+        let position = match self.offsets.binary_search(&offset) {
+            Ok(position) => position,
+            Err(position) => position - 1,
+        };
+        self.position = position;
+        self.current = self.input.get(position).copied();
+        self.token_start = position;
+        self.modes.clear();
+        self.bracket_depth = 0;
+    }
+
+    /// Checks the length of a token that carries its characters against the configured limit, failing or cutting
+    /// its characters to the limit as the configuration chooses
+    fn limit_length(&mut self, mut token: Token) -> Result<Token, ErrorType> {
+        let Some(limit) = self.config.max_token_length.filter(|_| token.text().is_some()) else {
+            return Ok(token);
+        };
+        let length: usize = self.position.min(self.input.len()) - self.token_start;
+        if length <= limit {
+            return Ok(token);
         }
-        Err(errors)
+        if self.config.long_tokens == LongTokenPolicy::Error {
+            return Err(ErrorType::TokenTooLong { kind: token.name().to_string(), length, limit });
+        }
+
+        if let Token::NUMBER(text)
+        | Token::IDENTIFIER(text)
+        | Token::STRINGLITERAL(text)
+        | Token::HASHLINE(text)
+        | Token::TYPENAME(text)
+        | Token::UNKNOWN(text)
+        | Token::CUSTOM(_, text) = &mut token
+        {
+            text.truncate(limit);
+        }
+        let message: String = format!("{} of {} characters was cut to the limit of {}", token.name(), length, limit);
+        self.diagnostics.push(Diagnostic::warning("token-too-long", message, self.current_span()));
+        Ok(token)
+    }
+
+    /// Counts the brackets a token opens or closes, failing if it opens one more than the configured limit allows
+    fn track_brackets(&mut self, token: Token) -> Result<Token, ErrorType> {
+        match token {
+            Token::LPAREN | Token::LBRACE | Token::LBRACKET => {
+                let limit: usize = self.config.max_bracket_depth();
+                if self.bracket_depth >= limit {
+                    return Err(ErrorType::NestingTooDeep { construct: "bracket".to_string(), limit });
+                }
+                self.bracket_depth += 1;
+            }
+            Token::RPAREN | Token::RBRACE | Token::RBRACKET => {
+                self.bracket_depth = self.bracket_depth.saturating_sub(1);
+            }
+            _ => {}
+        }
+        Ok(token)
+    }
+
+    /// Returns true if a block comment ran to the end of the input, so more input could still close it.
+    pub(crate) fn unclosed_comment(&self) -> bool {
+        self.unclosed_comment
+    }
+
+    /// Checks whether the token just lexed is an identifier spelled like a registered contextual keyword
+    fn is_contextual_keyword(&self, token: &Token) -> bool {
+        if !matches!(token, Token::IDENTIFIER(_)) {
+            return false;
+        }
+        let word: &[char] = &self.input[self.token_start..self.position.min(self.input.len())];
+        self.config.keyword_table().is_contextual(word)
+    }
+
+    /// Lexically analyzes raw source bytes in the given encoding, reporting spans as offsets into `bytes`.
+    ///
+    /// # Parameters
+    /// * `bytes` - The raw contents of a source file.
+    /// * `encoding` - The encoding of `bytes`, or `Encoding::Auto` to detect UTF-16 by its byte order mark and
+    ///   fall back from UTF-8 to Latin-1.
+    ///
+    /// # Returns
+    /// * `Ok(Vec<SpannedToken>)` - The tokens, with spans relative to the original bytes.
+    /// * `Err(Vec<ErrorType>)` - `ErrorType::InvalidEncoding` if the bytes cannot be decoded, or the lex errors.
+    pub fn lex_bytes_with_encoding(bytes: &[u8], encoding: Encoding) -> Result<Vec<SpannedToken>, Vec<ErrorType>> {
+        let transcoded: Transcoded = encoding::transcode(bytes, encoding)?;
+        Lexer::lex_transcoded(&transcoded)
+    }
+
+    /// Lexically analyzes raw source bytes as UTF-8, replacing invalid sequences rather than refusing the input.
+    ///
+    /// Each invalid sequence becomes U+FFFD REPLACEMENT CHARACTER, which is harmless inside a comment and reported
+    /// as an unrecognized token anywhere else.
+    ///
+    /// # Parameters
+    /// * `bytes` - The raw contents of a source file.
+    ///
+    /// # Returns
+    /// * `(Result<Vec<SpannedToken>, Vec<ErrorType>>, Vec<Diagnostic>)` - The result of lexing the decoded
+    ///   source with spans relative to `bytes`, and a warning for every invalid sequence that was replaced. The
+    ///   warnings are returned whether or not lexing succeeds.
+    pub fn lex_bytes_lossy(bytes: &[u8]) -> (Result<Vec<SpannedToken>, Vec<ErrorType>>, Vec<Diagnostic>) {
+        let (transcoded, diagnostics) = encoding::transcode_utf8_lossy(bytes);
+        (Lexer::lex_transcoded(&transcoded), diagnostics)
+    }
+
+    /// Lexes transcoded source code, mapping the spans of the tokens back to the original bytes.
+    fn lex_transcoded(transcoded: &Transcoded) -> Result<Vec<SpannedToken>, Vec<ErrorType>> {
+        let tokens: Vec<SpannedToken> = Lexer::lex_spanned(&transcoded.text)?;
+        Ok(tokens
+            .into_iter()
+            .map(|spanned| {
+                let span = Span::new(
+                    transcoded.original_offset(spanned.span.start),
+                    transcoded.original_offset(spanned.span.end),
+                );
+                SpannedToken::new(spanned.token, span)
+            })
+            .collect())
     }
 
+    /// Converts a character position into a byte offset, clamping positions past the end of the input.
+    fn byte_offset(&self, position: usize) -> usize {
+        self.offsets[position.min(self.input.len())]
+    }
 
+    /// Gives the span of the token most recently returned by `next_token`.
+    fn current_span(&self) -> Span {
+        Span::new(self.byte_offset(self.token_start), self.byte_offset(self.position))
+    }
 
     // Advances the currently read character
     fn read_char(&mut self) {
         self.position += 1;
-        if self.position >= self.input.len() {
-            self.current = '@';
-        } else {
-            self.current = self.input[self.position];
-        }
+        self.current = self.input.get(self.position).copied();
     }
 
     // Advances the currently read character n times
@@ -122,31 +634,96 @@ impl Lexer {
         }
     }
 
-    /// Gives the next character without changing the position
-    fn peek_char(&self) -> char {
-        if self.position + 1 >= self.input.len() {
-            '@' // EOF token
-        } else {
-            self.input[self.position + 1]
-        }
+    /// Gives the character `n` positions ahead of the current one without changing the position, where
+    /// `peek_nth(0)` is the current character. Returns `None` past the end of the input.
+    ///
+    /// Lookahead is bounded by `MAX_LOOKAHEAD` so that no token rule depends on more context than a fixed-size
+    /// window over the input.
+    fn peek_nth(&self, n: usize) -> Option<char> {
+        debug_assert!(n < MAX_LOOKAHEAD, "lookahead of {} exceeds MAX_LOOKAHEAD", n);
+        self.input.get(self.position + n).copied()
     }
 
-    // Gives the next n characters without changing the position
-    fn peek_chars(&self, n: usize) -> String {
-        (0..n).map(|i| {
-            if self.position + i >= self.input.len() {
-                '@' // EOF (end of file marker)
-            } else {
-                self.input[self.position + i]
-            }
-        }).collect() // Collects characters into a string
+    /// Checks whether the input at the current position begins with `pattern`, without allocating.
+    fn starts_with(&self, pattern: &str) -> bool {
+        pattern.chars().enumerate().all(|(i, c)| self.peek_nth(i) == Some(c))
+    }
+
+    /// Gives the mode the lexer is currently in
+    fn mode(&self) -> LexMode {
+        self.modes.last().copied().unwrap_or_default()
+    }
+
+    /// Enters a new mode, remembering the current one
+    fn push_mode(&mut self, mode: LexMode) {
+        self.modes.push(mode);
+    }
+
+    /// Leaves the current mode, returning to the one it was entered from
+    fn pop_mode(&mut self) {
+        self.modes.pop();
     }
 
     fn skip_whitespace(&mut self) {
+        let start: usize = self.position;
         // Rust's built-in is_whitespace method
-        while self.current.is_whitespace() {
+        while self.current.is_some_and(char::is_whitespace) {
             self.read_char();
         }
+        if self.config.whitespace_lints {
+            self.lint_whitespace(start);
+        }
+        if self.config.reports_missing_final_newline() {
+            self.lint_final_newline();
+        }
+    }
+
+    /// Reports trailing whitespace and indentation with a tab after a space in the run of whitespace just skipped
+    /// from `start`
+    fn lint_whitespace(&mut self, start: usize) {
+        let end: usize = self.position.min(self.input.len());
+        let start: usize = start.min(end);
+        let mut line_start: usize = start;
+        let mut begins_line: bool = start == 0 || self.input[start - 1] == '\n';
+        for position in start..=end {
+            if position < end && self.input[position] != '\n' {
+                continue;
+            }
+            let mut content_end: usize = position;
+            if position < end && content_end > line_start && self.input[content_end - 1] == '\r' {
+                content_end -= 1;
+            }
+            if (position < end || end == self.input.len()) && content_end > line_start {
+                self.report_trailing_whitespace(line_start, content_end);
+            } else if begins_line {
+                let indentation: &[char] = &self.input[line_start..content_end];
+                let first_space: Option<usize> = indentation.iter().position(|c| *c == ' ');
+                if first_space.is_some_and(|space| indentation[space..].contains(&'\t')) {
+                    let span: Span = Span::new(self.byte_offset(line_start), self.byte_offset(content_end));
+                    let message: String = "indentation has a tab after a space".to_string();
+                    self.diagnostics.push(Diagnostic::warning("mixed-indentation", message, span));
+                }
+            }
+            line_start = position + 1;
+            begins_line = true;
+        }
+    }
+
+    /// Reports a missing final newline once the lexer has reached the end of the input
+    fn lint_final_newline(&mut self) {
+        let end_of_input: usize = self.byte_offset(self.input.len());
+        let reported = self.diagnostics.last().is_some_and(|last| last.code == "missing-final-newline");
+        if self.is_at_end() && self.input.last().is_some_and(|c| *c != '\n') && !reported {
+            let span: Span = Span::new(end_of_input, end_of_input);
+            let message: String = "file does not end with a newline".to_string();
+            self.diagnostics.push(Diagnostic::warning("missing-final-newline", message, span));
+        }
+    }
+
+    /// Reports the whitespace between two positions as trailing whitespace
+    fn report_trailing_whitespace(&mut self, start: usize, end: usize) {
+        let span: Span = Span::new(self.byte_offset(start), self.byte_offset(end));
+        self.diagnostics.push(Diagnostic::warning("trailing-whitespace", "trailing whitespace".to_string(), span));
     }
 
     /// Helper function to create unrecognized token error
@@ -158,178 +735,328 @@ impl Lexer {
 
     /// Processes boolean comparison operators.
     /// Note: Does NOT handle logical operators (&&, ||) anymore
-    fn boolean_comparison(&mut self) -> Result<Token, ErrorType> {
-        match self.current {
-            '=' => match self.peek_char() {
-                '=' => {
+    fn boolean_comparison(&mut self, c: char) -> Result<Token, ErrorType> {
+        match c {
+            '=' => match self.peek_nth(1) {
+                Some('=') => {
                     self.read_char();
                     Ok(Token::EQUALEQUAL)
                 }
                 _ => Ok(Token::EQUAL),
             },
                         
-            '!' => match self.peek_char() {
-                '=' => {
+            '!' => match self.peek_nth(1) {
+                Some('=') => {
                     self.read_char();
                     Ok(Token::NOTEQUAL)
                 }
                 _ => Ok(Token::EXCLAMATIONPOINT),
             },
             
-            '<' => match self.peek_char() {
-                '=' => {
+            '<' => match self.peek_nth(1) {
+                Some('=') => {
                     self.read_char();
                     Ok(Token::LESSTHANEQUAL)
                 }
                 _ => Ok(Token::LESSTHAN),
             },
             
-            '>' => match self.peek_char() {
-                '=' => {
+            '>' => match self.peek_nth(1) {
+                Some('=') if !self.config.split_angle_brackets => {
                     self.read_char();
                     Ok(Token::GREATERTHANEQUAL)
                 }
                 _ => Ok(Token::GREATERTHAN),
             },
             
-            _ => Err(self.make_unrecognized_error(self.current)),
+            _ => Err(self.make_unrecognized_error(c)),
         }
     }
     
-    /// Handles keywords and identifiers starting with letters or underscore
+    /// Handles keywords and identifiers, which start with a letter, an underscore, or a character the configuration
+    /// allows
     fn handle_keywords_and_identifiers(&mut self) -> Result<Token, ErrorType> {
-        let keyword_map = [
-            ("struct", Token::STRUCT),
-            ("enum", Token::ENUM),
-            ("if", Token::IF),
-            ("else", Token::ELSE),
-            ("return", Token::RETURN),
-            ("for", Token::FOR),
-            ("while", Token::WHILE),
-            ("do", Token::DO),
-            ("break", Token::BREAK),
-            ("continue", Token::CONTINUE),
-            ("switch", Token::SWITCH),
-            ("case", Token::CASE),
-            ("int", Token::TINTEGER),
-            ("bool", Token::TBOOLEAN),
-            ("double", Token::TDOUBLE),
-            ("float", Token::TFLOAT),
-            ("char", Token::TCHAR),
-            ("void", Token::TVOID),
-            ("signed", Token::TSIGNINT),
-            ("unsigned", Token::TUSIGN),
-            ("long", Token::TLONG),
-            ("const", Token::CONST),
-            ("true", Token::CTRUE),
-        ];
-
-        // First, collect the entire identifier to check against keywords
-        let mut id = vec![self.current];
-        loop {
-            match self.peek_char() {
-                'a'..='z' | 'A'..='Z' | '0'..='9' | '_' => {
-                    id.push(self.peek_char());
-                    self.read_char();
-                }
-                _ => break,
-            }
+        // First, find the end of the entire identifier to check against keywords
+        let start: usize = self.position;
+        while self.peek_nth(1).is_some_and(|c| self.config.is_identifier_continue(c)) {
+            self.read_char();
         }
-        
-        // Convert the collected characters to a string for keyword matching
-        let identifier: String = id.iter().collect();
-        
+        let id: &[char] = &self.input[start..=self.position];
+
         // Check if the entire identifier matches a keyword
-        for (keyword, token) in keyword_map.iter() {
-            if &identifier == keyword {
-                return Ok(token.clone());
-            }
+        if let Some(token) = self.config.keyword_table().keyword(id) {
+            return Ok(token.clone());
+        }
+        if let Some(token) = self.config.keyword_table().operator_alias(id) {
+            return Ok(token.clone());
         }
 
         // If no keyword matches, treat as identifier
-        Ok(Token::IDENTIFIER(id))
+        Ok(Token::IDENTIFIER(self.payload(start)))
     }
 
     // Handles numbers
     fn numbers(&mut self) -> Result<Token, ErrorType> {
-        if !('0'..='9').contains(&self.current) {
-            return Err(self.make_unrecognized_error(self.current));
+        if let Some(c) = self.current.filter(|c| !c.is_ascii_digit()) {
+            return Err(self.make_unrecognized_error(c));
+        }
+
+        let start: usize = self.position;
+        while self.peek_nth(1).is_some_and(|c| c.is_ascii_digit()) {
+            self.read_char();
+        }
+        self.check_number(start)?;
+        Ok(Token::NUMBER(self.payload(start)))
+    }
+
+    /// Checks the number from `start` through the current character against the configured numeric literal policy
+    fn check_number(&mut self, start: usize) -> Result<(), ErrorType> {
+        let digits: &[char] = &self.input[start..=self.position];
+        let invalid = |reason: &str| ErrorType::InvalidLiteral {
+            literal: digits.iter().collect(),
+            reason: reason.to_string(),
+        };
+        if let Some(max) = self.config.max_number_digits.filter(|max| digits.len() > *max) {
+            return Err(invalid(&format!("has {} digits, more than the limit of {}", digits.len(), max)));
         }
+        if digits.len() < 2 || digits[0] != '0' {
+            return Ok(());
+        }
+        if self.config.reject_repeated_zeros && digits[1] == '0' {
+            return Err(invalid("starts with more than one zero"));
+        }
+        match self.config.leading_zeros {
+            LeadingZeros::Allow => {}
+            LeadingZeros::Octal => {
+                if let Some(digit) = digits.iter().find(|c| matches!(c, '8' | '9')) {
+                    return Err(invalid(&format!("`{}` is not an octal digit", digit)));
+                }
+            }
+            LeadingZeros::DecimalWithWarning => {
+                let span: Span = Span::new(self.byte_offset(start), self.byte_offset(self.position + 1));
+                let message: String = format!(
+                    "`{}` has a leading zero, which C reads as octal",
+                    digits.iter().collect::<String>(),
+                );
+                // Taken back by `longest_of_regex_and_builtin` if a regex rule claims the number instead
+                self.diagnostics.push(Diagnostic::warning("leading-zero", message, span));
+            }
+        }
+        Ok(())
+    }
 
-        let mut num = vec![self.current];
+    /// Gives the characters from `start` through the current one as the payload of a token, or an empty payload
+    /// when payloads are not kept
+    fn payload(&self, start: usize) -> Vec<char> {
+        self.payload_between(start, self.position + 1)
+    }
+
+    /// Gives the characters in `[start, end)` as the payload of a token, or an empty payload when payloads are not
+    /// kept
+    fn payload_between(&self, start: usize, end: usize) -> Vec<char> {
+        // Tokens past the length limit are failed or cut to it later, so never copy more than the limit
+        let end: usize = self.config.max_token_length.map_or(end, |limit| end.min(start + limit));
+        if self.keep_payloads {
+            self.input[start..end].to_vec()
+        } else {
+            Vec::new()
+        }
+    }
+
+    /// Handles string literals. The payload is the text between the quotes, with escape sequences left as written
+    fn string_literal(&mut self) -> Result<Token, ErrorType> {
+        let start: usize = self.position;
+        let closed: bool = self.scan_quoted('"');
+        let content_end: usize = if closed { self.position } else { self.position + 1 };
+        let token = Token::STRINGLITERAL(self.payload_between(start + 1, content_end));
+
+        if closed {
+            return Ok(token);
+        }
+        self.unterminated(token, UNTERMINATED_STRING)
+    }
+
+    /// Handles character literals, computing their value
+    fn char_literal(&mut self) -> Result<Token, ErrorType> {
+        let start: usize = self.position;
+        let closed: bool = self.scan_quoted('\'');
+        let mut text: String = self.input[start..=self.position].iter().collect();
+        if !closed {
+            text.push('\'');
+        }
+
+        let value = literal::parse_char(&text).and_then(|value| {
+            char::from_u32(value).ok_or_else(|| ErrorType::InvalidLiteral {
+                literal: text.clone(),
+                reason: "not a valid Unicode scalar value".to_string(),
+            })
+        });
+        if closed {
+            return value.map(Token::CHAR);
+        }
+        self.unterminated(Token::CHAR(value.unwrap_or_default()), "unterminated character literal")
+    }
+
+    /// Advances through a literal delimited by `quote` that starts at the current position, skipping over escaped
+    /// characters. Stops on the closing quote and returns true, or stops on the last character before the end of
+    /// the line or input and returns false. Strings run past the end of the line when multiline strings are on.
+    fn scan_quoted(&mut self, quote: char) -> bool {
+        let multiline: bool = quote == '"' && self.config.multiline_strings;
         loop {
-            match self.peek_char() {
-                '0'..='9' => {
-                    num.push(self.peek_char());
+            match self.peek_nth(1) {
+                Some(c) if c == quote => {
                     self.read_char();
+                    return true;
                 }
-                _ => {
-                    break;
-                }
+                Some('\\') if self.peek_nth(2).is_some_and(|c| c != '\n') => self.read_chars(2),
+                Some('\n') if !multiline => return false,
+                None => return false,
+                Some(_) => self.read_char(),
             }
         }
-        Ok(Token::NUMBER(num))
+    }
+
+    /// Handles a `#` that begins a preprocessor line, taking the rest of the line and any lines joined to it with
+    /// a trailing backslash. A `#` anywhere else is unrecognized
+    fn hash_line(&mut self) -> Result<Token, ErrorType> {
+        let line_start: usize = self.input[..self.position].iter().rposition(|c| *c == '\n').map_or(0, |i| i + 1);
+        if !self.input[line_start..self.position].iter().all(|c| c.is_whitespace()) {
+            return Err(self.make_unrecognized_error('#'));
+        }
+
+        let start: usize = self.position;
+        loop {
+            match self.peek_nth(1) {
+                Some('\n') | None => break,
+                Some('\\') if self.peek_nth(2) == Some('\n') => self.read_chars(2),
+                Some(_) => self.read_char(),
+            }
+        }
+        Ok(Token::HASHLINE(self.payload(start)))
+    }
+
+    /// Fails on an unterminated literal, or when recovering, records the error and returns the literal's token as
+    /// if it had been closed
+    fn unterminated(&mut self, token: Token, message: &str) -> Result<Token, ErrorType> {
+        let error = ErrorType::SyntaxError { message: message.to_string() };
+        if !self.recovering {
+            return Err(error);
+        }
+        let span: Span = Span::new(self.byte_offset(self.token_start), self.byte_offset(self.position + 1));
+        self.recovered_errors.push(LexError::new(error, span));
+        self.synthetic = true;
+        Ok(token)
     }
     
-    /// Handles single-line and block comments
-    fn handle_comments(&mut self) -> Option<Result<Token, ErrorType>> {
-        if self.current == '/' {
-            match self.peek_char() {
-                '/' => {
-                    // Skip single-line comment
-                    while self.current != '\n' && self.current != '@' {
-                        self.read_char();
-                    }
-                    return Some(self.next_token());
+    /// Skips a block comment starting at the current position. An unterminated comment runs to the end of the
+    /// input.
+    ///
+    /// As in standard C, the first `*/` ends the comment, unless nested comments are turned on, in which case each
+    /// `/*` inside opens another level that needs its own `*/`. Levels nested beyond the configured limit are only
+    /// counted rather than pushed as modes, and the first of them is recorded as an error.
+    fn skip_block_comment(&mut self) {
+        let limit: usize = self.config.max_comment_depth();
+        let mut excess: usize = 0;
+        self.read_chars(2); // Skip '/*'
+        self.push_mode(LexMode::InBlockComment);
+
+        while self.mode() == LexMode::InBlockComment {
+            // Check for the end of a block comment
+            if self.starts_with("*/") {
+                self.read_chars(2); // Skip '*/'
+                if excess > 0 {
+                    excess -= 1;
+                } else {
+                    self.pop_mode();
                 }
-                '*' => {
-                    // Process block comment
-                    self.read_char(); // Skip '*'
-                    
-                    // Keep track of nesting level to handle nested comments
-                    let mut level = 1;
-                    
-                    loop {
-                        // Check for the end of a block comment
-                        if self.current == '*' && self.peek_char() == '/' {
-                            level -= 1;
-                            self.read_char(); // Skip '*'
-                            self.read_char(); // Skip '/'
-                            
-                            if level == 0 {
-                                // We've found the matching end comment
-                                break;
-                            }
-                        } 
-                        // Check for a nested block comment
-                        else if self.current == '/' && self.peek_char() == '*' {
-                            level += 1;
-                            self.read_char(); // Skip '/'
-                            self.read_char(); // Skip '*'
-                        }
-                        // Check for EOF (end of file)
-                        else if self.current == '@' {
-                            // Instead of returning an error for an unclosed comment,
-                            // we'll treat it as a sequence of tokens to match the test
-                            // (Though in a real compiler this would be an error)
-                            return None;
-                        }
-                        else {
-                            self.read_char();
-                        }
+            }
+            // Check for a nested block comment
+            else if self.config.nested_comments && self.starts_with("/*") {
+                if self.modes.len() < limit {
+                    self.push_mode(LexMode::InBlockComment);
+                } else {
+                    if excess == 0 {
+                        let span = Span::new(self.byte_offset(self.position), self.byte_offset(self.position + 2));
+                        let error = ErrorType::NestingTooDeep { construct: "block comment".to_string(), limit };
+                        self.recovered_errors.push(LexError::new(error, span));
                     }
-                    return Some(self.next_token());
+                    excess += 1;
                 }
-                _ => {}
+                self.read_chars(2); // Skip '/*'
+            }
+            // Check for EOF (end of file)
+            else if self.peek_nth(0).is_none() {
+                // Instead of returning an error for an unclosed comment,
+                // we'll treat it as running to the end of the input
+                // (Though in a real compiler this would be an error)
+                self.modes.clear();
+                self.unclosed_comment = true;
+            }
+            else {
+                self.read_char();
             }
         }
-        None
+    }
+
+    /// Skips a single-line comment, leaving the lexer on the newline that ends it.
+    fn skip_line_comment(&mut self) {
+        let start: usize = self.position;
+        while self.peek_nth(0).is_some_and(|c| c != '\n') {
+            self.read_char();
+        }
+        if self.config.whitespace_lints {
+            let mut content_end: usize = self.position;
+            while content_end > start && matches!(self.input[content_end - 1], ' ' | '\t' | '\r') {
+                content_end -= 1;
+            }
+            if content_end < self.position && self.input[content_end..self.position].iter().any(|c| *c != '\r') {
+                self.report_trailing_whitespace(content_end, self.position);
+            }
+        }
+    }
+
+    /// Skips whitespace and comments until the start of the next token.
+    ///
+    /// This loops rather than recursing once per comment, so runs of thousands of comments cannot overflow the stack.
+    fn skip_trivia(&mut self) {
+        loop {
+            self.skip_whitespace();
+            if self.starts_with("/*") {
+                self.skip_block_comment();
+            } else if self.config.control_chars != ControlCharPolicy::Error && self.at_control_char() {
+                let (character, count, span) = self.skip_control_chars();
+                if self.config.control_chars == ControlCharPolicy::Warn {
+                    let message = format!("ignored {}", describe_control_chars(character, count));
+                    self.diagnostics.push(Diagnostic::warning("control-character", message, span));
+                }
+            } else if self.starts_with("//") {
+                self.skip_line_comment();
+            } else {
+                break;
+            }
+        }
+    }
+
+    /// Checks whether the current character is a control character that is not whitespace
+    fn at_control_char(&self) -> bool {
+        self.current.is_some_and(is_control_char)
+    }
+
+    /// Skips a run of control characters, giving its first character, its length, and its span
+    fn skip_control_chars(&mut self) -> (char, usize, Span) {
+        // Only called at a control character, so `current` is never `None` here
+        let (start, character) = (self.position, self.current.unwrap_or_default());
+        while self.at_control_char() {
+            self.read_char();
+        }
+        let span = Span::new(self.byte_offset(start), self.byte_offset(self.position));
+        (character, self.position - start, span)
     }
 
     /// Handles plus sign and increment operator
     fn handle_plus(&mut self) -> Result<Token, ErrorType> {
-        match self.peek_char() {
-            '+' => {
+        match self.peek_nth(1) {
+            Some('+') => {
                 self.read_char();
                 Ok(Token::PLUSPLUS)
             }
@@ -339,12 +1066,12 @@ impl Lexer {
 
     /// Handles minus sign, decrement operator, and pointer
     fn handle_minus(&mut self) -> Result<Token, ErrorType> {
-        match self.peek_char() {
-            '>' => {
+        match self.peek_nth(1) {
+            Some('>') => {
                 self.read_char();
                 Ok(Token::POINTER)
             }
-            '-' => {
+            Some('-') => {
                 self.read_char();
                 Ok(Token::MINUSMINUS)
             }
@@ -354,8 +1081,8 @@ impl Lexer {
 
     /// Handles ampersand and logical AND
     fn handle_ampersand(&mut self) -> Result<Token, ErrorType> {
-        match self.peek_char() {
-            '&' => {
+        match self.peek_nth(1) {
+            Some('&') => {
                 self.read_char(); // Advance to the second &
                 Ok(Token::ANDAND)
             }
@@ -365,8 +1092,8 @@ impl Lexer {
 
     /// Handles pipe and logical OR
     fn handle_pipe(&mut self) -> Result<Token, ErrorType> {
-        match self.peek_char() {
-            '|' => {
+        match self.peek_nth(1) {
+            Some('|') => {
                 self.read_char(); // Advance to the second |
                 Ok(Token::BARBAR)
             }
@@ -377,7 +1104,6 @@ impl Lexer {
     /// Handles special-character tokens and single-character tokens
     fn handle_single_char_token(&self, c: char) -> Result<Token, ErrorType> {
         match c {
-            '@' => Ok(Token::EOF),
             '*' => Ok(Token::ASTERISK),
             '/' => Ok(Token::FSLASH),
             '%' => Ok(Token::PERCENT),
@@ -398,18 +1124,115 @@ impl Lexer {
         }
     }
 
+    /// Tries each custom token rule at the current position, consuming the token of the first rule that applies
+    fn try_custom_rules(&mut self) -> Option<Result<Token, ErrorType>> {
+        let (result, consumed) = self.config.rules.iter().find_map(|rule| {
+            let mut cursor = Cursor::new(&self.input, self.position);
+            match rule.try_lex(&mut cursor) {
+                Some(Ok(_)) if cursor.consumed() == 0 => None,
+                Some(result) => Some((result, cursor.consumed())),
+                None => None,
+            }
+        })?;
+
+        self.read_chars(consumed);
+        Some(result)
+    }
+
     /// Returns the current token type and advances to the next token
-    fn next_token(&mut self) -> Result<Token, ErrorType> {
-        self.skip_whitespace();
-    
-        // Handle comments
-        if let Some(comment_result) = self.handle_comments() {
-            return comment_result;
+    fn scan_token(&mut self) -> Result<Token, ErrorType> {
+        let trivia_start: usize = self.position;
+        self.skip_trivia();
+        if self.position > trivia_start {
+            self.record(Span::new(self.byte_offset(trivia_start), self.byte_offset(self.position)), Decision::Trivia);
         }
-    
+        self.token_start = self.position;
+
+        let (result, rule) = self.select_token();
+        let decision = match &result {
+            Ok(Token::CUSTOM(kind, _)) => Decision::Token { rule, kind: kind.clone() },
+            Ok(token) => Decision::Token { rule, kind: token.name().to_string() },
+            Err(_) => Decision::Error { rule },
+        };
+        self.record(self.current_span(), decision);
+        result
+    }
+
+    /// Lexes the token at the current position, reporting which kind of rule produced it
+    fn select_token(&mut self) -> (Result<Token, ErrorType>, Rule) {
+        if let Some(result) = self.try_custom_rules() {
+            return (result, Rule::Custom);
+        }
+
+        match self.longest_regex_match() {
+            Some((kind, length)) => {
+                let result = self.longest_of_regex_and_builtin(kind, length);
+                let rule = if matches!(result, Ok(Token::CUSTOM(..))) { Rule::Regex } else { Rule::Builtin };
+                (result, rule)
+            }
+            None => (self.scan_builtin(), Rule::Builtin),
+        }
+    }
+
+    /// Records a step of the trace, if one is being kept
+    fn record(&mut self, span: Span, decision: Decision) {
+        let mode: LexMode = self.mode();
+        if let Some(trace) = self.trace.as_mut() {
+            trace.push(TraceStep { span, mode, decision });
+        }
+    }
+
+    /// Finds the longest non-empty match of any regex rule at the current position, returning the kind of the rule
+    /// and the length of the match in characters
+    fn longest_regex_match(&self) -> Option<(String, usize)> {
+        if self.config.regex_rules.is_empty() {
+            return None;
+        }
+        let rest = &self.source[self.byte_offset(self.position)..];
+        let mut best: Option<(&str, usize)> = None;
+        for rule in self.config.regex_rules.iter() {
+            if let Some(found) = rule.regex.find(rest) {
+                if found.end() > best.map_or(0, |(_, length)| length) {
+                    best = Some((rule.kind.as_str(), found.end()));
+                }
+            }
+        }
+        best.map(|(kind, bytes)| (kind.to_string(), rest[..bytes].chars().count()))
+    }
+
+    /// Produces a regex token of `length` characters if it is strictly longer than the built-in token at the
+    /// current position, and the built-in token otherwise. The built-in scan is speculative, so the errors,
+    /// warnings, and flags it records are rolled back when the regex token wins.
+    fn longest_of_regex_and_builtin(&mut self, kind: String, length: usize) -> Result<Token, ErrorType> {
+        let (position, current) = (self.position, self.current);
+        let (errors, diagnostics) = (self.recovered_errors.len(), self.diagnostics.len());
+        let (synthetic, unclosed_comment) = (self.synthetic, self.unclosed_comment);
+        let builtin = self.scan_builtin();
+        let builtin_length = match builtin {
+            Ok(_) => self.position.min(self.input.len()) - self.token_start,
+            Err(_) => 0,
+        };
+        if builtin_length >= length {
+            return builtin;
+        }
+
+        self.position = position;
+        self.current = current;
+        self.recovered_errors.truncate(errors);
+        self.diagnostics.truncate(diagnostics);
+        self.synthetic = synthetic;
+        self.unclosed_comment = unclosed_comment;
+        let kept: usize = self.config.max_token_length.map_or(length, |limit| length.min(limit));
+        let lexeme: Vec<char> = self.input[position..position + kept].to_vec();
+        self.read_chars(length);
+        Ok(Token::CUSTOM(kind, lexeme))
+    }
+
+    /// Scans a token using the built-in rules
+    fn scan_builtin(&mut self) -> Result<Token, ErrorType> {
         // Try boolean comparison operators but only for the ones that are actually comparison operators
-        if matches!(self.current, '=' | '!' | '<' | '>') {
-            let token = self.boolean_comparison();
+        if let Some(c @ ('=' | '!' | '<' | '>')) = self.current {
+            let token = self.boolean_comparison(c);
             // Always advance the lexer position for single character tokens
             self.read_char();
             
@@ -419,21 +1242,15 @@ impl Lexer {
         }
     
         let token = match self.current {
-            '@' => {
-                // Check if we're actually at the end of input
-                if self.position >= self.input.len() {
-                    // This is the EOF marker
-                    Ok(Token::EOF)
-                } else {
-                    // This is an actual '@' in the input
-                    Err(self.make_unrecognized_error('@'))
-                }
-            },
-            '0'..='9' => self.numbers(),
-            'a'..='z' | 'A'..='Z' | '_' => self.handle_keywords_and_identifiers(),
-            '+' => self.handle_plus(),
-            '-' => self.handle_minus(),
-            '&' => {
+            None => Ok(Token::EOF),
+            Some(c) if self.config.is_identifier_start(c) => self.handle_keywords_and_identifiers(),
+            Some('0'..='9') => self.numbers(),
+            Some('"') => self.string_literal(),
+            Some('\'') => self.char_literal(),
+            Some('#') => self.hash_line(),
+            Some('+') => self.handle_plus(),
+            Some('-') => self.handle_minus(),
+            Some('&') => {
                 let result = self.handle_ampersand();
                 if result.is_ok() {
                     if *result.as_ref().unwrap() == Token::AMPERSAND {
@@ -446,7 +1263,7 @@ impl Lexer {
                 }
                 return result;
             },
-            '|' => {
+            Some('|') => {
                 let result = self.handle_pipe();
                 if result.is_ok() {
                     if *result.as_ref().unwrap() == Token::BAR {
@@ -459,9 +1276,13 @@ impl Lexer {
                 }
                 return result;
             },
-            '*' | '/' | '%' | '{' | '}' | '(' | ')' | '[' | ']' | ';' | ':' | ',' | '.' | '^' | '~' | '?' => 
-                self.handle_single_char_token(self.current),
-            _ => Err(self.make_unrecognized_error(self.current)),
+            Some(c @ ('*' | '/' | '%' | '{' | '}' | '(' | ')' | '[' | ']' | ';' | ':' | ',' | '.' | '^' | '~' | '?')) =>
+                self.handle_single_char_token(c),
+            _ if self.at_control_char() => {
+                let (character, count, _) = self.skip_control_chars();
+                return Err(ErrorType::ControlCharacter { character, count });
+            }
+            Some(c) => Err(self.make_unrecognized_error(c)),
         };
     
         self.read_char();
@@ -469,3 +1290,17 @@ impl Lexer {
     }
 
 }
+
+/// Checks whether a character is a C0 control character other than whitespace
+fn is_control_char(c: char) -> bool {
+    (c as u32) < 0x20 && !c.is_whitespace()
+}
+
+/// Describes a run of control characters for a diagnostic, such as `control character U+0000`.
+pub(crate) fn describe_control_chars(character: char, count: usize) -> String {
+    if count == 1 {
+        format!("control character U+{:04X}", character as u32)
+    } else {
+        format!("{} control characters starting with U+{:04X}", count, character as u32)
+    }
+}
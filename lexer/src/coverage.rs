@@ -0,0 +1,214 @@
+//! This file measures how much of the lexer a corpus exercises: which kinds of token it produces and which of the
+//! lexer's handlers it reaches. Kinds and handlers a corpus never reaches are rules without tests, which is easy to
+//! miss as the token set grows.
+//!
+//! Coverage is worked out from the trace `Lexer::lex_traced` records, so inputs that fail to lex still count for
+//! everything lexed before and at the failure, and a failure is attributed to the handler that reported it.
+
+use std::collections::HashMap;
+
+use crate::{
+    config::LexerConfig,
+    core::Lexer,
+    span::Span,
+    token::{Token, TokenKind},
+    trace::{Decision, Rule},
+    trivia::{split_trivia, TriviaKind},
+};
+
+/// A path through the lexer, chosen by the character a token starts with.
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Hash, PartialOrd, Ord)]
+pub enum Handler {
+    /// Skipping spaces, tabs, and newlines.
+    Whitespace,
+    /// Skipping `//` comments.
+    LineComment,
+    /// Skipping `/* */` comments.
+    BlockComment,
+    /// Keywords and identifiers.
+    Word,
+    /// Numbers.
+    Number,
+    /// String literals.
+    StringLiteral,
+    /// Character literals.
+    CharLiteral,
+    /// Preprocessor lines.
+    HashLine,
+    /// `+` and `++`.
+    Plus,
+    /// `-`, `--`, and `->`.
+    Minus,
+    /// `&` and `&&`.
+    Ampersand,
+    /// `|` and `||`.
+    Pipe,
+    /// `=`, `!`, `<`, `>`, and the comparisons they start.
+    Comparison,
+    /// Operators and punctuation of a single character.
+    SingleChar,
+    /// Runs of control characters rejected as errors.
+    ControlCharacter,
+    /// Characters no rule recognizes.
+    Unrecognized,
+    /// The end of the input.
+    EndOfInput,
+    /// Custom token rules.
+    CustomRule,
+    /// Regex token rules.
+    RegexRule,
+}
+
+impl Handler {
+    /// Every handler, in the order the lexer tries them.
+    pub const ALL: &[Handler] = &[
+        Handler::Whitespace,
+        Handler::LineComment,
+        Handler::BlockComment,
+        Handler::CustomRule,
+        Handler::RegexRule,
+        Handler::Comparison,
+        Handler::Word,
+        Handler::EndOfInput,
+        Handler::Number,
+        Handler::StringLiteral,
+        Handler::CharLiteral,
+        Handler::HashLine,
+        Handler::Plus,
+        Handler::Minus,
+        Handler::Ampersand,
+        Handler::Pipe,
+        Handler::SingleChar,
+        Handler::ControlCharacter,
+        Handler::Unrecognized,
+    ];
+}
+
+/// What a corpus exercised.
+///
+/// # Fields
+/// * `inputs` - How many inputs were lexed.
+/// * `kinds` - How many tokens of each kind were produced.
+/// * `handlers` - How many times each handler was reached.
+/// * `reachable_kinds` - The kinds the lexer can produce with the configuration used.
+/// * `reachable_handlers` - The handlers the lexer can reach with the configuration used.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct CoverageReport {
+    pub inputs: usize,
+    pub kinds: HashMap<TokenKind, usize>,
+    pub handlers: HashMap<Handler, usize>,
+    reachable_kinds: Vec<TokenKind>,
+    reachable_handlers: Vec<Handler>,
+}
+
+impl CoverageReport {
+    /// Gives the kinds the lexer could have produced but never did, in `Token::ALL_KINDS` order.
+    ///
+    /// `DEFAULT` and `TYPENAME` are never listed, since the lexer itself never produces them, nor is `UNKNOWN`,
+    /// which marks gaps in the lexer rather than something a corpus should exercise, and neither is `CUSTOM` unless
+    /// the configuration registers custom or regex rules.
+    pub fn missing_kinds(&self) -> Vec<TokenKind> {
+        self.reachable_kinds.iter().copied().filter(|kind| !self.kinds.contains_key(kind)).collect()
+    }
+
+    /// Gives the handlers the lexer could have reached but never did, in `Handler::ALL` order.
+    ///
+    /// `CustomRule` and `RegexRule` are only listed if the configuration registers rules of that kind.
+    pub fn missing_handlers(&self) -> Vec<Handler> {
+        self.reachable_handlers.iter().copied().filter(|handler| !self.handlers.contains_key(handler)).collect()
+    }
+}
+
+/// Lexes every input of a corpus and reports which kinds of token and which handlers it exercised.
+///
+/// # Parameters
+/// * `corpus` - The inputs to lex, such as the contents of every file in a test suite.
+/// * `config` - The settings to lex with, as built by a `LexerBuilder`.
+pub fn coverage<S: AsRef<str>>(corpus: impl IntoIterator<Item = S>, config: &LexerConfig) -> CoverageReport {
+    let has_rules = !config.rules.is_empty() || !config.regex_rules.is_empty();
+    let mut report = CoverageReport {
+        inputs: 0,
+        kinds: HashMap::new(),
+        handlers: HashMap::new(),
+        reachable_kinds: Token::ALL_KINDS
+            .iter()
+            .map(Token::kind)
+            .filter(|kind| match kind {
+                TokenKind::DEFAULT | TokenKind::TYPENAME | TokenKind::UNKNOWN => false,
+                TokenKind::CUSTOM => has_rules,
+                _ => true,
+            })
+            .collect(),
+        reachable_handlers: Handler::ALL
+            .iter()
+            .copied()
+            .filter(|handler| match handler {
+                Handler::CustomRule => !config.rules.is_empty(),
+                Handler::RegexRule => !config.regex_rules.is_empty(),
+                _ => true,
+            })
+            .collect(),
+    };
+
+    for input in corpus {
+        let input = input.as_ref();
+        report.inputs += 1;
+        let (_, trace) = Lexer::lex_traced(input, config);
+        for step in trace.steps {
+            let handlers: Vec<Handler> = match &step.decision {
+                Decision::Trivia => trivia_handlers(input, step.span, config),
+                Decision::Token { rule, kind } => {
+                    // Regex tokens are always custom, and a custom rule may produce a built-in token instead
+                    let kind = TokenKind::from_name(kind).filter(|_| *rule != Rule::Regex);
+                    *report.kinds.entry(kind.unwrap_or(TokenKind::CUSTOM)).or_default() += 1;
+                    vec![token_handler(input, step.span, *rule, config)]
+                }
+                Decision::Error { rule } => vec![token_handler(input, step.span, *rule, config)],
+            };
+            for handler in handlers {
+                *report.handlers.entry(handler).or_default() += 1;
+            }
+        }
+    }
+    report
+}
+
+/// Gives the handlers that skipped a stretch of trivia
+fn trivia_handlers(input: &str, span: Span, config: &LexerConfig) -> Vec<Handler> {
+    let mut handlers: Vec<Handler> = split_trivia(input, span, config.nested_comments())
+        .into_iter()
+        .map(|trivia| match trivia.kind {
+            TriviaKind::Whitespace | TriviaKind::Newline => Handler::Whitespace,
+            TriviaKind::LineComment => Handler::LineComment,
+            TriviaKind::BlockComment => Handler::BlockComment,
+        })
+        .collect();
+    handlers.dedup();
+    handlers
+}
+
+/// Gives the handler that produced a token or reported an error, following the dispatch of the built-in rules
+fn token_handler(input: &str, span: Span, rule: Rule, config: &LexerConfig) -> Handler {
+    let Some(c) = input[span.start..].chars().next() else {
+        return Handler::EndOfInput;
+    };
+    match (rule, c) {
+        (Rule::Custom, _) => Handler::CustomRule,
+        (Rule::Regex, _) => Handler::RegexRule,
+        (_, '=' | '!' | '<' | '>') => Handler::Comparison,
+        (_, c) if config.is_identifier_start(c) => Handler::Word,
+        (_, '0'..='9') => Handler::Number,
+        (_, '"') => Handler::StringLiteral,
+        (_, '\'') => Handler::CharLiteral,
+        (_, '#') => Handler::HashLine,
+        (_, '+') => Handler::Plus,
+        (_, '-') => Handler::Minus,
+        (_, '&') => Handler::Ampersand,
+        (_, '|') => Handler::Pipe,
+        (_, '*' | '/' | '%' | '{' | '}' | '(' | ')' | '[' | ']' | ';' | ':' | ',' | '.' | '^' | '~' | '?') => {
+            Handler::SingleChar
+        }
+        (_, c) if (c as u32) < 0x20 && !c.is_whitespace() => Handler::ControlCharacter,
+        _ => Handler::Unrecognized,
+    }
+}
@@ -0,0 +1,153 @@
+//! This file provides a cursor over a lexed token stream for parsers, with helpers for skipping over bracket
+//! groups, which error recovery and the collection of macro arguments both need. The cursor moves backwards as well
+//! as forwards, so that error messages such as "unexpected token after X" and heuristics that look back, such as
+//! telling a cast from a parenthesized expression, need no state of their own.
+//!
+//! Brackets are matched once, on first use, so finding the partner of any bracket afterwards takes constant time.
+//! A closing bracket matches the nearest open bracket of its kind; brackets opened inside that one and never
+//! closed, as in `( [ )`, are left unmatched, and a closing bracket with no open partner is ignored.
+
+use std::cell::OnceCell;
+
+use crate::{span::SpannedToken, token::Token};
+
+/// A cursor over a stream of spanned tokens.
+///
+/// # Fields
+/// * `tokens` - The tokens, usually ending with `Token::EOF`.
+/// * `position` - The index of the current token.
+/// * `matches` - The index of the partner of every bracket, computed on first use.
+#[derive(Debug, Clone)]
+pub struct TokenStream {
+    tokens: Vec<SpannedToken>,
+    position: usize,
+    matches: OnceCell<Vec<Option<usize>>>,
+}
+
+impl TokenStream {
+    /// Creates a cursor at the first token.
+    pub fn new(tokens: Vec<SpannedToken>) -> Self {
+        Self { tokens, position: 0, matches: OnceCell::new() }
+    }
+
+    /// Gives every token in the stream.
+    pub fn tokens(&self) -> &[SpannedToken] {
+        &self.tokens
+    }
+
+    /// Gives the index of the current token.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Returns true if every token has been consumed, or the current token is `Token::EOF`.
+    pub fn is_at_end(&self) -> bool {
+        self.peek().is_none_or(|spanned| spanned.token == Token::EOF)
+    }
+
+    /// Gives the current token without consuming it.
+    pub fn peek(&self) -> Option<&SpannedToken> {
+        self.peek_nth(0)
+    }
+
+    /// Gives the token `n` places after the current one without consuming anything. `peek_nth(0)` is the current
+    /// token.
+    pub fn peek_nth(&self, n: usize) -> Option<&SpannedToken> {
+        self.tokens.get(self.position + n)
+    }
+
+    /// Consumes the current token.
+    ///
+    /// # Returns
+    /// * `Some(&SpannedToken)` - The token consumed.
+    /// * `None` - If every token has already been consumed.
+    pub fn advance(&mut self) -> Option<&SpannedToken> {
+        let token = self.tokens.get(self.position)?;
+        self.position += 1;
+        Some(token)
+    }
+
+    /// Gives the token `n` places before the current one without moving. `peek_back(1)` is the token most recently
+    /// consumed, and `peek_back(0)` is the current token.
+    pub fn peek_back(&self, n: usize) -> Option<&SpannedToken> {
+        self.tokens.get(self.position.checked_sub(n)?)
+    }
+
+    /// Moves back to the token before the current one, undoing one `advance`.
+    ///
+    /// # Returns
+    /// * `Some(&SpannedToken)` - The token moved back to, which is now the current token.
+    /// * `None` - If the cursor is at the first token.
+    pub fn prev(&mut self) -> Option<&SpannedToken> {
+        self.position = self.position.checked_sub(1)?;
+        self.tokens.get(self.position)
+    }
+
+    /// Moves the cursor to a token, so that it is the current one. An index past the end moves to the end, where
+    /// every token has been consumed.
+    pub fn seek(&mut self, index: usize) {
+        self.position = index.min(self.tokens.len());
+    }
+
+    /// Skips the bracket group that starts at the current token, from its opening bracket through its closing one.
+    /// A group that is never closed is skipped to the end of the stream, stopping at `Token::EOF`.
+    ///
+    /// # Returns
+    /// * `bool` - Whether a group was skipped. The cursor does not move if the current token does not open one.
+    pub fn skip_balanced(&mut self) -> bool {
+        if self.peek().is_none_or(|spanned| closer(&spanned.token).is_none()) {
+            return false;
+        }
+        self.position = match self.find_matching(self.position) {
+            Some(close) => close + 1,
+            None => {
+                let rest = &self.tokens[self.position..];
+                self.position + rest.iter().position(|spanned| spanned.token == Token::EOF).unwrap_or(rest.len())
+            }
+        };
+        true
+    }
+
+    /// Finds the closing bracket that matches the opening bracket at an index.
+    ///
+    /// # Returns
+    /// * `Some(usize)` - The index of the closing bracket.
+    /// * `None` - If the token at `open_index` is not an opening bracket, or is never closed.
+    pub fn find_matching(&self, open_index: usize) -> Option<usize> {
+        let matches = self.matches.get_or_init(|| match_brackets(&self.tokens));
+        let close = (*matches.get(open_index)?)?;
+        (close > open_index).then_some(close)
+    }
+}
+
+/// Finds the partner of every bracket in one pass
+fn match_brackets(tokens: &[SpannedToken]) -> Vec<Option<usize>> {
+    let mut matches: Vec<Option<usize>> = vec![None; tokens.len()];
+    let mut open: Vec<(usize, Token)> = Vec::new();
+    for (index, spanned) in tokens.iter().enumerate() {
+        if let Some(close) = closer(&spanned.token) {
+            open.push((index, close));
+            continue;
+        }
+        if !matches!(spanned.token, Token::RPAREN | Token::RBRACE | Token::RBRACKET) {
+            continue;
+        }
+        if let Some(depth) = open.iter().rposition(|(_, close)| *close == spanned.token) {
+            let opener = open[depth].0;
+            matches[opener] = Some(index);
+            matches[index] = Some(opener);
+            open.truncate(depth);
+        }
+    }
+    matches
+}
+
+/// Gives the closing bracket of an opening bracket
+fn closer(token: &Token) -> Option<Token> {
+    match token {
+        Token::LPAREN => Some(Token::RPAREN),
+        Token::LBRACE => Some(Token::RBRACE),
+        Token::LBRACKET => Some(Token::RBRACKET),
+        _ => None,
+    }
+}
@@ -0,0 +1,266 @@
+//! This file guesses which language and dialect a file is written in from its contents, for tools processing mixed
+//! codebases where file extensions cannot be trusted, such as headers shared between C and C++ or generated files
+//! without an extension.
+//!
+//! Only the start of a file is sampled. It is lexed with recovery, so foreign syntax does not stop the guess, and
+//! the tokens and the text between them are searched for clues: `::`, `class`, and extensionless headers such as
+//! `<iostream>` point to C++; `let`, `fn`, `=>`, and characters C has no use for point away from C altogether;
+//! `//` comments and the keywords and headers C99 added point to C99 rather than C89. The guess is the language with
+//! the most clues, or C if there are none, with a confidence saying how lopsided the clues were.
+
+use crate::{
+    config::{LexerBuilder, LexerConfig},
+    core::Lexer,
+    project::Dialect,
+    span::SpannedToken,
+    token::{Token, TokenCategory},
+};
+
+/// How many bytes from the start of an input are sampled.
+pub const SAMPLE_BYTES: usize = 64 * 1024;
+
+/// A language family a file may be written in.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Language {
+    /// C, which the lexer is built for.
+    C,
+    /// C++, which the lexer handles well enough with operator aliases turned on.
+    Cpp,
+    /// A language outside the C family, such as Rust or JavaScript, which the C profiles fit poorly.
+    Other,
+}
+
+/// How the lines of a file are indented.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Indentation {
+    /// No line is indented.
+    None,
+    /// Lines are indented with tabs.
+    Tabs,
+    /// Lines are indented with spaces, by the smallest nonzero indentation found.
+    Spaces(usize),
+    /// Some lines are indented with tabs and others with spaces.
+    Mixed,
+}
+
+/// A guess at the language and dialect of a file.
+///
+/// # Fields
+/// * `language` - The language family with the most clues.
+/// * `dialect` - The C dialect to lex with: C99 if any clue points to it, C89 otherwise.
+/// * `confidence` - The share of clues that agree with `language`, from 0.0 when there were none to 1.0 when they
+///   all agree. Clues for C agree with C++, since C++ takes in most of C.
+/// * `indentation` - How the sampled lines are indented, for tools that write code back in the same style.
+/// * `evidence` - A description of every distinct clue found, in the order they were found.
+#[derive(PartialEq, Debug, Clone)]
+pub struct DialectGuess {
+    pub language: Language,
+    pub dialect: Dialect,
+    pub confidence: f64,
+    pub indentation: Indentation,
+    pub evidence: Vec<String>,
+}
+
+impl DialectGuess {
+    /// Creates a builder with the settings of the guessed profile: the C99 contextual keywords for C99, and
+    /// operator aliases for C++.
+    pub fn lexer_builder(&self) -> LexerBuilder {
+        let mut builder = LexerBuilder::new();
+        if self.dialect == Dialect::C99 {
+            builder = builder.c99_contextual_keywords();
+        }
+        builder.operator_aliases(self.language == Language::Cpp)
+    }
+}
+
+/// Words that only C++ gives meaning to
+const CPP_WORDS: [&str; 12] = [
+    "class", "namespace", "template", "typename", "public", "private", "protected", "virtual", "nullptr", "new",
+    "delete", "std",
+];
+
+/// Words that introduce declarations in languages outside the C family
+const OTHER_WORDS: [&str; 10] = ["let", "fn", "func", "function", "def", "var", "import", "impl", "mut", "pub"];
+
+/// Words C99 added
+const C99_WORDS: [&str; 4] = ["inline", "restrict", "_Bool", "_Complex"];
+
+/// Headers C99 added
+const C99_HEADERS: [&str; 5] = ["stdbool.h", "stdint.h", "inttypes.h", "complex.h", "tgmath.h"];
+
+/// Guesses the language and dialect of a file from its contents.
+///
+/// # Parameters
+/// * `input` - The contents of the file. Only the first `SAMPLE_BYTES` are looked at.
+pub fn detect_dialect(input: &str) -> DialectGuess {
+    let mut end = input.len().min(SAMPLE_BYTES);
+    while !input.is_char_boundary(end) {
+        end -= 1;
+    }
+    let sample = &input[..end];
+    let config = LexerConfig::default();
+    let (tokens, errors) = Lexer::lex_recovering(sample, &config);
+
+    let mut clues = Clues::default();
+    for (index, spanned) in tokens.iter().enumerate() {
+        let next = tokens.get(index + 1);
+        let text = &sample[spanned.span.start..spanned.span.end];
+        match &spanned.token {
+            Token::HASHLINE(_) => clues.hash_line(text),
+            Token::IDENTIFIER(_) if CPP_WORDS.contains(&text) => clues.cpp(format!("`{}` is a C++ keyword", text)),
+            Token::IDENTIFIER(_) if OTHER_WORDS.contains(&text) => {
+                clues.other(format!("`{}` declares things in languages outside C", text))
+            }
+            Token::IDENTIFIER(_) if C99_WORDS.contains(&text) => clues.c99(format!("`{}` is a C99 keyword", text)),
+            Token::COLON if next.is_some_and(|next| adjacent(spanned, next, Token::COLON)) => {
+                clues.cpp("`::` qualifies names in C++".to_string())
+            }
+            Token::EQUAL if next.is_some_and(|next| adjacent(spanned, next, Token::GREATERTHAN)) => {
+                clues.other("`=>` is not C".to_string())
+            }
+            Token::TLONG if next.is_some_and(|next| next.token == Token::TLONG) => {
+                clues.c99("`long long` is C99".to_string())
+            }
+            Token::FOR if tokens.get(index + 2).is_some_and(|kind| kind.token.category() == TokenCategory::Type) => {
+                clues.c99("declaring a variable in a `for` loop is C99".to_string())
+            }
+            Token::STRUCT | Token::TINTEGER | Token::TCHAR | Token::TVOID => clues.family += 1,
+            _ => {}
+        }
+    }
+
+    // The text between tokens is trivia and characters the lexer did not recognize
+    let mut previous_end = 0;
+    for spanned in tokens.iter() {
+        if has_line_comment(&sample[previous_end..spanned.span.start]) {
+            clues.c99("`//` comments are C99".to_string());
+        }
+        previous_end = previous_end.max(spanned.span.end);
+    }
+    if !errors.is_empty() {
+        clues.other(format!("{} characters or literals C does not allow", errors.len()));
+    }
+
+    // Plain C is valid C++, so clues for C count for C++ too, and any clue for C++ tips the family to it
+    let total = clues.family + clues.cpp + clues.other;
+    let (language, score) = if clues.other > clues.family + clues.cpp {
+        (Language::Other, clues.other)
+    } else if clues.cpp > 0 {
+        (Language::Cpp, clues.family + clues.cpp)
+    } else {
+        (Language::C, clues.family)
+    };
+    DialectGuess {
+        language,
+        dialect: if clues.c99 > 0 { Dialect::C99 } else { Dialect::C89 },
+        confidence: if total == 0 { 0.0 } else { score as f64 / total as f64 },
+        indentation: indentation(sample),
+        evidence: clues.evidence,
+    }
+}
+
+/// The clues found so far
+///
+/// # Fields
+/// * `family` - How many clues point to C, and so to the C family.
+/// * `cpp` - How many clues point to C++.
+/// * `other` - How many clues point outside the C family.
+/// * `c99` - How many clues point to C99.
+/// * `evidence` - The description of every distinct clue.
+#[derive(Default)]
+struct Clues {
+    family: usize,
+    cpp: usize,
+    other: usize,
+    c99: usize,
+    evidence: Vec<String>,
+}
+
+impl Clues {
+    /// Records a clue for C++
+    fn cpp(&mut self, description: String) {
+        self.cpp += 1;
+        self.note(description);
+    }
+
+    /// Records a clue for a language outside the C family
+    fn other(&mut self, description: String) {
+        self.other += 1;
+        self.note(description);
+    }
+
+    /// Records a clue for C99, which also points to C
+    fn c99(&mut self, description: String) {
+        self.c99 += 1;
+        self.family += 1;
+        self.note(description);
+    }
+
+    /// Looks for clues in a preprocessor line, which on its own points to the C family
+    fn hash_line(&mut self, text: &str) {
+        let include = text.strip_prefix('#').map(str::trim_start).and_then(|rest| rest.strip_prefix("include"));
+        // Includes of a macro rather than a header name say nothing
+        let Some(header) = include.map(str::trim).filter(|header| header.starts_with(['<', '"'])) else {
+            self.family += 1;
+            return;
+        };
+        let header = header.trim_matches(|c| matches!(c, '<' | '>' | '"'));
+        if C99_HEADERS.contains(&header) {
+            self.c99(format!("`{}` is a C99 header", header));
+        } else if !header.contains('.') {
+            self.cpp(format!("`{}` is a C++ standard header", header));
+        } else {
+            self.family += 1;
+        }
+    }
+
+    /// Keeps the description of a clue unless it was already found
+    fn note(&mut self, description: String) {
+        if !self.evidence.contains(&description) {
+            self.evidence.push(description);
+        }
+    }
+}
+
+/// Returns true if `next` is `token` and starts where `spanned` ends
+fn adjacent(spanned: &SpannedToken, next: &SpannedToken, token: Token) -> bool {
+    next.token == token && next.span.start == spanned.span.end
+}
+
+/// Returns true if trivia holds a `//` comment, skipping over block comments, which may contain `//` themselves
+fn has_line_comment(trivia: &str) -> bool {
+    let mut rest = trivia;
+    while let Some(start) = rest.find('/') {
+        rest = &rest[start..];
+        if rest.starts_with("//") {
+            return true;
+        }
+        rest = match rest.strip_prefix("/*") {
+            Some(comment) => comment.find("*/").map_or("", |end| &comment[end + 2..]),
+            None => &rest[1..],
+        };
+    }
+    false
+}
+
+/// Works out how the lines of a sample are indented
+fn indentation(sample: &str) -> Indentation {
+    let mut tabs = false;
+    let mut spaces: Option<usize> = None;
+    for line in sample.lines().filter(|line| !line.trim().is_empty()) {
+        match line.chars().next() {
+            Some('\t') => tabs = true,
+            Some(' ') => {
+                let width = line.chars().take_while(|c| *c == ' ').count();
+                spaces = Some(spaces.map_or(width, |smallest| smallest.min(width)));
+            }
+            _ => {}
+        }
+    }
+    match (tabs, spaces) {
+        (false, None) => Indentation::None,
+        (true, None) => Indentation::Tabs,
+        (false, Some(width)) => Indentation::Spaces(width),
+        (true, Some(_)) => Indentation::Mixed,
+    }
+}
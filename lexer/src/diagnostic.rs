@@ -0,0 +1,207 @@
+//! This file defines diagnostics, which report problems found in the source code along with where they occur.
+//!
+//! Unlike `ErrorType`, which only describes hard failures, a diagnostic carries a severity so that optional
+//! passes such as lints can report warnings without failing the lex.
+
+use std::fmt;
+
+use crate::{
+    column::{display_width, expand_tabs},
+    source::SourceFile,
+    span::{Location, Span},
+};
+
+/// How serious a diagnostic is.
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Hash, PartialOrd, Ord)]
+pub enum Severity {
+    /// The source code cannot be compiled as written.
+    Error,
+    /// The source code is valid but likely to be a mistake.
+    Warning,
+    /// Purely informational.
+    Info,
+}
+
+/// A single message about the source code.
+///
+/// # Fields
+/// * `severity` - How serious the diagnostic is.
+/// * `code` - A short, stable identifier for the kind of diagnostic, such as `"identifier-too-long"`.
+/// * `message` - A human readable description of the problem.
+/// * `span` - The location in the source code the diagnostic refers to.
+/// * `suppressed` - Whether a comment in the source code suppresses the diagnostic, as described in the `suppress`
+///   module. Suppressed diagnostics are still reported, but do not count towards failure.
+#[derive(PartialEq, Debug, Clone)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub code: &'static str,
+    pub message: String,
+    pub span: Span,
+    pub suppressed: bool,
+}
+
+impl Diagnostic {
+    /// Creates an error-severity diagnostic.
+    pub fn error(code: &'static str, message: String, span: Span) -> Self {
+        Self { severity: Severity::Error, code, message, span, suppressed: false }
+    }
+
+    /// Creates a warning-severity diagnostic.
+    pub fn warning(code: &'static str, message: String, span: Span) -> Self {
+        Self { severity: Severity::Warning, code, message, span, suppressed: false }
+    }
+
+    /// Creates an info-severity diagnostic.
+    pub fn info(code: &'static str, message: String, span: Span) -> Self {
+        Self { severity: Severity::Info, code, message, span, suppressed: false }
+    }
+
+    /// Renders the diagnostic for a terminal, quoting the offending line of source with carets under the span.
+    ///
+    /// Columns are measured in display cells, so carets stay aligned on lines containing tabs, wide characters,
+    /// or combining marks. A span covering several lines is underlined to the end of its first line.
+    ///
+    /// # Parameters
+    /// * `source` - The source code the diagnostic refers to.
+    /// * `tab_width` - The distance between tab stops.
+    pub fn render(&self, source: &str, tab_width: usize) -> String {
+        let mut annotations = Annotations::new();
+        annotations.label(self.span, "");
+        self.render_annotated(source, tab_width, &annotations)
+    }
+
+    /// Renders the diagnostic with labels attached to spans of the source, such as the use of a name and the
+    /// place it was declared.
+    ///
+    /// The header points at the diagnostic's own span. Each line of source with a label on it is quoted once, in
+    /// line order, with one underline per label: `^` for primary labels and `-` for secondary ones.
+    ///
+    /// # Parameters
+    /// * `source` - The source code the diagnostic refers to.
+    /// * `tab_width` - The distance between tab stops.
+    /// * `annotations` - The labels to show.
+    pub fn render_annotated(&self, source: &str, tab_width: usize, annotations: &Annotations) -> String {
+        let file = SourceFile::new("", source);
+        let index = file.line_index();
+        let start = index.location(source, self.span.start, tab_width);
+        let mut labels: Vec<(Location, &Label)> = annotations.labels
+            .iter()
+            .map(|label| (index.location(source, label.span.start, tab_width), label))
+            .collect();
+        labels.sort_by_key(|(location, _)| (location.line, location.display_column));
+
+        let last_line = labels.iter().map(|(location, _)| location.line).max().unwrap_or(0).max(start.line);
+        let gutter = " ".repeat(last_line.to_string().len());
+        let mut rendered = format!(
+            "{}[{}]: {}\n{}--> {}:{}\n{} |\n",
+            self.severity, self.code, self.message,
+            gutter, start.line, start.display_column,
+            gutter,
+        );
+
+        let mut quoted_line: Option<usize> = None;
+        for (location, label) in labels {
+            let line = file.line_containing(label.span.start);
+            let (line_start, line_end) = (line.span.start, line.span.end);
+            if quoted_line != Some(location.line) {
+                rendered += &format!(
+                    "{:>width$} | {}\n",
+                    location.line, expand_tabs(line.text, tab_width),
+                    width = gutter.len(),
+                );
+                quoted_line = Some(location.line);
+            }
+
+            let underline_end = label.span.end.clamp(line_start, line_end);
+            let underline_width = display_width(&source[line_start..underline_end], tab_width)
+                .saturating_sub(location.display_column - 1)
+                .max(1);
+            let marker = match label.style {
+                LabelStyle::Primary => "^",
+                LabelStyle::Secondary => "-",
+            };
+            let mut underline = format!("{}{}", " ".repeat(location.display_column - 1), marker.repeat(underline_width));
+            if !label.message.is_empty() {
+                underline += &format!(" {}", label.message);
+            }
+            rendered += &format!("{} | {}\n", gutter, underline);
+        }
+        rendered
+    }
+}
+
+/// Whether a label marks the main cause of a diagnostic or supporting context.
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Hash)]
+pub enum LabelStyle {
+    /// The location the diagnostic is about, underlined with `^`.
+    Primary,
+    /// Related context, underlined with `-`.
+    Secondary,
+}
+
+/// A message attached to a span of source code.
+///
+/// # Fields
+/// * `span` - The source code the label points at.
+/// * `message` - The text shown beside the underline. May be empty.
+/// * `style` - Whether the label is primary or secondary.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Label {
+    pub span: Span,
+    pub message: String,
+    pub style: LabelStyle,
+}
+
+/// A set of labels for later passes, such as the parser or semantic analysis, to attach to spans provided by the
+/// lexer, rendered together by `Diagnostic::render_annotated`.
+///
+/// # Fields
+/// * `labels` - The labels, in the order they were added.
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub struct Annotations {
+    labels: Vec<Label>,
+}
+
+impl Annotations {
+    /// Creates an empty set of labels.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a primary label.
+    pub fn label(&mut self, span: Span, text: &str) -> &mut Self {
+        self.push(span, text, LabelStyle::Primary)
+    }
+
+    /// Adds a secondary label.
+    pub fn secondary(&mut self, span: Span, text: &str) -> &mut Self {
+        self.push(span, text, LabelStyle::Secondary)
+    }
+
+    /// Gives the labels in the order they were added.
+    pub fn labels(&self) -> &[Label] {
+        &self.labels
+    }
+
+    /// Adds a label of the given style.
+    fn push(&mut self, span: Span, text: &str, style: LabelStyle) -> &mut Self {
+        self.labels.push(Label { span, message: text.to_string(), style });
+        self
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+            Severity::Info => write!(f, "info"),
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}[{}]: {} at {}..{}", self.severity, self.code, self.message, self.span.start, self.span.end)
+    }
+}
@@ -0,0 +1,71 @@
+//! This file reports the preprocessor lines found in a token stream.
+//!
+//! The lexer does not run the preprocessor. It passes each `#` line through as a `Token::HASHLINE` so that real C
+//! files, which nearly all begin with `#include`, can still be lexed. The check here lists those lines so that
+//! users know they were not acted on, and warns about directives that no C preprocessor would accept.
+
+use crate::{
+    diagnostic::Diagnostic,
+    span::SpannedToken,
+    token::Token,
+};
+
+/// The directives defined by the C standard, plus the common `warning` extension.
+const KNOWN_DIRECTIVES: [&str; 14] = [
+    "include", "define", "undef", "if", "ifdef", "ifndef", "elif", "else", "endif",
+    "line", "error", "warning", "pragma", "embed",
+];
+
+/// Gives the name of the directive on a preprocessor line, such as `"include"` for `#include <stdio.h>`.
+///
+/// # Returns
+/// * `Some(String)` - The name, which is empty for a line holding only `#`.
+/// * `None` - If the token is not a `Token::HASHLINE`.
+pub fn directive_name(token: &Token) -> Option<String> {
+    let Token::HASHLINE(text) = token else {
+        return None;
+    };
+    Some(text.iter()
+        .skip(1)
+        .skip_while(|c| c.is_whitespace())
+        .take_while(|c| c.is_ascii_alphanumeric() || **c == '_')
+        .collect())
+}
+
+/// Lists every preprocessor line in a stream of spanned tokens.
+///
+/// # Parameters
+/// * `tokens` - The tokens to check, as produced by `Lexer::lex_spanned`.
+///
+/// # Returns
+/// * `Vec<Diagnostic>` - In source order, an info diagnostic for every known directive and every line holding
+///   only `#`, and a warning for every unknown directive.
+pub fn check_directives(tokens: &[SpannedToken]) -> Vec<Diagnostic> {
+    let mut diagnostics: Vec<Diagnostic> = Vec::new();
+
+    for spanned in tokens {
+        let Some(name) = directive_name(&spanned.token) else {
+            continue;
+        };
+        if name.is_empty() {
+            diagnostics.push(Diagnostic::info(
+                "null-directive",
+                "empty preprocessor line has no effect".to_string(),
+                spanned.span,
+            ));
+        } else if KNOWN_DIRECTIVES.contains(&name.as_str()) {
+            diagnostics.push(Diagnostic::info(
+                "preprocessor-directive",
+                format!("`#{}` directive was passed through without preprocessing", name),
+                spanned.span,
+            ));
+        } else {
+            diagnostics.push(Diagnostic::warning(
+                "unknown-directive",
+                format!("unknown preprocessor directive `#{}`", name),
+                spanned.span,
+            ));
+        }
+    }
+    diagnostics
+}
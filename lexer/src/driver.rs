@@ -0,0 +1,297 @@
+//! This file analyzes a whole project at once: it finds the source files under a root directory, lexes and checks
+//! them in parallel, and gathers their diagnostics and metrics into one result.
+//!
+//! Each file is lexed with error recovery and then run through the same checks as `check::check_source`, using the
+//! lexer and lint settings of the `DriverConfig`. A file that cannot be read is reported with an `unreadable-file`
+//! error rather than stopping the analysis.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::Mutex,
+    thread,
+    time::{Duration, Instant},
+};
+
+use common::error::ErrorType;
+
+use crate::{
+    check::{check_lexed_with, FileReport},
+    config::LexerConfig,
+    core::Lexer,
+    diagnostic::{Diagnostic, Severity},
+    lint::LintConfig,
+    parallel::map_with_threads,
+    progress::{Progress, ProgressReporter},
+    project::{is_ignored, ProjectConfig, CONFIG_FILE_NAME},
+    span::Span,
+};
+
+/// Controls which files a project analysis covers and how they are lexed.
+///
+/// # Fields
+/// * `lexer` - The settings every file is lexed with.
+/// * `lints` - Which identifier lints run.
+/// * `extensions` - The extensions, without the `.`, of the files to analyze.
+/// * `ignore` - Patterns for files and directories to skip, as described in the `project` module.
+/// * `threads` - How many worker threads to use, or `None` for one per available core.
+#[derive(Clone)]
+pub struct DriverConfig {
+    pub lexer: LexerConfig,
+    pub lints: LintConfig,
+    pub extensions: Vec<String>,
+    pub ignore: Vec<String>,
+    pub threads: Option<usize>,
+}
+
+impl DriverConfig {
+    /// Reads the settings from the `lexer.toml` at the root of a project, or uses the defaults if there is none.
+    ///
+    /// # Errors
+    /// * `ErrorType::IoError` or `ErrorType::InvalidConfigFile` if the file cannot be read or is invalid.
+    /// * `ErrorType::InvalidIdentifierCharacter` if it allows an identifier character that is not allowed.
+    pub fn for_project(root: &Path) -> Result<Self, ErrorType> {
+        let path = root.join(CONFIG_FILE_NAME);
+        if !path.is_file() {
+            return Ok(DriverConfig::default());
+        }
+        ProjectConfig::load(&path)?.driver_config()
+    }
+}
+
+impl Default for DriverConfig {
+    fn default() -> Self {
+        Self {
+            lexer: LexerConfig::default(),
+            lints: LintConfig::default(),
+            extensions: vec!["c".to_string(), "h".to_string()],
+            ignore: Vec::new(),
+            threads: None,
+        }
+    }
+}
+
+/// The analysis of one file.
+///
+/// # Fields
+/// * `report` - The file's diagnostics, with its path relative to the project root.
+/// * `tokens` - The number of tokens lexed, including `EOF`.
+/// * `elapsed` - How long reading, lexing, and checking the file took.
+#[derive(PartialEq, Debug, Clone)]
+pub struct FileAnalysis {
+    pub report: FileReport,
+    pub tokens: usize,
+    pub elapsed: Duration,
+}
+
+/// Totals over every file in a project.
+///
+/// # Fields
+/// * `files` - The number of files analyzed.
+/// * `bytes` - The total size of the files that could be read.
+/// * `tokens` - The total number of tokens.
+/// * `errors` - The number of error diagnostics.
+/// * `warnings` - The number of warning diagnostics.
+/// * `infos` - The number of info diagnostics.
+/// * `suppressed` - The number of diagnostics muted by suppression comments, which are not counted by severity.
+/// * `elapsed` - The wall-clock time of the whole analysis, including finding the files.
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub struct ProjectMetrics {
+    pub files: usize,
+    pub bytes: usize,
+    pub tokens: usize,
+    pub errors: usize,
+    pub warnings: usize,
+    pub infos: usize,
+    pub suppressed: usize,
+    pub elapsed: Duration,
+}
+
+/// The result of analyzing a project.
+///
+/// # Fields
+/// * `root` - The directory that was analyzed.
+/// * `files` - The analysis of every file, in path order.
+/// * `metrics` - Totals over every file.
+#[derive(PartialEq, Debug, Clone)]
+pub struct ProjectAnalysis {
+    pub root: PathBuf,
+    pub files: Vec<FileAnalysis>,
+    pub metrics: ProjectMetrics,
+}
+
+impl ProjectAnalysis {
+    /// Returns true if any file has an error diagnostic.
+    pub fn has_errors(&self) -> bool {
+        self.metrics.errors > 0
+    }
+
+    /// Gives the report of every file, in path order, ready for `check::write_json` or `sarif::write_sarif`.
+    pub fn reports(&self) -> Vec<FileReport> {
+        self.files.iter().map(|file| file.report.clone()).collect()
+    }
+}
+
+/// Finds, lexes, and checks every source file under a project root.
+///
+/// Hidden files and directories, whose names start with `.`, are skipped, as are those matching an ignore
+/// pattern.
+///
+/// # Parameters
+/// * `root` - The root directory of the project.
+/// * `config` - Which files to analyze and how.
+///
+/// # Returns
+/// * `Ok(ProjectAnalysis)` - The diagnostics and metrics of every file.
+/// * `Err(ErrorType)` - `ErrorType::IoError` if a directory cannot be read.
+pub fn analyze_project(root: &Path, config: &DriverConfig) -> Result<ProjectAnalysis, ErrorType> {
+    analyze_project_with_progress(root, config, &|_: &Progress| {})
+}
+
+/// Analyzes a project as `analyze_project` does, reporting progress as it goes.
+///
+/// Progress is reported when each file is started, with the file as `current_file`, and when it is finished, with
+/// no current file and its bytes counted. A project with no files gets a single report. The total bytes are the
+/// sizes the files had when they were found.
+///
+/// # Parameters
+/// * `root` - The root directory of the project.
+/// * `config` - Which files to analyze and how.
+/// * `reporter` - What to report progress to.
+///
+/// # Returns
+/// * `Ok(ProjectAnalysis)` - The diagnostics and metrics of every file.
+/// * `Err(ErrorType)` - `ErrorType::IoError` if a directory cannot be read.
+pub fn analyze_project_with_progress(
+    root: &Path,
+    config: &DriverConfig,
+    reporter: &dyn ProgressReporter,
+) -> Result<ProjectAnalysis, ErrorType> {
+    let start = Instant::now();
+    let paths = discover_files(root, config)?;
+    let sizes: Vec<usize> =
+        paths.iter().map(|path| fs::metadata(path).map_or(0, |metadata| metadata.len() as usize)).collect();
+    let (bytes_total, files_total) = (sizes.iter().sum(), paths.len());
+    // Bytes processed and files completed, locked while reporting so that reports come in order
+    let done: Mutex<(usize, usize)> = Mutex::new((0, 0));
+    let report = |current_file: Option<&Path>, finished: Option<usize>| {
+        let mut done = done.lock().expect("a progress reporter panicked");
+        if let Some(size) = finished {
+            *done = (done.0 + size, done.1 + 1);
+        }
+        let (bytes_processed, files_completed) = *done;
+        reporter.report(&Progress { bytes_processed, bytes_total, files_completed, files_total, current_file });
+    };
+    if paths.is_empty() {
+        report(None, None);
+    }
+
+    let threads = config.threads.unwrap_or_else(|| thread::available_parallelism().map_or(1, |n| n.get()));
+    let indices: Vec<usize> = (0..paths.len()).collect();
+    let files: Vec<FileAnalysis> = map_with_threads(&indices, threads, |&index| {
+        report(Some(&paths[index]), None);
+        let analysis = analyze_file(root, &paths[index], config);
+        report(None, Some(sizes[index]));
+        analysis
+    });
+
+    let mut metrics = ProjectMetrics { files: files.len(), ..ProjectMetrics::default() };
+    for file in &files {
+        metrics.bytes += file.report.source.len();
+        metrics.tokens += file.tokens;
+        for diagnostic in &file.report.diagnostics {
+            if diagnostic.suppressed {
+                metrics.suppressed += 1;
+                continue;
+            }
+            match diagnostic.severity {
+                Severity::Error => metrics.errors += 1,
+                Severity::Warning => metrics.warnings += 1,
+                Severity::Info => metrics.infos += 1,
+            }
+        }
+    }
+    metrics.elapsed = start.elapsed();
+    Ok(ProjectAnalysis { root: root.to_path_buf(), files, metrics })
+}
+
+/// Finds every file under a project root with one of the configured extensions.
+///
+/// # Returns
+/// * `Ok(Vec<PathBuf>)` - The files, in path order.
+/// * `Err(ErrorType)` - `ErrorType::IoError` if a directory cannot be read.
+pub fn discover_files(root: &Path, config: &DriverConfig) -> Result<Vec<PathBuf>, ErrorType> {
+    let mut paths: Vec<PathBuf> = Vec::new();
+    collect_files(root, root, config, &mut paths)?;
+    paths.sort();
+    Ok(paths)
+}
+
+/// Checks whether `discover_files` would find a file under a project root: it has one of the configured
+/// extensions, and neither it nor any directory between it and the root is hidden or ignored.
+pub fn is_source_file(root: &Path, path: &Path, config: &DriverConfig) -> bool {
+    let Ok(relative) = path.strip_prefix(root) else {
+        return false;
+    };
+    let mut prefix: PathBuf = PathBuf::new();
+    for component in relative.components() {
+        prefix.push(component);
+        if is_skipped(&prefix, config) {
+            return false;
+        }
+    }
+    has_extension(path, &config.extensions)
+}
+
+/// Reads, lexes, and checks one file
+fn analyze_file(root: &Path, path: &Path, config: &DriverConfig) -> FileAnalysis {
+    let start = Instant::now();
+    let display = path.strip_prefix(root).unwrap_or(path).display().to_string();
+    let source = match fs::read_to_string(path) {
+        Ok(source) => source,
+        Err(error) => {
+            let diagnostic = Diagnostic::error("unreadable-file", error.to_string(), Span::new(0, 0));
+            let report = FileReport { path: display, source: String::new(), diagnostics: vec![diagnostic] };
+            return FileAnalysis { report, tokens: 0, elapsed: start.elapsed() };
+        }
+    };
+
+    let (tokens, errors, reported) = Lexer::lex_with_diagnostics(&source, &config.lexer);
+    let diagnostics = check_lexed_with(&source, &tokens, &errors, reported, &config.lints);
+    let report = FileReport { path: display, source, diagnostics };
+    FileAnalysis { report, tokens: tokens.len(), elapsed: start.elapsed() }
+}
+
+/// Adds every file under `dir` with a configured extension to `paths`, skipping hidden and ignored entries
+fn collect_files(root: &Path, dir: &Path, config: &DriverConfig, paths: &mut Vec<PathBuf>) -> Result<(), ErrorType> {
+    let entries = fs::read_dir(dir).map_err(|error| io_error(dir, error))?;
+    for entry in entries {
+        let path = entry.map_err(|error| io_error(dir, error))?.path();
+        if is_skipped(path.strip_prefix(root).unwrap_or(&path), config) {
+            continue;
+        }
+        if path.is_dir() {
+            collect_files(root, &path, config, paths)?;
+        } else if has_extension(&path, &config.extensions) {
+            paths.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Checks whether a file or directory, relative to the project root, is hidden or ignored
+fn is_skipped(relative: &Path, config: &DriverConfig) -> bool {
+    let name = relative.file_name().and_then(|name| name.to_str()).unwrap_or("");
+    name.starts_with('.') || is_ignored(relative, &config.ignore)
+}
+
+/// Checks whether a file's extension is one of `extensions`
+fn has_extension(path: &Path, extensions: &[String]) -> bool {
+    let extension = path.extension().and_then(|extension| extension.to_str());
+    extension.is_some_and(|extension| extensions.iter().any(|allowed| allowed == extension))
+}
+
+/// Helper function to create an error for a failed file operation
+fn io_error(path: &Path, error: std::io::Error) -> ErrorType {
+    ErrorType::IoError { path: path.display().to_string(), message: error.to_string() }
+}
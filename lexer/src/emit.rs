@@ -0,0 +1,300 @@
+//! This file turns token streams back into indented, readable source, for code generators built on
+//! `splice::TokenStreamBuilder` and for formatters. Where `splice::detokenize` writes the least source that lexes
+//! back to the same tokens, `emit` lays it out the way a person would: one statement per line, blocks indented, and
+//! spaces around binary operators and after commas.
+//!
+//! No `#line` directives are written, so the output reads as ordinary source rather than as generated code pointing
+//! back at its templates. The output always lexes back to the same tokens. Comments are not tokens, so they are
+//! not kept.
+//!
+//! Layout is decided from the tokens alone, without parsing, so a few constructs are guessed at: `*` and `&` are
+//! taken as unary after an operator, an opening bracket, a keyword, or a type, which makes `int *p` a declaration
+//! but `size * count` a product, and `{` after `=`, or inside parentheses or another such brace, opens an
+//! initializer kept on one line rather than a block.
+
+use crate::{
+    splice::{needs_space, token_text},
+    token::Token,
+};
+
+/// Where the opening brace of a block goes.
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub enum BraceStyle {
+    /// On the same line as what comes before it, as in `if (x) {`, with `else` after the closing brace.
+    #[default]
+    SameLine,
+    /// On a line of its own, as in the Allman style.
+    NextLine,
+}
+
+/// How emitted source is laid out.
+///
+/// # Fields
+/// * `indent` - The text one level of indentation adds, such as four spaces or a tab.
+/// * `brace_style` - Where the opening brace of a block goes.
+/// * `space_around_binary` - Whether binary operators, including assignment, are surrounded by spaces.
+/// * `space_after_comma` - Whether a comma is followed by a space.
+/// * `space_after_keyword` - Whether a keyword such as `if` or `while` is followed by a space before `(`.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct EmitStyle {
+    pub indent: String,
+    pub brace_style: BraceStyle,
+    pub space_around_binary: bool,
+    pub space_after_comma: bool,
+    pub space_after_keyword: bool,
+}
+
+impl Default for EmitStyle {
+    fn default() -> Self {
+        Self {
+            indent: "    ".to_string(),
+            brace_style: BraceStyle::SameLine,
+            space_around_binary: true,
+            space_after_comma: true,
+            space_after_keyword: true,
+        }
+    }
+}
+
+/// What an open brace started
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+enum Brace {
+    /// A block of statements
+    Block,
+    /// The body of a `do` loop, which `while` follows on the same line
+    DoBlock,
+    /// An initializer, kept on one line
+    Initializer,
+}
+
+/// Lays tokens out as indented source. `EOF` and `DEFAULT` are skipped.
+///
+/// # Parameters
+/// * `tokens` - The tokens to write, such as those of `TokenStreamBuilder::tokens` or a lexed file.
+/// * `style` - How to lay them out.
+///
+/// # Returns
+/// * `String` - The source, ending with a newline unless there were no tokens.
+pub fn emit(tokens: &[Token], style: &EmitStyle) -> String {
+    let tokens: Vec<&Token> = tokens.iter().filter(|token| !matches!(token, Token::EOF | Token::DEFAULT)).collect();
+    let mut emitter =
+        Emitter { style, out: String::new(), depth: 0, parens: 0, braces: Vec::new(), label: false, unary: false };
+    for (index, token) in tokens.iter().enumerate() {
+        let previous = index.checked_sub(1).map(|previous| tokens[previous]);
+        emitter.token(previous, token, tokens.get(index + 1).copied());
+    }
+
+    let mut out: String = emitter.out.lines().map(|line| line.trim_end().to_string() + "\n").collect();
+    while out.starts_with('\n') {
+        out.remove(0);
+    }
+    out
+}
+
+/// The state of the layout as tokens are written
+///
+/// # Fields
+/// * `style` - How to lay tokens out.
+/// * `out` - The source written so far.
+/// * `depth` - The number of blocks open.
+/// * `parens` - The number of parentheses open, inside which semicolons do not end lines.
+/// * `braces` - What every open brace started.
+/// * `label` - Whether the line so far is `case` and its value, so that the next `:` ends it.
+/// * `unary` - Whether the last token written was a unary operator, which its operand follows without a space.
+struct Emitter<'a> {
+    style: &'a EmitStyle,
+    out: String,
+    depth: usize,
+    parens: usize,
+    braces: Vec<Brace>,
+    label: bool,
+    unary: bool,
+}
+
+impl Emitter<'_> {
+    /// Writes one token with the whitespace that goes before it
+    fn token(&mut self, previous: Option<&Token>, token: &Token, next: Option<&Token>) {
+        let text = token_text(token);
+        match token {
+            Token::HASHLINE(_) => {
+                self.unary = false;
+                self.newline();
+                self.out.push_str(&text);
+                self.out.push('\n');
+            }
+            Token::LBRACKET if self.opens_block(previous) => {
+                self.unary = false;
+                let brace = if previous == Some(&Token::DO) { Brace::DoBlock } else { Brace::Block };
+                self.braces.push(brace);
+                match self.style.brace_style {
+                    BraceStyle::SameLine if !self.at_line_start() => self.out.push(' '),
+                    BraceStyle::SameLine => self.indent(),
+                    BraceStyle::NextLine => {
+                        self.newline();
+                        self.indent();
+                    }
+                }
+                self.out.push('{');
+                self.depth += 1;
+                self.newline();
+            }
+            Token::RBRACKET if matches!(self.braces.last(), Some(Brace::Block | Brace::DoBlock) | None) => {
+                let brace = self.braces.pop().unwrap_or(Brace::Block);
+                self.unary = false;
+                self.depth = self.depth.saturating_sub(1);
+                self.newline();
+                self.indent();
+                self.out.push('}');
+                let continues = match next {
+                    Some(Token::SEMICOLON | Token::COMMA) => true,
+                    Some(Token::ELSE) => self.style.brace_style == BraceStyle::SameLine,
+                    Some(Token::WHILE) => brace == Brace::DoBlock && self.style.brace_style == BraceStyle::SameLine,
+                    _ => false,
+                };
+                if !continues {
+                    self.newline();
+                }
+            }
+            _ => {
+                match token {
+                    Token::LBRACKET => self.braces.push(Brace::Initializer),
+                    Token::RBRACKET => {
+                        self.braces.pop();
+                    }
+                    Token::LPAREN => self.parens += 1,
+                    Token::RPAREN => self.parens = self.parens.saturating_sub(1),
+                    Token::CASE => self.label = true,
+                    _ => {}
+                }
+                let unary = is_unary(token) && previous.is_none_or(|previous| !is_operand(previous));
+                let ends_line = self.ends_line(previous, token);
+                if self.at_line_start() {
+                    self.indent();
+                } else if let Some(previous) = previous {
+                    if self.spaced(previous, token, unary) || needs_space(&token_text(previous), &text) {
+                        self.out.push(' ');
+                    }
+                }
+                self.out.push_str(&text);
+                self.unary = unary;
+                if ends_line {
+                    self.label = false;
+                    self.newline();
+                }
+            }
+        }
+    }
+
+    /// Returns true if a `{` opens a block rather than an initializer
+    fn opens_block(&self, previous: Option<&Token>) -> bool {
+        self.parens == 0 && self.braces.last() != Some(&Brace::Initializer) && previous != Some(&Token::EQUAL)
+    }
+
+    /// Returns true if a token ends its line: a semicolon outside parentheses, or the colon of a label
+    fn ends_line(&self, previous: Option<&Token>, token: &Token) -> bool {
+        match token {
+            Token::SEMICOLON => self.parens == 0 && self.braces.last() != Some(&Brace::Initializer),
+            // `default` is not a keyword, so a colon after an identifier that starts its line is a label too
+            Token::COLON => self.label || (matches!(previous, Some(Token::IDENTIFIER(_))) && self.line_has_one_word()),
+            _ => false,
+        }
+    }
+
+    /// Returns true if a space goes between two tokens, by the style
+    fn spaced(&self, previous: &Token, token: &Token, unary: bool) -> bool {
+        let style = self.style;
+        match (previous, token) {
+            (_, Token::RPAREN | Token::RBRACE | Token::SEMICOLON | Token::COMMA | Token::DOT | Token::POINTER) => false,
+            (Token::LPAREN | Token::LBRACE | Token::DOT | Token::POINTER, _) => false,
+            (Token::EXCLAMATIONPOINT | Token::TILDE, _) => false,
+            _ if self.unary => false,
+            // Only initializers reach here, which are kept tight, as in `{1, 2}`
+            (Token::LBRACKET, _) | (_, Token::RBRACKET) => false,
+            (_, Token::COLON) if self.label || self.line_has_one_word() => false,
+            // C's compound assignments lex as an operator followed by `=`, and are written together
+            (Token::PLUS | Token::DASH | Token::ASTERISK | Token::FSLASH | Token::PERCENT, Token::EQUAL)
+            | (Token::AMPERSAND | Token::BAR | Token::CARET, Token::EQUAL) => false,
+            (Token::COMMA, _) => style.space_after_comma,
+            (Token::IF | Token::FOR | Token::WHILE | Token::SWITCH, Token::LPAREN) => style.space_after_keyword,
+            (previous, Token::LPAREN | Token::LBRACE) if is_operand(previous) => false,
+            (Token::PLUSPLUS | Token::MINUSMINUS, token) if is_operand(token) => false,
+            (previous, Token::PLUSPLUS | Token::MINUSMINUS) if is_operand(previous) => false,
+            (_, token) if is_binary(token) && !unary => style.space_around_binary,
+            (previous, _) if is_binary(previous) => style.space_around_binary,
+            _ => true,
+        }
+    }
+
+    /// Returns true if the current line holds a single word so far
+    fn line_has_one_word(&self) -> bool {
+        let line = self.out.rsplit('\n').next().unwrap_or("").trim();
+        !line.is_empty() && line.chars().all(|c| c.is_alphanumeric() || c == '_')
+    }
+
+    /// Returns true if nothing but indentation has been written on the current line
+    fn at_line_start(&self) -> bool {
+        self.out.rsplit('\n').next().unwrap_or("").trim().is_empty()
+    }
+
+    /// Ends the current line unless it is empty
+    fn newline(&mut self) {
+        if !self.at_line_start() {
+            self.out.push('\n');
+        }
+        let line_start = self.out.rfind('\n').map_or(0, |index| index + 1);
+        self.out.truncate(line_start);
+    }
+
+    /// Indents an empty line to the current depth
+    fn indent(&mut self) {
+        self.out.push_str(&self.style.indent.repeat(self.depth));
+    }
+}
+
+/// Returns true if a token can end an operand, so that an operator after it is binary or postfix
+fn is_operand(token: &Token) -> bool {
+    matches!(
+        token,
+        Token::IDENTIFIER(_)
+            | Token::TYPENAME(_)
+            | Token::NUMBER(_)
+            | Token::STRINGLITERAL(_)
+            | Token::CHAR(_)
+            | Token::CUSTOM(..)
+            | Token::RPAREN
+            | Token::RBRACE
+            | Token::PLUSPLUS
+            | Token::MINUSMINUS
+    )
+}
+
+/// Returns true if a token can be a unary operator
+fn is_unary(token: &Token) -> bool {
+    matches!(token, Token::DASH | Token::PLUS | Token::ASTERISK | Token::AMPERSAND)
+}
+
+/// Returns true if a token can be a binary operator
+fn is_binary(token: &Token) -> bool {
+    matches!(
+        token,
+        Token::PLUS
+            | Token::DASH
+            | Token::ASTERISK
+            | Token::FSLASH
+            | Token::PERCENT
+            | Token::EQUAL
+            | Token::EQUALEQUAL
+            | Token::NOTEQUAL
+            | Token::LESSTHAN
+            | Token::GREATERTHAN
+            | Token::LESSTHANEQUAL
+            | Token::GREATERTHANEQUAL
+            | Token::ANDAND
+            | Token::BARBAR
+            | Token::AMPERSAND
+            | Token::BAR
+            | Token::CARET
+            | Token::CTRUE
+            | Token::COLON
+    )
+}
@@ -0,0 +1,182 @@
+//! This file detects the text encoding of raw source bytes and transcodes them to UTF-8 for lexing.
+//!
+//! Transcoding changes where characters sit, so the result keeps a map from offsets in the UTF-8 text back to
+//! offsets in the original bytes. Spans can then be reported against the file as it exists on disk.
+
+use common::error::ErrorType;
+
+use crate::{
+    diagnostic::Diagnostic,
+    span::Span,
+};
+
+/// The text encodings source code can be read in.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Encoding {
+    /// Detect the encoding: a byte order mark selects UTF-8 or UTF-16, otherwise valid UTF-8 is read as UTF-8
+    /// and anything else as Latin-1.
+    Auto,
+    /// UTF-8, with an optional byte order mark.
+    Utf8,
+    /// Little-endian UTF-16, with an optional byte order mark.
+    Utf16Le,
+    /// Big-endian UTF-16, with an optional byte order mark.
+    Utf16Be,
+    /// ISO-8859-1, where every byte is the character with the same code point.
+    Latin1,
+}
+
+impl Encoding {
+    /// Gives the conventional name of the encoding.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Encoding::Auto => "auto",
+            Encoding::Utf8 => "UTF-8",
+            Encoding::Utf16Le => "UTF-16LE",
+            Encoding::Utf16Be => "UTF-16BE",
+            Encoding::Latin1 => "ISO-8859-1",
+        }
+    }
+}
+
+/// Source code transcoded to UTF-8.
+///
+/// # Fields
+/// * `text` - The transcoded source code, without any byte order mark.
+/// * `encoding` - The encoding the bytes were read in. Never `Encoding::Auto`.
+/// * `offsets` - Pairs of the UTF-8 offset of each character in `text` and the offset of the same character in
+///   the original bytes, followed by a pair for the end of both.
+#[derive(PartialEq, Debug, Clone)]
+pub struct Transcoded {
+    pub text: String,
+    pub encoding: Encoding,
+    offsets: Vec<(usize, usize)>,
+}
+
+impl Transcoded {
+    /// Converts a byte offset in `text` to the corresponding byte offset in the original input. Offsets inside a
+    /// character map to the start of that character.
+    pub fn original_offset(&self, offset: usize) -> usize {
+        match self.offsets.binary_search_by_key(&offset, |(utf8, _)| *utf8) {
+            Ok(index) => self.offsets[index].1,
+            Err(index) => self.offsets[index.saturating_sub(1)].1,
+        }
+    }
+}
+
+/// Decodes raw source bytes into UTF-8.
+///
+/// # Parameters
+/// * `bytes` - The raw contents of a source file.
+/// * `encoding` - The encoding to read the bytes in, or `Encoding::Auto` to detect it.
+///
+/// # Returns
+/// * `Ok(Transcoded)` - The source code as UTF-8, with a map back to the original offsets.
+/// * `Err(ErrorType)` - `ErrorType::InvalidEncoding` if the bytes are not valid in the requested encoding.
+pub fn transcode(bytes: &[u8], encoding: Encoding) -> Result<Transcoded, ErrorType> {
+    let encoding = match encoding {
+        Encoding::Auto => detect(bytes),
+        explicit => explicit,
+    };
+
+    let mut text = String::with_capacity(bytes.len());
+    let mut offsets: Vec<(usize, usize)> = Vec::with_capacity(bytes.len() + 1);
+    let mut push = |c: char, original: usize, text: &mut String| {
+        offsets.push((text.len(), original));
+        text.push(c);
+    };
+
+    match encoding {
+        Encoding::Utf8 | Encoding::Auto => {
+            let start = if bytes.starts_with(&[0xEF, 0xBB, 0xBF]) { 3 } else { 0 };
+            let decoded = std::str::from_utf8(&bytes[start..]).map_err(|error| ErrorType::InvalidEncoding {
+                encoding: encoding.name().to_string(),
+                offset: start + error.valid_up_to(),
+            })?;
+            for (offset, c) in decoded.char_indices() {
+                push(c, start + offset, &mut text);
+            }
+        }
+        Encoding::Utf16Le | Encoding::Utf16Be => {
+            let bom: [u8; 2] = if encoding == Encoding::Utf16Le { [0xFF, 0xFE] } else { [0xFE, 0xFF] };
+            let start = if bytes.starts_with(&bom) { 2 } else { 0 };
+            let invalid = |offset: usize| ErrorType::InvalidEncoding { encoding: encoding.name().to_string(), offset };
+            if !(bytes.len() - start).is_multiple_of(2) {
+                return Err(invalid(bytes.len() - 1));
+            }
+
+            let units = bytes[start..].chunks(2).map(|pair| match encoding {
+                Encoding::Utf16Le => u16::from_le_bytes([pair[0], pair[1]]),
+                _ => u16::from_be_bytes([pair[0], pair[1]]),
+            });
+            let mut original = start;
+            for decoded in char::decode_utf16(units) {
+                let c = decoded.map_err(|_| invalid(original))?;
+                push(c, original, &mut text);
+                original += c.len_utf16() * 2;
+            }
+        }
+        Encoding::Latin1 => {
+            for (offset, byte) in bytes.iter().enumerate() {
+                push(*byte as char, offset, &mut text);
+            }
+        }
+    }
+
+    offsets.push((text.len(), bytes.len()));
+    Ok(Transcoded { text, encoding, offsets })
+}
+
+/// Decodes bytes as UTF-8, replacing each invalid sequence with U+FFFD REPLACEMENT CHARACTER instead of failing.
+///
+/// # Parameters
+/// * `bytes` - The raw contents of a source file, with an optional byte order mark.
+///
+/// # Returns
+/// * `(Transcoded, Vec<Diagnostic>)` - The decoded source code, and a warning giving the byte range of each
+///   sequence that was replaced.
+pub fn transcode_utf8_lossy(bytes: &[u8]) -> (Transcoded, Vec<Diagnostic>) {
+    let start = if bytes.starts_with(&[0xEF, 0xBB, 0xBF]) { 3 } else { 0 };
+    let mut text = String::with_capacity(bytes.len());
+    let mut offsets: Vec<(usize, usize)> = Vec::with_capacity(bytes.len() + 1);
+    let mut diagnostics: Vec<Diagnostic> = Vec::new();
+    let mut original = start;
+
+    for chunk in bytes[start..].utf8_chunks() {
+        for (offset, c) in chunk.valid().char_indices() {
+            offsets.push((text.len(), original + offset));
+            text.push(c);
+        }
+        original += chunk.valid().len();
+
+        let invalid = chunk.invalid();
+        if !invalid.is_empty() {
+            offsets.push((text.len(), original));
+            text.push(char::REPLACEMENT_CHARACTER);
+            diagnostics.push(Diagnostic::warning(
+                "invalid-utf8",
+                format!("invalid UTF-8 sequence {:02X?} replaced with U+FFFD", invalid),
+                Span::new(original, original + invalid.len()),
+            ));
+            original += invalid.len();
+        }
+    }
+
+    offsets.push((text.len(), bytes.len()));
+    (Transcoded { text, encoding: Encoding::Utf8, offsets }, diagnostics)
+}
+
+/// Guesses the encoding of raw source bytes.
+pub fn detect(bytes: &[u8]) -> Encoding {
+    if bytes.starts_with(&[0xEF, 0xBB, 0xBF]) {
+        Encoding::Utf8
+    } else if bytes.starts_with(&[0xFF, 0xFE]) {
+        Encoding::Utf16Le
+    } else if bytes.starts_with(&[0xFE, 0xFF]) {
+        Encoding::Utf16Be
+    } else if std::str::from_utf8(bytes).is_ok() {
+        Encoding::Utf8
+    } else {
+        Encoding::Latin1
+    }
+}
@@ -0,0 +1,55 @@
+//! This file pairs lex errors with the span of source code they were found at.
+//!
+//! `ErrorType` is shared with the rest of the compiler and does not know about spans, so the lexer wraps it when a
+//! caller needs to point at the offending source, such as when rendering diagnostics or comparing snapshots.
+
+use common::error::ErrorType;
+
+use crate::span::Span;
+
+/// An error found while lexing, together with where it was found.
+///
+/// # Fields
+/// * `error` - What went wrong.
+/// * `span` - The source code that could not be lexed.
+#[derive(PartialEq, Debug, Clone)]
+pub struct LexError {
+    error: ErrorType,
+    span: Span,
+}
+
+impl LexError {
+    /// Pairs an error with its span.
+    pub fn new(error: ErrorType, span: Span) -> Self {
+        Self { error, span }
+    }
+
+    /// Gives the span of source code the error refers to.
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    /// Gives the underlying error.
+    pub fn error(&self) -> &ErrorType {
+        &self.error
+    }
+
+    /// Discards the span, leaving the underlying error.
+    pub fn into_error(self) -> ErrorType {
+        self.error
+    }
+}
+
+impl From<LexError> for ErrorType {
+    fn from(error: LexError) -> Self {
+        error.into_error()
+    }
+}
+
+/// Sorts errors into source order by the start and then the end of their spans.
+///
+/// The sort is stable, so errors with identical spans keep the order in which they were reported, which makes the
+/// result the same on every run and platform.
+pub fn sort_by_span(errors: &mut [LexError]) {
+    errors.sort_by_key(|error| (error.span.start, error.span.end));
+}
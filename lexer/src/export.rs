@@ -0,0 +1,82 @@
+//! This file writes token streams as delimited text, so they can be loaded into spreadsheets and data analysis
+//! tools.
+//!
+//! Every token becomes one row with the columns `kind`, `lexeme`, `line`, `column`, and `length`. Lines and
+//! columns count from 1, columns and lengths are measured in bytes, and the lexeme is the exact source text of the
+//! token.
+
+use std::io::{self, Write};
+
+use crate::{
+    column::DEFAULT_TAB_WIDTH,
+    span::SpannedToken,
+};
+
+/// The names of the columns, written as the first row.
+pub const HEADER: [&str; 5] = ["kind", "lexeme", "line", "column", "length"];
+
+/// Writes tokens as comma-separated values following RFC 4180.
+///
+/// Fields containing a comma, a double quote, or a line break are quoted, with double quotes doubled.
+///
+/// # Parameters
+/// * `writer` - Where to write the rows.
+/// * `source` - The source code the tokens were lexed from, used for lexemes and locations.
+/// * `tokens` - The tokens to write.
+pub fn write_csv<W: Write>(writer: &mut W, source: &str, tokens: &[SpannedToken]) -> io::Result<()> {
+    write_rows(writer, source, tokens, ',', quote_csv)
+}
+
+/// Writes tokens as tab-separated values.
+///
+/// Tab-separated fields cannot be quoted, so tabs, line breaks, and backslashes in lexemes are written as `\t`,
+/// `\n`, `\r`, and `\\`.
+///
+/// # Parameters
+/// * `writer` - Where to write the rows.
+/// * `source` - The source code the tokens were lexed from, used for lexemes and locations.
+/// * `tokens` - The tokens to write.
+pub fn write_tsv<W: Write>(writer: &mut W, source: &str, tokens: &[SpannedToken]) -> io::Result<()> {
+    write_rows(writer, source, tokens, '\t', escape_tsv)
+}
+
+/// Writes the header and one row per token, separating fields with `delimiter` and making each field safe with
+/// `escape`.
+fn write_rows<W: Write>(
+    writer: &mut W,
+    source: &str,
+    tokens: &[SpannedToken],
+    delimiter: char,
+    escape: fn(&str) -> String,
+) -> io::Result<()> {
+    writeln!(writer, "{}", HEADER.join(&delimiter.to_string()))?;
+    for spanned in tokens {
+        let location = spanned.span.start_location(source, DEFAULT_TAB_WIDTH);
+        let lexeme = source.get(spanned.span.start..spanned.span.end).unwrap_or("");
+        writeln!(
+            writer,
+            "{}{d}{}{d}{}{d}{}{d}{}",
+            spanned.token.name(),
+            escape(lexeme),
+            location.line,
+            location.byte_column,
+            spanned.span.len(),
+            d = delimiter,
+        )?;
+    }
+    Ok(())
+}
+
+/// Quotes a CSV field if it needs to be.
+fn quote_csv(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Escapes the characters a TSV field cannot contain.
+fn escape_tsv(field: &str) -> String {
+    field.replace('\\', "\\\\").replace('\t', "\\t").replace('\n', "\\n").replace('\r', "\\r")
+}
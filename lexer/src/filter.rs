@@ -0,0 +1,149 @@
+//! This file adds iterator adapters that filter token streams by kind, so that consumers can write
+//! `tokens.iter().without_directives().only_kind(TokenKind::IDENTIFIER)` instead of a filtering loop of their own.
+//!
+//! The adapters are lazy and can be chained in any order. They pass the tokens they keep through unchanged, so
+//! spanned tokens keep their spans. Raw tokens have no span of their own, so filter `raw::with_spans` rather than
+//! the raw tokens themselves, whose spans follow from every token before them.
+//!
+//! ```
+//! use lexer::{core::Lexer, filter::TokenFilters, span::Span, token::TokenKind};
+//! let tokens = Lexer::lex_spanned("#define N 4\nint n = N;").unwrap();
+//! let names = tokens.iter().without_directives().only_kind(TokenKind::IDENTIFIER);
+//! let spans: Vec<Span> = names.map(|spanned| spanned.span).collect();
+//! assert_eq!(spans, vec![Span::new(16, 17), Span::new(20, 21)]);
+//! ```
+
+use crate::{
+    raw::RawToken,
+    span::{Span, SpannedToken},
+    token::{Token, TokenKind},
+    trivia::LosslessToken,
+};
+
+/// Something with a token kind, which the adapters of `TokenFilters` can filter.
+pub trait HasTokenKind {
+    /// Gives the kind of the token.
+    fn token_kind(&self) -> TokenKind;
+}
+
+impl HasTokenKind for Token {
+    fn token_kind(&self) -> TokenKind {
+        self.kind()
+    }
+}
+
+impl HasTokenKind for SpannedToken {
+    fn token_kind(&self) -> TokenKind {
+        self.token.kind()
+    }
+}
+
+impl HasTokenKind for LosslessToken {
+    fn token_kind(&self) -> TokenKind {
+        self.token.token.kind()
+    }
+}
+
+impl HasTokenKind for RawToken {
+    fn token_kind(&self) -> TokenKind {
+        self.kind
+    }
+}
+
+impl HasTokenKind for (RawToken, Span) {
+    fn token_kind(&self) -> TokenKind {
+        self.0.kind
+    }
+}
+
+impl<T: HasTokenKind + ?Sized> HasTokenKind for &T {
+    fn token_kind(&self) -> TokenKind {
+        (**self).token_kind()
+    }
+}
+
+/// Adapters that filter an iterator of tokens by kind.
+pub trait TokenFilters: Iterator + Sized
+where
+    Self::Item: HasTokenKind,
+{
+    /// Skips whitespace and comments, which only raw token streams hold as `TokenKind::TRIVIA` tokens. Other
+    /// streams hold no trivia, so nothing is skipped from them.
+    fn without_trivia(self) -> Filtered<Self> {
+        Filtered { tokens: self, filter: KindFilter::WithoutTrivia }
+    }
+
+    /// Skips preprocessor lines.
+    fn without_directives(self) -> Filtered<Self> {
+        Filtered { tokens: self, filter: KindFilter::WithoutDirectives }
+    }
+
+    /// Keeps only tokens of one kind.
+    fn only_kind(self, kind: TokenKind) -> Filtered<Self> {
+        Filtered { tokens: self, filter: KindFilter::Only(kind) }
+    }
+}
+
+impl<I> TokenFilters for I
+where
+    I: Iterator,
+    I::Item: HasTokenKind,
+{
+}
+
+/// Which tokens a `Filtered` keeps
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+enum KindFilter {
+    /// Every token but trivia
+    WithoutTrivia,
+    /// Every token but preprocessor lines
+    WithoutDirectives,
+    /// Only tokens of the kind
+    Only(TokenKind),
+}
+
+impl KindFilter {
+    /// Returns true if tokens of a kind are kept
+    fn keeps(self, kind: TokenKind) -> bool {
+        match self {
+            KindFilter::WithoutTrivia => kind != TokenKind::TRIVIA,
+            KindFilter::WithoutDirectives => kind != TokenKind::HASHLINE,
+            KindFilter::Only(only) => kind == only,
+        }
+    }
+}
+
+/// An iterator over the tokens of another that pass a filter, made by the methods of `TokenFilters`.
+#[derive(Debug, Clone)]
+pub struct Filtered<I> {
+    tokens: I,
+    filter: KindFilter,
+}
+
+impl<I> Iterator for Filtered<I>
+where
+    I: Iterator,
+    I::Item: HasTokenKind,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let filter = self.filter;
+        self.tokens.find(|token| filter.keeps(token.token_kind()))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.tokens.size_hint().1)
+    }
+}
+
+impl<I> DoubleEndedIterator for Filtered<I>
+where
+    I: DoubleEndedIterator,
+    I::Item: HasTokenKind,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let filter = self.filter;
+        self.tokens.rfind(|token| filter.keeps(token.token_kind()))
+    }
+}
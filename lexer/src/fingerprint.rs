@@ -0,0 +1,263 @@
+//! This file fingerprints token streams for finding similar code, such as a course instructor checking submissions
+//! for plagiarism, using the winnowing algorithm of Schleimer, Wilkerson, and Aiken.
+//!
+//! Only the kinds of tokens are hashed, so renaming identifiers, changing literals, and reformatting or recommenting
+//! code leave a fingerprint unchanged. Every run of `k` consecutive kinds, a k-gram, is hashed, and from every
+//! window of `window` consecutive k-gram hashes the smallest is kept, the rightmost if several are equal. Two files
+//! that share a run of at least `k + window - 1` tokens are then guaranteed to share a hash, while runs shorter than
+//! `k` never count, which filters out matches as common as `for (i = 0;`.
+//!
+//! Hashes are 64-bit FNV-1a over the stable ids of `TokenKind`, so fingerprints can be stored and compared between
+//! runs and versions.
+//!
+//! `hash_tokens` hashes a whole stream, payloads included, for build tools that want to know whether an edit
+//! changed more than formatting and comments, and `content_hash` hashes the source itself, for telling whether a
+//! file changed at all.
+
+use std::collections::HashSet;
+
+use crate::{
+    span::{Span, SpannedToken},
+    token::{Token, TokenKind},
+};
+
+/// The k-gram length used by `Fingerprint::new`.
+pub const DEFAULT_K: usize = 8;
+
+/// The window size used by `Fingerprint::new`.
+pub const DEFAULT_WINDOW: usize = 4;
+
+/// One hash selected by winnowing.
+///
+/// # Fields
+/// * `hash` - The hash of the k-gram.
+/// * `span` - The source the k-gram covers, from the start of its first token to the end of its last.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub struct FingerprintHash {
+    pub hash: u64,
+    pub span: Span,
+}
+
+/// The fingerprint of a token stream.
+///
+/// # Fields
+/// * `hashes` - The selected hashes, in source order.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Fingerprint {
+    pub hashes: Vec<FingerprintHash>,
+}
+
+impl Fingerprint {
+    /// Fingerprints tokens with `DEFAULT_K` and `DEFAULT_WINDOW`.
+    pub fn new(tokens: &[SpannedToken]) -> Self {
+        Self::with_params(tokens, DEFAULT_K, DEFAULT_WINDOW)
+    }
+
+    /// Fingerprints tokens.
+    ///
+    /// `EOF` is left out, and type names count as identifiers, so a fingerprint does not depend on which names the
+    /// caller registered as types.
+    ///
+    /// # Parameters
+    /// * `tokens` - The tokens of one file.
+    /// * `k` - How many tokens each hash covers. Matches shorter than this are never found.
+    /// * `window` - How many consecutive k-grams to keep a hash from. Matches of `k + window - 1` tokens or more are
+    ///   always found.
+    ///
+    /// # Panics
+    /// * If `k` or `window` is zero.
+    pub fn with_params(tokens: &[SpannedToken], k: usize, window: usize) -> Self {
+        assert!(k > 0 && window > 0, "the k-gram length and window size must be positive");
+        let tokens: Vec<(TokenKind, Span)> = tokens
+            .iter()
+            .map(|spanned| (spanned.token.kind(), spanned.span))
+            .filter(|(kind, _)| !matches!(kind, TokenKind::EOF | TokenKind::TRIVIA))
+            .map(|(kind, span)| if kind == TokenKind::TYPENAME { (TokenKind::IDENTIFIER, span) } else { (kind, span) })
+            .collect();
+
+        let grams: Vec<FingerprintHash> = tokens
+            .windows(k)
+            .map(|gram| FingerprintHash {
+                hash: hash_kinds(gram.iter().map(|(kind, _)| *kind)),
+                span: Span::new(gram[0].1.start, gram[k - 1].1.end),
+            })
+            .collect();
+
+        let mut hashes: Vec<FingerprintHash> = Vec::new();
+        if grams.is_empty() {
+            return Self { hashes };
+        }
+        // With fewer k-grams than a window holds, they all form one window, so short files still have a hash
+        let mut last: Option<usize> = None;
+        for start in 0..=grams.len().saturating_sub(window) {
+            let end = (start + window).min(grams.len());
+            let (index, _) = grams[start..end]
+                .iter()
+                .enumerate()
+                .rev()
+                .min_by_key(|(_, gram)| gram.hash)
+                .expect("windows are never empty");
+            let index = start + index;
+            if last != Some(index) {
+                hashes.push(grams[index]);
+                last = Some(index);
+            }
+        }
+        Self { hashes }
+    }
+
+    /// Gives the Jaccard similarity of two fingerprints: the number of distinct hashes they share over the number
+    /// of distinct hashes in either, from 0.0 for nothing in common to 1.0 for the same hashes. Two fingerprints
+    /// without hashes have a similarity of 0.0.
+    pub fn similarity(&self, other: &Fingerprint) -> f64 {
+        let ours = self.hash_set();
+        let theirs = other.hash_set();
+        let union = ours.union(&theirs).count();
+        if union == 0 {
+            return 0.0;
+        }
+        ours.intersection(&theirs).count() as f64 / union as f64
+    }
+
+    /// Gives the fraction of this fingerprint's distinct hashes that `other` also has, which unlike `similarity`
+    /// stays high when a small file was copied into a large one. A fingerprint without hashes has a containment
+    /// of 0.0.
+    pub fn containment(&self, other: &Fingerprint) -> f64 {
+        let ours = self.hash_set();
+        if ours.is_empty() {
+            return 0.0;
+        }
+        let theirs = other.hash_set();
+        ours.iter().filter(|hash| theirs.contains(hash)).count() as f64 / ours.len() as f64
+    }
+
+    /// Pairs every hash of this fingerprint with every hash of `other` that equals it, giving the source each
+    /// covers in both files, in the order of this fingerprint.
+    pub fn matches(&self, other: &Fingerprint) -> Vec<(Span, Span)> {
+        self.hashes
+            .iter()
+            .flat_map(|ours| {
+                other.hashes.iter().filter(|theirs| theirs.hash == ours.hash).map(|theirs| (ours.span, theirs.span))
+            })
+            .collect()
+    }
+
+    /// Collects the distinct hashes
+    fn hash_set(&self) -> HashSet<u64> {
+        self.hashes.iter().map(|hash| hash.hash).collect()
+    }
+}
+
+/// Hashes the structure of a token stream: the kind and payload of every token, but not spans, whitespace, or
+/// comments, which are not tokens. Two sources with the same hash differ at most in formatting and comments, so
+/// build tools can skip recompiling after such an edit.
+///
+/// Preprocessor lines are hashed without their comments and line continuations, and with every run of whitespace
+/// collapsed to one space, so that they too only change the hash when more than their formatting changes.
+///
+/// The hash is 64-bit FNV-1a over the stable kind ids and the payloads, so it is the same across runs and Rust
+/// versions and can be stored between builds. `EOF` and `DEFAULT` are skipped, so it does not matter whether the
+/// stream ends with `EOF`.
+///
+/// ```
+/// use lexer::{core::Lexer, fingerprint::hash_tokens};
+/// let before = Lexer::lex("int x = 1; /* one */").unwrap();
+/// let after = Lexer::lex("int  x=1;\n// still one\n").unwrap();
+/// assert_eq!(hash_tokens(&before), hash_tokens(&after));
+/// ```
+pub fn hash_tokens(tokens: &[Token]) -> u64 {
+    let mut bytes: Vec<u8> = Vec::new();
+    for token in tokens.iter().filter(|token| !matches!(token, Token::EOF | Token::DEFAULT)) {
+        bytes.push(token.kind().id());
+        match token {
+            Token::CHAR(c) => bytes.extend((*c as u32).to_le_bytes()),
+            Token::HASHLINE(text) => push_text(&mut bytes, normalize_directive(text).chars()),
+            Token::CUSTOM(name, text) => {
+                push_text(&mut bytes, name.chars());
+                push_text(&mut bytes, text.iter().copied());
+            }
+            token => {
+                if let Some(text) = token.text() {
+                    push_text(&mut bytes, text.iter().copied());
+                }
+            }
+        }
+    }
+    fnv1a(bytes)
+}
+
+/// Appends text prefixed with its length, so that where one payload ends and the next token starts is never
+/// ambiguous
+fn push_text(bytes: &mut Vec<u8>, text: impl Iterator<Item = char>) {
+    let text: String = text.collect();
+    bytes.extend((text.len() as u64).to_le_bytes());
+    bytes.extend(text.bytes());
+}
+
+/// Hashes source code with 64-bit FNV-1a, which unlike the standard library's hasher is stable across runs and
+/// Rust versions, for telling whether a file's content changed.
+pub fn content_hash(source: &str) -> u64 {
+    fnv1a(source.bytes())
+}
+
+/// Gives the text of a preprocessor line without its comments and line continuations, with every run of whitespace
+/// collapsed to one space and none after the `#`
+fn normalize_directive(text: &[char]) -> String {
+    let mut normalized: String = String::new();
+    let mut space: bool = false;
+    let mut quote: Option<char> = None;
+    let mut index: usize = 0;
+    while index < text.len() {
+        let (c, next) = (text[index], text.get(index + 1).copied());
+        index += 1;
+        if let Some(open) = quote {
+            normalized.push(c);
+            if c == '\\' {
+                normalized.extend(next);
+                index += 1;
+            } else if c == open {
+                quote = None;
+            }
+            continue;
+        }
+        match (c, next) {
+            ('/', Some('/')) => break,
+            ('/', Some('*')) => match text[index + 1..].windows(2).position(|pair| pair == ['*', '/']) {
+                Some(end) => {
+                    space = true;
+                    index += end + 3;
+                }
+                None => break,
+            },
+            ('\\', Some('\n')) => {
+                space = true;
+                index += 1;
+            }
+            (c, _) if c.is_whitespace() => space = true,
+            (c, _) => {
+                if space && !normalized.is_empty() && normalized != "#" {
+                    normalized.push(' ');
+                }
+                space = false;
+                if matches!(c, '"' | '\'') {
+                    quote = Some(c);
+                }
+                normalized.push(c);
+            }
+        }
+    }
+    normalized
+}
+
+/// Hashes a run of kinds with 64-bit FNV-1a over their ids
+fn hash_kinds(kinds: impl Iterator<Item = TokenKind>) -> u64 {
+    fnv1a(kinds.map(TokenKind::id))
+}
+
+/// Hashes bytes with 64-bit FNV-1a
+pub(crate) fn fnv1a(bytes: impl IntoIterator<Item = u8>) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+
+    bytes.into_iter().fold(OFFSET_BASIS, |hash, byte| (hash ^ byte as u64).wrapping_mul(PRIME))
+}
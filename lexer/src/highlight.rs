@@ -0,0 +1,143 @@
+//! This file classifies lexed tokens for syntax highlighting, in the shape editors expect for semantic tokens.
+//!
+//! Classification looks only at the neighbours of each token, so it is available as soon as a file is lexed,
+//! without waiting for the parser. The heuristics are:
+//!
+//! * An identifier after a type keyword, `struct`, or `enum`, skipping any `*` and `const` in between, is a
+//!   declaration.
+//! * An identifier directly before `(` names a function.
+//! * An identifier written in ALL_CAPS is a constant.
+
+use std::ops::BitOr;
+
+use crate::{
+    span::{Span, SpannedToken},
+    token::{Token, TokenCategory},
+};
+
+/// What a highlighted token is.
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Hash)]
+pub enum HighlightKind {
+    /// A reserved word that is not a type name.
+    Keyword,
+    /// A type name.
+    Type,
+    /// An identifier that names a variable, or anything that is not known to be a function.
+    Variable,
+    /// An identifier that names a function.
+    Function,
+    /// A number.
+    Number,
+    /// A string or character literal.
+    String,
+    /// An operator.
+    Operator,
+    /// A bracket or separator.
+    Punctuation,
+    /// A preprocessor line.
+    Preprocessor,
+    /// A token produced by a custom rule.
+    Custom,
+}
+
+/// A set of modifiers refining a `HighlightKind`, stored as bits so that it can be sent to an editor as is.
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Hash, Default)]
+pub struct Modifiers(u8);
+
+impl Modifiers {
+    /// No modifiers.
+    pub const NONE: Modifiers = Modifiers(0);
+    /// The token declares the name, rather than using it.
+    pub const DECLARATION: Modifiers = Modifiers(1);
+    /// The name is a constant.
+    pub const CONSTANT: Modifiers = Modifiers(1 << 1);
+
+    /// Returns true if every modifier in `other` is set.
+    pub fn contains(self, other: Modifiers) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Gives the modifiers as bits, with `DECLARATION` as bit 0 and `CONSTANT` as bit 1.
+    pub fn bits(self) -> u8 {
+        self.0
+    }
+}
+
+impl BitOr for Modifiers {
+    type Output = Modifiers;
+
+    fn bitor(self, other: Modifiers) -> Modifiers {
+        Modifiers(self.0 | other.0)
+    }
+}
+
+/// A token classified for highlighting.
+///
+/// # Fields
+/// * `span` - Where the token appears in the source code.
+/// * `kind` - What the token is.
+/// * `modifiers` - How the kind is refined.
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Hash)]
+pub struct SemanticToken {
+    pub span: Span,
+    pub kind: HighlightKind,
+    pub modifiers: Modifiers,
+}
+
+/// Classifies a stream of spanned tokens for highlighting.
+///
+/// # Parameters
+/// * `tokens` - The tokens to classify, as produced by `Lexer::lex_spanned`.
+///
+/// # Returns
+/// * `Vec<SemanticToken>` - A classification for every token except `EOF`, in source order.
+pub fn semantic_tokens(tokens: &[SpannedToken]) -> Vec<SemanticToken> {
+    let mut highlighted: Vec<SemanticToken> = Vec::with_capacity(tokens.len());
+
+    for (index, spanned) in tokens.iter().enumerate() {
+        let (kind, modifiers) = match &spanned.token {
+            Token::EOF | Token::DEFAULT => continue,
+            Token::IDENTIFIER(name) => classify_identifier(tokens, index, name),
+            Token::NUMBER(_) => (HighlightKind::Number, Modifiers::NONE),
+            Token::STRINGLITERAL(_) | Token::CHAR(_) => (HighlightKind::String, Modifiers::NONE),
+            Token::HASHLINE(_) => (HighlightKind::Preprocessor, Modifiers::NONE),
+            token => match token.category() {
+                TokenCategory::Type => (HighlightKind::Type, Modifiers::NONE),
+                TokenCategory::Operator => (HighlightKind::Operator, Modifiers::NONE),
+                TokenCategory::Punctuation => (HighlightKind::Punctuation, Modifiers::NONE),
+                TokenCategory::Custom => (HighlightKind::Custom, Modifiers::NONE),
+                _ => (HighlightKind::Keyword, Modifiers::NONE),
+            },
+        };
+        highlighted.push(SemanticToken { span: spanned.span, kind, modifiers });
+    }
+    highlighted
+}
+
+/// Classifies the identifier at `index` from the tokens around it
+fn classify_identifier(tokens: &[SpannedToken], index: usize, name: &[char]) -> (HighlightKind, Modifiers) {
+    let kind = match tokens.get(index + 1).map(|next| &next.token) {
+        Some(Token::LPAREN) => HighlightKind::Function,
+        _ => HighlightKind::Variable,
+    };
+
+    let mut modifiers = Modifiers::NONE;
+    let declarer = tokens[..index]
+        .iter()
+        .rev()
+        .find(|previous| !matches!(previous.token, Token::ASTERISK | Token::CONST));
+    if let Some(declarer) = declarer {
+        if declarer.token.category() == TokenCategory::Type || matches!(declarer.token, Token::STRUCT | Token::ENUM) {
+            modifiers = modifiers | Modifiers::DECLARATION;
+        }
+    }
+    if is_all_caps(name) {
+        modifiers = modifiers | Modifiers::CONSTANT;
+    }
+    (kind, modifiers)
+}
+
+/// Checks whether a name is written in ALL_CAPS, with at least one letter and no lowercase ones
+fn is_all_caps(name: &[char]) -> bool {
+    name.iter().any(|c| c.is_ascii_uppercase()) && !name.iter().any(|c| c.is_ascii_lowercase())
+}
@@ -0,0 +1,156 @@
+//! This file interns strings such as identifiers and file paths into symbols shared across every file in a
+//! project.
+//!
+//! Lexing a whole project gives each file its own tokens, so the same identifier appears once per use. A
+//! `GlobalInterner` keeps one copy of each distinct string for all files and threads, and hands out `Symbol`s that
+//! compare and hash as integers. Its contents can be snapshotted and restored, so a cache of lexed files can keep
+//! the symbols it stored meaningful between runs.
+
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{OnceLock, RwLock, RwLockReadGuard},
+};
+
+use serde::{Deserialize, Serialize};
+
+/// A string interned in a `GlobalInterner`. Symbols are only meaningful to the interner that made them.
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Hash, PartialOrd, Ord)]
+pub struct Symbol(u32);
+
+impl Symbol {
+    /// Gives the index of the symbol, which counts up from 0 in the order strings were first interned.
+    pub fn as_u32(self) -> u32 {
+        self.0
+    }
+}
+
+/// A thread-safe table of interned strings.
+///
+/// # Fields
+/// * `table` - The strings in symbol order and the symbol of each, behind a lock.
+#[derive(Debug, Default)]
+pub struct GlobalInterner {
+    table: RwLock<Table>,
+}
+
+/// The contents of an interner.
+///
+/// # Fields
+/// * `strings` - Every interned string, indexed by symbol. Strings are never removed, so their buffers stay put.
+/// * `symbols` - The symbol of every interned string.
+#[derive(Debug, Default)]
+struct Table {
+    strings: Vec<Box<str>>,
+    symbols: HashMap<Box<str>, Symbol>,
+}
+
+/// The strings of an interner in symbol order, which rebuild it with the same symbols.
+///
+/// # Fields
+/// * `strings` - Every interned string, indexed by symbol.
+#[derive(PartialEq, Eq, Debug, Clone, Default, Serialize, Deserialize)]
+pub struct InternerSnapshot {
+    pub strings: Vec<String>,
+}
+
+impl GlobalInterner {
+    /// Creates an empty interner.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Gives the interner shared by the whole process.
+    pub fn global() -> &'static GlobalInterner {
+        static GLOBAL: OnceLock<GlobalInterner> = OnceLock::new();
+        GLOBAL.get_or_init(GlobalInterner::new)
+    }
+
+    /// Interns a string, returning the symbol it already has if it was interned before.
+    pub fn intern(&self, text: &str) -> Symbol {
+        if let Some(symbol) = self.get(text) {
+            return symbol;
+        }
+        let mut table = self.table.write().unwrap_or_else(|poisoned| poisoned.into_inner());
+        table.insert(text)
+    }
+
+    /// Interns the characters of a token's payload, as held by `Token::IDENTIFIER`.
+    pub fn intern_chars(&self, chars: &[char]) -> Symbol {
+        self.intern(&chars.iter().collect::<String>())
+    }
+
+    /// Gives the symbol of a string without interning it.
+    pub fn get(&self, text: &str) -> Option<Symbol> {
+        self.read().symbols.get(text).copied()
+    }
+
+    /// Gives the string a symbol stands for.
+    ///
+    /// # Panics
+    /// Panics if the symbol was made by a different interner and is out of range for this one.
+    pub fn resolve(&self, symbol: Symbol) -> &str {
+        let table = self.read();
+        let stored: *const str = &*table.strings[symbol.0 as usize];
+        // The pointer outlives the lock guard but not the interner: strings are boxed, so growing `strings` moves
+        // the boxes but not their buffers, and nothing is ever removed from the table.
+        unsafe { &*stored }
+    }
+
+    /// Gives the number of distinct strings interned.
+    pub fn len(&self) -> usize {
+        self.read().strings.len()
+    }
+
+    /// Returns true if nothing has been interned.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Copies out every interned string in symbol order.
+    pub fn snapshot(&self) -> InternerSnapshot {
+        InternerSnapshot { strings: self.read().strings.iter().map(|text| text.to_string()).collect() }
+    }
+
+    /// Interns the strings of a snapshot in order, so that every symbol recorded alongside the snapshot means the
+    /// same string in this interner.
+    ///
+    /// # Returns
+    /// * `bool` - Whether the snapshot was restored. It is not, and the interner is left unchanged, if the
+    ///   interner already gives one of the snapshot's symbols to a different string, or if the snapshot holds a
+    ///   string twice.
+    pub fn restore(&self, snapshot: &InternerSnapshot) -> bool {
+        let mut table = self.table.write().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let agrees = table.strings
+            .iter()
+            .zip(snapshot.strings.iter())
+            .all(|(existing, restored)| **existing == **restored);
+        let rest = snapshot.strings.get(table.strings.len()..).unwrap_or_default();
+        let mut seen: HashSet<&str> = HashSet::new();
+        if !agrees || rest.iter().any(|text| table.symbols.contains_key(text.as_str()) || !seen.insert(text)) {
+            return false;
+        }
+        for text in rest {
+            table.insert(text);
+        }
+        true
+    }
+
+    /// Locks the table for reading. The table is never left half-updated, so a panic while it was locked does not
+    /// invalidate it.
+    fn read(&self) -> RwLockReadGuard<'_, Table> {
+        self.table.read().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl Table {
+    /// Interns a string, which may have been interned by another thread since the caller last looked.
+    fn insert(&mut self, text: &str) -> Symbol {
+        if let Some(symbol) = self.symbols.get(text) {
+            return *symbol;
+        }
+        let symbol = Symbol(u32::try_from(self.strings.len()).expect("fewer than 2^32 distinct strings"));
+        self.strings.push(text.into());
+        self.symbols.insert(text.into(), symbol);
+        symbol
+    }
+}
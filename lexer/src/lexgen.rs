@@ -0,0 +1,269 @@
+//! This file builds lexers at runtime from a declarative specification, so that toy languages can be defined
+//! without writing any Rust.
+//!
+//! A specification lists keywords, operators, comment styles, and which literal forms are recognized. It can
+//! be written in TOML or JSON:
+//!
+//! ```toml
+//! [keywords]
+//! let = "LET"
+//! print = "PRINT"
+//!
+//! [operators]
+//! "+" = "PLUS"
+//! ":=" = "ASSIGN"
+//!
+//! [comments]
+//! line = ["#"]
+//! block = [["(*", "*)"]]
+//!
+//! [literals]
+//! identifiers = true
+//! numbers = true
+//! strings = ["\""]
+//! ```
+//!
+//! Keywords and operators become `Token::CUSTOM(kind, lexeme)`, while identifiers, numbers, and strings use
+//! `Token::IDENTIFIER`, `Token::NUMBER`, and `Token::STRINGLITERAL` as they do in the C lexer.
+
+use std::{collections::BTreeMap, fs, path::Path};
+
+use common::error::ErrorType;
+use serde::Deserialize;
+
+use crate::{
+    span::{Span, SpannedToken},
+    token::Token,
+};
+
+/// A declarative description of a lexer.
+///
+/// # Fields
+/// * `keywords` - Maps each keyword to the kind of token it produces.
+/// * `operators` - Maps each operator to the kind of token it produces. The longest matching operator wins.
+/// * `comments` - The comment styles of the language.
+/// * `literals` - The literal forms of the language.
+#[derive(PartialEq, Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LexerSpec {
+    #[serde(default)]
+    pub keywords: BTreeMap<String, String>,
+    #[serde(default)]
+    pub operators: BTreeMap<String, String>,
+    #[serde(default)]
+    pub comments: CommentSpec,
+    #[serde(default)]
+    pub literals: LiteralSpec,
+}
+
+/// The comment styles of a specified language.
+///
+/// # Fields
+/// * `line` - Markers that start a comment running to the end of the line.
+/// * `block` - Pairs of markers that open and close a comment.
+#[derive(PartialEq, Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CommentSpec {
+    #[serde(default)]
+    pub line: Vec<String>,
+    #[serde(default)]
+    pub block: Vec<(String, String)>,
+}
+
+/// The literal forms of a specified language.
+///
+/// # Fields
+/// * `identifiers` - Whether identifiers (a letter or underscore followed by letters, digits, or underscores) are
+///   recognized.
+/// * `numbers` - Whether runs of decimal digits are recognized as numbers.
+/// * `strings` - The quote characters that delimit string literals. Strings may not span lines.
+#[derive(PartialEq, Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LiteralSpec {
+    #[serde(default = "enabled")]
+    pub identifiers: bool,
+    #[serde(default = "enabled")]
+    pub numbers: bool,
+    #[serde(default)]
+    pub strings: Vec<char>,
+}
+
+impl Default for LiteralSpec {
+    fn default() -> Self {
+        Self { identifiers: true, numbers: true, strings: Vec::new() }
+    }
+}
+
+/// Serde default for literal forms that are on unless turned off.
+fn enabled() -> bool {
+    true
+}
+
+impl LexerSpec {
+    /// Parses a specification written in TOML.
+    pub fn from_toml(text: &str) -> Result<Self, ErrorType> {
+        toml::from_str(text).map_err(|error| invalid(error.message()))
+    }
+
+    /// Parses a specification written in JSON.
+    pub fn from_json(text: &str) -> Result<Self, ErrorType> {
+        serde_json::from_str(text).map_err(|error| invalid(&error.to_string()))
+    }
+
+    /// Reads a specification from a file, choosing the format by its extension (`.json`, or TOML otherwise).
+    pub fn load(path: &Path) -> Result<Self, ErrorType> {
+        let text = fs::read_to_string(path).map_err(|error| ErrorType::IoError {
+            path: path.display().to_string(),
+            message: error.to_string(),
+        })?;
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("json") => LexerSpec::from_json(&text),
+            _ => LexerSpec::from_toml(&text),
+        }
+    }
+}
+
+/// A lexer generated from a `LexerSpec`.
+///
+/// # Fields
+/// * `spec` - The validated specification.
+/// * `operators` - The operators as characters, longest first, so the first match is the longest.
+#[derive(PartialEq, Debug, Clone)]
+pub struct GeneratedLexer {
+    spec: LexerSpec,
+    operators: Vec<(Vec<char>, String)>,
+}
+
+impl GeneratedLexer {
+    /// Generates a lexer from a specification.
+    ///
+    /// # Errors
+    /// * `ErrorType::InvalidLexerSpec` if a keyword is not a valid identifier, an operator or comment marker is
+    ///   empty, or a keyword is given while identifiers are disabled.
+    pub fn new(spec: LexerSpec) -> Result<Self, ErrorType> {
+        for keyword in spec.keywords.keys() {
+            let mut chars = keyword.chars();
+            let valid = chars.next().is_some_and(is_identifier_start) && chars.all(is_identifier_continue);
+            if !valid {
+                return Err(invalid(&format!("keyword `{}` is not a valid identifier", keyword)));
+            }
+        }
+        if !spec.keywords.is_empty() && !spec.literals.identifiers {
+            return Err(invalid("keywords require identifiers to be enabled"));
+        }
+        let markers = spec.operators.keys()
+            .chain(spec.comments.line.iter())
+            .chain(spec.comments.block.iter().flat_map(|(open, close)| [open, close]));
+        if markers.into_iter().any(|marker| marker.is_empty()) {
+            return Err(invalid("operators and comment markers must not be empty"));
+        }
+
+        let mut operators: Vec<(Vec<char>, String)> = spec.operators
+            .iter()
+            .map(|(spelling, kind)| (spelling.chars().collect(), kind.clone()))
+            .collect();
+        operators.sort_by_key(|(spelling, _)| std::cmp::Reverse(spelling.len()));
+        Ok(Self { spec, operators })
+    }
+
+    /// Lexes `input` according to the specification.
+    ///
+    /// # Returns
+    /// * `Ok(Vec<SpannedToken>)` - The tokens, ending with `Token::EOF`.
+    /// * `Err(Vec<ErrorType>)` - Every unrecognized character and unterminated string or comment.
+    pub fn lex(&self, input: &str) -> Result<Vec<SpannedToken>, Vec<ErrorType>> {
+        let chars: Vec<char> = input.chars().collect();
+        let offsets: Vec<usize> = input.char_indices().map(|(offset, _)| offset).chain([input.len()]).collect();
+        let mut tokens: Vec<SpannedToken> = Vec::new();
+        let mut errors: Vec<ErrorType> = Vec::new();
+        let mut position: usize = 0;
+
+        while position < chars.len() {
+            let rest = &chars[position..];
+            let start = position;
+            let c = rest[0];
+
+            if c.is_whitespace() {
+                position += 1;
+                continue;
+            }
+            if let Some(marker) = self.spec.comments.line.iter().find(|marker| starts_with(rest, marker)) {
+                position += marker.chars().count();
+                while position < chars.len() && chars[position] != '\n' {
+                    position += 1;
+                }
+                continue;
+            }
+            if let Some((open, close)) = self.spec.comments.block.iter().find(|(open, _)| starts_with(rest, open)) {
+                position += open.chars().count();
+                while position < chars.len() && !starts_with(&chars[position..], close) {
+                    position += 1;
+                }
+                if position == chars.len() {
+                    errors.push(ErrorType::SyntaxError { message: format!("unterminated comment starting with `{}`", open) });
+                } else {
+                    position += close.chars().count();
+                }
+                continue;
+            }
+
+            let token = if self.spec.literals.identifiers && is_identifier_start(c) {
+                let length = rest.iter().take_while(|c| is_identifier_continue(**c)).count();
+                position += length;
+                let word: String = rest[..length].iter().collect();
+                match self.spec.keywords.get(&word) {
+                    Some(kind) => Token::CUSTOM(kind.clone(), rest[..length].to_vec()),
+                    None => Token::IDENTIFIER(rest[..length].to_vec()),
+                }
+            } else if self.spec.literals.numbers && c.is_ascii_digit() {
+                let length = rest.iter().take_while(|c| c.is_ascii_digit()).count();
+                position += length;
+                Token::NUMBER(rest[..length].to_vec())
+            } else if self.spec.literals.strings.contains(&c) {
+                let length = rest[1..].iter().take_while(|next| **next != c && **next != '\n').count();
+                if rest.get(length + 1) != Some(&c) {
+                    errors.push(ErrorType::SyntaxError { message: "unterminated string literal".to_string() });
+                    position += length + 1;
+                    continue;
+                }
+                position += length + 2;
+                Token::STRINGLITERAL(rest[1..=length].to_vec())
+            } else if let Some((spelling, kind)) = self.operators.iter().find(|(spelling, _)| rest.starts_with(spelling)) {
+                position += spelling.len();
+                Token::CUSTOM(kind.clone(), spelling.clone())
+            } else {
+                errors.push(ErrorType::UnrecognizedToken { token: c.to_string() });
+                position += 1;
+                continue;
+            };
+            tokens.push(SpannedToken::new(token, Span::new(offsets[start], offsets[position])));
+        }
+
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+        tokens.push(SpannedToken::new(Token::EOF, Span::new(input.len(), input.len())));
+        Ok(tokens)
+    }
+}
+
+/// Checks whether `chars` begins with `pattern`.
+fn starts_with(chars: &[char], pattern: &str) -> bool {
+    let mut rest = chars.iter();
+    pattern.chars().all(|c| rest.next() == Some(&c))
+}
+
+/// Checks whether a character can start an identifier.
+fn is_identifier_start(c: char) -> bool {
+    c.is_ascii_alphabetic() || c == '_'
+}
+
+/// Checks whether a character can continue an identifier.
+fn is_identifier_continue(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+/// Helper function to create an invalid specification error
+fn invalid(message: &str) -> ErrorType {
+    ErrorType::InvalidLexerSpec { message: message.to_string() }
+}
@@ -9,9 +9,251 @@
 //!
 //! - `core`: Core takes source code as input and outputs tokens and ignores comments and whitespace. 
 //! - 'token': Tokens are the base unit of our compiler. This module contains accepted tokens.
+//! - `mode`: The states of the lexer's state machine.
+//! - `config`: Settings for the lexer and the builder used to set them.
+//! - `rule`: Custom token rules that extend the lexer.
+//! - `span`: Spans locate tokens and diagnostics within the source code.
+//! - `diagnostic`: Diagnostics report problems in the source code with a severity and a span.
+//! - `column`: Measures the display width of source text for column reporting.
+//! - `lint`: An optional pass that flags questionable identifiers.
+//! - `confusable`: Detects lookalike and invisible characters in the source code.
+//! - `literal`: Computes the values of integer and character literals.
+//! - `codec`: Encodes token streams into a compact binary form for caching.
+//! - `cache`: Caches lex results by the content of the source.
+//! - `parallel`: Lexes many files concurrently.
+//! - `encoding`: Detects the encoding of raw source bytes and transcodes them to UTF-8.
+//! - `lexgen`: Builds lexers for other languages from a declarative specification.
+//! - `error`: Pairs lex errors with the span of source they were found at.
+//! - `export`: Writes token streams as CSV or TSV.
+//! - `trace`: Records the lexer's decisions and explains token boundaries.
+//! - `bench`: Measures lexing throughput, allocations, and error rates over a corpus of files.
+//! - `arena`: Lexes into tokens that borrow their text from a session-owned arena.
+//! - `raw`: Lexes into compact 8-byte tokens holding only a kind and a length.
+//! - `precedence`: The binding strength and associativity of C operators.
+//! - `line_index`: Converts between byte offsets and line/column positions.
+//! - `statement`: Splits token streams into top-level statements without parsing.
+//! - `directive`: Reports the preprocessor lines the lexer passed through.
+//! - `interner`: A thread-safe string interner shared by every file in a project.
+//! - `highlight`: Classifies tokens for syntax highlighting using lexical heuristics.
+//! - `check`: Runs every check over a file and writes the diagnostics as JSON.
+//! - `sarif`: Writes diagnostics as SARIF for code scanning tools.
+//! - `watch`: Re-checks files as they change on disk.
+//! - `stream`: Lexes input that arrives a piece at a time, emitting tokens once they are complete.
+//! - `compat`: Compares the lexer's output with a reference C tokenizer.
+//! - `driver`: Analyzes every source file in a project in parallel.
+//! - `project`: Loads the settings a project shares from its `lexer.toml`.
+//! - `suppress`: Honors comments that suppress diagnostics.
+//! - `outline`: Lists the functions and types a file defines, using only its tokens.
+//! - `block`: Finds every brace-delimited block with what introduces it and how deeply it is nested.
+//! - `tree`: Arranges tokens into a tree of nodes in the shape tree-sitter uses, for tree-sitter-based tools.
+//! - `lsp`: A language server for editors, built with the `lsp` feature.
+//! - `repl`: Lexes entries typed in an interactive session, continuing an entry while it is incomplete.
+//! - `case`: Rewrites source so that every keyword is spelled in one case, for case-insensitive dialects.
+//! - `text`: Applies batches of edits to source code and translates spans to the edited source.
+//! - `cursor`: A cursor over a token stream for parsers, with helpers for skipping bracket groups.
+//! - `typedefs`: Tracks the names declared as types and tags identifiers naming them, the classic lexer hack.
+//! - `trivia`: Lossless lexing that attaches the whitespace and comments around each token to it, for doc tooling.
+//! - `source`: Source files with their names and line indexes, and excerpts of them for quoting code.
+//! - `tables`: The keyword tables the lexer looks words up in, built once and shared between configurations.
+//! - `shrink`: Minimizes inputs that make the lexer fail, for turning fuzzing failures into regression tests.
+//! - `coverage`: Reports which kinds of token and which lexer handlers a corpus never exercises.
+//! - `output`: `LexOutput`, which bundles the tokens, errors, diagnostics, line index, and stats of one lex.
+//! - `prelude`: The stable API, re-exporting the types most callers need under one path.
+//! - `replay`: Records lexer sessions to files, optionally redacted, and replays them step by step.
+//! - `redact`: Replaces identifier and literal payloads with stable placeholders, for sharing token dumps.
+//! - `fingerprint`: Winnowing fingerprints of token kinds, for finding similar code such as plagiarized submissions,
+//!   and `hash_tokens`, which hashes a token stream ignoring formatting and comments.
+//! - `splice`: `TokenStreamBuilder`, for assembling token streams in code and turning them back into source.
+//! - `emit`: Lays token streams out as indented source, with configurable brace and spacing styles.
+//! - `detect`: Guesses the language and dialect of a file from its contents.
+//! - `budget`: Checks files and their top-level blocks against token and line budgets.
+//! - `progress`: Progress reports for long lexes, for hosts that show progress bars.
+//! - `teach`: Source annotated with its tokens, for demonstrating lexing in compiler courses.
+//! - `filter`: Lazy adapters that filter token streams by kind, keeping spans.
 
 /// Core of the Lexer
 pub mod core;
 
 /// Lists accepted tokens
 pub mod token;
+
+/// States of the lexer
+pub mod mode;
+
+/// Lexer settings and their builder
+pub mod config;
+
+/// Custom token rules
+pub mod rule;
+
+/// Locates tokens within the source code
+pub mod span;
+
+/// Describes problems found in the source code
+pub mod diagnostic;
+
+/// Display widths of source text
+pub mod column;
+
+/// Identifier lints
+pub mod lint;
+
+/// Lookalike and invisible character detection
+pub mod confusable;
+
+/// Values of integer and character literals
+pub mod literal;
+
+/// Binary encoding of token streams
+pub mod codec;
+
+/// Content-addressed lex cache
+pub mod cache;
+
+/// Concurrent lexing of many files
+pub mod parallel;
+
+/// Source text encodings
+pub mod encoding;
+
+/// Lexers generated from a specification
+pub mod lexgen;
+
+/// Lex errors with spans
+pub mod error;
+
+/// Delimited text export of tokens
+pub mod export;
+
+/// Traces of lexer decisions
+pub mod trace;
+
+/// Corpus benchmarks
+pub mod bench;
+
+/// Arena-allocated tokens
+pub mod arena;
+
+/// Compact raw tokens
+pub mod raw;
+
+/// Operator precedence
+pub mod precedence;
+
+/// Line and column lookup
+pub mod line_index;
+
+/// Statement slicing
+pub mod statement;
+
+/// Preprocessor line reporting
+pub mod directive;
+
+/// Shared string interning
+pub mod interner;
+
+/// Semantic highlighting
+pub mod highlight;
+
+/// Diagnostics reports
+pub mod check;
+
+/// SARIF export of diagnostics
+pub mod sarif;
+
+/// File watching
+pub mod watch;
+
+/// Incremental lexing of streamed input
+pub mod stream;
+
+/// Differential testing against a reference C tokenizer
+pub mod compat;
+
+/// Project-wide analysis
+pub mod driver;
+
+/// Project configuration files
+pub mod project;
+
+/// Suppression comments
+pub mod suppress;
+
+/// File outlines from tokens
+pub mod outline;
+
+/// Brace-delimited blocks
+pub mod block;
+
+/// Interactive lexing sessions
+pub mod repl;
+
+/// Tree-sitter-style node trees
+pub mod tree;
+
+/// Language Server Protocol support
+#[cfg(feature = "lsp")]
+pub mod lsp;
+
+/// Keyword case normalization
+pub mod case;
+
+/// Source edits and span remapping
+pub mod text;
+
+/// Token stream cursor
+pub mod cursor;
+
+/// Type name tracking
+pub mod typedefs;
+
+/// Trivia attachment
+pub mod trivia;
+
+/// Source files and excerpts
+pub mod source;
+
+/// Shared keyword tables
+pub mod tables;
+
+/// Failing input minimization
+pub mod shrink;
+
+/// Corpus coverage reports
+pub mod coverage;
+
+/// Structured lex results
+pub mod output;
+
+/// Stable API re-exports
+pub mod prelude;
+
+/// Session recording and replay
+pub mod replay;
+
+/// Token stream redaction
+pub mod redact;
+
+/// Similarity fingerprints
+pub mod fingerprint;
+
+/// Token stream construction
+pub mod splice;
+
+/// Pretty source emission
+pub mod emit;
+
+/// Dialect detection
+pub mod detect;
+
+/// Size budgets
+pub mod budget;
+
+/// Progress reporting
+pub mod progress;
+
+/// Annotated source for teaching
+pub mod teach;
+
+/// Token stream filters
+pub mod filter;
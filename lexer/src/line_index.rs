@@ -0,0 +1,76 @@
+//! This file converts between byte offsets and line/column positions without rescanning the source each time.
+//!
+//! A `LineIndex` records where every line starts once per file. Converting an offset to a line is then a binary
+//! search, and converting a line and column back to an offset is a lookup.
+
+use crate::{
+    column::display_width,
+    span::{Location, Span},
+};
+
+/// The start of every line in a source file.
+///
+/// # Fields
+/// * `line_starts` - The byte offset at which each line starts, beginning with 0 for the first line.
+/// * `len` - The length of the source in bytes.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct LineIndex {
+    line_starts: Vec<usize>,
+    len: usize,
+}
+
+impl LineIndex {
+    /// Indexes the lines of `source`. Lines are separated by `\n`; a `\r` before it is part of the line.
+    pub fn new(source: &str) -> Self {
+        let mut line_starts: Vec<usize> = vec![0];
+        line_starts.extend(source.match_indices('\n').map(|(offset, _)| offset + 1));
+        Self { line_starts, len: source.len() }
+    }
+
+    /// Gives the number of lines. A source ending in a newline has an empty last line after it.
+    pub fn line_count(&self) -> usize {
+        self.line_starts.len()
+    }
+
+    /// Gives the line and byte column of an offset, both counting from 1. Offsets past the end of the source are
+    /// clamped to it.
+    pub fn line_col(&self, offset: usize) -> (usize, usize) {
+        let offset = offset.min(self.len);
+        let line = self.line_starts.partition_point(|start| *start <= offset);
+        (line, offset - self.line_starts[line - 1] + 1)
+    }
+
+    /// Gives the full location of an offset, as `Location::of` does, scanning only the line the offset is on.
+    ///
+    /// # Parameters
+    /// * `source` - The source code the index was built from.
+    /// * `offset` - The byte offset to locate.
+    /// * `tab_width` - The distance between tab stops, used for the display column.
+    pub fn location(&self, source: &str, offset: usize, tab_width: usize) -> Location {
+        let mut offset = offset.min(self.len);
+        while !source.is_char_boundary(offset) {
+            offset -= 1;
+        }
+        let (line, byte_column) = self.line_col(offset);
+        let line_start = self.line_starts[line - 1];
+        Location { line, byte_column, display_column: display_width(&source[line_start..offset], tab_width) + 1 }
+    }
+
+    /// Gives the byte offset of a line and byte column, both counting from 1.
+    ///
+    /// # Returns
+    /// * `Some(usize)` - The offset, if the column lies within the line or just past its end.
+    /// * `None` - If the line does not exist or the column is beyond the end of the line.
+    pub fn offset(&self, line: usize, byte_column: usize) -> Option<usize> {
+        let span = self.line_span(line)?;
+        let offset = span.start + byte_column.checked_sub(1)?;
+        (offset <= span.end).then_some(offset)
+    }
+
+    /// Gives the span of a line, counting from 1, excluding its newline.
+    pub fn line_span(&self, line: usize) -> Option<Span> {
+        let start = *self.line_starts.get(line.checked_sub(1)?)?;
+        let end = self.line_starts.get(line).map_or(self.len, |next| next - 1);
+        Some(Span::new(start, end))
+    }
+}
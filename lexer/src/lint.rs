@@ -0,0 +1,202 @@
+//! This file contains an optional lint pass over lexed tokens that checks identifiers for length, reserved
+//! names, naming style, and spellings that differ only before Unicode normalization.
+//!
+//! The pass never fails the lex; every problem is reported as a warning-severity `Diagnostic`.
+
+use std::collections::HashMap;
+
+use serde::Deserialize;
+use unicode_normalization::UnicodeNormalization;
+
+use crate::{
+    budget::Budgets,
+    diagnostic::Diagnostic,
+    span::SpannedToken,
+    token::Token,
+};
+
+/// The naming convention identifiers are expected to follow.
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NamingStyle {
+    /// Lowercase words separated by underscores, e.g. `my_variable`.
+    SnakeCase,
+    /// Words joined with each word after the first capitalized, e.g. `myVariable`.
+    CamelCase,
+}
+
+/// The Unicode normalization form identifiers are compared under.
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NormalizationForm {
+    /// Canonical composition, which equates precomposed characters with their decomposed spellings, such as `é`
+    /// written as one code point or as `e` and a combining accent.
+    Nfc,
+    /// Compatibility composition, which also equates compatibility characters such as the ligature `ﬁ` and `fi`.
+    Nfkc,
+}
+
+/// Controls which identifier lints run.
+///
+/// # Fields
+/// * `max_identifier_length` - Identifiers longer than this many characters are flagged, if set.
+/// * `reserved_names` - Whether to flag names reserved for the implementation (leading `__` or `_` followed by
+///   an uppercase letter).
+/// * `naming_style` - The naming convention to enforce, if any. ALL_CAPS names are treated as constants and
+///   are exempt.
+/// * `normalization` - The normalization form under which identifiers spelled with different code points are
+///   flagged as the same name, if any. Only identifiers outside ASCII can differ this way, so the lint only matters
+///   when `LexerBuilder::identifier_start` or `identifier_continue` allow such characters.
+/// * `budgets` - The size budgets `check::check_lexed` holds files and their blocks to, none by default. They are
+///   not identifier lints, so `lint_identifiers` ignores them.
+#[derive(PartialEq, Debug, Clone)]
+pub struct LintConfig {
+    pub max_identifier_length: Option<usize>,
+    pub reserved_names: bool,
+    pub naming_style: Option<NamingStyle>,
+    pub normalization: Option<NormalizationForm>,
+    pub budgets: Budgets,
+}
+
+impl Default for LintConfig {
+    fn default() -> Self {
+        Self {
+            max_identifier_length: Some(31),
+            reserved_names: true,
+            naming_style: None,
+            normalization: Some(NormalizationForm::Nfkc),
+            budgets: Budgets::default(),
+        }
+    }
+}
+
+/// Runs the identifier lints over a stream of spanned tokens.
+///
+/// # Parameters
+/// * `tokens` - The tokens to check, as produced by `Lexer::lex_spanned`.
+/// * `config` - Which lints to run.
+///
+/// # Returns
+/// * `Vec<Diagnostic>` - A warning for every problem found, in source order.
+pub fn lint_identifiers(tokens: &[SpannedToken], config: &LintConfig) -> Vec<Diagnostic> {
+    let mut diagnostics: Vec<Diagnostic> = Vec::new();
+
+    for spanned in tokens {
+        let name: String = match &spanned.token {
+            Token::IDENTIFIER(chars) => chars.iter().collect(),
+            _ => continue,
+        };
+
+        if let Some(max) = config.max_identifier_length {
+            let length = name.chars().count();
+            if length > max {
+                diagnostics.push(Diagnostic::warning(
+                    "identifier-too-long",
+                    format!("identifier `{}` is {} characters long, the limit is {}", name, length, max),
+                    spanned.span,
+                ));
+            }
+        }
+
+        if config.reserved_names && is_reserved(&name) {
+            diagnostics.push(Diagnostic::warning(
+                "reserved-identifier",
+                format!("identifier `{}` is reserved for the implementation", name),
+                spanned.span,
+            ));
+        }
+
+        if let Some(style) = config.naming_style {
+            if !matches_style(&name, style) {
+                let expected = match style {
+                    NamingStyle::SnakeCase => "snake_case",
+                    NamingStyle::CamelCase => "camelCase",
+                };
+                diagnostics.push(Diagnostic::warning(
+                    "naming-style",
+                    format!("identifier `{}` is not {}", name, expected),
+                    spanned.span,
+                ));
+            }
+        }
+    }
+    if let Some(form) = config.normalization {
+        diagnostics.extend(find_normalization_duplicates(tokens, form));
+    }
+    diagnostics.sort_by_key(|diagnostic| diagnostic.span.start);
+    diagnostics
+}
+
+/// Finds identifiers spelled with different code points that are the same name after normalization.
+///
+/// Each spelling is reported once, at its first use, as the same name as the first spelling seen.
+///
+/// # Parameters
+/// * `tokens` - The tokens to check, as produced by `Lexer::lex_spanned`.
+/// * `form` - The normalization form to compare under.
+///
+/// # Returns
+/// * `Vec<Diagnostic>` - A `normalization-duplicate` warning for every spelling after the first, in source order.
+pub fn find_normalization_duplicates(tokens: &[SpannedToken], form: NormalizationForm) -> Vec<Diagnostic> {
+    let mut diagnostics: Vec<Diagnostic> = Vec::new();
+    let mut spellings: HashMap<String, Vec<String>> = HashMap::new();
+
+    for spanned in tokens {
+        let Token::IDENTIFIER(chars) = &spanned.token else {
+            continue;
+        };
+        let name: String = chars.iter().collect();
+        let normalized: String = match form {
+            _ if name.is_ascii() => name.clone(),
+            NormalizationForm::Nfc => name.nfc().collect(),
+            NormalizationForm::Nfkc => name.nfkc().collect(),
+        };
+        let seen = spellings.entry(normalized).or_default();
+        if seen.contains(&name) {
+            continue;
+        }
+        if let Some(first) = seen.first() {
+            let form_name = match form {
+                NormalizationForm::Nfc => "NFC",
+                NormalizationForm::Nfkc => "NFKC",
+            };
+            diagnostics.push(Diagnostic::warning(
+                "normalization-duplicate",
+                format!("identifier `{}` is the same name as `{}` after {} normalization", name, first, form_name),
+                spanned.span,
+            ));
+        }
+        seen.push(name);
+    }
+    diagnostics
+}
+
+/// Names starting with two underscores, or an underscore and an uppercase letter, are reserved in C.
+fn is_reserved(name: &str) -> bool {
+    let mut chars = name.chars();
+    match (chars.next(), chars.next()) {
+        (Some('_'), Some('_')) => true,
+        (Some('_'), Some(c)) => c.is_ascii_uppercase(),
+        _ => false,
+    }
+}
+
+/// Checks a name against a naming style, ignoring leading underscores and exempting ALL_CAPS constants.
+fn matches_style(name: &str, style: NamingStyle) -> bool {
+    let trimmed = name.trim_start_matches('_');
+    if trimmed.is_empty() {
+        return true;
+    }
+    let is_constant = trimmed.chars().any(|c| c.is_ascii_uppercase())
+        && !trimmed.chars().any(|c| c.is_ascii_lowercase());
+    if is_constant {
+        return true;
+    }
+
+    match style {
+        NamingStyle::SnakeCase => !trimmed.chars().any(|c| c.is_ascii_uppercase()),
+        NamingStyle::CamelCase => {
+            !trimmed.contains('_') && trimmed.chars().next().is_none_or(|c| !c.is_ascii_uppercase())
+        }
+    }
+}
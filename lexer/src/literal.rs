@@ -0,0 +1,205 @@
+//! This file computes the values of integer and character literals following C semantics.
+//!
+//! Anything that needs the value of a literal (the lexer, a constant evaluator, or a preprocessor) should go
+//! through these functions so that they can never disagree about what, for example, `010` or `'\101'` means.
+//!
+//! The lexer only lexes decimal digits as a number, so a number written directly before a word, as in `123abc`,
+//! becomes two tokens. `check_literal_suffixes` reports those: as an error when the word cannot continue a C
+//! literal, which is usually a typo, and as a warning when it forms C the lexer does not support yet, such as
+//! `10UL`, `0x1F`, or `1e10`.
+
+use common::error::ErrorType;
+
+use crate::{
+    diagnostic::Diagnostic,
+    span::SpannedToken,
+    token::Token,
+};
+
+/// The integer suffixes C allows, in lowercase. `ll` must be written in one case, so `lL` is not allowed.
+const INTEGER_SUFFIXES: [&str; 7] = ["u", "l", "ul", "lu", "ll", "ull", "llu"];
+
+/// Parses the spelling of an integer literal into its value.
+///
+/// A leading `0x` or `0X` selects hexadecimal, any other leading `0` selects octal, and everything else is
+/// decimal, so `010` is 8 and `0x10` is 16.
+///
+/// # Parameters
+/// * `text` - The literal as written, such as `"42"`, `"010"`, or `"0x2A"`.
+///
+/// # Returns
+/// * `Ok(u64)` - The value of the literal.
+/// * `Err(ErrorType)` - `ErrorType::InvalidLiteral` if the literal has no digits, contains a digit invalid for
+///   its radix, or does not fit in 64 bits.
+pub fn parse_int(text: &str) -> Result<u64, ErrorType> {
+    let (digits, radix) = if let Some(hex) = text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+        (hex, 16)
+    } else if text.len() > 1 && text.starts_with('0') {
+        (&text[1..], 8)
+    } else {
+        (text, 10)
+    };
+
+    if digits.is_empty() {
+        return Err(invalid(text, "no digits"));
+    }
+
+    let mut value: u64 = 0;
+    for c in digits.chars() {
+        let digit = c.to_digit(radix).ok_or_else(|| invalid(text, &format!("`{}` is not a base {} digit", c, radix)))?;
+        value = value
+            .checked_mul(radix as u64)
+            .and_then(|v| v.checked_add(digit as u64))
+            .ok_or_else(|| invalid(text, "value does not fit in 64 bits"))?;
+    }
+    Ok(value)
+}
+
+/// Parses the spelling of a character literal into its value.
+///
+/// Supports plain characters, the simple escapes (`\n`, `\t`, `\\`, `\'` and so on), octal escapes of up to
+/// three digits (`'\101'` is 65), and hexadecimal escapes (`'\x41'` is 65).
+///
+/// # Parameters
+/// * `text` - The literal as written, including the surrounding single quotes.
+///
+/// # Returns
+/// * `Ok(u32)` - The value of the character.
+/// * `Err(ErrorType)` - `ErrorType::InvalidLiteral` if the literal is not quoted, is empty, uses an unknown
+///   escape, or contains more than one character.
+pub fn parse_char(text: &str) -> Result<u32, ErrorType> {
+    let body = text
+        .strip_prefix('\'')
+        .and_then(|t| t.strip_suffix('\''))
+        .filter(|body| !body.is_empty())
+        .ok_or_else(|| invalid(text, "expected a single character between single quotes"))?;
+
+    let value = if let Some(escape) = body.strip_prefix('\\') {
+        let (value, length) = parse_escape(text, escape)?;
+        if length != escape.len() {
+            return Err(invalid(text, "more than one character in character literal"));
+        }
+        value
+    } else {
+        let mut chars = body.chars();
+        let c = chars.next().unwrap_or_default();
+        if chars.next().is_some() {
+            return Err(invalid(text, "more than one character in character literal"));
+        }
+        c as u32
+    };
+    Ok(value)
+}
+
+/// Parses the escape sequence following a backslash, returning its value and how many bytes it spans.
+fn parse_escape(text: &str, escape: &str) -> Result<(u32, usize), ErrorType> {
+    let simple = match escape.chars().next() {
+        Some('n') => 0x0A,
+        Some('t') => 0x09,
+        Some('r') => 0x0D,
+        Some('a') => 0x07,
+        Some('b') => 0x08,
+        Some('f') => 0x0C,
+        Some('v') => 0x0B,
+        Some(c @ ('\\' | '\'' | '"' | '?')) => c as u32,
+        Some('0'..='7') => {
+            let length = escape.chars().take(3).take_while(|c| ('0'..='7').contains(c)).count();
+            let value = u32::from_str_radix(&escape[..length], 8).map_err(|_| invalid(text, "malformed octal escape"))?;
+            return Ok((value, length));
+        }
+        Some('x') => {
+            let length = escape[1..].chars().take_while(|c| c.is_ascii_hexdigit()).count();
+            let value = u32::from_str_radix(&escape[1..1 + length], 16)
+                .map_err(|_| invalid(text, "malformed hexadecimal escape"))?;
+            return Ok((value, 1 + length));
+        }
+        Some(c) => return Err(invalid(text, &format!("unknown escape sequence `\\{}`", c))),
+        None => return Err(invalid(text, "incomplete escape sequence")),
+    };
+    Ok((simple, 1))
+}
+
+/// Reports every number written directly before a word.
+///
+/// # Parameters
+/// * `source` - The source code.
+/// * `tokens` - The tokens lexed from `source`.
+///
+/// # Returns
+/// * `Vec<Diagnostic>` - In source order, covering the number and the word, an `invalid-literal-suffix` error if
+///   the word cannot continue a C literal, or an `unsupported-literal` warning if it forms a hexadecimal or binary
+///   literal, an exponent, or a suffix, which the lexer splits off.
+pub fn check_literal_suffixes(source: &str, tokens: &[SpannedToken]) -> Vec<Diagnostic> {
+    let mut diagnostics: Vec<Diagnostic> = Vec::new();
+    for (index, pair) in tokens.windows(2).enumerate() {
+        let (number, word) = (&pair[0], &pair[1]);
+        if !matches!(number.token, Token::NUMBER(_)) || number.span.end != word.span.start || word.span.is_empty() {
+            continue;
+        }
+        let word_text = &source[word.span.start..word.span.end];
+        if !word_text.starts_with(|c: char| c.is_alphabetic() || c == '_') {
+            continue;
+        }
+
+        let number_text = &source[number.span.start..number.span.end];
+        let after_dot = index > 0 && {
+            let previous = &tokens[index - 1];
+            previous.token == Token::DOT && previous.span.end == number.span.start
+        };
+        let span = number.span.merge(word.span);
+        if continues_literal(number_text, word_text, after_dot) {
+            diagnostics.push(Diagnostic::warning(
+                "unsupported-literal",
+                format!("`{0}{1}` is valid C, but is lexed as `{0}` followed by `{1}`", number_text, word_text),
+                span,
+            ));
+        } else {
+            diagnostics.push(Diagnostic::error(
+                "invalid-literal-suffix",
+                format!(
+                    "`{}` is not a valid suffix for the number `{}`; add a space if they are separate tokens",
+                    word_text, number_text,
+                ),
+                span,
+            ));
+        }
+    }
+    diagnostics
+}
+
+/// Checks whether a word written directly after the digits of a number continues it as a C literal
+fn continues_literal(number: &str, word: &str, after_dot: bool) -> bool {
+    let lower = word.to_ascii_lowercase();
+    let is_integer_suffix = |suffix: &str| suffix.is_empty() || INTEGER_SUFFIXES.contains(&suffix);
+    if word.contains("lL") || word.contains("Ll") {
+        return false;
+    }
+
+    if number == "0" {
+        let radix_digits = match lower.chars().next() {
+            Some('x') => Some(16),
+            Some('b') => Some(2),
+            _ => None,
+        };
+        if let Some(radix) = radix_digits {
+            let digits = lower[1..].chars().take_while(|c| c.is_digit(radix)).count();
+            return digits > 0 && is_integer_suffix(&lower[1 + digits..]);
+        }
+    }
+    if let Some(exponent) = lower.strip_prefix('e') {
+        let digits = exponent.chars().take_while(|c| c.is_ascii_digit()).count();
+        return matches!(&exponent[digits..], "" | "f" | "l");
+    }
+    if after_dot {
+        return matches!(lower.as_str(), "f" | "l");
+    }
+    is_integer_suffix(&lower)
+}
+
+/// Helper function to create an invalid literal error
+fn invalid(text: &str, reason: &str) -> ErrorType {
+    ErrorType::InvalidLiteral {
+        literal: text.to_string(),
+        reason: reason.to_string(),
+    }
+}
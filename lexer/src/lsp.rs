@@ -0,0 +1,310 @@
+//! This file implements the basics of the Language Server Protocol on top of the lexer, so that editors can show
+//! its diagnostics, highlighting, outline, and folding while a file is being edited. The `lexer-lsp` binary, built
+//! with the `lsp` feature, runs a `Server` over standard input and output.
+//!
+//! The server keeps the full text of every open document, sent whole on every change, and answers:
+//!
+//! * `textDocument/publishDiagnostics`, sent after every open and change, with the diagnostics of
+//!   `check::check_lexed_with` that are not suppressed.
+//! * `textDocument/semanticTokens/full`, from `highlight::semantic_tokens`. Punctuation and custom tokens are left
+//!   for the editor's own highlighting.
+//! * `textDocument/documentSymbol`, from `outline::outline`.
+//! * `textDocument/foldingRange`, for every block from `block::extract_blocks` that spans more than one line.
+//!
+//! Positions are converted to the protocol's zero-based lines and UTF-16 columns.
+
+use std::{
+    collections::HashMap,
+    io::{self, BufRead, Write},
+};
+
+use serde_json::{json, Value};
+
+use crate::{
+    block::extract_blocks,
+    check::check_lexed_with,
+    config::LexerConfig,
+    core::Lexer,
+    diagnostic::Severity,
+    highlight::{semantic_tokens, HighlightKind},
+    line_index::LineIndex,
+    lint::LintConfig,
+    outline::{outline, OutlineKind},
+    span::{Span, SpannedToken},
+};
+
+/// The semantic token types the server reports, in the order of their indices in the legend.
+pub const TOKEN_TYPES: [&str; 8] = ["keyword", "type", "variable", "function", "number", "string", "operator", "macro"];
+
+/// The semantic token modifiers the server reports, in the order of their bits, matching `highlight::Modifiers`.
+pub const TOKEN_MODIFIERS: [&str; 2] = ["declaration", "readonly"];
+
+/// The JSON-RPC error code for a request the server does not support.
+const METHOD_NOT_FOUND: i64 = -32601;
+
+/// A language server for the documents an editor has open.
+///
+/// # Fields
+/// * `config` - The settings every document is lexed with.
+/// * `documents` - The text of every open document, by URI.
+/// * `shutdown` - Whether the client has asked the server to shut down.
+/// * `exit_code` - The code to exit with, once the client has sent `exit`.
+pub struct Server {
+    config: LexerConfig,
+    documents: HashMap<String, String>,
+    shutdown: bool,
+    exit_code: Option<u8>,
+}
+
+impl Server {
+    /// Creates a server with no open documents that lexes with the given settings.
+    pub fn new(config: LexerConfig) -> Self {
+        Self { config, documents: HashMap::new(), shutdown: false, exit_code: None }
+    }
+
+    /// Handles one message from the client.
+    ///
+    /// # Parameters
+    /// * `message` - A JSON-RPC request or notification.
+    ///
+    /// # Returns
+    /// * `Vec<Value>` - The response to a request, or the notifications a document change causes, in the order
+    ///   they should be sent.
+    pub fn handle(&mut self, message: &Value) -> Vec<Value> {
+        let method = message["method"].as_str().unwrap_or("");
+        let params = &message["params"];
+        let uri = params["textDocument"]["uri"].as_str().unwrap_or("").to_string();
+        let result = match method {
+            "initialize" => capabilities(),
+            "shutdown" => {
+                self.shutdown = true;
+                Value::Null
+            }
+            "exit" => {
+                self.exit_code = Some(if self.shutdown { 0 } else { 1 });
+                return Vec::new();
+            }
+            "textDocument/didOpen" => {
+                let text = params["textDocument"]["text"].as_str().unwrap_or("").to_string();
+                return self.update(uri, text);
+            }
+            "textDocument/didChange" => {
+                let changes = params["contentChanges"].as_array();
+                let Some(text) = changes.and_then(|changes| changes.last()).and_then(|change| change["text"].as_str())
+                else {
+                    return Vec::new();
+                };
+                return self.update(uri, text.to_string());
+            }
+            "textDocument/didClose" => {
+                self.documents.remove(&uri);
+                return vec![publish_diagnostics(&uri, Vec::new())];
+            }
+            "textDocument/semanticTokens/full" => self.with_document(&uri, |source, tokens| {
+                json!({ "data": encode_semantic_tokens(source, tokens) })
+            }),
+            "textDocument/documentSymbol" => self.with_document(&uri, document_symbols),
+            "textDocument/foldingRange" => self.with_document(&uri, folding_ranges),
+            _ if message.get("id").is_none() => return Vec::new(),
+            _ => {
+                let error = json!({ "code": METHOD_NOT_FOUND, "message": format!("unsupported method `{}`", method) });
+                return vec![json!({ "jsonrpc": "2.0", "id": message["id"], "error": error })];
+            }
+        };
+        match message.get("id") {
+            Some(id) => vec![json!({ "jsonrpc": "2.0", "id": id, "result": result })],
+            None => Vec::new(),
+        }
+    }
+
+    /// Gives the code the process should exit with once the client has sent `exit`: 0 if it asked the server to
+    /// shut down first, and 1 otherwise.
+    pub fn exit_code(&self) -> Option<u8> {
+        self.exit_code
+    }
+
+    /// Stores the new text of a document and gives the notification publishing its diagnostics
+    fn update(&mut self, uri: String, text: String) -> Vec<Value> {
+        let (tokens, errors, reported) = Lexer::lex_with_diagnostics(&text, &self.config);
+        let index = LineIndex::new(&text);
+        let diagnostics = check_lexed_with(&text, &tokens, &errors, reported, &LintConfig::default())
+            .into_iter()
+            .filter(|diagnostic| !diagnostic.suppressed)
+            .map(|diagnostic| {
+                let severity = match diagnostic.severity {
+                    Severity::Error => 1,
+                    Severity::Warning => 2,
+                    Severity::Info => 3,
+                };
+                json!({
+                    "range": range(&text, &index, diagnostic.span),
+                    "severity": severity,
+                    "code": diagnostic.code,
+                    "source": "lexer",
+                    "message": diagnostic.message,
+                })
+            })
+            .collect();
+        let notification = publish_diagnostics(&uri, diagnostics);
+        self.documents.insert(uri, text);
+        vec![notification]
+    }
+
+    /// Lexes an open document and answers a request from its tokens, or answers `null` if it is not open
+    fn with_document(&self, uri: &str, answer: impl Fn(&str, &[SpannedToken]) -> Value) -> Value {
+        let Some(source) = self.documents.get(uri) else {
+            return Value::Null;
+        };
+        let (tokens, _) = Lexer::lex_recovering(source, &self.config);
+        answer(source, &tokens)
+    }
+}
+
+/// Reads one message framed with a `Content-Length` header.
+///
+/// # Returns
+/// * `Ok(Some(Value))` - The message.
+/// * `Ok(None)` - If the input ended before another message started.
+/// * `Err(io::Error)` - If the input cannot be read, or a header or message is malformed.
+pub fn read_message<R: BufRead>(reader: &mut R) -> io::Result<Option<Value>> {
+    let mut length: Option<usize> = None;
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        let header = line.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("Content-Length") {
+                length = Some(value.trim().parse().map_err(|_| malformed("invalid Content-Length header"))?);
+            }
+        }
+    }
+
+    let length = length.ok_or_else(|| malformed("missing Content-Length header"))?;
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body)?;
+    serde_json::from_slice(&body).map(Some).map_err(|error| malformed(&error.to_string()))
+}
+
+/// Writes one message framed with a `Content-Length` header, and flushes it.
+pub fn write_message<W: Write>(writer: &mut W, message: &Value) -> io::Result<()> {
+    let body = message.to_string();
+    write!(writer, "Content-Length: {}\r\n\r\n{}", body.len(), body)?;
+    writer.flush()
+}
+
+/// Gives the result of `initialize`, announcing what the server supports
+fn capabilities() -> Value {
+    json!({
+        "capabilities": {
+            "textDocumentSync": 1,
+            "semanticTokensProvider": {
+                "legend": { "tokenTypes": TOKEN_TYPES, "tokenModifiers": TOKEN_MODIFIERS },
+                "full": true,
+            },
+            "documentSymbolProvider": true,
+            "foldingRangeProvider": true,
+        },
+        "serverInfo": { "name": "lexer-lsp", "version": env!("CARGO_PKG_VERSION") },
+    })
+}
+
+/// Builds a `textDocument/publishDiagnostics` notification
+fn publish_diagnostics(uri: &str, diagnostics: Vec<Value>) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/publishDiagnostics",
+        "params": { "uri": uri, "diagnostics": diagnostics },
+    })
+}
+
+/// Encodes tokens as the relative five-number groups of `semanticTokens/full`, cutting tokens that span lines at
+/// the end of their first line
+fn encode_semantic_tokens(source: &str, tokens: &[SpannedToken]) -> Vec<u32> {
+    let index = LineIndex::new(source);
+    let mut data: Vec<u32> = Vec::new();
+    let (mut previous_line, mut previous_start) = (0, 0);
+    for token in semantic_tokens(tokens) {
+        let token_type = match token.kind {
+            HighlightKind::Keyword => 0,
+            HighlightKind::Type => 1,
+            HighlightKind::Variable => 2,
+            HighlightKind::Function => 3,
+            HighlightKind::Number => 4,
+            HighlightKind::String => 5,
+            HighlightKind::Operator => 6,
+            HighlightKind::Preprocessor => 7,
+            HighlightKind::Punctuation | HighlightKind::Custom => continue,
+        };
+        let (line, start) = position(source, &index, token.span.start);
+        let text = &source[token.span.start..token.span.end];
+        let length = text.split('\n').next().unwrap_or("").encode_utf16().count() as u32;
+        let delta_start = if line == previous_line { start - previous_start } else { start };
+        data.extend([line - previous_line, delta_start, length, token_type, u32::from(token.modifiers.bits())]);
+        (previous_line, previous_start) = (line, start);
+    }
+    data
+}
+
+/// Lists the outline of a document as `DocumentSymbol`s
+fn document_symbols(source: &str, tokens: &[SpannedToken]) -> Value {
+    let index = LineIndex::new(source);
+    let symbols = outline(tokens)
+        .into_iter()
+        .map(|item| {
+            let kind = match item.kind {
+                OutlineKind::Function => 12,
+                OutlineKind::Struct => 23,
+                OutlineKind::Enum => 10,
+            };
+            json!({
+                "name": item.name,
+                "kind": kind,
+                "range": range(source, &index, item.span),
+                "selectionRange": range(source, &index, item.name_span),
+            })
+        })
+        .collect();
+    Value::Array(symbols)
+}
+
+/// Lists a folding range for every block that spans more than one line
+fn folding_ranges(source: &str, tokens: &[SpannedToken]) -> Value {
+    let index = LineIndex::new(source);
+    let ranges = extract_blocks(tokens)
+        .into_iter()
+        .filter_map(|block| {
+            let start_line = index.line_col(block.span.start).0 - 1;
+            let end_line = index.line_col(block.span.end).0 - 1;
+            (end_line > start_line).then(|| json!({ "startLine": start_line, "endLine": end_line }))
+        })
+        .collect();
+    Value::Array(ranges)
+}
+
+/// Converts a span into a protocol `Range`
+fn range(source: &str, index: &LineIndex, span: Span) -> Value {
+    let point = |offset: usize| {
+        let (line, character) = position(source, index, offset);
+        json!({ "line": line, "character": character })
+    };
+    json!({ "start": point(span.start), "end": point(span.end) })
+}
+
+/// Gives the zero-based line and UTF-16 column of a byte offset
+fn position(source: &str, index: &LineIndex, offset: usize) -> (u32, u32) {
+    let (line, column) = index.line_col(offset);
+    let line_start = offset.min(source.len()) + 1 - column;
+    let character = source[line_start..offset.min(source.len())].encode_utf16().count();
+    ((line - 1) as u32, character as u32)
+}
+
+/// Helper function to create an error for a malformed message
+fn malformed(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
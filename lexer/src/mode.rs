@@ -0,0 +1,20 @@
+//! This file defines the modes the lexer's state machine can be in.
+//!
+//! The lexer keeps a stack of modes rather than a single one so that constructs which nest, such as block
+//! comments, can be entered and left without recursion.
+
+/// The state the lexer is in while scanning.
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub enum LexMode {
+    /// Scanning ordinary tokens.
+    #[default]
+    Normal,
+    /// Inside a string literal.
+    InString,
+    /// Inside a block comment. When comments nest, each nested comment pushes this mode again.
+    InBlockComment,
+    /// Inside a preprocessor directive line.
+    InDirective,
+    /// Inside an interpolated expression within a string literal.
+    InInterpolation,
+}
@@ -0,0 +1,146 @@
+//! This file extracts an outline of a file from its tokens alone, so that editors can list the functions and types
+//! it defines without waiting for the parser.
+//!
+//! The outline is found by shape:
+//!
+//! * An identifier directly followed by a parenthesized list and then `{`, outside any braces, is a function
+//!   definition. Its span runs from the first token after the previous top-level declaration to its closing `}`.
+//! * `struct` or `enum` followed by a name and `{`, at any depth, defines a type. Its span runs from the keyword to
+//!   the closing `}`.
+//!
+//! Prototypes, anonymous types, and types only referred to by name do not appear in the outline.
+
+use crate::{
+    span::{Span, SpannedToken},
+    token::Token,
+};
+
+/// What an outline item defines.
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Hash)]
+pub enum OutlineKind {
+    /// A function definition.
+    Function,
+    /// A struct definition.
+    Struct,
+    /// An enum definition.
+    Enum,
+}
+
+/// A definition listed in a file's outline.
+///
+/// # Fields
+/// * `kind` - What is defined.
+/// * `name` - The name it is defined with.
+/// * `name_span` - Where the name appears.
+/// * `span` - The whole definition, up to and including its closing `}`, or to the end of the file if it is never
+///   closed.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct OutlineItem {
+    pub kind: OutlineKind,
+    pub name: String,
+    pub name_span: Span,
+    pub span: Span,
+}
+
+/// Extracts the function and type definitions from a stream of spanned tokens.
+///
+/// # Parameters
+/// * `tokens` - The tokens of a whole file, as produced by `Lexer::lex_spanned`.
+///
+/// # Returns
+/// * `Vec<OutlineItem>` - The definitions in the order they start.
+pub fn outline(tokens: &[SpannedToken]) -> Vec<OutlineItem> {
+    let mut items: Vec<OutlineItem> = Vec::new();
+    let mut depth: usize = 0;
+    let mut declaration_start: usize = 0;
+
+    for (index, spanned) in tokens.iter().enumerate() {
+        match &spanned.token {
+            Token::STRUCT | Token::ENUM => {
+                if let Some(item) = type_definition(tokens, index) {
+                    items.push(item);
+                }
+            }
+            Token::IDENTIFIER(name) if depth == 0 => {
+                if let Some(end) = function_body(tokens, index) {
+                    let span = Span::new(tokens[declaration_start.min(index)].span.start, end);
+                    let name = name.iter().collect();
+                    items.push(OutlineItem { kind: OutlineKind::Function, name, name_span: spanned.span, span });
+                }
+            }
+            Token::LBRACKET => depth += 1,
+            Token::RBRACKET => {
+                depth = depth.saturating_sub(1);
+                if depth == 0 && !matches!(tokens.get(index + 1).map(|next| &next.token), Some(Token::SEMICOLON)) {
+                    declaration_start = index + 1;
+                }
+            }
+            Token::SEMICOLON | Token::HASHLINE(_) if depth == 0 => declaration_start = index + 1,
+            _ => {}
+        }
+    }
+    items.sort_by_key(|item| item.span.start);
+    items
+}
+
+/// Recognizes `struct` or `enum` at `index` followed by a name and a body
+fn type_definition(tokens: &[SpannedToken], index: usize) -> Option<OutlineItem> {
+    let kind = if tokens[index].token == Token::STRUCT { OutlineKind::Struct } else { OutlineKind::Enum };
+    let name_token = tokens.get(index + 1)?;
+    let Token::IDENTIFIER(name) = &name_token.token else {
+        return None;
+    };
+    if tokens.get(index + 2)?.token != Token::LBRACKET {
+        return None;
+    }
+    let end = closing_end(tokens, index + 2);
+    Some(OutlineItem {
+        kind,
+        name: name.iter().collect(),
+        name_span: name_token.span,
+        span: Span::new(tokens[index].span.start, end),
+    })
+}
+
+/// Checks whether the identifier at `index` is followed by a parenthesized list and a body, returning where the body
+/// ends
+fn function_body(tokens: &[SpannedToken], index: usize) -> Option<usize> {
+    if tokens.get(index + 1)?.token != Token::LPAREN {
+        return None;
+    }
+    let mut parens: usize = 0;
+    for (offset, spanned) in tokens[index + 1..].iter().enumerate() {
+        match spanned.token {
+            Token::LPAREN => parens += 1,
+            Token::RPAREN => {
+                parens -= 1;
+                if parens == 0 {
+                    let body = index + 2 + offset;
+                    return (tokens.get(body)?.token == Token::LBRACKET).then(|| closing_end(tokens, body));
+                }
+            }
+            Token::SEMICOLON | Token::LBRACKET | Token::RBRACKET | Token::EOF => return None,
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Gives the end of the `}` matching the `{` at `open`, or of the last token if it is never closed
+fn closing_end(tokens: &[SpannedToken], open: usize) -> usize {
+    let mut depth: usize = 0;
+    for spanned in &tokens[open..] {
+        match spanned.token {
+            Token::LBRACKET => depth += 1,
+            Token::RBRACKET => {
+                depth -= 1;
+                if depth == 0 {
+                    return spanned.span.end;
+                }
+            }
+            _ => {}
+        }
+    }
+    let last = tokens.iter().rev().find(|spanned| spanned.token != Token::EOF);
+    last.map_or(tokens[open].span.end, |last| last.span.end)
+}
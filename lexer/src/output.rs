@@ -0,0 +1,92 @@
+//! This file defines `LexOutput`, everything one lex of a source file produces, bundled so that callers take one
+//! value rather than a bare `Result`, and so that more can be added to it without changing any signature.
+//!
+//! `LexOutput` and `LexStats` are `#[non_exhaustive]`: they can only be made by the lexer, and new fields may be
+//! added in any minor release.
+
+use std::time::Duration;
+
+use crate::{
+    diagnostic::{Diagnostic, Severity},
+    error::LexError,
+    interner::GlobalInterner,
+    line_index::LineIndex,
+    span::SpannedToken,
+};
+
+/// Counts and timings for one lex.
+///
+/// # Fields
+/// * `bytes` - The size of the input in bytes.
+/// * `lines` - The number of lines in the input, as `LineIndex::line_count` gives it.
+/// * `tokens` - The number of tokens lexed, including `EOF` if it was kept.
+/// * `errors` - The number of errors.
+/// * `warnings` - The number of warning diagnostics.
+/// * `elapsed` - How long lexing took.
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+#[non_exhaustive]
+pub struct LexStats {
+    pub bytes: usize,
+    pub lines: usize,
+    pub tokens: usize,
+    pub errors: usize,
+    pub warnings: usize,
+    pub elapsed: Duration,
+}
+
+/// Everything one lex produces, as returned by `Lexer::lex_output`.
+///
+/// # Fields
+/// * `tokens` - Every token that could be lexed, with its span. Text that failed to lex is left out.
+/// * `errors` - Every error, in source order.
+/// * `diagnostics` - The warnings reported while lexing, such as whitespace lints, in source order.
+/// * `line_index` - The start of every line of the input, for turning spans into lines and columns.
+/// * `interner` - The interner that identifiers and paths from this lex should be interned into, shared by every
+///   file in the process.
+/// * `stats` - Counts and timings.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct LexOutput {
+    pub tokens: Vec<SpannedToken>,
+    pub errors: Vec<LexError>,
+    pub diagnostics: Vec<Diagnostic>,
+    pub line_index: LineIndex,
+    pub interner: &'static GlobalInterner,
+    pub stats: LexStats,
+}
+
+impl LexOutput {
+    /// Bundles the results of a lex, counting them into its stats
+    pub(crate) fn new(
+        input: &str,
+        tokens: Vec<SpannedToken>,
+        errors: Vec<LexError>,
+        diagnostics: Vec<Diagnostic>,
+        elapsed: Duration,
+    ) -> Self {
+        let line_index = LineIndex::new(input);
+        let stats = LexStats {
+            bytes: input.len(),
+            lines: line_index.line_count(),
+            tokens: tokens.len(),
+            errors: errors.len(),
+            warnings: diagnostics.iter().filter(|diagnostic| diagnostic.severity == Severity::Warning).count(),
+            elapsed,
+        };
+        Self { tokens, errors, diagnostics, line_index, interner: GlobalInterner::global(), stats }
+    }
+
+    /// Returns true if the input lexed without errors.
+    pub fn is_ok(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// Converts the output into the result `Lexer::lex_located` gives, discarding everything else.
+    pub fn into_result(self) -> Result<Vec<SpannedToken>, Vec<LexError>> {
+        if self.errors.is_empty() {
+            Ok(self.tokens)
+        } else {
+            Err(self.errors)
+        }
+    }
+}
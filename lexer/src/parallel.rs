@@ -0,0 +1,95 @@
+//! This file lexes many files concurrently.
+//!
+//! Lexing a file shares no state with lexing any other, and `Token`, `Span`, and `ErrorType` are all `Send + Sync`,
+//! so files can be handed out to worker threads freely. Workers pull the next unclaimed file from a shared counter
+//! as soon as they finish their current one, so a few large files do not leave the other threads idle.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    thread,
+};
+
+use common::error::ErrorType;
+
+use crate::{
+    core::Lexer,
+    span::SpannedToken,
+};
+
+/// The outcome of lexing one file.
+///
+/// # Fields
+/// * `path` - The file that was lexed.
+/// * `result` - Its tokens, or the errors encountered reading or lexing it.
+#[derive(PartialEq, Debug, Clone)]
+pub struct FileLexResult {
+    pub path: PathBuf,
+    pub result: Result<Vec<SpannedToken>, Vec<ErrorType>>,
+}
+
+/// Lexes every file in `paths` across all available cores.
+///
+/// # Parameters
+/// * `paths` - The files to lex.
+///
+/// # Returns
+/// * `Vec<FileLexResult>` - One result per path, in the same order as `paths`.
+pub fn lex_files_parallel<P: AsRef<Path> + Sync>(paths: &[P]) -> Vec<FileLexResult> {
+    let threads = thread::available_parallelism().map_or(1, |n| n.get());
+    lex_files_with_threads(paths, threads)
+}
+
+/// Lexes every file in `paths` using at most `threads` worker threads.
+///
+/// # Returns
+/// * `Vec<FileLexResult>` - One result per path, in the same order as `paths`.
+pub fn lex_files_with_threads<P: AsRef<Path> + Sync>(paths: &[P], threads: usize) -> Vec<FileLexResult> {
+    map_with_threads(paths, threads, |path| lex_file(path.as_ref()))
+}
+
+/// Applies `f` to every item using at most `threads` worker threads, each pulling the next unclaimed item.
+///
+/// # Returns
+/// * `Vec<R>` - One result per item, in the same order as `items`.
+pub(crate) fn map_with_threads<T, R, F>(items: &[T], threads: usize, f: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync,
+{
+    let next: AtomicUsize = AtomicUsize::new(0);
+    let results: Mutex<Vec<Option<R>>> = Mutex::new(items.iter().map(|_| None).collect());
+
+    thread::scope(|scope| {
+        for _ in 0..threads.clamp(1, items.len().max(1)) {
+            scope.spawn(|| loop {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                let Some(item) = items.get(index) else {
+                    break;
+                };
+                let result = f(item);
+                results.lock().expect("a worker panicked while storing a result")[index] = Some(result);
+            });
+        }
+    });
+
+    results
+        .into_inner()
+        .expect("a worker panicked while storing a result")
+        .into_iter()
+        .map(|result| result.expect("every path is claimed by exactly one worker"))
+        .collect()
+}
+
+/// Reads and lexes a single file.
+fn lex_file(path: &Path) -> FileLexResult {
+    let result = fs::read_to_string(path)
+        .map_err(|error| vec![ErrorType::IoError { path: path.display().to_string(), message: error.to_string() }])
+        .and_then(|source| Lexer::lex_spanned(&source));
+    FileLexResult { path: path.to_path_buf(), result }
+}
@@ -0,0 +1,64 @@
+//! This file defines how tightly each operator binds, following C.
+//!
+//! The table lives beside the token definitions so that the parser, constant evaluation, and external tools all
+//! agree on it. Higher numbers bind more tightly. Binary and ternary operators use `precedence`; operators that
+//! can also appear before an operand, such as `-` and `*`, bind at `UNARY_PRECEDENCE` in that position.
+
+use crate::token::TokenKind;
+
+/// Which side an operator groups towards when it appears several times in a row.
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Hash)]
+pub enum Assoc {
+    /// `a - b - c` is `(a - b) - c`.
+    Left,
+    /// `a = b = c` is `a = (b = c)`.
+    Right,
+}
+
+/// The binding strength of prefix operators: `-`, `!`, `~`, `*`, `&`, `++`, and `--`.
+pub const UNARY_PRECEDENCE: u8 = 13;
+
+/// The binding strength of postfix operators: `++`, `--`, `->`, `.`, calls, and indexing.
+pub const POSTFIX_PRECEDENCE: u8 = 14;
+
+/// Gives the precedence and associativity of a binary or ternary operator.
+///
+/// # Parameters
+/// * `kind` - The kind of the operator token.
+///
+/// # Returns
+/// * `Some((u8, Assoc))` - How tightly the operator binds and how it groups. `?` is the ternary conditional.
+/// * `None` - If the token is not a binary or ternary operator.
+pub fn precedence(kind: TokenKind) -> Option<(u8, Assoc)> {
+    let entry = match kind {
+        TokenKind::EQUAL => (1, Assoc::Right),
+        TokenKind::CTRUE => (2, Assoc::Right),
+        TokenKind::BARBAR => (3, Assoc::Left),
+        TokenKind::ANDAND => (4, Assoc::Left),
+        TokenKind::BAR => (5, Assoc::Left),
+        TokenKind::CARET => (6, Assoc::Left),
+        TokenKind::AMPERSAND => (7, Assoc::Left),
+        TokenKind::EQUALEQUAL | TokenKind::NOTEQUAL => (8, Assoc::Left),
+        TokenKind::LESSTHAN | TokenKind::GREATERTHAN | TokenKind::LESSTHANEQUAL | TokenKind::GREATERTHANEQUAL => {
+            (9, Assoc::Left)
+        }
+        TokenKind::PLUS | TokenKind::DASH => (11, Assoc::Left),
+        TokenKind::ASTERISK | TokenKind::FSLASH | TokenKind::PERCENT => (12, Assoc::Left),
+        _ => return None,
+    };
+    Some(entry)
+}
+
+/// Returns true if the token can be used as a prefix operator.
+pub fn is_prefix_operator(kind: TokenKind) -> bool {
+    matches!(
+        kind,
+        TokenKind::DASH
+            | TokenKind::EXCLAMATIONPOINT
+            | TokenKind::TILDE
+            | TokenKind::ASTERISK
+            | TokenKind::AMPERSAND
+            | TokenKind::PLUSPLUS
+            | TokenKind::MINUSMINUS
+    )
+}
@@ -0,0 +1,27 @@
+//! This file is the stable face of the crate: everything most callers need, re-exported under one path.
+//!
+//! ```
+//! use lexer::prelude::*;
+//! let output: LexOutput = Lexer::lex_output("int x;", &LexerBuilder::new().build());
+//! assert!(output.is_ok());
+//! ```
+//!
+//! Everything re-exported here follows semantic versioning: it is only removed or changed incompatibly in a major
+//! release. The modules behind it, such as `core` and the scanner tables, may be reorganized in any release, so
+//! code that reaches into them directly should expect to follow those changes. Items the prelude does not re-export
+//! are usable but carry no such promise.
+
+pub use common::error::ErrorType;
+
+pub use crate::{
+    config::{LexerBuilder, LexerConfig},
+    core::Lexer,
+    diagnostic::{Diagnostic, Severity},
+    error::LexError,
+    fingerprint::hash_tokens,
+    line_index::LineIndex,
+    output::{LexOutput, LexStats},
+    source::SourceFile,
+    span::{Location, Span, SpannedToken},
+    token::{Token, TokenCategory, TokenKind},
+};
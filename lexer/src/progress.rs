@@ -0,0 +1,56 @@
+//! This file reports how far long-running lexes have got, so that GUI and language server hosts can show a
+//! progress bar and stay responsive during operations that take seconds, such as lexing a generated file of many
+//! megabytes with `Lexer::lex_with_progress` or a whole project with `driver::analyze_project_with_progress`.
+//!
+//! Progress is reported through a `ProgressReporter`, which any `Fn(&Progress) + Sync` closure is. Reports come in
+//! order, with counts that never go down, even when files are lexed on several threads, and the last report of an
+//! operation always has every byte and file counted.
+
+use std::path::Path;
+
+/// How far an operation has got.
+///
+/// # Fields
+/// * `bytes_processed` - How many bytes of input have been lexed.
+/// * `bytes_total` - How many bytes the operation will lex in all.
+/// * `files_completed` - How many files have been finished, or 0 when lexing a single input.
+/// * `files_total` - How many files the operation will lex, or 0 when lexing a single input.
+/// * `current_file` - The file just started, if the report is for one.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub struct Progress<'a> {
+    pub bytes_processed: usize,
+    pub bytes_total: usize,
+    pub files_completed: usize,
+    pub files_total: usize,
+    pub current_file: Option<&'a Path>,
+}
+
+impl Progress<'_> {
+    /// Gives the share of the work done, from 0.0 to 1.0, by bytes. An operation with no bytes to lex is done.
+    pub fn fraction(&self) -> f64 {
+        if self.bytes_total == 0 {
+            return 1.0;
+        }
+        self.bytes_processed as f64 / self.bytes_total as f64
+    }
+
+    /// Returns true if every byte and file has been counted.
+    pub fn is_done(&self) -> bool {
+        self.bytes_processed == self.bytes_total && self.files_completed == self.files_total
+    }
+}
+
+/// Something that is told how far an operation has got.
+///
+/// Reports are made on the threads doing the work, one at a time, so a reporter should return quickly, for
+/// example by sending the progress to the host's event loop rather than redrawing there and then.
+pub trait ProgressReporter: Sync {
+    /// Receives the progress of an operation.
+    fn report(&self, progress: &Progress);
+}
+
+impl<F: Fn(&Progress) + Sync> ProgressReporter for F {
+    fn report(&self, progress: &Progress) {
+        self(progress)
+    }
+}
@@ -0,0 +1,300 @@
+//! This file loads the settings a team shares for a project from a `lexer.toml` file, so that the CLI and the
+//! project driver lex every file the same way.
+//!
+//! ```toml
+//! dialect = "c99"
+//! nested_comments = false
+//! soft_fail = false
+//! include_paths = ["include"]
+//! ignore = ["build", "*.gen.c", "vendor/**/*.h"]
+//!
+//! [keywords]
+//! contextual = ["defer"]
+//! operator_aliases = true
+//!
+//! [identifiers]
+//! start = "$"
+//!
+//! [defines]
+//! DEBUG = "1"
+//!
+//! [lints]
+//! max_identifier_length = 31
+//! reserved_names = true
+//! naming_style = "snake_case"
+//! normalization = "nfkc"
+//! whitespace = true
+//!
+//! [budgets]
+//! max_file_tokens = 20000
+//! max_block_tokens = 2000
+//! max_block_lines = 200
+//! ```
+//!
+//! Every key is optional. Block comments nest only if `nested_comments` is true, since neither dialect nests them.
+//! `soft_fail` keeps text the lexer does not support as unknown tokens with warnings, as `LexerBuilder::soft_fail`
+//! describes. `operator_aliases` lexes `and`, `or`, `not`, and the other C++ alternative operator spellings as
+//! operators. A `max_identifier_length` of 0 turns the length lint off. Budgets, described in the `budget` module,
+//! are unset unless given. Include paths are relative to the directory holding the file. Ignore patterns use `*`
+//! and `?` within one path component and `**` across any number of them; a pattern without a `/` matches any single
+//! component, so `build` ignores every directory or file named `build`, while a pattern with one matches the whole
+//! path relative to the project root.
+//!
+//! Settings can still be overridden in code: `lexer_builder` returns a `LexerBuilder` that further calls refine.
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use common::error::ErrorType;
+use serde::Deserialize;
+
+use crate::{
+    budget::Budgets,
+    config::LexerBuilder,
+    driver::DriverConfig,
+    lint::{LintConfig, NamingStyle, NormalizationForm},
+};
+
+/// The name of the configuration file looked for in a project.
+pub const CONFIG_FILE_NAME: &str = "lexer.toml";
+
+/// The version of C whose keywords the lexer recognizes.
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Dialect {
+    /// C89, the lexer's default.
+    #[default]
+    C89,
+    /// C99, which adds `inline` and `restrict` as contextual keywords.
+    C99,
+}
+
+impl Dialect {
+    /// Returns true if block comments nest in the dialect. Neither C89 nor C99 nests them.
+    pub fn nested_comments(self) -> bool {
+        match self {
+            Dialect::C89 | Dialect::C99 => false,
+        }
+    }
+}
+
+/// The settings of a project, as read from a `lexer.toml` file.
+///
+/// # Fields
+/// * `dialect` - The version of C to lex.
+/// * `nested_comments` - Whether block comments nest, or `None` to follow the dialect.
+/// * `soft_fail` - Whether to pass `LexerBuilder::soft_fail` true.
+/// * `include_paths` - Where to look for included headers, for tools that preprocess.
+/// * `ignore` - Patterns for files and directories the driver skips.
+/// * `keywords` - Extra keywords to recognize.
+/// * `identifiers` - Extra identifier characters to allow.
+/// * `defines` - Macros that tools which preprocess should treat as defined, with their values.
+/// * `lints` - Which identifier lints run.
+/// * `budgets` - How large files and their blocks may be.
+#[derive(PartialEq, Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProjectConfig {
+    #[serde(default)]
+    pub dialect: Dialect,
+    pub nested_comments: Option<bool>,
+    #[serde(default)]
+    pub soft_fail: bool,
+    #[serde(default)]
+    pub include_paths: Vec<PathBuf>,
+    #[serde(default)]
+    pub ignore: Vec<String>,
+    #[serde(default)]
+    pub keywords: KeywordProfile,
+    #[serde(default)]
+    pub identifiers: IdentifierChars,
+    #[serde(default)]
+    pub defines: BTreeMap<String, String>,
+    #[serde(default)]
+    pub lints: LintToggles,
+    #[serde(default)]
+    pub budgets: Budgets,
+}
+
+/// Extra keywords a project recognizes.
+///
+/// # Fields
+/// * `contextual` - Words registered with `LexerBuilder::contextual_keyword`.
+/// * `operator_aliases` - Whether to pass `LexerBuilder::operator_aliases` true.
+#[derive(PartialEq, Eq, Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct KeywordProfile {
+    #[serde(default)]
+    pub contextual: Vec<String>,
+    #[serde(default)]
+    pub operator_aliases: bool,
+}
+
+/// Extra identifier characters a project allows.
+///
+/// # Fields
+/// * `start` - Characters passed to `LexerBuilder::identifier_start`.
+/// * `continue_chars` - Characters passed to `LexerBuilder::identifier_continue`, written as `continue`.
+#[derive(PartialEq, Eq, Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct IdentifierChars {
+    #[serde(default)]
+    pub start: String,
+    #[serde(default, rename = "continue")]
+    pub continue_chars: String,
+}
+
+/// The identifier lints a project turns on or off. Unset lints keep their `LintConfig::default` setting.
+///
+/// # Fields
+/// * `max_identifier_length` - The longest identifier allowed, or 0 for no limit.
+/// * `reserved_names` - Whether to flag names reserved for the implementation.
+/// * `naming_style` - The naming convention to enforce, `snake_case` or `camel_case`.
+/// * `normalization` - The normalization form to compare identifiers under, `nfc` or `nfkc`.
+/// * `whitespace` - Whether to warn about trailing whitespace, mixed indentation, and a missing final newline, off
+///   unless set.
+#[derive(PartialEq, Eq, Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LintToggles {
+    pub max_identifier_length: Option<usize>,
+    pub reserved_names: Option<bool>,
+    pub naming_style: Option<NamingStyle>,
+    pub normalization: Option<NormalizationForm>,
+    pub whitespace: Option<bool>,
+}
+
+impl ProjectConfig {
+    /// Parses a configuration written in TOML.
+    ///
+    /// # Errors
+    /// * `ErrorType::InvalidConfigFile` if the text is not valid TOML or has an unknown key or a value of the
+    ///   wrong type.
+    pub fn from_toml(text: &str) -> Result<Self, ErrorType> {
+        toml::from_str(text).map_err(|error| invalid(error.message()))
+    }
+
+    /// Reads a configuration file, resolving its include paths against the directory holding it.
+    ///
+    /// # Errors
+    /// * `ErrorType::IoError` if the file cannot be read.
+    /// * `ErrorType::InvalidConfigFile` if its contents are invalid.
+    pub fn load(path: &Path) -> Result<Self, ErrorType> {
+        let text = fs::read_to_string(path).map_err(|error| ErrorType::IoError {
+            path: path.display().to_string(),
+            message: error.to_string(),
+        })?;
+        let mut config = ProjectConfig::from_toml(&text)?;
+        let dir = path.parent().unwrap_or(Path::new(""));
+        config.include_paths = config.include_paths.iter().map(|include| dir.join(include)).collect();
+        Ok(config)
+    }
+
+    /// Finds the `lexer.toml` in `dir` or the nearest directory above it and reads it.
+    ///
+    /// # Returns
+    /// * `Ok(Some((PathBuf, ProjectConfig)))` - The file found and its configuration.
+    /// * `Ok(None)` - If no directory up to the root of the file system has one.
+    /// * `Err(ErrorType)` - If the file found cannot be read or is invalid.
+    pub fn discover(dir: &Path) -> Result<Option<(PathBuf, ProjectConfig)>, ErrorType> {
+        for ancestor in dir.ancestors() {
+            let path = ancestor.join(CONFIG_FILE_NAME);
+            if path.is_file() {
+                let config = ProjectConfig::load(&path)?;
+                return Ok(Some((path, config)));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Creates a builder holding the project's lexer settings, which further calls can override before it is
+    /// built.
+    ///
+    /// # Errors
+    /// * `ErrorType::InvalidIdentifierCharacter` if an extra identifier character is not allowed.
+    pub fn lexer_builder(&self) -> Result<LexerBuilder, ErrorType> {
+        let mut builder =
+            LexerBuilder::new().nested_comments(self.nested_comments.unwrap_or(self.dialect.nested_comments()));
+        if self.dialect == Dialect::C99 {
+            builder = builder.c99_contextual_keywords();
+        }
+        if let Some(whitespace) = self.lints.whitespace {
+            builder = builder.whitespace_lints(whitespace);
+        }
+        for keyword in &self.keywords.contextual {
+            builder = builder.contextual_keyword(keyword);
+        }
+        builder = builder.operator_aliases(self.keywords.operator_aliases).soft_fail(self.soft_fail);
+        builder.identifier_start(&self.identifiers.start)?.identifier_continue(&self.identifiers.continue_chars)
+    }
+
+    /// Applies the project's lint toggles and budgets to the default lint settings.
+    pub fn lint_config(&self) -> LintConfig {
+        let mut lints = LintConfig::default();
+        if let Some(length) = self.lints.max_identifier_length {
+            lints.max_identifier_length = (length > 0).then_some(length);
+        }
+        if let Some(reserved_names) = self.lints.reserved_names {
+            lints.reserved_names = reserved_names;
+        }
+        if self.lints.naming_style.is_some() {
+            lints.naming_style = self.lints.naming_style;
+        }
+        if self.lints.normalization.is_some() {
+            lints.normalization = self.lints.normalization;
+        }
+        lints.budgets = self.budgets;
+        lints
+    }
+
+    /// Builds the settings the project driver runs with.
+    ///
+    /// # Errors
+    /// * `ErrorType::InvalidIdentifierCharacter` if an extra identifier character is not allowed.
+    pub fn driver_config(&self) -> Result<DriverConfig, ErrorType> {
+        Ok(DriverConfig {
+            lexer: self.lexer_builder()?.build(),
+            lints: self.lint_config(),
+            ignore: self.ignore.clone(),
+            ..DriverConfig::default()
+        })
+    }
+}
+
+/// Checks whether a path, relative to the project root, matches any ignore pattern.
+pub fn is_ignored(relative: &Path, patterns: &[String]) -> bool {
+    let components: Vec<String> =
+        relative.components().map(|component| component.as_os_str().to_string_lossy().into_owned()).collect();
+    let joined = components.join("/");
+    patterns.iter().any(|pattern| {
+        if pattern.contains('/') {
+            glob_matches(pattern.trim_start_matches('/').as_bytes(), joined.as_bytes())
+        } else {
+            components.iter().any(|component| glob_matches(pattern.as_bytes(), component.as_bytes()))
+        }
+    })
+}
+
+/// Matches text against a glob pattern, where `*` and `?` stay within a path component and `**` spans any number
+/// of them
+fn glob_matches(pattern: &[u8], text: &[u8]) -> bool {
+    match pattern {
+        [] => text.is_empty(),
+        [b'*', b'*', rest @ ..] => {
+            let rest = rest.strip_prefix(b"/").unwrap_or(rest);
+            (0..=text.len()).any(|skip| (skip == 0 || text[skip - 1] == b'/') && glob_matches(rest, &text[skip..]))
+        }
+        [b'*', rest @ ..] => {
+            let run = text.iter().position(|&b| b == b'/').unwrap_or(text.len());
+            (0..=run).any(|skip| glob_matches(rest, &text[skip..]))
+        }
+        [b'?', rest @ ..] => text.first().is_some_and(|&b| b != b'/') && glob_matches(rest, &text[1..]),
+        [b, rest @ ..] => text.first() == Some(b) && glob_matches(rest, &text[1..]),
+    }
+}
+
+/// Helper function to create an invalid configuration file error
+fn invalid(message: &str) -> ErrorType {
+    ErrorType::InvalidConfigFile { message: message.to_string() }
+}
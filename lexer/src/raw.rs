@@ -0,0 +1,108 @@
+//! This file lexes into a compact stream of raw tokens, each holding only its kind and its length.
+//!
+//! A `Token` is at least 32 bytes and owns a heap allocation for every identifier and number, which adds up on
+//! files of millions of tokens. A `RawToken` is 8 bytes. Whitespace and comments are kept as `TokenKind::TRIVIA`
+//! tokens, so the raw tokens tile the source exactly and the span of every token follows from the lengths of the
+//! ones before it. Payloads are recovered from the source text on demand.
+
+use crate::{
+    config::LexerConfig,
+    core::Lexer,
+    error::LexError,
+    literal,
+    span::Span,
+    token::{Token, TokenKind},
+};
+
+/// A token reduced to its kind and its length in bytes.
+///
+/// # Fields
+/// * `kind` - The kind of the token. Custom tokens are `TokenKind::CUSTOM`; the name of their kind is not kept.
+/// * `len` - The length of the token in bytes.
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Hash)]
+pub struct RawToken {
+    pub kind: TokenKind,
+    pub len: u32,
+}
+
+const _: () = assert!(std::mem::size_of::<RawToken>() == 8);
+
+impl RawToken {
+    /// Creates a raw token.
+    pub fn new(kind: TokenKind, len: u32) -> Self {
+        Self { kind, len }
+    }
+}
+
+/// Lexes `input` into raw tokens, including trivia, ending with `TokenKind::EOF`.
+///
+/// # Returns
+/// * `Ok(Vec<RawToken>)` - The raw tokens, whose lengths sum to the length of `input`.
+/// * `Err(Vec<LexError>)` - Every error and the span of source it was found at.
+pub fn lex_raw(input: &str) -> Result<Vec<RawToken>, Vec<LexError>> {
+    lex_raw_with_config(input, &LexerConfig::default())
+}
+
+/// Lexes `input` into raw tokens with a custom configuration.
+///
+/// # Returns
+/// * `Ok(Vec<RawToken>)` - The raw tokens, whose lengths sum to the length of `input`.
+/// * `Err(Vec<LexError>)` - Every error and the span of source it was found at.
+pub fn lex_raw_with_config(input: &str, config: &LexerConfig) -> Result<Vec<RawToken>, Vec<LexError>> {
+    let tokens = Lexer::lex_without_payloads(input, config)?;
+    let mut raw: Vec<RawToken> = Vec::with_capacity(tokens.len() * 2);
+    let mut offset: usize = 0;
+
+    for spanned in tokens {
+        push_trivia(&mut raw, spanned.span.start - offset);
+        let len = u32::try_from(spanned.span.len()).expect("a single token is longer than 4 GiB");
+        raw.push(RawToken::new(spanned.token.kind(), len));
+        offset = spanned.span.end;
+    }
+    Ok(raw)
+}
+
+/// Appends `len` bytes of trivia, split across several tokens if it does not fit in one.
+fn push_trivia(raw: &mut Vec<RawToken>, mut len: usize) {
+    while len > 0 {
+        let chunk = len.min(u32::MAX as usize);
+        raw.push(RawToken::new(TokenKind::TRIVIA, chunk as u32));
+        len -= chunk;
+    }
+}
+
+/// Pairs every raw token with its span, computed from the lengths of the tokens before it.
+pub fn with_spans(tokens: &[RawToken]) -> impl Iterator<Item = (RawToken, Span)> + '_ {
+    tokens.iter().scan(0, |offset: &mut usize, token| {
+        let start = *offset;
+        *offset += token.len as usize;
+        Some((*token, Span::new(start, *offset)))
+    })
+}
+
+/// Rebuilds the full token for a raw token from the source it was lexed from.
+///
+/// # Parameters
+/// * `source` - The source code the raw tokens were lexed from.
+/// * `kind` - The kind of the raw token.
+/// * `span` - The span of the raw token, as given by `with_spans`.
+///
+/// # Returns
+/// * `Some(Token)` - The token, with its payload read from the source. Custom tokens get an empty kind name.
+/// * `None` - For trivia, which is not a token.
+pub fn recover(source: &str, kind: TokenKind, span: Span) -> Option<Token> {
+    let text = &source[span.start..span.end];
+    let token = match kind {
+        TokenKind::TRIVIA => return None,
+        TokenKind::NUMBER => Token::NUMBER(text.chars().collect()),
+        TokenKind::IDENTIFIER => Token::IDENTIFIER(text.chars().collect()),
+        TokenKind::STRINGLITERAL => Token::STRINGLITERAL(text[1..text.len() - 1].chars().collect()),
+        TokenKind::CHAR => Token::CHAR(literal::parse_char(text).ok().and_then(char::from_u32).unwrap_or_default()),
+        TokenKind::CUSTOM => Token::CUSTOM(String::new(), text.chars().collect()),
+        TokenKind::HASHLINE => Token::HASHLINE(text.chars().collect()),
+        TokenKind::TYPENAME => Token::TYPENAME(text.chars().collect()),
+        TokenKind::UNKNOWN => Token::UNKNOWN(text.chars().collect()),
+        kind => Token::ALL_KINDS[kind as usize].clone(),
+    };
+    Some(token)
+}
@@ -0,0 +1,110 @@
+//! This file anonymizes token streams, so that users can share token dumps from proprietary code, and reproduce
+//! lexer bugs with them, without giving the code away.
+//!
+//! Redaction replaces the payload of every identifier, literal, preprocessor line, custom token, and unknown text
+//! with a placeholder, and keeps everything else: kinds, spans, keywords, and operators. Placeholders are numbered in
+//! order of first appearance, separately for each kind of payload, and the same payload always gets the same
+//! placeholder, so a redacted stream still shows which names and literals repeat. Numbering restarts with every
+//! `Redactor`, so redacting the same file twice gives the same result.
+//!
+//! | Payload | Placeholder |
+//! |---------|-------------|
+//! | Identifiers and type names | `id1`, `id2`, ... |
+//! | Numbers | `1`, `2`, ... |
+//! | String literals | `str1`, `str2`, ... |
+//! | Character literals | `a` to `z`, starting again at `a` after `z` |
+//! | Preprocessor lines | The directive followed by `line1`, `line2`, ..., such as `#include line1` |
+//! | Custom tokens | `custom1`, `custom2`, ... |
+//! | Unknown text | `unknown1`, `unknown2`, ... |
+
+use std::{collections::HashMap, hash::Hash};
+
+use crate::{span::SpannedToken, token::Token};
+
+/// Assigns placeholders to payloads, remembering them so that repeated payloads get the same one.
+///
+/// # Fields
+/// * `identifiers` - The placeholder number of every identifier seen.
+/// * `numbers` - The placeholder number of every number seen.
+/// * `strings` - The placeholder number of every string literal seen.
+/// * `chars` - The placeholder number of every character literal seen.
+/// * `lines` - The placeholder number of every preprocessor line seen.
+/// * `custom` - The placeholder number of every custom token text seen.
+/// * `unknown` - The placeholder number of every unknown text seen.
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub struct Redactor {
+    identifiers: HashMap<Vec<char>, usize>,
+    numbers: HashMap<Vec<char>, usize>,
+    strings: HashMap<Vec<char>, usize>,
+    chars: HashMap<char, usize>,
+    lines: HashMap<Vec<char>, usize>,
+    custom: HashMap<Vec<char>, usize>,
+    unknown: HashMap<Vec<char>, usize>,
+}
+
+impl Redactor {
+    /// Creates a redactor that has seen nothing. Use one per file.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Gives the placeholder of an identifier, such as `id1`.
+    pub fn identifier(&mut self, name: &[char]) -> String {
+        format!("id{}", number(&mut self.identifiers, name))
+    }
+
+    /// Gives the placeholder of a number, such as `1`.
+    pub fn number(&mut self, digits: &[char]) -> String {
+        number(&mut self.numbers, digits).to_string()
+    }
+
+    /// Replaces the payload of a token with its placeholder, leaving tokens without a payload unchanged.
+    pub fn redact(&mut self, token: &Token) -> Token {
+        match token {
+            Token::IDENTIFIER(name) => Token::IDENTIFIER(self.identifier(name).chars().collect()),
+            Token::TYPENAME(name) => Token::TYPENAME(self.identifier(name).chars().collect()),
+            Token::NUMBER(digits) => Token::NUMBER(self.number(digits).chars().collect()),
+            Token::STRINGLITERAL(text) => {
+                Token::STRINGLITERAL(format!("str{}", number(&mut self.strings, text)).chars().collect())
+            }
+            Token::CHAR(c) => {
+                let n = number(&mut self.chars, c);
+                Token::CHAR((b'a' + ((n - 1) % 26) as u8) as char)
+            }
+            Token::HASHLINE(line) => {
+                let directive: String = line
+                    .iter()
+                    .skip(1)
+                    .skip_while(|c| c.is_whitespace())
+                    .take_while(|c| c.is_ascii_alphabetic())
+                    .collect();
+                let n = number(&mut self.lines, line);
+                let text = if directive.is_empty() {
+                    format!("#line{}", n)
+                } else {
+                    format!("#{} line{}", directive, n)
+                };
+                Token::HASHLINE(text.chars().collect())
+            }
+            Token::CUSTOM(kind, text) => {
+                Token::CUSTOM(kind.clone(), format!("custom{}", number(&mut self.custom, text)).chars().collect())
+            }
+            Token::UNKNOWN(text) => {
+                Token::UNKNOWN(format!("unknown{}", number(&mut self.unknown, text)).chars().collect())
+            }
+            token => token.clone(),
+        }
+    }
+}
+
+/// Redacts a file's tokens with a fresh `Redactor`, keeping their spans.
+pub fn redact_tokens(tokens: &[SpannedToken]) -> Vec<SpannedToken> {
+    let mut redactor = Redactor::new();
+    tokens.iter().map(|spanned| SpannedToken::new(redactor.redact(&spanned.token), spanned.span)).collect()
+}
+
+/// Gives the placeholder number of a payload, assigning the next one if it has not been seen
+fn number<K: Hash + Eq, Q: ToOwned<Owned = K> + ?Sized>(seen: &mut HashMap<K, usize>, key: &Q) -> usize {
+    let next = seen.len() + 1;
+    *seen.entry(key.to_owned()).or_insert(next)
+}
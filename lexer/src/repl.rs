@@ -0,0 +1,124 @@
+//! This file holds the state of an interactive session that lexes each entry as it is typed, for exploring how the
+//! lexer treats edge cases.
+//!
+//! An entry is usually one line, but continues onto the next line while it is incomplete:
+//!
+//! * A `(`, `[`, or `{` is still open.
+//! * A block comment is still open.
+//! * The line ends with a backslash. As in C, the backslash and the line break are removed, so a string literal
+//!   or preprocessor line can be continued.
+//!
+//! A string literal left open without a trailing backslash is complete, and reported as unterminated, since C
+//! literals cannot span lines.
+
+use crate::{
+    config::LexerConfig,
+    core::Lexer,
+    error::LexError,
+    span::SpannedToken,
+    token::Token,
+};
+
+/// The prompt shown before the first line of an entry.
+pub const PROMPT: &str = "lex> ";
+
+/// The prompt shown before each line that continues an entry.
+pub const CONTINUATION_PROMPT: &str = "...> ";
+
+/// The result of feeding a line to a `Repl`.
+#[derive(PartialEq, Debug, Clone)]
+pub enum ReplEntry {
+    /// The entry continues on the next line.
+    Incomplete,
+    /// The entry is complete.
+    ///
+    /// # Fields
+    /// * `source` - The text of the entry, with continued lines joined.
+    /// * `tokens` - Its tokens, ending with `Token::EOF`, as produced by `Lexer::lex_recovering`.
+    /// * `errors` - Every error found in it, in source order.
+    Complete {
+        source: String,
+        tokens: Vec<SpannedToken>,
+        errors: Vec<LexError>,
+    },
+}
+
+/// An interactive lexing session.
+///
+/// # Fields
+/// * `config` - The settings every entry is lexed with.
+/// * `buffer` - The lines of the current entry received so far.
+pub struct Repl {
+    config: LexerConfig,
+    buffer: String,
+}
+
+impl Repl {
+    /// Creates a session with no entry in progress.
+    pub fn new(config: LexerConfig) -> Self {
+        Self { config, buffer: String::new() }
+    }
+
+    /// Adds a line typed by the user.
+    ///
+    /// # Parameters
+    /// * `line` - The line, with or without its line break.
+    ///
+    /// # Returns
+    /// * `ReplEntry::Incomplete` - If the entry continues on the next line.
+    /// * `ReplEntry::Complete` - The lexed entry, after which the next line starts a new one.
+    pub fn feed(&mut self, line: &str) -> ReplEntry {
+        let line = line.strip_suffix('\n').unwrap_or(line);
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        if let Some(spliced) = line.strip_suffix('\\') {
+            self.buffer.push_str(spliced);
+            return ReplEntry::Incomplete;
+        }
+        self.buffer.push_str(line);
+        if is_incomplete(&self.buffer, &self.config) {
+            self.buffer.push('\n');
+            return ReplEntry::Incomplete;
+        }
+        self.finish()
+    }
+
+    /// Lexes the entry in progress as it stands, such as when the input ends, and starts a new one.
+    pub fn finish(&mut self) -> ReplEntry {
+        let source = std::mem::take(&mut self.buffer);
+        let (tokens, errors) = Lexer::lex_recovering(&source, &self.config);
+        ReplEntry::Complete { source, tokens, errors }
+    }
+
+    /// Returns true if an entry has been started but not completed.
+    pub fn is_continuing(&self) -> bool {
+        !self.buffer.is_empty()
+    }
+
+    /// Gives the prompt to show before the next line.
+    pub fn prompt(&self) -> &'static str {
+        if self.is_continuing() {
+            CONTINUATION_PROMPT
+        } else {
+            PROMPT
+        }
+    }
+}
+
+/// Checks whether an entry leaves a bracket or block comment open
+fn is_incomplete(source: &str, config: &LexerConfig) -> bool {
+    let mut lexer = Lexer::new(source, config.clone());
+    let mut depths: [usize; 3] = [0; 3];
+    loop {
+        match lexer.next_token().map(|spanned| spanned.token) {
+            Ok(Token::EOF) => break,
+            Ok(Token::LPAREN) => depths[0] += 1,
+            Ok(Token::RPAREN) => depths[0] = depths[0].saturating_sub(1),
+            Ok(Token::LBRACE) => depths[1] += 1,
+            Ok(Token::RBRACE) => depths[1] = depths[1].saturating_sub(1),
+            Ok(Token::LBRACKET) => depths[2] += 1,
+            Ok(Token::RBRACKET) => depths[2] = depths[2].saturating_sub(1),
+            _ => {}
+        }
+    }
+    lexer.unclosed_comment() || depths.iter().any(|depth| *depth > 0)
+}
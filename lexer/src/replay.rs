@@ -0,0 +1,288 @@
+//! This file records whole lexer sessions so that they can be replayed step by step elsewhere, making a bug a user
+//! hits reproducible from a file they can attach to a report.
+//!
+//! A recording holds a hash of the input, the settings the lexer ran with, the trace of every decision it made, and
+//! the text each decision covered. Recorded with redaction, that text no longer gives the source away: identifiers
+//! are renamed `id1`, `id2`, and so on, and numbers `1`, `2`, and so on, as the `redact` module names them, and the
+//! contents of comments, string and character literals, preprocessor lines, and tokens from custom rules are masked
+//! with `x`, keeping whitespace and the delimiters.
+//! Spans still refer to the original source, and the kinds, rules, and modes of every step are kept, so the
+//! recording still shows where and how the lexer went wrong.
+//!
+//! ## File format
+//!
+//! The magic bytes `LXRC` and a version byte, the input hash as 8 little-endian bytes, the input length, a byte that
+//! is 1 if the recording is redacted, the number of settings followed by each name and value, the length of the
+//! trace followed by the trace in the format `Trace::encode` writes, and the number of lexemes followed by each one.
+//! Counts and lengths are LEB128 varints, and strings are a varint length followed by UTF-8 bytes.
+
+use common::error::ErrorType;
+
+use crate::{
+    codec::{self, Reader},
+    config::LexerConfig,
+    core::Lexer,
+    fingerprint::content_hash,
+    redact::Redactor,
+    span::Span,
+    trace::{Decision, Rule, Trace, TraceStep},
+    trivia::{split_trivia, TriviaKind},
+};
+
+/// The bytes every recording starts with.
+const MAGIC: &[u8; 4] = b"LXRC";
+
+/// The version of the recording file format.
+pub const RECORDING_VERSION: u8 = 2;
+
+/// A recorded lexer session.
+///
+/// # Fields
+/// * `input_hash` - The hash of the input, as `fingerprint::content_hash` gives it.
+/// * `input_len` - The length of the input in bytes.
+/// * `redacted` - Whether the lexemes were redacted.
+/// * `settings` - The settings the lexer ran with, as names and values.
+/// * `trace` - Every decision the lexer made.
+/// * `lexemes` - The text each step of the trace covered, redacted if `redacted` is set.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Recording {
+    pub input_hash: u64,
+    pub input_len: usize,
+    pub redacted: bool,
+    pub settings: Vec<(String, String)>,
+    pub trace: Trace,
+    pub lexemes: Vec<String>,
+}
+
+/// One step of a replayed session.
+///
+/// # Fields
+/// * `index` - The position of the step in the session, counting from 0.
+/// * `step` - The decision the lexer made.
+/// * `lexeme` - The text the step covered, redacted if the recording is.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub struct ReplayStep<'a> {
+    pub index: usize,
+    pub step: &'a TraceStep,
+    pub lexeme: &'a str,
+}
+
+impl ReplayStep<'_> {
+    /// Gives the span of the original source the step covered.
+    pub fn span(&self) -> Span {
+        self.step.span
+    }
+}
+
+impl Recording {
+    /// Lexes an input while recording the session.
+    ///
+    /// # Parameters
+    /// * `input` - The source code to lex.
+    /// * `config` - The settings to lex with, as built by a `LexerBuilder`.
+    /// * `redact` - Whether to redact identifiers and the contents of comments, literals, and preprocessor lines.
+    pub fn record(input: &str, config: &LexerConfig, redact: bool) -> Self {
+        let (_, trace) = Lexer::lex_traced(input, config);
+        let mut redactor = Redactor::new();
+        let lexemes: Vec<String> = trace
+            .steps
+            .iter()
+            .map(|step| {
+                if redact {
+                    redact_step(input, step, &mut redactor, config)
+                } else {
+                    input[step.span.start..step.span.end].to_string()
+                }
+            })
+            .collect();
+        Self {
+            input_hash: content_hash(input),
+            input_len: input.len(),
+            redacted: redact,
+            settings: describe_settings(config),
+            trace,
+            lexemes,
+        }
+    }
+
+    /// Returns true if the recording was made from `input`.
+    pub fn matches(&self, input: &str) -> bool {
+        input.len() == self.input_len && content_hash(input) == self.input_hash
+    }
+
+    /// Replays the session one step at a time.
+    pub fn replay(&self) -> impl Iterator<Item = ReplayStep<'_>> {
+        self.trace
+            .steps
+            .iter()
+            .zip(self.lexemes.iter())
+            .enumerate()
+            .map(|(index, (step, lexeme))| ReplayStep { index, step, lexeme })
+    }
+
+    /// Joins the lexemes back together: the input itself, or a redacted copy of it with the same token structure.
+    pub fn source(&self) -> String {
+        self.lexemes.concat()
+    }
+
+    /// Encodes the recording into its binary file format.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut bytes: Vec<u8> = MAGIC.to_vec();
+        bytes.push(RECORDING_VERSION);
+        bytes.extend_from_slice(&self.input_hash.to_le_bytes());
+        codec::write_varint(&mut bytes, self.input_len as u64);
+        bytes.push(self.redacted as u8);
+        codec::write_varint(&mut bytes, self.settings.len() as u64);
+        for (name, value) in self.settings.iter() {
+            write_string(&mut bytes, name);
+            write_string(&mut bytes, value);
+        }
+        let trace = self.trace.encode();
+        codec::write_varint(&mut bytes, trace.len() as u64);
+        bytes.extend_from_slice(&trace);
+        codec::write_varint(&mut bytes, self.lexemes.len() as u64);
+        for lexeme in self.lexemes.iter() {
+            write_string(&mut bytes, lexeme);
+        }
+        bytes
+    }
+
+    /// Decodes a recording produced by `encode`.
+    ///
+    /// # Errors
+    /// * `ErrorType::MalformedTokenStream` if the bytes are truncated, corrupt, or from another format version, or
+    ///   if the trace and the lexemes do not line up.
+    pub fn decode(bytes: &[u8]) -> Result<Self, ErrorType> {
+        let mut reader = Reader { bytes, position: 0 };
+        if reader.take(MAGIC.len())? != MAGIC {
+            return Err(codec::malformed("not a lexer recording"));
+        }
+        let version = reader.take(1)?[0];
+        if version != RECORDING_VERSION {
+            return Err(codec::malformed(&format!("unsupported recording version {}", version)));
+        }
+
+        let input_hash = u64::from_le_bytes(reader.take(8)?.try_into().expect("8 bytes were taken"));
+        let input_len = reader.varint()? as usize;
+        let redacted = match reader.take(1)?[0] {
+            0 => false,
+            1 => true,
+            flag => return Err(codec::malformed(&format!("unknown redaction flag {}", flag))),
+        };
+        let count = reader.varint()? as usize;
+        let mut settings: Vec<(String, String)> = Vec::with_capacity(count.min(bytes.len()));
+        for _ in 0..count {
+            settings.push((read_string(&mut reader)?, read_string(&mut reader)?));
+        }
+        let length = reader.varint()? as usize;
+        let trace = Trace::decode(reader.take(length)?)?;
+        let count = reader.varint()? as usize;
+        if count != trace.steps.len() {
+            return Err(codec::malformed("the lexemes do not match the trace"));
+        }
+        let mut lexemes: Vec<String> = Vec::with_capacity(count.min(bytes.len()));
+        for _ in 0..count {
+            lexemes.push(read_string(&mut reader)?);
+        }
+
+        if reader.position != bytes.len() {
+            return Err(codec::malformed("trailing bytes after recording"));
+        }
+        Ok(Self { input_hash, input_len, redacted, settings, trace, lexemes })
+    }
+}
+
+/// Lists the settings of a configuration that can be written down. Custom and regex rules are code, so only their
+/// number and the kinds of the regex rules are given
+fn describe_settings(config: &LexerConfig) -> Vec<(String, String)> {
+    let chars = |chars: &[char]| chars.iter().collect::<String>();
+    let mut contextual: Vec<String> = config.keyword_table().contextual_keywords().collect();
+    contextual.sort();
+    let regex_kinds: Vec<&str> = config.regex_rules.iter().map(|rule| rule.kind.as_str()).collect();
+    [
+        ("custom_rules", config.rules.len().to_string()),
+        ("regex_rules", regex_kinds.join(",")),
+        ("split_angle_brackets", config.split_angle_brackets.to_string()),
+        ("contextual_keywords", contextual.join(",")),
+        ("operator_aliases", config.keyword_table().has_operator_aliases().to_string()),
+        ("identifier_start", chars(&config.identifier_start)),
+        ("identifier_continue", chars(&config.identifier_continue)),
+        ("max_comment_depth", config.max_comment_depth().to_string()),
+        ("max_bracket_depth", config.max_bracket_depth().to_string()),
+        ("nested_comments", config.nested_comments.to_string()),
+        ("whitespace_lints", config.whitespace_lints.to_string()),
+        ("final_newline_lint", config.reports_missing_final_newline().to_string()),
+        ("emit_eof", config.emits_eof().to_string()),
+        ("nul_ends_input", config.nul_ends_input.to_string()),
+        ("control_chars", format!("{:?}", config.control_chars)),
+        ("leading_zeros", format!("{:?}", config.leading_zeros)),
+        ("max_number_digits", config.max_number_digits.map_or("none".to_string(), |max| max.to_string())),
+        ("reject_repeated_zeros", config.reject_repeated_zeros.to_string()),
+        ("multiline_strings", config.multiline_strings.to_string()),
+        ("soft_fail", config.soft_fail.to_string()),
+        ("max_token_length", config.max_token_length.map_or("none".to_string(), |max| max.to_string())),
+        ("long_tokens", format!("{:?}", config.long_tokens)),
+    ]
+    .into_iter()
+    .map(|(name, value)| (name.to_string(), value))
+    .collect()
+}
+
+/// Redacts the text one step covered
+fn redact_step(input: &str, step: &TraceStep, redactor: &mut Redactor, config: &LexerConfig) -> String {
+    let text = &input[step.span.start..step.span.end];
+    match &step.decision {
+        Decision::Trivia => split_trivia(input, step.span, config.nested_comments())
+            .into_iter()
+            .map(|trivia| {
+                let piece = &input[trivia.span.start..trivia.span.end];
+                match trivia.kind {
+                    TriviaKind::LineComment => mask_between(piece, 2, 0),
+                    TriviaKind::BlockComment => mask_between(piece, 2, 2),
+                    TriviaKind::Whitespace | TriviaKind::Newline => piece.to_string(),
+                }
+            })
+            .collect(),
+        // Custom and regex rules can match anything, so nothing they match is kept
+        Decision::Token { rule, .. } if *rule != Rule::Builtin => mask_between(text, 0, 0),
+        Decision::Token { kind, .. } if kind == "IDENTIFIER" || kind == "TYPENAME" => {
+            redactor.identifier(&text.chars().collect::<Vec<char>>())
+        }
+        Decision::Token { kind, .. } if kind == "NUMBER" => redactor.number(&text.chars().collect::<Vec<char>>()),
+        Decision::Token { kind, .. } if kind == "STRINGLITERAL" || kind == "CHAR" => mask_between(text, 1, 1),
+        Decision::Token { kind, .. } if kind == "HASHLINE" => mask_between(text, 1, 0),
+        Decision::Token { .. } => text.to_string(),
+        Decision::Error { .. } => mask_between(text, 0, 0),
+    }
+}
+
+/// Replaces every character of `text` but whitespace with `x`, keeping `head` characters at the start as they are,
+/// and `tail` at the end if they are not part of the head, so that delimiters such as quotes and `/*` stay
+fn mask_between(text: &str, head: usize, tail: usize) -> String {
+    let count = text.chars().count();
+    let tail = if count >= head + tail { tail } else { 0 };
+    text.chars()
+        .enumerate()
+        .map(|(index, c)| {
+            if index < head || index >= count - tail || c.is_whitespace() {
+                c
+            } else {
+                'x'
+            }
+        })
+        .collect()
+}
+
+/// Appends a string as its length and UTF-8 bytes
+fn write_string(bytes: &mut Vec<u8>, text: &str) {
+    codec::write_varint(bytes, text.len() as u64);
+    bytes.extend_from_slice(text.as_bytes());
+}
+
+/// Reads a string written by `write_string`
+fn read_string(reader: &mut Reader) -> Result<String, ErrorType> {
+    let length = reader.varint()? as usize;
+    std::str::from_utf8(reader.take(length)?)
+        .map(str::to_string)
+        .map_err(|_| codec::malformed("string is not valid UTF-8"))
+}
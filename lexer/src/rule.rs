@@ -0,0 +1,121 @@
+//! This file defines custom token rules, which let users extend the lexer with their own kinds of tokens (for
+//! example SQL strings or date literals) without changing the built-in scanning code.
+//!
+//! Rules are registered on a `LexerBuilder` with a priority, and are tried in order of priority before the
+//! built-in rules at the start of every token. For quick prototyping, token kinds can instead be defined by a
+//! regular expression, which competes with the built-in rules by longest match.
+
+use common::error::ErrorType;
+use regex::Regex;
+
+use crate::token::Token;
+
+/// A custom rule for recognizing tokens.
+///
+/// Rules must be `Send + Sync` so that a configured lexer can be shared between threads.
+pub trait TokenRule: Send + Sync {
+    /// Tries to lex a token at the cursor.
+    ///
+    /// # Parameters
+    /// * `cursor` - The input from the start of the token onwards. Characters the rule consumes become the token.
+    ///
+    /// # Returns
+    /// * `None` - The rule does not apply here. Anything consumed from the cursor is discarded and the next rule
+    ///   is tried.
+    /// * `Some(Ok(Token))` - The token, spanning the characters consumed. A rule that returns a token without
+    ///   consuming anything is treated as not applying, since it would never make progress.
+    /// * `Some(Err(ErrorType))` - The rule applies but the input is malformed.
+    fn try_lex(&self, cursor: &mut Cursor) -> Option<Result<Token, ErrorType>>;
+}
+
+/// A read-only view of the input from the start of the current token, through which a rule consumes characters.
+///
+/// # Fields
+/// * `input` - The entire input being lexed.
+/// * `start` - The position of the start of the current token.
+/// * `position` - The position of the next character to consume.
+pub struct Cursor<'a> {
+    input: &'a [char],
+    start: usize,
+    position: usize,
+}
+
+impl<'a> Cursor<'a> {
+    /// Creates a cursor at `start` within `input`.
+    pub fn new(input: &'a [char], start: usize) -> Self {
+        Self { input, start, position: start }
+    }
+
+    /// Gives the next character without consuming it.
+    pub fn peek(&self) -> Option<char> {
+        self.peek_nth(0)
+    }
+
+    /// Gives the character `n` positions past the next one without consuming anything.
+    pub fn peek_nth(&self, n: usize) -> Option<char> {
+        self.input.get(self.position + n).copied()
+    }
+
+    /// Consumes and returns the next character.
+    pub fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.position += 1;
+        Some(c)
+    }
+
+    /// Checks whether the remaining input begins with `pattern`.
+    pub fn starts_with(&self, pattern: &str) -> bool {
+        pattern.chars().enumerate().all(|(i, c)| self.peek_nth(i) == Some(c))
+    }
+
+    /// Consumes `pattern` if the remaining input begins with it.
+    pub fn eat(&mut self, pattern: &str) -> bool {
+        if self.starts_with(pattern) {
+            self.position += pattern.chars().count();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Consumes characters while `predicate` holds, returning how many were consumed.
+    pub fn eat_while(&mut self, predicate: impl Fn(char) -> bool) -> usize {
+        let before = self.position;
+        while self.peek().is_some_and(&predicate) {
+            self.position += 1;
+        }
+        self.position - before
+    }
+
+    /// Gives the characters consumed so far.
+    pub fn lexeme(&self) -> &'a [char] {
+        &self.input[self.start..self.position]
+    }
+
+    /// Gives the number of characters consumed so far.
+    pub fn consumed(&self) -> usize {
+        self.position - self.start
+    }
+}
+
+/// A token kind defined by a regular expression.
+///
+/// # Fields
+/// * `kind` - The name given to tokens matched by the rule.
+/// * `regex` - The pattern, anchored to the start of the token.
+#[derive(Debug, Clone)]
+pub(crate) struct RegexRule {
+    pub(crate) kind: String,
+    pub(crate) regex: Regex,
+}
+
+impl RegexRule {
+    /// Compiles a regex rule, anchoring `pattern` so it only matches at the start of a token.
+    pub(crate) fn new(kind: &str, pattern: &str) -> Result<Self, ErrorType> {
+        let regex = Regex::new(&format!("^(?:{})", pattern)).map_err(|error| ErrorType::InvalidTokenPattern {
+            pattern: pattern.to_string(),
+            message: error.to_string(),
+        })?;
+        Ok(Self { kind: kind.to_string(), regex })
+    }
+}
@@ -0,0 +1,119 @@
+//! This file writes diagnostics in the Static Analysis Results Interchange Format (SARIF) 2.1.0, which code scanning
+//! tools such as GitHub code scanning import to show results alongside the code.
+//!
+//! All files go into a single run. Every distinct diagnostic code becomes a rule, and every diagnostic a result
+//! referring to its rule. Columns are given in Unicode code points, as declared by the run's `columnKind`, and each
+//! region also carries the byte offset and length of the span. Diagnostics muted by a suppression comment carry an
+//! `inSource` suppression.
+
+use std::io::{self, Write};
+
+use serde_json::{json, Value};
+
+use crate::{
+    check::FileReport,
+    diagnostic::{Diagnostic, Severity},
+};
+
+/// The version of SARIF written.
+pub const SARIF_VERSION: &str = "2.1.0";
+
+/// The schema of the version of SARIF written.
+pub const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// Builds a SARIF log holding the diagnostics of every file.
+///
+/// # Parameters
+/// * `reports` - The files and their diagnostics, as made by `FileReport::check`.
+///
+/// # Returns
+/// * `Value` - The log as a JSON value, ready to be serialized.
+pub fn to_sarif(reports: &[FileReport]) -> Value {
+    let mut rules: Vec<&str> = Vec::new();
+    let mut results: Vec<Value> = Vec::new();
+
+    for report in reports {
+        for diagnostic in &report.diagnostics {
+            let rule_index = match rules.iter().position(|rule| *rule == diagnostic.code) {
+                Some(index) => index,
+                None => {
+                    rules.push(diagnostic.code);
+                    rules.len() - 1
+                }
+            };
+            results.push(result(report, diagnostic, rule_index));
+        }
+    }
+
+    json!({
+        "$schema": SARIF_SCHEMA,
+        "version": SARIF_VERSION,
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "lex",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "rules": rules.iter().map(|rule| json!({ "id": rule })).collect::<Vec<Value>>(),
+                },
+            },
+            "columnKind": "unicodeCodePoints",
+            "results": results,
+        }],
+    })
+}
+
+/// Writes the diagnostics of every file as a SARIF log.
+///
+/// # Parameters
+/// * `writer` - Where to write the log.
+/// * `reports` - The files and their diagnostics.
+pub fn write_sarif<W: Write>(writer: &mut W, reports: &[FileReport]) -> io::Result<()> {
+    serde_json::to_writer_pretty(&mut *writer, &to_sarif(reports))?;
+    writeln!(writer)
+}
+
+/// Converts one diagnostic into a SARIF result
+fn result(report: &FileReport, diagnostic: &Diagnostic, rule_index: usize) -> Value {
+    let level = match diagnostic.severity {
+        Severity::Error => "error",
+        Severity::Warning => "warning",
+        Severity::Info => "note",
+    };
+    let (start_line, start_column) = line_and_code_point_column(&report.source, diagnostic.span.start);
+    let (end_line, end_column) = line_and_code_point_column(&report.source, diagnostic.span.end);
+
+    let mut result = json!({
+        "ruleId": diagnostic.code,
+        "ruleIndex": rule_index,
+        "level": level,
+        "message": { "text": diagnostic.message },
+        "locations": [{
+            "physicalLocation": {
+                "artifactLocation": { "uri": report.path.replace('\\', "/") },
+                "region": {
+                    "startLine": start_line,
+                    "startColumn": start_column,
+                    "endLine": end_line,
+                    "endColumn": end_column,
+                    "byteOffset": diagnostic.span.start,
+                    "byteLength": diagnostic.span.len(),
+                },
+            },
+        }],
+    });
+    if diagnostic.suppressed {
+        result["suppressions"] = json!([{ "kind": "inSource" }]);
+    }
+    result
+}
+
+/// Gives the line of a byte offset and its column counted in code points, both from 1
+fn line_and_code_point_column(source: &str, offset: usize) -> (usize, usize) {
+    let mut offset = offset.min(source.len());
+    while !source.is_char_boundary(offset) {
+        offset -= 1;
+    }
+    let before = &source[..offset];
+    let line_start = before.rfind('\n').map_or(0, |newline| newline + 1);
+    (before.matches('\n').count() + 1, before[line_start..].chars().count() + 1)
+}
@@ -0,0 +1,70 @@
+//! This file minimizes inputs that make the lexer fail, so that a failure found by fuzzing or in a large corpus
+//! file can be turned into a regression test a few characters long.
+//!
+//! Shrinking is delta debugging over characters, so every candidate is valid UTF-8: chunks of the input are
+//! removed, starting with halves and working down to single characters, for as long as the failure persists. It
+//! is deterministic, so the same input and predicate always shrink to the same result, and the result is minimal
+//! in that removing any one character from it makes the failure go away.
+
+use std::panic::{self, AssertUnwindSafe};
+
+use common::error::ErrorType;
+
+use crate::{config::LexerConfig, core::Lexer};
+
+/// Minimizes an input while a failure persists.
+///
+/// # Parameters
+/// * `input` - An input that fails.
+/// * `predicate` - Returns true if an input still fails in the way being shrunk for, such as `panics_on` or
+///   `fails_with` give. It should be deterministic.
+///
+/// # Returns
+/// * `String` - The smallest failing input found. If `input` does not fail, it is returned unchanged.
+pub fn shrink_failing_input(input: &str, mut predicate: impl FnMut(&str) -> bool) -> String {
+    let mut chars: Vec<char> = input.chars().collect();
+    if !predicate(input) {
+        return input.to_string();
+    }
+
+    let mut chunks: usize = 2;
+    while !chars.is_empty() {
+        let chunk_len = chars.len().div_ceil(chunks);
+        let mut removed = false;
+        for start in (0..chars.len()).step_by(chunk_len) {
+            let mut candidate = chars.clone();
+            candidate.drain(start..(start + chunk_len).min(chars.len()));
+            if predicate(&candidate.iter().collect::<String>()) {
+                chars = candidate;
+                chunks = (chunks - 1).max(2);
+                removed = true;
+                break;
+            }
+        }
+        if !removed {
+            if chunks >= chars.len() {
+                break;
+            }
+            chunks = (chunks * 2).min(chars.len());
+        }
+    }
+    chars.into_iter().collect()
+}
+
+/// Gives a predicate that holds for inputs on which lexing with `config` panics.
+///
+/// Panics are caught, but the panic hook still runs, so each one is reported on standard error as usual.
+pub fn panics_on(config: &LexerConfig) -> impl Fn(&str) -> bool + '_ {
+    move |input| panic::catch_unwind(AssertUnwindSafe(|| Lexer::lex_located(input, config))).is_err()
+}
+
+/// Gives a predicate that holds for inputs on which lexing with `config` reports an error `matches` accepts.
+pub fn fails_with<'a>(
+    config: &'a LexerConfig,
+    matches: impl Fn(&ErrorType) -> bool + 'a,
+) -> impl Fn(&str) -> bool + 'a {
+    move |input| match Lexer::lex_located(input, config) {
+        Ok(_) => false,
+        Err(errors) => errors.iter().any(|error| matches(error.error())),
+    }
+}
@@ -0,0 +1,86 @@
+//! This file defines source files, which pair source code with its name and line index, and extract excerpts of
+//! it for diagnostics and for tools that quote code in their reports.
+
+use crate::{line_index::LineIndex, span::Span};
+
+/// A named piece of source code with its lines indexed.
+///
+/// # Fields
+/// * `name` - The name to report the source under, usually its path.
+/// * `text` - The source code.
+/// * `index` - The start of every line of `text`.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct SourceFile {
+    name: String,
+    text: String,
+    index: LineIndex,
+}
+
+/// One line of a source file.
+///
+/// # Fields
+/// * `number` - The line number, counting from 1.
+/// * `span` - The span of the line, excluding its newline, as `LineIndex::line_span` gives it.
+/// * `text` - The text of the line.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub struct SourceLine<'a> {
+    pub number: usize,
+    pub span: Span,
+    pub text: &'a str,
+}
+
+impl SourceFile {
+    /// Creates a source file, indexing its lines.
+    pub fn new(name: &str, text: &str) -> Self {
+        Self { name: name.to_string(), text: text.to_string(), index: LineIndex::new(text) }
+    }
+
+    /// Gives the name of the source file.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Gives the source code.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Gives the line index of the source code.
+    pub fn line_index(&self) -> &LineIndex {
+        &self.index
+    }
+
+    /// Gives the source code a span covers.
+    ///
+    /// # Returns
+    /// * `Some(&str)` - The text of the span.
+    /// * `None` - If the span is out of bounds, or either end of it lies inside a multi-byte character.
+    pub fn snippet(&self, span: Span) -> Option<&str> {
+        self.text.get(span.start..span.end)
+    }
+
+    /// Gives the line an offset is on. Offsets past the end of the source are clamped to it, so the last line is
+    /// given.
+    pub fn line_containing(&self, offset: usize) -> SourceLine<'_> {
+        let (number, _) = self.index.line_col(offset);
+        self.line(number).expect("a located line exists")
+    }
+
+    /// Gives the lines a span is on, along with up to `n` lines either side of them, in order.
+    ///
+    /// A span ending just after a newline ends on the line before it. An empty span is on the line it points to.
+    pub fn context_lines(&self, span: Span, n: usize) -> Vec<SourceLine<'_>> {
+        let (first, _) = self.index.line_col(span.start);
+        let (last, _) = self.index.line_col(if span.is_empty() { span.end } else { span.end - 1 });
+        let last = last.max(first);
+        (first.saturating_sub(n).max(1)..=(last + n).min(self.index.line_count()))
+            .filter_map(|number| self.line(number))
+            .collect()
+    }
+
+    /// Gives a line by its number, counting from 1
+    fn line(&self, number: usize) -> Option<SourceLine<'_>> {
+        let span = self.index.line_span(number)?;
+        Some(SourceLine { number, span, text: &self.text[span.start..span.end] })
+    }
+}
@@ -0,0 +1,156 @@
+//! This file defines spans, which locate tokens and diagnostics within the source code.
+
+use crate::{
+    column::display_width,
+    token::{Token, TokenKind},
+};
+
+/// A half-open range of byte offsets `[start, end)` into the source code.
+///
+/// # Fields
+/// * `start` - The byte offset of the first character covered by the span.
+/// * `end` - The byte offset one past the last character covered by the span.
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Default, Hash, PartialOrd, Ord)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    /// Creates a new span covering `[start, end)`.
+    pub fn new(start: usize, end: usize) -> Self {
+        Self { start, end }
+    }
+
+    /// Returns the number of bytes covered by the span.
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    /// Returns true if the span covers no bytes, as is the case for `EOF`.
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    /// Gives the smallest span covering both spans, such as the span of a syntax tree node from the spans of its
+    /// first and last tokens.
+    pub fn merge(&self, other: Span) -> Span {
+        Span::new(self.start.min(other.start), self.end.max(other.end))
+    }
+
+    /// Returns true if `other` lies entirely within the span. An empty span at either end of the span counts as
+    /// within it.
+    pub fn contains(&self, other: Span) -> bool {
+        self.start <= other.start && other.end <= self.end
+    }
+
+    /// Gives the line and columns at which the span starts.
+    ///
+    /// # Parameters
+    /// * `source` - The source code the span refers to.
+    /// * `tab_width` - The distance between tab stops, used for the display column.
+    pub fn start_location(&self, source: &str, tab_width: usize) -> Location {
+        Location::of(source, self.start, tab_width)
+    }
+
+    /// Gives the line and columns at which the span ends.
+    pub fn end_location(&self, source: &str, tab_width: usize) -> Location {
+        Location::of(source, self.end, tab_width)
+    }
+}
+
+/// A position in the source code as a human would describe it. All fields count from 1.
+///
+/// # Fields
+/// * `line` - The line number.
+/// * `byte_column` - The column counted in bytes from the start of the line.
+/// * `display_column` - The column counted in terminal cells, accounting for tabs, wide characters, and
+///   combining marks.
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Hash)]
+pub struct Location {
+    pub line: usize,
+    pub byte_column: usize,
+    pub display_column: usize,
+}
+
+impl Location {
+    /// Locates a byte offset within `source`. Offsets past the end of `source` are clamped to it, and offsets
+    /// inside a multi-byte character are moved back to its start.
+    pub fn of(source: &str, offset: usize, tab_width: usize) -> Self {
+        let mut offset = offset.min(source.len());
+        while !source.is_char_boundary(offset) {
+            offset -= 1;
+        }
+        let before = &source[..offset];
+        let line_start = before.rfind('\n').map_or(0, |newline| newline + 1);
+
+        Self {
+            line: before.matches('\n').count() + 1,
+            byte_column: offset - line_start + 1,
+            display_column: display_width(&before[line_start..], tab_width) + 1,
+        }
+    }
+}
+
+/// A token paired with the span of source code it was lexed from.
+///
+/// # Fields
+/// * `token` - The lexed token.
+/// * `span` - Where the token appears in the source code.
+/// * `synthetic` - Whether the lexer synthesized the token while recovering from an error, such as closing an
+///   unterminated string at the end of its line. Always false outside `Lexer::lex_recovering`.
+/// * `contextual_keyword` - Whether the token is an identifier spelled like a contextual keyword registered with
+///   `LexerBuilder::contextual_keyword`.
+#[derive(PartialEq, Debug, Clone)]
+pub struct SpannedToken {
+    pub token: Token,
+    pub span: Span,
+    pub synthetic: bool,
+    pub contextual_keyword: bool,
+}
+
+impl SpannedToken {
+    /// Pairs a token with its span.
+    pub fn new(token: Token, span: Span) -> Self {
+        Self { token, span, synthetic: false, contextual_keyword: false }
+    }
+
+    /// Returns the kind of the token.
+    pub fn kind(&self) -> TokenKind {
+        self.token.kind()
+    }
+
+    /// Gives the source text of the token, such as `and` for an `ANDAND` written with its alternative spelling.
+    pub fn lexeme<'a>(&self, source: &'a str) -> &'a str {
+        &source[self.span.start..self.span.end]
+    }
+
+    /// Splits a two-character operator into two tokens as `Token::split_first` does, dividing the span between
+    /// them. Operators written with an alternative spelling, such as `and`, cannot be split.
+    pub fn split_first(&self) -> Option<(SpannedToken, SpannedToken)> {
+        if self.span.len() != 2 {
+            return None;
+        }
+        let (first, second) = self.token.split_first()?;
+        // Every operator is ASCII, so its first character is one byte long
+        let middle = self.span.start + 1;
+        Some((
+            SpannedToken::new(first, Span::new(self.span.start, middle)),
+            SpannedToken::new(second, Span::new(middle, self.span.end)),
+        ))
+    }
+}
+
+/// Replaces the token at `index` with the two halves given by `SpannedToken::split_first`, leaving the stream
+/// unchanged if it cannot be split.
+///
+/// # Returns
+/// * `bool` - Whether the token was split.
+pub fn split_token_at(tokens: &mut Vec<SpannedToken>, index: usize) -> bool {
+    let Some((first, second)) = tokens.get(index).and_then(SpannedToken::split_first) else {
+        return false;
+    };
+    tokens[index] = first;
+    tokens.insert(index + 1, second);
+    true
+}
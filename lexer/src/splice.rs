@@ -0,0 +1,233 @@
+//! This file builds token streams in code and turns them back into source, so that code generators can assemble
+//! output from tokens, much as Rust's `quote!` does, mixing tokens they write with streams lexed from templates or
+//! user input.
+//!
+//! A `TokenStreamBuilder` collects tokens and bracket groups, and `build` lays them out as source and gives a
+//! `cursor::TokenStream` whose spans point into that source. Layout is minimal: everything goes on one line, apart
+//! from preprocessor lines, which get a line of their own, and a space is only written between two tokens where it
+//! is needed to keep them apart, as in `int x`, `- -`, or `/ *`. Lexing the source again gives the same tokens.
+
+use common::error::ErrorType;
+
+use crate::{
+    cursor::TokenStream,
+    emit::{emit, EmitStyle},
+    span::{Span, SpannedToken},
+    token::Token,
+};
+
+/// The brackets around a group.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Delimiter {
+    /// `( )`.
+    Parenthesis,
+    /// `[ ]`.
+    Bracket,
+    /// `{ }`.
+    Brace,
+}
+
+impl Delimiter {
+    /// Gives the opening and closing tokens of the delimiter.
+    pub fn tokens(self) -> (Token, Token) {
+        match self {
+            Delimiter::Parenthesis => (Token::LPAREN, Token::RPAREN),
+            Delimiter::Bracket => (Token::LBRACE, Token::RBRACE),
+            Delimiter::Brace => (Token::LBRACKET, Token::RBRACKET),
+        }
+    }
+}
+
+/// Assembles a token stream piece by piece.
+///
+/// ```
+/// use lexer::{core::Lexer, splice::{Delimiter, TokenStreamBuilder}};
+/// let body = Lexer::lex_spanned("return a + b;").unwrap();
+/// let parameters = TokenStreamBuilder::new().keyword("int")?.ident("a").punct(",")?.keyword("int")?.ident("b");
+/// let source = TokenStreamBuilder::new()
+///     .keyword("int")?
+///     .ident("add")
+///     .group(Delimiter::Parenthesis, parameters)
+///     .group(Delimiter::Brace, TokenStreamBuilder::new().splice(&body))
+///     .to_source();
+/// assert_eq!(source, "int add(int a,int b){return a+b;}");
+/// # Ok::<(), common::error::ErrorType>(())
+/// ```
+///
+/// # Fields
+/// * `tokens` - The tokens pushed so far.
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct TokenStreamBuilder {
+    tokens: Vec<Token>,
+}
+
+impl TokenStreamBuilder {
+    /// Creates an empty builder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Pushes a token. `Token::EOF` and `Token::DEFAULT` are skipped, since they have no source.
+    pub fn token(mut self, token: Token) -> Self {
+        if !matches!(token, Token::EOF | Token::DEFAULT) {
+            self.tokens.push(token);
+        }
+        self
+    }
+
+    /// Pushes a keyword by its spelling, such as `"while"`.
+    ///
+    /// # Errors
+    /// * `ErrorType::UnrecognizedToken` if the word is not a keyword.
+    pub fn keyword(self, word: &str) -> Result<Self, ErrorType> {
+        match Token::keywords().find(|(spelling, _)| *spelling == word) {
+            Some((_, token)) => Ok(self.token(token)),
+            None => Err(ErrorType::UnrecognizedToken { token: word.to_string() }),
+        }
+    }
+
+    /// Pushes an operator or punctuation by its spelling, such as `"->"`.
+    ///
+    /// # Errors
+    /// * `ErrorType::UnrecognizedToken` if no operator is spelled that way.
+    pub fn punct(self, spelling: &str) -> Result<Self, ErrorType> {
+        match Token::operators().find(|(operator, _)| *operator == spelling) {
+            Some((_, token)) => Ok(self.token(token)),
+            None => Err(ErrorType::UnrecognizedToken { token: spelling.to_string() }),
+        }
+    }
+
+    /// Pushes an identifier. The name is not checked, so it should be one the lexer accepts.
+    pub fn ident(self, name: &str) -> Self {
+        self.token(Token::IDENTIFIER(name.chars().collect()))
+    }
+
+    /// Pushes a number as written, such as `"0x1F"`. The text is not checked.
+    pub fn number(self, text: &str) -> Self {
+        self.token(Token::NUMBER(text.chars().collect()))
+    }
+
+    /// Pushes a string literal holding `text`, escaping backslashes, quotes, and control characters.
+    pub fn string(self, text: &str) -> Self {
+        let escaped: String = text.chars().map(|c| escape(c, '"')).collect();
+        self.token(Token::STRINGLITERAL(escaped.chars().collect()))
+    }
+
+    /// Pushes a character literal.
+    pub fn char(self, c: char) -> Self {
+        self.token(Token::CHAR(c))
+    }
+
+    /// Pushes a group: an opening bracket, the tokens of `inner`, and the matching closing bracket.
+    pub fn group(mut self, delimiter: Delimiter, inner: TokenStreamBuilder) -> Self {
+        let (open, close) = delimiter.tokens();
+        self.tokens.push(open);
+        self.tokens.extend(inner.tokens);
+        self.tokens.push(close);
+        self
+    }
+
+    /// Pushes lexed tokens, leaving out `Token::EOF`. Their spans are dropped, and new ones are given by `build`.
+    pub fn splice(mut self, tokens: &[SpannedToken]) -> Self {
+        for spanned in tokens {
+            self = self.token(spanned.token.clone());
+        }
+        self
+    }
+
+    /// Gives the tokens pushed so far.
+    pub fn tokens(&self) -> &[Token] {
+        &self.tokens
+    }
+
+    /// Lays the tokens out as source.
+    pub fn to_source(&self) -> String {
+        layout(&self.tokens).0
+    }
+
+    /// Lays the tokens out as indented source in the given style, as `emit::emit` does.
+    pub fn to_pretty_source(&self, style: &EmitStyle) -> String {
+        emit(&self.tokens, style)
+    }
+
+    /// Lays the tokens out as source and gives a cursor over them, with spans into the source and a final
+    /// `Token::EOF`.
+    pub fn build(self) -> (String, TokenStream) {
+        let (source, spans) = layout(&self.tokens);
+        let mut tokens: Vec<SpannedToken> =
+            self.tokens.into_iter().zip(spans).map(|(token, span)| SpannedToken::new(token, span)).collect();
+        tokens.push(SpannedToken::new(Token::EOF, Span::new(source.len(), source.len())));
+        (source, TokenStream::new(tokens))
+    }
+}
+
+/// Gives the source text of a token: its payload for identifiers, numbers, preprocessor lines, custom tokens, and
+/// unknown text, its payload in quotes for literals, and its spelling otherwise. `EOF` and `DEFAULT` have no text.
+pub fn token_text(token: &Token) -> String {
+    match token {
+        Token::IDENTIFIER(text) | Token::TYPENAME(text) | Token::NUMBER(text) | Token::HASHLINE(text) => {
+            text.iter().collect()
+        }
+        Token::CUSTOM(_, text) | Token::UNKNOWN(text) => text.iter().collect(),
+        Token::STRINGLITERAL(text) => format!("\"{}\"", text.iter().collect::<String>()),
+        Token::CHAR(c) => format!("'{}'", escape(*c, '\'')),
+        token => token.example().to_string(),
+    }
+}
+
+/// Turns tokens back into source with the minimal layout `TokenStreamBuilder::to_source` uses.
+pub fn detokenize(tokens: &[Token]) -> String {
+    layout(tokens).0
+}
+
+/// Writes tokens out, giving the span each was written at
+fn layout(tokens: &[Token]) -> (String, Vec<Span>) {
+    let mut source = String::new();
+    let mut spans: Vec<Span> = Vec::with_capacity(tokens.len());
+    let mut previous: Option<(&Token, String)> = None;
+    for token in tokens {
+        let text = token_text(token);
+        if let Some((previous, previous_text)) = &previous {
+            if matches!(previous, Token::HASHLINE(_)) || matches!(token, Token::HASHLINE(_)) {
+                source.push('\n');
+            } else if needs_space(previous_text, &text) {
+                source.push(' ');
+            }
+        }
+        let start = source.len();
+        source.push_str(&text);
+        spans.push(Span::new(start, source.len()));
+        previous = Some((token, text));
+    }
+    (source, spans)
+}
+
+/// Returns true if two tokens written next to each other would lex differently, as words and numbers run
+/// together, and operators combine or start a comment
+pub(crate) fn needs_space(previous: &str, next: &str) -> bool {
+    let (Some(last), Some(first)) = (previous.chars().last(), next.chars().next()) else {
+        return false;
+    };
+    let operator = |c: char| "+-*/%=!<>&|^~?:.".contains(c);
+    // Anything that is neither an operator nor a bracket, separator, or quote may continue a word or number
+    let wordlike = |c: char| !"(){}[];,\"'".contains(c) && (c == '.' || !operator(c));
+    let joined: String = [last, first].into_iter().collect();
+    (wordlike(last) && wordlike(first))
+        || joined == "//"
+        || joined == "/*"
+        || Token::operators().any(|(spelling, _)| spelling == joined)
+}
+
+/// Escapes a character for a literal delimited by `quote`
+fn escape(c: char, quote: char) -> String {
+    match c {
+        '\\' => "\\\\".to_string(),
+        '\n' => "\\n".to_string(),
+        '\t' => "\\t".to_string(),
+        '\r' => "\\r".to_string(),
+        c if c == quote => format!("\\{}", c),
+        // Octal escapes end after three digits, so unlike hexadecimal ones they cannot run into what follows
+        c if c.is_control() => format!("\\{:03o}", c as u32),
+        c => c.to_string(),
+    }
+}
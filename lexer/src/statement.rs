@@ -0,0 +1,83 @@
+//! This file splits a token stream into statements without parsing it, for tools that only need to count or
+//! visit statements.
+//!
+//! A statement ends at a `;` outside any brackets, or at a `}` that closes a block opened at the top level. A
+//! block followed by `else`, `while` (closing a `do` loop), or `;` (ending a `struct` or `enum` declaration)
+//! continues the same statement.
+
+use std::ops::Range;
+
+use crate::{
+    span::{Span, SpannedToken},
+    token::Token,
+};
+
+/// A run of tokens forming one top-level statement.
+///
+/// # Fields
+/// * `tokens` - The indices of the statement's tokens in the stream it was split from.
+/// * `span` - The source code from the start of its first token to the end of its last.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Statement {
+    pub tokens: Range<usize>,
+    pub span: Span,
+}
+
+/// Splits a token stream into top-level statements.
+///
+/// # Parameters
+/// * `tokens` - The tokens to split. A final `Token::EOF` is not part of any statement.
+///
+/// # Returns
+/// * `Vec<Statement>` - The statements in source order. Tokens after the last terminator form a final, unterminated
+///   statement.
+pub fn statements(tokens: &[SpannedToken]) -> Vec<Statement> {
+    let mut statements: Vec<Statement> = Vec::new();
+    let mut start: usize = 0;
+    let mut braces: usize = 0;
+    let mut parens: usize = 0;
+
+    for (index, spanned) in tokens.iter().enumerate() {
+        let ends_statement = match spanned.token {
+            Token::LBRACKET => {
+                braces += 1;
+                false
+            }
+            Token::RBRACKET => {
+                braces = braces.saturating_sub(1);
+                let continues = matches!(
+                    tokens.get(index + 1).map(|next| &next.token),
+                    Some(Token::ELSE | Token::WHILE | Token::SEMICOLON)
+                );
+                braces == 0 && parens == 0 && !continues
+            }
+            Token::LPAREN | Token::LBRACE => {
+                parens += 1;
+                false
+            }
+            Token::RPAREN | Token::RBRACE => {
+                parens = parens.saturating_sub(1);
+                false
+            }
+            Token::SEMICOLON => braces == 0 && parens == 0,
+            Token::EOF => break,
+            _ => false,
+        };
+        if ends_statement {
+            statements.push(statement(tokens, start..index + 1));
+            start = index + 1;
+        }
+    }
+
+    let end = tokens.iter().position(|spanned| spanned.token == Token::EOF).unwrap_or(tokens.len());
+    if start < end {
+        statements.push(statement(tokens, start..end));
+    }
+    statements
+}
+
+/// Builds the statement covering a range of tokens.
+fn statement(tokens: &[SpannedToken], range: Range<usize>) -> Statement {
+    let span = Span::new(tokens[range.start].span.start, tokens[range.end - 1].span.end);
+    Statement { tokens: range, span }
+}
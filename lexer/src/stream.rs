@@ -0,0 +1,150 @@
+//! This file lexes input that arrives a piece at a time, such as standard input fed by another program, emitting
+//! each token as soon as it is certain to be complete.
+//!
+//! Text is buffered until a line ends. Tokens never span lines, apart from preprocessor lines continued with a
+//! backslash, so the end of a line is a safe place to stop: every token on complete lines is emitted, except one
+//! that runs up to the end of the buffered text and so might continue, and nothing is emitted while a block
+//! comment is still open. Spans are byte offsets from the start of the whole stream.
+
+use serde_json::{json, Value};
+
+use crate::{
+    check::error_diagnostic,
+    config::LexerConfig,
+    core::Lexer,
+    error::LexError,
+    span::{Span, SpannedToken},
+    token::Token,
+};
+
+/// A token or error emitted by a `StreamLexer`.
+#[derive(PartialEq, Debug, Clone)]
+pub enum StreamItem {
+    /// A complete token and its exact source text.
+    Token {
+        token: SpannedToken,
+        lexeme: String,
+    },
+    /// An error and where it was found.
+    Error(LexError),
+}
+
+impl StreamItem {
+    /// Converts the item into one line of newline-delimited JSON.
+    ///
+    /// Tokens become `{"kind", "lexeme", "start", "end"}`, and errors `{"error", "message", "start", "end"}`, where
+    /// `error` is the diagnostic code `lex check` reports.
+    pub fn to_json(&self) -> Value {
+        match self {
+            StreamItem::Token { token, lexeme } => json!({
+                "kind": token.token.name(),
+                "lexeme": lexeme,
+                "start": token.span.start,
+                "end": token.span.end,
+            }),
+            StreamItem::Error(error) => {
+                let diagnostic = error_diagnostic(error);
+                json!({
+                    "error": diagnostic.code,
+                    "message": diagnostic.message,
+                    "start": error.span().start,
+                    "end": error.span().end,
+                })
+            }
+        }
+    }
+
+    /// Gives the span of the token or error.
+    fn span(&self) -> Span {
+        match self {
+            StreamItem::Token { token, .. } => token.span,
+            StreamItem::Error(error) => error.span(),
+        }
+    }
+}
+
+/// Lexes a stream of text pushed to it in pieces.
+///
+/// # Fields
+/// * `config` - The settings to lex with.
+/// * `pending` - The text received but not yet emitted as tokens.
+/// * `base` - The offset of `pending` from the start of the stream.
+pub struct StreamLexer {
+    config: LexerConfig,
+    pending: String,
+    base: usize,
+}
+
+impl StreamLexer {
+    /// Creates a lexer for a stream that has not started yet.
+    pub fn new(config: LexerConfig) -> Self {
+        Self { config, pending: String::new(), base: 0 }
+    }
+
+    /// Adds the next piece of the stream.
+    ///
+    /// # Returns
+    /// * `Vec<StreamItem>` - Every token and error that is now known to be complete, in source order.
+    pub fn push(&mut self, text: &str) -> Vec<StreamItem> {
+        self.pending.push_str(text);
+        let Some(newline) = self.pending.rfind('\n') else {
+            return Vec::new();
+        };
+        let safe_end = newline + 1;
+
+        let Some(mut items) = lex_to_end(&self.pending[..safe_end], self.base, &self.config) else {
+            return Vec::new();
+        };
+        // A token touching the end of the safe text may continue past it, so lex it again with what follows
+        let committed = match items.iter().position(|item| item.span().end == self.base + safe_end) {
+            Some(index) => {
+                let start = items[index].span().start - self.base;
+                items.truncate(index);
+                start
+            }
+            None => safe_end,
+        };
+        self.pending.drain(..committed);
+        self.base += committed;
+        items
+    }
+
+    /// Ends the stream.
+    ///
+    /// # Returns
+    /// * `Vec<StreamItem>` - Every remaining token and error, ending with `Token::EOF`.
+    pub fn finish(self) -> Vec<StreamItem> {
+        let mut items = lex_to_end(&self.pending, self.base, &self.config).unwrap_or_default();
+        let end = self.base + self.pending.len();
+        items.push(StreamItem::Token {
+            token: SpannedToken::new(Token::EOF, Span::new(end, end)),
+            lexeme: String::new(),
+        });
+        items
+    }
+}
+
+/// Lexes `source`, which starts `base` bytes into the stream, up to `EOF`, which is left out
+///
+/// # Returns
+/// * `Some(Vec<StreamItem>)` - The tokens and errors, with spans relative to the whole stream.
+/// * `None` - If a block comment is still open at the end of `source`.
+fn lex_to_end(source: &str, base: usize, config: &LexerConfig) -> Option<Vec<StreamItem>> {
+    let mut lexer = Lexer::new(source, config.clone());
+    let mut items: Vec<StreamItem> = Vec::new();
+    loop {
+        match lexer.next_token() {
+            Ok(token) if token.token == Token::EOF => break,
+            Ok(mut token) => {
+                let lexeme = source[token.span.start..token.span.end].to_string();
+                token.span = Span::new(token.span.start + base, token.span.end + base);
+                items.push(StreamItem::Token { token, lexeme });
+            }
+            Err(error) => {
+                let span = Span::new(error.span().start + base, error.span().end + base);
+                items.push(StreamItem::Error(LexError::new(error.into_error(), span)));
+            }
+        }
+    }
+    (!lexer.unclosed_comment()).then_some(items)
+}
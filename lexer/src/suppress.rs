@@ -0,0 +1,201 @@
+//! This file honors comments that suppress diagnostics, the escape hatch lint-style workflows need for code that is
+//! deliberately unusual.
+//!
+//! Two forms are recognized, in line or block comments:
+//!
+//! * `lexer: ignore-next-line` suppresses every diagnostic that starts on the line after the comment, and
+//!   `lexer: ignore-next-line(code, ...)` only those with the listed codes.
+//! * `lexer: allow(code, ...)` suppresses diagnostics with the listed codes that overlap the token after the
+//!   comment.
+//!
+//! A listed name matches a diagnostic whose code is the name or starts with the name followed by `-`, so
+//! `allow(unrecognized)` covers `unrecognized-token`. Suppressed diagnostics are kept, marked as suppressed, so that
+//! reports can still show them. A comment starting with `lexer:` that is neither form is reported as an
+//! `invalid-suppression` warning.
+
+use crate::{
+    diagnostic::Diagnostic,
+    error::LexError,
+    line_index::LineIndex,
+    span::{Span, SpannedToken},
+    token::Token,
+};
+
+/// The marker that starts a suppression comment.
+const MARKER: &str = "lexer:";
+
+/// A suppression comment found in the source code.
+///
+/// # Fields
+/// * `span` - The comment.
+/// * `scope` - What the comment applies to.
+/// * `codes` - The codes it suppresses, or empty for every code.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Suppression {
+    pub span: Span,
+    pub scope: SuppressionScope,
+    pub codes: Vec<String>,
+}
+
+/// What a suppression comment applies to.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum SuppressionScope {
+    /// Diagnostics starting on the line after the comment.
+    NextLine,
+    /// Diagnostics overlapping the first token or error after the comment.
+    NextToken,
+}
+
+impl Suppression {
+    /// Checks whether the suppression covers a diagnostic code.
+    pub fn covers_code(&self, code: &str) -> bool {
+        self.codes.is_empty()
+            || self.codes.iter().any(|name| {
+                code == name || code.strip_prefix(name.as_str()).is_some_and(|rest| rest.starts_with('-'))
+            })
+    }
+}
+
+/// Finds the suppression comments between the tokens of a file.
+///
+/// # Parameters
+/// * `source` - The source code.
+/// * `tokens` - The tokens lexed from `source`, as produced by `Lexer::lex_recovering`.
+/// * `errors` - The errors reported while lexing `source`.
+///
+/// # Returns
+/// * `Vec<Suppression>` - The suppressions in source order.
+/// * `Vec<Diagnostic>` - An `invalid-suppression` warning for every comment that starts with `lexer:` but cannot be
+///   understood.
+pub fn find_suppressions(
+    source: &str,
+    tokens: &[SpannedToken],
+    errors: &[LexError],
+) -> (Vec<Suppression>, Vec<Diagnostic>) {
+    let mut suppressions: Vec<Suppression> = Vec::new();
+    let mut invalid: Vec<Diagnostic> = Vec::new();
+
+    let mut gap_start: usize = 0;
+    for occupied in occupied_spans(tokens, errors).into_iter().chain([Span::new(source.len(), source.len())]) {
+        if occupied.start > gap_start {
+            for (span, text) in comments(source, gap_start, occupied.start) {
+                match parse(text) {
+                    Some(Ok((scope, codes))) => suppressions.push(Suppression { span, scope, codes }),
+                    Some(Err(message)) => invalid.push(Diagnostic::warning("invalid-suppression", message, span)),
+                    None => {}
+                }
+            }
+        }
+        gap_start = gap_start.max(occupied.end);
+    }
+    (suppressions, invalid)
+}
+
+/// Marks every diagnostic covered by a suppression comment as suppressed.
+///
+/// # Parameters
+/// * `source` - The source code the diagnostics refer to.
+/// * `tokens` - The tokens lexed from `source`.
+/// * `errors` - The errors reported while lexing `source`.
+/// * `diagnostics` - The diagnostics to mark. Warnings about invalid suppression comments are added to them.
+pub fn apply_suppressions(
+    source: &str,
+    tokens: &[SpannedToken],
+    errors: &[LexError],
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let (suppressions, invalid) = find_suppressions(source, tokens, errors);
+    diagnostics.extend(invalid);
+    if suppressions.is_empty() {
+        return;
+    }
+
+    let index = LineIndex::new(source);
+    let occupied = occupied_spans(tokens, errors);
+    for diagnostic in diagnostics.iter_mut() {
+        diagnostic.suppressed |= suppressions.iter().any(|suppression| {
+            if !suppression.covers_code(diagnostic.code) {
+                return false;
+            }
+            match suppression.scope {
+                SuppressionScope::NextLine => {
+                    index.line_col(diagnostic.span.start).0 == index.line_col(suppression.span.end).0 + 1
+                }
+                SuppressionScope::NextToken => {
+                    let next = occupied.iter().find(|span| span.start >= suppression.span.end);
+                    next.is_some_and(|next| overlaps(*next, diagnostic.span))
+                }
+            }
+        });
+    }
+}
+
+/// Gives the spans of every token except `EOF` and every error, in source order
+fn occupied_spans(tokens: &[SpannedToken], errors: &[LexError]) -> Vec<Span> {
+    let mut spans: Vec<Span> = tokens
+        .iter()
+        .filter(|spanned| spanned.token != Token::EOF)
+        .map(|spanned| spanned.span)
+        .chain(errors.iter().map(LexError::span))
+        .collect();
+    spans.sort_by_key(|span| (span.start, span.end));
+    spans
+}
+
+/// Finds the comments in a stretch of source between tokens, giving each one's span and its text without the
+/// comment markers
+fn comments(source: &str, start: usize, end: usize) -> Vec<(Span, &str)> {
+    let mut found: Vec<(Span, &str)> = Vec::new();
+    let mut position = start;
+    while position < end {
+        let rest = &source[position..end];
+        let Some(offset) = rest.find('/') else {
+            break;
+        };
+        let comment_start = position + offset;
+        let after = &source[comment_start..end];
+        if let Some(body) = after.strip_prefix("//") {
+            let len = body.find('\n').unwrap_or(body.len());
+            found.push((Span::new(comment_start, comment_start + 2 + len), &body[..len]));
+            position = comment_start + 2 + len;
+        } else if let Some(body) = after.strip_prefix("/*") {
+            let len = body.find("*/").unwrap_or(body.len());
+            let close = if len < body.len() { 2 } else { 0 };
+            found.push((Span::new(comment_start, comment_start + 2 + len + close), &body[..len]));
+            position = comment_start + 2 + len + close;
+        } else {
+            position = comment_start + 1;
+        }
+    }
+    found
+}
+
+/// Parses the text of a comment
+///
+/// Returns `None` if it is not a suppression comment, or the reason it is invalid if it starts with the marker but
+/// cannot be understood
+fn parse(text: &str) -> Option<Result<(SuppressionScope, Vec<String>), String>> {
+    let directive = text.trim().strip_prefix(MARKER)?.trim();
+    let (name, codes) = match directive.split_once('(') {
+        Some((name, rest)) => match rest.trim_end().strip_suffix(')') {
+            Some(list) => (name.trim(), list.split(',').map(|code| code.trim().to_string()).collect::<Vec<String>>()),
+            None => return Some(Err(format!("missing `)` in suppression `{}`", directive))),
+        },
+        None => (directive, Vec::new()),
+    };
+    if codes.iter().any(String::is_empty) {
+        return Some(Err(format!("empty code in suppression `{}`", directive)));
+    }
+    let result = match name {
+        "ignore-next-line" => Ok((SuppressionScope::NextLine, codes)),
+        "allow" if !codes.is_empty() => Ok((SuppressionScope::NextToken, codes)),
+        "allow" => Err("`allow` needs a list of codes, as in `allow(unrecognized-token)`".to_string()),
+        _ => Err(format!("unknown suppression `{}`", name)),
+    };
+    Some(result)
+}
+
+/// Checks whether two spans share any source, treating an empty span as the point it sits at
+fn overlaps(a: Span, b: Span) -> bool {
+    a.start < b.end.max(b.start + 1) && b.start < a.end.max(a.start + 1)
+}
@@ -0,0 +1,75 @@
+//! This file holds the keyword tables the lexer looks words up in, built once and shared between lexers.
+//!
+//! A `LexerConfig` holds its table behind an `Arc`, so cloning a configuration to start another `Lexer`, as callers
+//! lexing thousands of small snippets such as macro bodies do, copies a pointer rather than rebuilding the table.
+//! Configurations without contextual keywords or operator aliases, including the one `Lexer::lex` uses, all share
+//! one standard table built on first use.
+
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, OnceLock},
+};
+
+use crate::token::{Token, KEYWORDS, OPERATOR_ALIASES};
+
+/// The words the lexer treats specially.
+///
+/// # Fields
+/// * `keywords` - The spelling of every keyword and the token it lexes to.
+/// * `contextual` - The spelling of every contextual keyword.
+/// * `aliases` - The spelling of every enabled operator alias and the token it lexes to.
+#[derive(PartialEq, Debug, Clone)]
+pub struct KeywordTable {
+    keywords: HashMap<Vec<char>, Token>,
+    contextual: HashSet<Vec<char>>,
+    aliases: HashMap<Vec<char>, Token>,
+}
+
+impl KeywordTable {
+    /// Builds a table of the keywords with the given contextual keywords.
+    pub fn new(contextual_keywords: &[String]) -> Self {
+        Self {
+            keywords: KEYWORDS.into_iter().map(|(spelling, token)| (spelling.chars().collect(), token)).collect(),
+            contextual: contextual_keywords.iter().map(|word| word.chars().collect()).collect(),
+            aliases: HashMap::new(),
+        }
+    }
+
+    /// Adds the alternative operator spellings of `token::OPERATOR_ALIASES`, such as `and` for `&&`.
+    pub fn with_operator_aliases(mut self) -> Self {
+        self.aliases =
+            OPERATOR_ALIASES.into_iter().map(|(spelling, token)| (spelling.chars().collect(), token)).collect();
+        self
+    }
+
+    /// Gives the table shared by every configuration without contextual keywords or operator aliases.
+    pub fn standard() -> &'static Arc<KeywordTable> {
+        static STANDARD: OnceLock<Arc<KeywordTable>> = OnceLock::new();
+        STANDARD.get_or_init(|| Arc::new(KeywordTable::new(&[])))
+    }
+
+    /// Gives the token a word lexes to if it is a keyword.
+    pub fn keyword(&self, word: &[char]) -> Option<&Token> {
+        self.keywords.get(word)
+    }
+
+    /// Gives the operator token a word lexes to if it is an enabled operator alias.
+    pub fn operator_alias(&self, word: &[char]) -> Option<&Token> {
+        self.aliases.get(word)
+    }
+
+    /// Returns true if the table has operator aliases.
+    pub fn has_operator_aliases(&self) -> bool {
+        !self.aliases.is_empty()
+    }
+
+    /// Returns true if a word is a registered contextual keyword.
+    pub fn is_contextual(&self, word: &[char]) -> bool {
+        self.contextual.contains(word)
+    }
+
+    /// Iterates over the registered contextual keywords, in no particular order.
+    pub fn contextual_keywords(&self) -> impl Iterator<Item = String> + '_ {
+        self.contextual.iter().map(|word| word.iter().collect())
+    }
+}
@@ -0,0 +1,269 @@
+//! This file shows how the lexer splits source into tokens in a form meant for teaching, as in compiler-course
+//! demonstrations: the source is printed line by line, each line followed by a row of markers underlining its
+//! tokens with a letter for their kind, and a legend explains the letters.
+//!
+//! ```text
+//! 1 | int count = 42;
+//!   | ttt iiiii o nnp
+//!
+//! legend: t type  i identifier  n number  o operator  p punctuation
+//! ```
+//!
+//! With color on, each token and its markers are also drawn in the color of their kind using ANSI escape codes.
+//! `teach_steps` goes through the same tokens one at a time, for stepping through a lex one keypress at a time.
+//! Errors are shown where they were found, and the lex recovers from them so that the rest of the source is still
+//! shown.
+
+use std::fmt::Write;
+
+use crate::{
+    check::error_diagnostic,
+    error::LexError,
+    span::{Location, Span, SpannedToken},
+    token::{Token, TokenCategory},
+};
+
+/// The ANSI escape code that ends a color.
+const RESET: &str = "\x1b[0m";
+
+/// What an annotated piece of source is.
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Hash, PartialOrd, Ord)]
+pub enum AnnotationKind {
+    /// A reserved word that is not a type name.
+    Keyword,
+    /// A type name, reserved or declared with `typedef`.
+    Type,
+    /// An identifier.
+    Identifier,
+    /// A number.
+    Number,
+    /// A string or character literal.
+    String,
+    /// An operator.
+    Operator,
+    /// A bracket or separator.
+    Punctuation,
+    /// A preprocessor line.
+    Preprocessor,
+    /// A token produced by a custom rule.
+    Custom,
+    /// Text kept as `Token::UNKNOWN` by `LexerBuilder::soft_fail`.
+    Unknown,
+    /// Text the lexer reported an error for.
+    Error,
+}
+
+impl AnnotationKind {
+    /// Classifies a token. `EOF` and `DEFAULT` have no source and are classified as unknown.
+    pub fn of(token: &Token) -> Self {
+        match token {
+            Token::IDENTIFIER(_) => AnnotationKind::Identifier,
+            Token::NUMBER(_) => AnnotationKind::Number,
+            Token::STRINGLITERAL(_) | Token::CHAR(_) => AnnotationKind::String,
+            token => match token.category() {
+                TokenCategory::Keyword => AnnotationKind::Keyword,
+                TokenCategory::Type => AnnotationKind::Type,
+                TokenCategory::Operator => AnnotationKind::Operator,
+                TokenCategory::Punctuation => AnnotationKind::Punctuation,
+                TokenCategory::Preprocessor => AnnotationKind::Preprocessor,
+                TokenCategory::Custom => AnnotationKind::Custom,
+                TokenCategory::Literal | TokenCategory::Special => AnnotationKind::Unknown,
+            },
+        }
+    }
+
+    /// Gives the letter or symbol that marks the kind under the source.
+    pub fn marker(self) -> char {
+        match self {
+            AnnotationKind::Keyword => 'k',
+            AnnotationKind::Type => 't',
+            AnnotationKind::Identifier => 'i',
+            AnnotationKind::Number => 'n',
+            AnnotationKind::String => 's',
+            AnnotationKind::Operator => 'o',
+            AnnotationKind::Punctuation => 'p',
+            AnnotationKind::Preprocessor => '#',
+            AnnotationKind::Custom => 'c',
+            AnnotationKind::Unknown => '?',
+            AnnotationKind::Error => '!',
+        }
+    }
+
+    /// Gives the name of the kind as the legend shows it.
+    pub fn description(self) -> &'static str {
+        match self {
+            AnnotationKind::Keyword => "keyword",
+            AnnotationKind::Type => "type",
+            AnnotationKind::Identifier => "identifier",
+            AnnotationKind::Number => "number",
+            AnnotationKind::String => "string",
+            AnnotationKind::Operator => "operator",
+            AnnotationKind::Punctuation => "punctuation",
+            AnnotationKind::Preprocessor => "preprocessor",
+            AnnotationKind::Custom => "custom",
+            AnnotationKind::Unknown => "unknown",
+            AnnotationKind::Error => "error",
+        }
+    }
+
+    /// Gives the ANSI escape code that starts the color of the kind.
+    pub fn color(self) -> &'static str {
+        match self {
+            AnnotationKind::Keyword => "\x1b[1;35m",
+            AnnotationKind::Type => "\x1b[36m",
+            AnnotationKind::Identifier => "\x1b[37m",
+            AnnotationKind::Number => "\x1b[33m",
+            AnnotationKind::String => "\x1b[32m",
+            AnnotationKind::Operator => "\x1b[34m",
+            AnnotationKind::Punctuation => "\x1b[90m",
+            AnnotationKind::Preprocessor => "\x1b[35m",
+            AnnotationKind::Custom => "\x1b[96m",
+            AnnotationKind::Unknown => "\x1b[1;33m",
+            AnnotationKind::Error => "\x1b[1;31m",
+        }
+    }
+}
+
+/// One token or error, as a step through a lex.
+///
+/// # Fields
+/// * `span` - Where it appears in the source.
+/// * `kind` - What it is.
+/// * `description` - What the lexer made of it, such as ``IDENTIFIER `count` `` or the message of an error.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct TeachStep {
+    pub span: Span,
+    pub kind: AnnotationKind,
+    pub description: String,
+}
+
+impl TeachStep {
+    /// Renders the step: a heading saying what was lexed and where, then the line it starts on with carets under
+    /// it, colored if `color` is set.
+    ///
+    /// # Parameters
+    /// * `source` - The source code the step was lexed from.
+    /// * `number` - The number of the step, counting from 1.
+    /// * `total` - How many steps there are.
+    /// * `color` - Whether to color the carets with ANSI escape codes.
+    pub fn render(&self, source: &str, number: usize, total: usize, color: bool) -> String {
+        let start = Location::of(source, self.span.start, 1);
+        let line_start = self.span.start - (start.byte_column - 1);
+        let line_end = source[line_start..].find('\n').map_or(source.len(), |newline| line_start + newline);
+        let line = &source[line_start..line_end];
+        let gutter = start.line.to_string().len();
+
+        let mut out = format!(
+            "step {} of {}: {} at {}:{} ({})\n",
+            number,
+            total,
+            self.description,
+            start.line,
+            start.byte_column,
+            self.kind.description()
+        );
+        let _ = writeln!(out, "{} | {}", start.line, line);
+        let pad = padding(&line[..self.span.start - line_start]);
+        let width = source[self.span.start..self.span.end.clamp(self.span.start, line_end)].chars().count();
+        let carets = paint(&"^".repeat(width.max(1)), self.kind, color);
+        let _ = writeln!(out, "{} | {}{}", " ".repeat(gutter), pad, carets);
+        out
+    }
+}
+
+/// Annotates source with its tokens: every line followed by a row of markers under its tokens and errors, and a
+/// legend of the markers used.
+///
+/// # Parameters
+/// * `source` - The source code.
+/// * `tokens` - The tokens lexed from `source`, as `Lexer::lex_recovering` gives them.
+/// * `errors` - The errors reported while lexing `source`. They are marked over any token at the same place.
+/// * `color` - Whether to color tokens and markers with ANSI escape codes.
+pub fn annotate(source: &str, tokens: &[SpannedToken], errors: &[LexError], color: bool) -> String {
+    let mut kinds: Vec<Option<AnnotationKind>> = vec![None; source.len()];
+    for step in teach_steps(tokens, errors) {
+        kinds[step.span.start.min(source.len())..step.span.end.min(source.len())].fill(Some(step.kind));
+    }
+
+    let gutter = source.lines().count().max(1).to_string().len();
+    let mut out = String::new();
+    let mut line_start = 0;
+    for (index, line) in source.split('\n').enumerate() {
+        if line_start == source.len() && index > 0 {
+            break;
+        }
+        let (mut text, mut markers) = (String::new(), String::new());
+        for (offset, c) in line.char_indices() {
+            let kind = kinds[line_start + offset];
+            let shown = match kind {
+                Some(kind) if color => paint(&c.to_string(), kind, true),
+                _ => c.to_string(),
+            };
+            text.push_str(&shown);
+            match kind {
+                Some(kind) if !c.is_whitespace() => markers.push_str(&paint(&kind.marker().to_string(), kind, color)),
+                _ if c == '\t' => markers.push('\t'),
+                _ => markers.push(' '),
+            }
+        }
+        let _ = writeln!(out, "{}", format!("{:>width$} | {}", index + 1, text, width = gutter).trim_end());
+        let _ = writeln!(out, "{}", format!("{:>width$} | {}", "", markers, width = gutter).trim_end());
+        line_start += line.len() + 1;
+    }
+
+    let mut used: Vec<AnnotationKind> = kinds.into_iter().flatten().collect();
+    used.sort();
+    used.dedup();
+    let legend: Vec<String> = used
+        .into_iter()
+        .map(|kind| format!("{} {}", paint(&kind.marker().to_string(), kind, color), kind.description()))
+        .collect();
+    if !legend.is_empty() {
+        let _ = writeln!(out, "\nlegend: {}", legend.join("  "));
+    }
+    out
+}
+
+/// Lists every token and error of a lex in source order, as steps to go through one at a time. `EOF` is left out.
+///
+/// # Parameters
+/// * `tokens` - The tokens of the lex, as `Lexer::lex_recovering` gives them.
+/// * `errors` - The errors reported during the lex. An error comes before a token starting at the same place.
+pub fn teach_steps(tokens: &[SpannedToken], errors: &[LexError]) -> Vec<TeachStep> {
+    let mut steps: Vec<TeachStep> = tokens
+        .iter()
+        .filter(|spanned| !matches!(spanned.token, Token::EOF | Token::DEFAULT))
+        .map(|spanned| {
+            let description = match spanned.token.text() {
+                Some(text) => format!("{} `{}`", spanned.token.name(), text.iter().collect::<String>()),
+                None => match &spanned.token {
+                    Token::CHAR(c) => format!("CHAR {:?}", c),
+                    token => format!("{} `{}`", token.name(), token.example()),
+                },
+            };
+            TeachStep { span: spanned.span, kind: AnnotationKind::of(&spanned.token), description }
+        })
+        .chain(errors.iter().map(|error| TeachStep {
+            span: error.span(),
+            kind: AnnotationKind::Error,
+            description: format!("error: {}", error_diagnostic(error).message),
+        }))
+        .collect();
+    // Errors sort last by kind, so put them first where they start with a token
+    steps.sort_by_key(|step| (step.span.start, step.kind != AnnotationKind::Error));
+    steps
+}
+
+/// Wraps text in the color of a kind if `color` is set
+fn paint(text: &str, kind: AnnotationKind, color: bool) -> String {
+    if color {
+        format!("{}{}{}", kind.color(), text, RESET)
+    } else {
+        text.to_string()
+    }
+}
+
+/// Gives whitespace as wide as `text`, keeping its tabs so that it lines up however tabs are shown
+fn padding(text: &str) -> String {
+    text.chars().map(|c| if c == '\t' { '\t' } else { ' ' }).collect()
+}
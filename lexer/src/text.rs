@@ -0,0 +1,104 @@
+//! This file applies batches of edits to source code, for refactoring tools built on the lexer, and translates
+//! spans into the original source to where the same text ends up in the edited source.
+
+use crate::span::Span;
+
+/// A replacement of one span of source code with new text. An empty span inserts the text, and empty text deletes
+/// the span.
+///
+/// # Fields
+/// * `span` - The span of the original source to replace.
+/// * `text` - The text to put in its place.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct TextEdit {
+    pub span: Span,
+    pub text: String,
+}
+
+impl TextEdit {
+    /// Creates an edit replacing `span` with `text`.
+    pub fn new(span: Span, text: &str) -> Self {
+        Self { span, text: text.to_string() }
+    }
+
+    /// Creates an edit inserting `text` at a byte offset.
+    pub fn insert(offset: usize, text: &str) -> Self {
+        Self::new(Span::new(offset, offset), text)
+    }
+
+    /// Creates an edit deleting `span`.
+    pub fn delete(span: Span) -> Self {
+        Self::new(span, "")
+    }
+}
+
+/// Translates offsets and spans in the original source to the edited source, as returned by `apply_edits`.
+///
+/// # Fields
+/// * `edits` - The span of every edit in the original source and in the edited source, in source order.
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub struct SpanRemapper {
+    edits: Vec<(Span, Span)>,
+}
+
+impl SpanRemapper {
+    /// Translates a byte offset in the original source.
+    ///
+    /// # Returns
+    /// * `Some(usize)` - Where the offset ends up. An offset at the start of an edit stays before its new text, and
+    ///   one at the end of an edit follows it.
+    /// * `None` - If the offset lies inside text an edit replaced, which no longer exists.
+    pub fn offset(&self, offset: usize) -> Option<usize> {
+        let before = self.edits.partition_point(|(old, _)| {
+            old.end < offset || (old.end == offset && !old.is_empty())
+        });
+        if self.edits.get(before).is_some_and(|(old, _)| old.start < offset) {
+            return None;
+        }
+        match before.checked_sub(1).map(|previous| self.edits[previous]) {
+            Some((old, new)) => Some(new.end + (offset - old.end)),
+            None => Some(offset),
+        }
+    }
+
+    /// Translates a span in the original source. A span covering whole edits grows or shrinks with them.
+    ///
+    /// # Returns
+    /// * `Some(Span)` - Where the span ends up.
+    /// * `None` - If either end of the span lies inside text an edit replaced.
+    pub fn span(&self, span: Span) -> Option<Span> {
+        Some(Span::new(self.offset(span.start)?, self.offset(span.end)?))
+    }
+}
+
+/// Applies a batch of edits to source code.
+///
+/// # Parameters
+/// * `source` - The original source code.
+/// * `edits` - The edits to apply, in any order, with spans into `source`. Several insertions at one offset are
+///   made in the order given.
+///
+/// # Returns
+/// * `(String, SpanRemapper)` - The edited source, and a remapper translating spans in `source` to it.
+///
+/// # Panics
+/// Panics if two edits overlap, or if an edit's span is out of bounds or inside a multi-byte character, as slicing
+/// would.
+pub fn apply_edits(source: &str, edits: &[TextEdit]) -> (String, SpanRemapper) {
+    let mut sorted: Vec<&TextEdit> = edits.iter().collect();
+    sorted.sort_by_key(|edit| (edit.span.start, edit.span.end));
+
+    let mut edited = String::with_capacity(source.len());
+    let mut remapped: Vec<(Span, Span)> = Vec::with_capacity(sorted.len());
+    let mut copied: usize = 0;
+    for edit in sorted {
+        assert!(edit.span.start >= copied, "edit at {:?} overlaps another edit", edit.span);
+        edited.push_str(&source[copied..edit.span.start]);
+        let start = edited.len();
+        edited.push_str(&edit.text);
+        remapped.push((edit.span, Span::new(start, edited.len())));
+        copied = edit.span.end;
+    }
+    edited.push_str(&source[copied..]);
+    (edited, SpanRemapper { edits: remapped })
+}
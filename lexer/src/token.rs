@@ -145,6 +145,748 @@ pub enum Token {
     CONST,
     /// Conditional true `?`.
     CTRUE,
+
+    /// A token produced by a custom `TokenRule`, holding the name of its kind and its lexeme.
+    CUSTOM(String, Vec<char>),
+
+    /// A preprocessor line such as `#include <stdio.h>`, passed through unprocessed. Holds the text of the line
+    /// from the `#`, including any lines joined to it with a trailing backslash.
+    HASHLINE(Vec<char>),
+
+    /// An identifier registered as a type name, such as one declared with `typedef`. The lexer itself never
+    /// produces it; `typedefs::TypedefTable::classify` retags identifiers after lexing.
+    TYPENAME(Vec<char>),
+
+    /// Text the lexer does not support, such as a character no rule accepts or a literal it cannot read, holding the
+    /// text. Only produced when `LexerBuilder::soft_fail` is on; otherwise such text is an error.
+    UNKNOWN(Vec<char>),
+}
+
+/// The broad group a kind of token belongs to.
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Hash)]
+pub enum TokenCategory {
+    /// Tokens that carry no source text, such as `EOF`, and unknown text kept by `LexerBuilder::soft_fail`.
+    Special,
+    /// Numbers, identifiers, strings, and characters.
+    Literal,
+    /// Reserved words that are not type names.
+    Keyword,
+    /// Reserved words that name types.
+    Type,
+    /// Arithmetic, logical, comparison, and bitwise operators.
+    Operator,
+    /// Brackets and separators.
+    Punctuation,
+    /// Tokens produced by custom rules.
+    Custom,
+    /// Preprocessor lines.
+    Preprocessor,
+}
+
+/// The kind of a token without its payload, cheap to copy and compare.
+///
+/// There is one kind for every variant of `Token`, in the same order, plus `TRIVIA`, which only appears in raw token
+/// streams.
+///
+/// Every kind has a stable numeric id, given by `id`, and a stable name, given by `name`, for serialized token
+/// caches and consumers across an FFI boundary. Neither changes between minor versions: new kinds only ever take
+/// new ids, and `TRIVIA` is always `TRIVIA_ID`.
+#[repr(u8)]
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Hash, PartialOrd, Ord)]
+pub enum TokenKind {
+    DEFAULT,
+    EOF,
+    NUMBER,
+    IDENTIFIER,
+    STRINGLITERAL,
+    CHAR,
+    PLUSPLUS,
+    MINUSMINUS,
+    FSLASH,
+    DASH,
+    PLUS,
+    EQUAL,
+    PERCENT,
+    ASTERISK,
+    STRUCT,
+    ENUM,
+    IF,
+    ELSE,
+    RETURN,
+    FOR,
+    WHILE,
+    DO,
+    BREAK,
+    CONTINUE,
+    SWITCH,
+    CASE,
+    RBRACKET,
+    LBRACKET,
+    LPAREN,
+    RPAREN,
+    LBRACE,
+    RBRACE,
+    SEMICOLON,
+    COMMA,
+    COLON,
+    DOT,
+    ANDAND,
+    BARBAR,
+    EXCLAMATIONPOINT,
+    LESSTHAN,
+    GREATERTHAN,
+    NOTEQUAL,
+    EQUALEQUAL,
+    LESSTHANEQUAL,
+    GREATERTHANEQUAL,
+    TINTEGER,
+    TBOOLEAN,
+    TDOUBLE,
+    TFLOAT,
+    TCHAR,
+    TVOID,
+    TSIGNINT,
+    TUSIGN,
+    TLONG,
+    AMPERSAND,
+    BAR,
+    CARET,
+    TILDE,
+    POINTER,
+    CONST,
+    CTRUE,
+    CUSTOM,
+    HASHLINE,
+    TYPENAME,
+    UNKNOWN,
+    /// Whitespace and comments between tokens.
+    TRIVIA,
+}
+
+/// The payload of a token, held apart from its kind.
+#[derive(PartialEq, Debug, Clone, Default)]
+pub enum TokenData {
+    /// The token has no payload.
+    #[default]
+    None,
+    /// The characters of a number, identifier, string literal, or preprocessor line.
+    Text(Vec<char>),
+    /// The character of a character literal.
+    Char(char),
+    /// The name of the kind of a custom token and its lexeme.
+    Custom(String, Vec<char>),
+}
+
+/// The stable id of `TokenKind::TRIVIA`, kept apart from the ids of the kinds of `Token` so that new ones can be added.
+pub const TRIVIA_ID: u8 = u8::MAX;
+
+impl TokenKind {
+    /// Returns the stable numeric id of the kind: its index in `Token::ALL_KINDS`, or `TRIVIA_ID` for trivia.
+    pub fn id(self) -> u8 {
+        match self {
+            TokenKind::TRIVIA => TRIVIA_ID,
+            kind => kind as u8,
+        }
+    }
+
+    /// Finds the kind with a stable numeric id.
+    ///
+    /// # Returns
+    /// * `Some(TokenKind)` - The kind, if `id` was given by `TokenKind::id`.
+    /// * `None` - If no kind has the id, such as one written by a newer version.
+    pub fn from_id(id: u8) -> Option<TokenKind> {
+        match id {
+            TRIVIA_ID => Some(TokenKind::TRIVIA),
+            id => Token::ALL_KINDS.get(id as usize).map(Token::kind),
+        }
+    }
+
+    /// Finds the kind with a name given by `TokenKind::name`, such as `"NUMBER"`.
+    pub fn from_name(name: &str) -> Option<TokenKind> {
+        match name {
+            "TRIVIA" => Some(TokenKind::TRIVIA),
+            name => Token::ALL_KINDS.iter().find(|token| token.name() == name).map(Token::kind),
+        }
+    }
+
+    /// Returns the name of the kind, such as `"NUMBER"`.
+    pub fn name(self) -> &'static str {
+        match self {
+            TokenKind::TRIVIA => "TRIVIA",
+            kind => Token::ALL_KINDS[kind as usize].name(),
+        }
+    }
+
+    /// Returns an example spelling of the kind, as `Token::example` does.
+    pub fn example(self) -> &'static str {
+        match self {
+            TokenKind::TRIVIA => " ",
+            kind => Token::ALL_KINDS[kind as usize].example(),
+        }
+    }
+
+    /// Returns the category of the kind.
+    pub fn category(self) -> TokenCategory {
+        match self {
+            TokenKind::TRIVIA => TokenCategory::Special,
+            kind => Token::ALL_KINDS[kind as usize].category(),
+        }
+    }
+
+    /// Returns true if tokens of this kind carry a payload.
+    pub fn has_payload(self) -> bool {
+        matches!(
+            self,
+            TokenKind::NUMBER
+                | TokenKind::IDENTIFIER
+                | TokenKind::STRINGLITERAL
+                | TokenKind::CHAR
+                | TokenKind::CUSTOM
+                | TokenKind::HASHLINE
+                | TokenKind::TYPENAME
+                | TokenKind::UNKNOWN
+        )
+    }
+}
+
+impl From<&Token> for TokenKind {
+    fn from(token: &Token) -> Self {
+        token.kind()
+    }
+}
+
+/// Every keyword and the token it lexes to. The lexer looks keywords up in a `tables::KeywordTable` built from these.
+pub const KEYWORDS: [(&str, Token); 23] = [
+    ("struct", Token::STRUCT),
+    ("enum", Token::ENUM),
+    ("if", Token::IF),
+    ("else", Token::ELSE),
+    ("return", Token::RETURN),
+    ("for", Token::FOR),
+    ("while", Token::WHILE),
+    ("do", Token::DO),
+    ("break", Token::BREAK),
+    ("continue", Token::CONTINUE),
+    ("switch", Token::SWITCH),
+    ("case", Token::CASE),
+    ("int", Token::TINTEGER),
+    ("bool", Token::TBOOLEAN),
+    ("double", Token::TDOUBLE),
+    ("float", Token::TFLOAT),
+    ("char", Token::TCHAR),
+    ("void", Token::TVOID),
+    ("signed", Token::TSIGNINT),
+    ("unsigned", Token::TUSIGN),
+    ("long", Token::TLONG),
+    ("const", Token::CONST),
+    ("true", Token::CTRUE),
+];
+
+/// The C++ alternative spellings of operators and the token each lexes to, recognized as words when
+/// `LexerBuilder::operator_aliases` is enabled. `and_eq`, `or_eq`, and `xor_eq` are left out, since the lexer has no
+/// compound assignment tokens for them to lex to.
+pub const OPERATOR_ALIASES: [(&str, Token); 8] = [
+    ("and", Token::ANDAND),
+    ("or", Token::BARBAR),
+    ("not", Token::EXCLAMATIONPOINT),
+    ("not_eq", Token::NOTEQUAL),
+    ("bitand", Token::AMPERSAND),
+    ("bitor", Token::BAR),
+    ("xor", Token::CARET),
+    ("compl", Token::TILDE),
+];
+
+/// Every operator and punctuation spelling and the token it lexes to, longest spellings first.
+pub const OPERATORS: [(&str, Token); 33] = [
+    ("++", Token::PLUSPLUS),
+    ("--", Token::MINUSMINUS),
+    ("->", Token::POINTER),
+    ("&&", Token::ANDAND),
+    ("||", Token::BARBAR),
+    ("!=", Token::NOTEQUAL),
+    ("==", Token::EQUALEQUAL),
+    ("<=", Token::LESSTHANEQUAL),
+    (">=", Token::GREATERTHANEQUAL),
+    ("/", Token::FSLASH),
+    ("-", Token::DASH),
+    ("+", Token::PLUS),
+    ("=", Token::EQUAL),
+    ("%", Token::PERCENT),
+    ("*", Token::ASTERISK),
+    ("!", Token::EXCLAMATIONPOINT),
+    ("<", Token::LESSTHAN),
+    (">", Token::GREATERTHAN),
+    ("&", Token::AMPERSAND),
+    ("|", Token::BAR),
+    ("^", Token::CARET),
+    ("~", Token::TILDE),
+    ("?", Token::CTRUE),
+    ("{", Token::LBRACKET),
+    ("}", Token::RBRACKET),
+    ("(", Token::LPAREN),
+    (")", Token::RPAREN),
+    ("[", Token::LBRACE),
+    ("]", Token::RBRACE),
+    (";", Token::SEMICOLON),
+    (",", Token::COMMA),
+    (":", Token::COLON),
+    (".", Token::DOT),
+];
+
+impl Token {
+    /// One token of every kind, in declaration order. Kinds that carry a payload hold an empty placeholder.
+    pub const ALL_KINDS: &[Token] = &[
+        Token::DEFAULT,
+        Token::EOF,
+        Token::NUMBER(Vec::new()),
+        Token::IDENTIFIER(Vec::new()),
+        Token::STRINGLITERAL(Vec::new()),
+        Token::CHAR('\0'),
+        Token::PLUSPLUS,
+        Token::MINUSMINUS,
+        Token::FSLASH,
+        Token::DASH,
+        Token::PLUS,
+        Token::EQUAL,
+        Token::PERCENT,
+        Token::ASTERISK,
+        Token::STRUCT,
+        Token::ENUM,
+        Token::IF,
+        Token::ELSE,
+        Token::RETURN,
+        Token::FOR,
+        Token::WHILE,
+        Token::DO,
+        Token::BREAK,
+        Token::CONTINUE,
+        Token::SWITCH,
+        Token::CASE,
+        Token::RBRACKET,
+        Token::LBRACKET,
+        Token::LPAREN,
+        Token::RPAREN,
+        Token::LBRACE,
+        Token::RBRACE,
+        Token::SEMICOLON,
+        Token::COMMA,
+        Token::COLON,
+        Token::DOT,
+        Token::ANDAND,
+        Token::BARBAR,
+        Token::EXCLAMATIONPOINT,
+        Token::LESSTHAN,
+        Token::GREATERTHAN,
+        Token::NOTEQUAL,
+        Token::EQUALEQUAL,
+        Token::LESSTHANEQUAL,
+        Token::GREATERTHANEQUAL,
+        Token::TINTEGER,
+        Token::TBOOLEAN,
+        Token::TDOUBLE,
+        Token::TFLOAT,
+        Token::TCHAR,
+        Token::TVOID,
+        Token::TSIGNINT,
+        Token::TUSIGN,
+        Token::TLONG,
+        Token::AMPERSAND,
+        Token::BAR,
+        Token::CARET,
+        Token::TILDE,
+        Token::POINTER,
+        Token::CONST,
+        Token::CTRUE,
+        Token::CUSTOM(String::new(), Vec::new()),
+        Token::HASHLINE(Vec::new()),
+        Token::TYPENAME(Vec::new()),
+        Token::UNKNOWN(Vec::new()),
+    ];
+
+    /// Returns the kind of the token.
+    pub fn kind(&self) -> TokenKind {
+        match self {
+            Token::DEFAULT => TokenKind::DEFAULT,
+            Token::EOF => TokenKind::EOF,
+            Token::NUMBER(_) => TokenKind::NUMBER,
+            Token::IDENTIFIER(_) => TokenKind::IDENTIFIER,
+            Token::STRINGLITERAL(_) => TokenKind::STRINGLITERAL,
+            Token::CHAR(_) => TokenKind::CHAR,
+            Token::PLUSPLUS => TokenKind::PLUSPLUS,
+            Token::MINUSMINUS => TokenKind::MINUSMINUS,
+            Token::FSLASH => TokenKind::FSLASH,
+            Token::DASH => TokenKind::DASH,
+            Token::PLUS => TokenKind::PLUS,
+            Token::EQUAL => TokenKind::EQUAL,
+            Token::PERCENT => TokenKind::PERCENT,
+            Token::ASTERISK => TokenKind::ASTERISK,
+            Token::STRUCT => TokenKind::STRUCT,
+            Token::ENUM => TokenKind::ENUM,
+            Token::IF => TokenKind::IF,
+            Token::ELSE => TokenKind::ELSE,
+            Token::RETURN => TokenKind::RETURN,
+            Token::FOR => TokenKind::FOR,
+            Token::WHILE => TokenKind::WHILE,
+            Token::DO => TokenKind::DO,
+            Token::BREAK => TokenKind::BREAK,
+            Token::CONTINUE => TokenKind::CONTINUE,
+            Token::SWITCH => TokenKind::SWITCH,
+            Token::CASE => TokenKind::CASE,
+            Token::RBRACKET => TokenKind::RBRACKET,
+            Token::LBRACKET => TokenKind::LBRACKET,
+            Token::LPAREN => TokenKind::LPAREN,
+            Token::RPAREN => TokenKind::RPAREN,
+            Token::LBRACE => TokenKind::LBRACE,
+            Token::RBRACE => TokenKind::RBRACE,
+            Token::SEMICOLON => TokenKind::SEMICOLON,
+            Token::COMMA => TokenKind::COMMA,
+            Token::COLON => TokenKind::COLON,
+            Token::DOT => TokenKind::DOT,
+            Token::ANDAND => TokenKind::ANDAND,
+            Token::BARBAR => TokenKind::BARBAR,
+            Token::EXCLAMATIONPOINT => TokenKind::EXCLAMATIONPOINT,
+            Token::LESSTHAN => TokenKind::LESSTHAN,
+            Token::GREATERTHAN => TokenKind::GREATERTHAN,
+            Token::NOTEQUAL => TokenKind::NOTEQUAL,
+            Token::EQUALEQUAL => TokenKind::EQUALEQUAL,
+            Token::LESSTHANEQUAL => TokenKind::LESSTHANEQUAL,
+            Token::GREATERTHANEQUAL => TokenKind::GREATERTHANEQUAL,
+            Token::TINTEGER => TokenKind::TINTEGER,
+            Token::TBOOLEAN => TokenKind::TBOOLEAN,
+            Token::TDOUBLE => TokenKind::TDOUBLE,
+            Token::TFLOAT => TokenKind::TFLOAT,
+            Token::TCHAR => TokenKind::TCHAR,
+            Token::TVOID => TokenKind::TVOID,
+            Token::TSIGNINT => TokenKind::TSIGNINT,
+            Token::TUSIGN => TokenKind::TUSIGN,
+            Token::TLONG => TokenKind::TLONG,
+            Token::AMPERSAND => TokenKind::AMPERSAND,
+            Token::BAR => TokenKind::BAR,
+            Token::CARET => TokenKind::CARET,
+            Token::TILDE => TokenKind::TILDE,
+            Token::POINTER => TokenKind::POINTER,
+            Token::CONST => TokenKind::CONST,
+            Token::CTRUE => TokenKind::CTRUE,
+            Token::CUSTOM(..) => TokenKind::CUSTOM,
+            Token::HASHLINE(_) => TokenKind::HASHLINE,
+            Token::TYPENAME(_) => TokenKind::TYPENAME,
+            Token::UNKNOWN(_) => TokenKind::UNKNOWN,
+        }
+    }
+
+    /// Separates the token into its kind and its payload, without copying the payload.
+    pub fn into_parts(self) -> (TokenKind, TokenData) {
+        let kind = self.kind();
+        let data = match self {
+            Token::NUMBER(text)
+            | Token::IDENTIFIER(text)
+            | Token::STRINGLITERAL(text)
+            | Token::HASHLINE(text)
+            | Token::TYPENAME(text)
+            | Token::UNKNOWN(text) => TokenData::Text(text),
+            Token::CHAR(c) => TokenData::Char(c),
+            Token::CUSTOM(name, lexeme) => TokenData::Custom(name, lexeme),
+            _ => TokenData::None,
+        };
+        (kind, data)
+    }
+
+    /// Reassembles a token from its kind and payload.
+    ///
+    /// # Returns
+    /// * `Some(Token)` - The token, if the payload is the one the kind carries.
+    /// * `None` - If the payload does not match the kind, or the kind is `TokenKind::TRIVIA`.
+    pub fn from_parts(kind: TokenKind, data: TokenData) -> Option<Token> {
+        match (kind, data) {
+            (TokenKind::NUMBER, TokenData::Text(text)) => Some(Token::NUMBER(text)),
+            (TokenKind::IDENTIFIER, TokenData::Text(text)) => Some(Token::IDENTIFIER(text)),
+            (TokenKind::STRINGLITERAL, TokenData::Text(text)) => Some(Token::STRINGLITERAL(text)),
+            (TokenKind::HASHLINE, TokenData::Text(text)) => Some(Token::HASHLINE(text)),
+            (TokenKind::TYPENAME, TokenData::Text(text)) => Some(Token::TYPENAME(text)),
+            (TokenKind::UNKNOWN, TokenData::Text(text)) => Some(Token::UNKNOWN(text)),
+            (TokenKind::CHAR, TokenData::Char(c)) => Some(Token::CHAR(c)),
+            (TokenKind::CUSTOM, TokenData::Custom(name, lexeme)) => Some(Token::CUSTOM(name, lexeme)),
+            (TokenKind::TRIVIA, _) => None,
+            (kind, TokenData::None) if !kind.has_payload() => Some(Token::ALL_KINDS[kind as usize].clone()),
+            _ => None,
+        }
+    }
+
+    /// Borrows the characters of a number, identifier, string literal, preprocessor line, unknown text, or custom
+    /// token's lexeme.
+    pub fn text(&self) -> Option<&[char]> {
+        match self {
+            Token::NUMBER(text)
+            | Token::IDENTIFIER(text)
+            | Token::STRINGLITERAL(text)
+            | Token::HASHLINE(text)
+            | Token::TYPENAME(text)
+            | Token::UNKNOWN(text)
+            | Token::CUSTOM(_, text) => Some(text),
+            _ => None,
+        }
+    }
+
+    /// Splits a two-character operator into the tokens for its first character and its second, such as `>=` into
+    /// `>` and `=`, for parsers that find the lexer's longest match was wrong in context.
+    ///
+    /// # Returns
+    /// * `Some((Token, Token))` - The two halves, in source order.
+    /// * `None` - If the token is not a two-character operator.
+    pub fn split_first(&self) -> Option<(Token, Token)> {
+        let halves = match self {
+            Token::PLUSPLUS => (Token::PLUS, Token::PLUS),
+            Token::MINUSMINUS => (Token::DASH, Token::DASH),
+            Token::POINTER => (Token::DASH, Token::GREATERTHAN),
+            Token::ANDAND => (Token::AMPERSAND, Token::AMPERSAND),
+            Token::BARBAR => (Token::BAR, Token::BAR),
+            Token::NOTEQUAL => (Token::EXCLAMATIONPOINT, Token::EQUAL),
+            Token::EQUALEQUAL => (Token::EQUAL, Token::EQUAL),
+            Token::LESSTHANEQUAL => (Token::LESSTHAN, Token::EQUAL),
+            Token::GREATERTHANEQUAL => (Token::GREATERTHAN, Token::EQUAL),
+            _ => return None,
+        };
+        Some(halves)
+    }
+
+    /// Splits a leading `>` off the token, as a parser closing an angle-bracketed list needs to.
+    ///
+    /// # Returns
+    /// * `Some((Token, Token))` - `Token::GREATERTHAN` and the rest of the operator.
+    /// * `None` - If the token is not an operator that starts with `>` and is longer than it.
+    pub fn split_greater(&self) -> Option<(Token, Token)> {
+        self.split_first().filter(|(first, _)| *first == Token::GREATERTHAN)
+    }
+
+    /// Returns the name of the token's kind, such as `"NUMBER"` for any number.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Token::DEFAULT => "DEFAULT",
+            Token::EOF => "EOF",
+            Token::NUMBER(_) => "NUMBER",
+            Token::IDENTIFIER(_) => "IDENTIFIER",
+            Token::STRINGLITERAL(_) => "STRINGLITERAL",
+            Token::CHAR(_) => "CHAR",
+            Token::PLUSPLUS => "PLUSPLUS",
+            Token::MINUSMINUS => "MINUSMINUS",
+            Token::FSLASH => "FSLASH",
+            Token::DASH => "DASH",
+            Token::PLUS => "PLUS",
+            Token::EQUAL => "EQUAL",
+            Token::PERCENT => "PERCENT",
+            Token::ASTERISK => "ASTERISK",
+            Token::STRUCT => "STRUCT",
+            Token::ENUM => "ENUM",
+            Token::IF => "IF",
+            Token::ELSE => "ELSE",
+            Token::RETURN => "RETURN",
+            Token::FOR => "FOR",
+            Token::WHILE => "WHILE",
+            Token::DO => "DO",
+            Token::BREAK => "BREAK",
+            Token::CONTINUE => "CONTINUE",
+            Token::SWITCH => "SWITCH",
+            Token::CASE => "CASE",
+            Token::RBRACKET => "RBRACKET",
+            Token::LBRACKET => "LBRACKET",
+            Token::LPAREN => "LPAREN",
+            Token::RPAREN => "RPAREN",
+            Token::LBRACE => "LBRACE",
+            Token::RBRACE => "RBRACE",
+            Token::SEMICOLON => "SEMICOLON",
+            Token::COMMA => "COMMA",
+            Token::COLON => "COLON",
+            Token::DOT => "DOT",
+            Token::ANDAND => "ANDAND",
+            Token::BARBAR => "BARBAR",
+            Token::EXCLAMATIONPOINT => "EXCLAMATIONPOINT",
+            Token::LESSTHAN => "LESSTHAN",
+            Token::GREATERTHAN => "GREATERTHAN",
+            Token::NOTEQUAL => "NOTEQUAL",
+            Token::EQUALEQUAL => "EQUALEQUAL",
+            Token::LESSTHANEQUAL => "LESSTHANEQUAL",
+            Token::GREATERTHANEQUAL => "GREATERTHANEQUAL",
+            Token::TINTEGER => "TINTEGER",
+            Token::TBOOLEAN => "TBOOLEAN",
+            Token::TDOUBLE => "TDOUBLE",
+            Token::TFLOAT => "TFLOAT",
+            Token::TCHAR => "TCHAR",
+            Token::TVOID => "TVOID",
+            Token::TSIGNINT => "TSIGNINT",
+            Token::TUSIGN => "TUSIGN",
+            Token::TLONG => "TLONG",
+            Token::AMPERSAND => "AMPERSAND",
+            Token::BAR => "BAR",
+            Token::CARET => "CARET",
+            Token::TILDE => "TILDE",
+            Token::POINTER => "POINTER",
+            Token::CONST => "CONST",
+            Token::CTRUE => "CTRUE",
+            Token::CUSTOM(..) => "CUSTOM",
+            Token::HASHLINE(_) => "HASHLINE",
+            Token::TYPENAME(_) => "TYPENAME",
+            Token::UNKNOWN(_) => "UNKNOWN",
+        }
+    }
+
+    /// Returns an example spelling of the token's kind as it would appear in source code. Tokens without source
+    /// text, custom tokens whose spelling is defined elsewhere, type names, which lex as identifiers, and unknown
+    /// text give an empty string.
+    pub fn example(&self) -> &'static str {
+        match self {
+            Token::DEFAULT => "",
+            Token::EOF => "",
+            Token::NUMBER(_) => "42",
+            Token::IDENTIFIER(_) => "count",
+            Token::STRINGLITERAL(_) => "\"hello\"",
+            Token::CHAR(_) => "'a'",
+            Token::PLUSPLUS => "++",
+            Token::MINUSMINUS => "--",
+            Token::FSLASH => "/",
+            Token::DASH => "-",
+            Token::PLUS => "+",
+            Token::EQUAL => "=",
+            Token::PERCENT => "%",
+            Token::ASTERISK => "*",
+            Token::STRUCT => "struct",
+            Token::ENUM => "enum",
+            Token::IF => "if",
+            Token::ELSE => "else",
+            Token::RETURN => "return",
+            Token::FOR => "for",
+            Token::WHILE => "while",
+            Token::DO => "do",
+            Token::BREAK => "break",
+            Token::CONTINUE => "continue",
+            Token::SWITCH => "switch",
+            Token::CASE => "case",
+            Token::RBRACKET => "}",
+            Token::LBRACKET => "{",
+            Token::LPAREN => "(",
+            Token::RPAREN => ")",
+            Token::LBRACE => "[",
+            Token::RBRACE => "]",
+            Token::SEMICOLON => ";",
+            Token::COMMA => ",",
+            Token::COLON => ":",
+            Token::DOT => ".",
+            Token::ANDAND => "&&",
+            Token::BARBAR => "||",
+            Token::EXCLAMATIONPOINT => "!",
+            Token::LESSTHAN => "<",
+            Token::GREATERTHAN => ">",
+            Token::NOTEQUAL => "!=",
+            Token::EQUALEQUAL => "==",
+            Token::LESSTHANEQUAL => "<=",
+            Token::GREATERTHANEQUAL => ">=",
+            Token::TINTEGER => "int",
+            Token::TBOOLEAN => "bool",
+            Token::TDOUBLE => "double",
+            Token::TFLOAT => "float",
+            Token::TCHAR => "char",
+            Token::TVOID => "void",
+            Token::TSIGNINT => "signed",
+            Token::TUSIGN => "unsigned",
+            Token::TLONG => "long",
+            Token::AMPERSAND => "&",
+            Token::BAR => "|",
+            Token::CARET => "^",
+            Token::TILDE => "~",
+            Token::POINTER => "->",
+            Token::CONST => "const",
+            Token::CTRUE => "?",
+            Token::CUSTOM(..) => "",
+            Token::HASHLINE(_) => "#include <stdio.h>",
+            Token::TYPENAME(_) => "",
+            Token::UNKNOWN(_) => "",
+        }
+    }
+
+    /// Returns the category of the token's kind.
+    pub fn category(&self) -> TokenCategory {
+        match self {
+            Token::DEFAULT => TokenCategory::Special,
+            Token::EOF => TokenCategory::Special,
+            Token::NUMBER(_) => TokenCategory::Literal,
+            Token::IDENTIFIER(_) => TokenCategory::Literal,
+            Token::STRINGLITERAL(_) => TokenCategory::Literal,
+            Token::CHAR(_) => TokenCategory::Literal,
+            Token::PLUSPLUS => TokenCategory::Operator,
+            Token::MINUSMINUS => TokenCategory::Operator,
+            Token::FSLASH => TokenCategory::Operator,
+            Token::DASH => TokenCategory::Operator,
+            Token::PLUS => TokenCategory::Operator,
+            Token::EQUAL => TokenCategory::Operator,
+            Token::PERCENT => TokenCategory::Operator,
+            Token::ASTERISK => TokenCategory::Operator,
+            Token::STRUCT => TokenCategory::Keyword,
+            Token::ENUM => TokenCategory::Keyword,
+            Token::IF => TokenCategory::Keyword,
+            Token::ELSE => TokenCategory::Keyword,
+            Token::RETURN => TokenCategory::Keyword,
+            Token::FOR => TokenCategory::Keyword,
+            Token::WHILE => TokenCategory::Keyword,
+            Token::DO => TokenCategory::Keyword,
+            Token::BREAK => TokenCategory::Keyword,
+            Token::CONTINUE => TokenCategory::Keyword,
+            Token::SWITCH => TokenCategory::Keyword,
+            Token::CASE => TokenCategory::Keyword,
+            Token::RBRACKET => TokenCategory::Punctuation,
+            Token::LBRACKET => TokenCategory::Punctuation,
+            Token::LPAREN => TokenCategory::Punctuation,
+            Token::RPAREN => TokenCategory::Punctuation,
+            Token::LBRACE => TokenCategory::Punctuation,
+            Token::RBRACE => TokenCategory::Punctuation,
+            Token::SEMICOLON => TokenCategory::Punctuation,
+            Token::COMMA => TokenCategory::Punctuation,
+            Token::COLON => TokenCategory::Punctuation,
+            Token::DOT => TokenCategory::Punctuation,
+            Token::ANDAND => TokenCategory::Operator,
+            Token::BARBAR => TokenCategory::Operator,
+            Token::EXCLAMATIONPOINT => TokenCategory::Operator,
+            Token::LESSTHAN => TokenCategory::Operator,
+            Token::GREATERTHAN => TokenCategory::Operator,
+            Token::NOTEQUAL => TokenCategory::Operator,
+            Token::EQUALEQUAL => TokenCategory::Operator,
+            Token::LESSTHANEQUAL => TokenCategory::Operator,
+            Token::GREATERTHANEQUAL => TokenCategory::Operator,
+            Token::TINTEGER => TokenCategory::Type,
+            Token::TBOOLEAN => TokenCategory::Type,
+            Token::TDOUBLE => TokenCategory::Type,
+            Token::TFLOAT => TokenCategory::Type,
+            Token::TCHAR => TokenCategory::Type,
+            Token::TVOID => TokenCategory::Type,
+            Token::TSIGNINT => TokenCategory::Type,
+            Token::TUSIGN => TokenCategory::Type,
+            Token::TLONG => TokenCategory::Type,
+            Token::AMPERSAND => TokenCategory::Operator,
+            Token::BAR => TokenCategory::Operator,
+            Token::CARET => TokenCategory::Operator,
+            Token::TILDE => TokenCategory::Operator,
+            Token::POINTER => TokenCategory::Operator,
+            Token::CONST => TokenCategory::Keyword,
+            Token::CTRUE => TokenCategory::Operator,
+            Token::CUSTOM(..) => TokenCategory::Custom,
+            Token::HASHLINE(_) => TokenCategory::Preprocessor,
+            Token::TYPENAME(_) => TokenCategory::Type,
+            Token::UNKNOWN(_) => TokenCategory::Special,
+        }
+    }
+
+    /// Iterates over every keyword and the token it lexes to.
+    pub fn keywords() -> impl Iterator<Item = (&'static str, Token)> {
+        KEYWORDS.into_iter()
+    }
+
+    /// Iterates over every operator and punctuation spelling and the token it lexes to.
+    pub fn operators() -> impl Iterator<Item = (&'static str, Token)> {
+        OPERATORS.into_iter()
+    }
+
+    /// Iterates over every alternative operator spelling and the token it lexes to.
+    pub fn operator_aliases() -> impl Iterator<Item = (&'static str, Token)> {
+        OPERATOR_ALIASES.into_iter()
+    }
 }
 
 impl fmt::Display for Token {
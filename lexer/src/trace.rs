@@ -0,0 +1,274 @@
+//! This file records the decisions the lexer makes, so that the boundaries it chose between tokens can be
+//! explained afterwards.
+//!
+//! A trace holds one step for every run of whitespace and comments and one for every token or error, in source
+//! order. It can be saved to a compact binary file and loaded again, so a trace recorded on one machine can be
+//! inspected on another.
+
+use common::error::ErrorType;
+
+use crate::{
+    codec::{self, Reader},
+    mode::LexMode,
+    span::Span,
+    token::Token,
+};
+
+/// The bytes every trace file starts with.
+const MAGIC: &[u8; 4] = b"LXTR";
+
+/// The version of the trace file format.
+pub const TRACE_VERSION: u8 = 1;
+
+/// The kind of rule that produced a token.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Rule {
+    /// The lexer's own rules for C.
+    Builtin,
+    /// A `TokenRule` registered with a `LexerBuilder`.
+    Custom,
+    /// A regex registered with `LexerBuilder::token_regex`.
+    Regex,
+}
+
+/// What the lexer decided to do with a stretch of the source code.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum Decision {
+    /// Skipped whitespace and comments.
+    Trivia,
+    /// Produced a token of the named kind.
+    Token {
+        /// The kind of rule that produced the token.
+        rule: Rule,
+        /// The name of the kind of token, as given by `Token::name` or the kind of a custom token.
+        kind: String,
+    },
+    /// Failed to produce a token.
+    Error {
+        /// The kind of rule that reported the error.
+        rule: Rule,
+    },
+}
+
+/// A single decision made by the lexer.
+///
+/// # Fields
+/// * `span` - The source code the decision covers.
+/// * `mode` - The mode the lexer was in once the decision was made.
+/// * `decision` - What the lexer did.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct TraceStep {
+    pub span: Span,
+    pub mode: LexMode,
+    pub decision: Decision,
+}
+
+/// Every decision made while lexing an input, in source order.
+///
+/// # Fields
+/// * `steps` - The decisions.
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub struct Trace {
+    pub steps: Vec<TraceStep>,
+}
+
+impl Trace {
+    /// Encodes the trace into its compact binary file format.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut bytes: Vec<u8> = MAGIC.to_vec();
+        bytes.push(TRACE_VERSION);
+        codec::write_varint(&mut bytes, self.steps.len() as u64);
+
+        for step in self.steps.iter() {
+            codec::write_varint(&mut bytes, step.span.start as u64);
+            codec::write_varint(&mut bytes, step.span.len() as u64);
+            bytes.push(mode_tag(step.mode));
+            match &step.decision {
+                Decision::Trivia => bytes.push(0),
+                Decision::Token { rule, kind } => {
+                    bytes.push(1);
+                    bytes.push(rule_tag(*rule));
+                    codec::write_varint(&mut bytes, kind.len() as u64);
+                    bytes.extend_from_slice(kind.as_bytes());
+                }
+                Decision::Error { rule } => {
+                    bytes.push(2);
+                    bytes.push(rule_tag(*rule));
+                }
+            }
+        }
+        bytes
+    }
+
+    /// Decodes a trace produced by `encode`.
+    ///
+    /// # Errors
+    /// * `ErrorType::MalformedTokenStream` if the bytes are truncated, corrupt, or from another format version.
+    pub fn decode(bytes: &[u8]) -> Result<Self, ErrorType> {
+        let mut reader = Reader { bytes, position: 0 };
+        if reader.take(MAGIC.len())? != MAGIC {
+            return Err(codec::malformed("not a lexer trace"));
+        }
+        let version = reader.take(1)?[0];
+        if version != TRACE_VERSION {
+            return Err(codec::malformed(&format!("unsupported trace version {}", version)));
+        }
+
+        let count = reader.varint()? as usize;
+        let mut steps: Vec<TraceStep> = Vec::with_capacity(count.min(bytes.len()));
+        for _ in 0..count {
+            let start = reader.varint()? as usize;
+            let end = start
+                .checked_add(reader.varint()? as usize)
+                .ok_or_else(|| codec::malformed("span is out of range"))?;
+            let mode = mode_from_tag(reader.take(1)?[0])?;
+            let decision = match reader.take(1)?[0] {
+                0 => Decision::Trivia,
+                1 => {
+                    let rule = rule_from_tag(reader.take(1)?[0])?;
+                    let length = reader.varint()? as usize;
+                    let kind = std::str::from_utf8(reader.take(length)?)
+                        .map_err(|_| codec::malformed("kind is not valid UTF-8"))?;
+                    Decision::Token { rule, kind: kind.to_string() }
+                }
+                2 => Decision::Error { rule: rule_from_tag(reader.take(1)?[0])? },
+                tag => return Err(codec::malformed(&format!("unknown decision {}", tag))),
+            };
+            steps.push(TraceStep { span: Span::new(start, end), mode, decision });
+        }
+
+        if reader.position != bytes.len() {
+            return Err(codec::malformed("trailing bytes after trace"));
+        }
+        Ok(Self { steps })
+    }
+
+    /// Finds the step covering a byte offset. An offset between two steps, or at the end of the input, belongs to
+    /// the step that starts there.
+    pub fn step_at(&self, offset: usize) -> Option<&TraceStep> {
+        self.steps
+            .iter()
+            .find(|step| step.span.start <= offset && offset < step.span.end)
+            .or_else(|| self.steps.iter().find(|step| step.span.start == offset))
+    }
+
+    /// Explains why the lexer placed the token boundaries around a byte offset where it did.
+    ///
+    /// # Parameters
+    /// * `source` - The source code the trace was recorded from.
+    /// * `offset` - The byte offset to explain.
+    ///
+    /// # Returns
+    /// * `Some(String)` - A human readable explanation, one sentence per line.
+    /// * `None` - If the trace has no step at `offset`.
+    pub fn explain(&self, source: &str, offset: usize) -> Option<String> {
+        let step = self.step_at(offset)?;
+        let span = step.span;
+        let lexeme = source.get(span.start..span.end).unwrap_or("");
+        let mut lines: Vec<String> = Vec::new();
+
+        match &step.decision {
+            Decision::Trivia => {
+                lines.push(format!("bytes {}..{} are whitespace or comments and produce no token", span.start, span.end));
+            }
+            Decision::Error { rule } => {
+                lines.push(format!("bytes {}..{} (`{}`) could not be lexed by the {} rules", span.start, span.end,
+                    lexeme, rule_name(*rule)));
+            }
+            Decision::Token { rule, kind } => {
+                lines.push(format!("bytes {}..{} (`{}`) were lexed as {} by the {} rules", span.start, span.end,
+                    lexeme, kind, rule_name(*rule)));
+                lines.extend(reason(source, span, *rule, kind));
+            }
+        }
+
+        let previous = self.steps.iter().rev().find(|previous| previous.span.end <= span.start && !previous.span.is_empty());
+        if let Some(previous) = previous {
+            lines.push(format!("the previous step ended at byte {}, so this one starts there", previous.span.end));
+        }
+        Some(lines.join("\n"))
+    }
+}
+
+/// Gives the reasons a token ended where it did.
+fn reason(source: &str, span: Span, rule: Rule, kind: &str) -> Vec<String> {
+    let rest = source.get(span.start..).unwrap_or("");
+    match rule {
+        Rule::Custom => vec!["custom rules are tried before all others, and this was the first to match".to_string()],
+        Rule::Regex => vec!["the regex rule matched more than the built-in rules, so the longer match wins".to_string()],
+        Rule::Builtin => {
+            let candidates: Vec<String> = Token::operators()
+                .filter(|(spelling, _)| rest.starts_with(spelling))
+                .map(|(spelling, token)| format!("`{}` ({})", spelling, token.name()))
+                .collect();
+            if !candidates.is_empty() {
+                let mut lines = vec![format!("operators matching at byte {}: {}", span.start, candidates.join(", "))];
+                if candidates.len() > 1 {
+                    lines.push("the longest matching operator wins (maximal munch)".to_string());
+                }
+                lines
+            } else if kind == "IDENTIFIER" {
+                vec!["identifiers extend over every following letter, digit, and underscore".to_string()]
+            } else if kind == "NUMBER" {
+                vec!["numbers extend over every following digit".to_string()]
+            } else if Token::operator_aliases().any(|(spelling, _)| rest.get(..span.len()) == Some(spelling)) {
+                vec![format!("the whole word is an alternative spelling of the {} operator", kind)]
+            } else if Token::keywords().any(|(_, token)| token.name() == kind) {
+                vec!["the whole word is a keyword, so it is not an identifier".to_string()]
+            } else {
+                Vec::new()
+            }
+        }
+    }
+}
+
+/// Names a kind of rule for explanations.
+fn rule_name(rule: Rule) -> &'static str {
+    match rule {
+        Rule::Builtin => "built-in",
+        Rule::Custom => "custom",
+        Rule::Regex => "regex",
+    }
+}
+
+/// Gives the byte a rule is encoded as.
+fn rule_tag(rule: Rule) -> u8 {
+    match rule {
+        Rule::Builtin => 0,
+        Rule::Custom => 1,
+        Rule::Regex => 2,
+    }
+}
+
+/// Decodes a rule from its byte.
+fn rule_from_tag(tag: u8) -> Result<Rule, ErrorType> {
+    match tag {
+        0 => Ok(Rule::Builtin),
+        1 => Ok(Rule::Custom),
+        2 => Ok(Rule::Regex),
+        _ => Err(codec::malformed(&format!("unknown rule {}", tag))),
+    }
+}
+
+/// Gives the byte a mode is encoded as.
+fn mode_tag(mode: LexMode) -> u8 {
+    match mode {
+        LexMode::Normal => 0,
+        LexMode::InString => 1,
+        LexMode::InBlockComment => 2,
+        LexMode::InDirective => 3,
+        LexMode::InInterpolation => 4,
+    }
+}
+
+/// Decodes a mode from its byte.
+fn mode_from_tag(tag: u8) -> Result<LexMode, ErrorType> {
+    match tag {
+        0 => Ok(LexMode::Normal),
+        1 => Ok(LexMode::InString),
+        2 => Ok(LexMode::InBlockComment),
+        3 => Ok(LexMode::InDirective),
+        4 => Ok(LexMode::InInterpolation),
+        _ => Err(codec::malformed(&format!("unknown mode {}", tag))),
+    }
+}
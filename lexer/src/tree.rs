@@ -0,0 +1,223 @@
+//! This file arranges tokens into a tree of nodes in the shape tree-sitter uses, so that tools built on tree-sitter,
+//! such as highlighters and structural search, can consume the lexer's output.
+//!
+//! The tree is built from tokens alone. Its root is a `translation_unit` spanning the whole source, and every
+//! `{ ... }` block becomes a node holding its braces and the tokens between them, named after what the block is the
+//! body of, as found by `block::extract_blocks`:
+//!
+//! * `field_declaration_list` for a struct.
+//! * `enumerator_list` for an enum.
+//! * `initializer_list` for a block after `=`.
+//! * `compound_statement` for any other block.
+//!
+//! Every other token is a leaf. As in tree-sitter's C grammar, identifiers, literals, type keywords, type names,
+//! and preprocessor lines are named nodes (`identifier`, `number_literal`, `string_literal`, `char_literal`,
+//! `primitive_type`, `type_identifier`, and `preproc_line`), while other keywords and punctuation are anonymous nodes whose kind is
+//! their source text. Positions are zero-based rows and byte columns, as in tree-sitter.
+
+use serde_json::{json, Value};
+
+use crate::{
+    block::{extract_blocks, BlockKind},
+    line_index::LineIndex,
+    span::{Span, SpannedToken},
+    token::{Token, TokenCategory},
+};
+
+/// A position in the source code.
+///
+/// # Fields
+/// * `row` - The line, counting from 0.
+/// * `column` - The byte offset within the line, counting from 0.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub struct Point {
+    pub row: usize,
+    pub column: usize,
+}
+
+/// A node of a tree.
+///
+/// # Fields
+/// * `id` - The node's index in the tree.
+/// * `kind` - The node's type, such as `identifier` or `{`.
+/// * `named` - Whether the node is named rather than anonymous.
+/// * `span` - The source code the node covers.
+/// * `start_point` - Where the node starts.
+/// * `end_point` - Where the node ends.
+/// * `parent` - The index of the node's parent, or `None` for the root.
+/// * `children` - The indices of the node's children, in source order.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Node {
+    pub id: usize,
+    pub kind: String,
+    pub named: bool,
+    pub span: Span,
+    pub start_point: Point,
+    pub end_point: Point,
+    pub parent: Option<usize>,
+    pub children: Vec<usize>,
+}
+
+/// A tree of nodes built from a token stream. The root is always the node at index 0.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Tree {
+    nodes: Vec<Node>,
+}
+
+impl Tree {
+    /// Builds the tree of a source file.
+    ///
+    /// # Parameters
+    /// * `source` - The source code.
+    /// * `tokens` - The tokens lexed from `source`, as produced by `Lexer::lex_spanned`. `EOF` is left out.
+    pub fn build(source: &str, tokens: &[SpannedToken]) -> Self {
+        let index = LineIndex::new(source);
+        let mut tree = Tree { nodes: Vec::new() };
+        tree.add(&index, None, "translation_unit", true, Span::new(0, source.len()));
+
+        let mut blocks = extract_blocks(tokens).into_iter();
+        let mut open: Vec<usize> = vec![0];
+        for (position, spanned) in tokens.iter().enumerate() {
+            let parent = open.last().copied();
+            match &spanned.token {
+                Token::EOF => {}
+                Token::LBRACKET => {
+                    let Some(block) = blocks.next() else {
+                        continue;
+                    };
+                    let kind = match block.kind {
+                        BlockKind::Struct => "field_declaration_list",
+                        BlockKind::Enum => "enumerator_list",
+                        _ if position > 0 && tokens[position - 1].token == Token::EQUAL => "initializer_list",
+                        _ => "compound_statement",
+                    };
+                    let id = tree.add(&index, parent, kind, true, block.span);
+                    tree.add(&index, Some(id), "{", false, spanned.span);
+                    open.push(id);
+                }
+                Token::RBRACKET if open.len() > 1 => {
+                    tree.add(&index, parent, "}", false, spanned.span);
+                    open.pop();
+                }
+                token => {
+                    let (kind, named) = leaf_kind(token, &source[spanned.span.start..spanned.span.end]);
+                    tree.add(&index, parent, kind, named, spanned.span);
+                }
+            }
+        }
+        tree
+    }
+
+    /// Gives the root node.
+    pub fn root(&self) -> &Node {
+        &self.nodes[0]
+    }
+
+    /// Gives the node at an index.
+    pub fn node(&self, id: usize) -> Option<&Node> {
+        self.nodes.get(id)
+    }
+
+    /// Gives every node in the order of a depth-first walk from the root.
+    pub fn nodes(&self) -> &[Node] {
+        &self.nodes
+    }
+
+    /// Gives the deepest node that covers a byte range, as tree-sitter's `descendant_for_byte_range` does.
+    pub fn descendant_for_byte_range(&self, start: usize, end: usize) -> &Node {
+        let mut node = self.root();
+        let range = Span::new(start, end);
+        while let Some(child) =
+            node.children.iter().map(|&child| &self.nodes[child]).find(|child| child.span.contains(range))
+        {
+            node = child;
+        }
+        node
+    }
+
+    /// Writes the tree as an S-expression of its named nodes, as tree-sitter's `to_sexp` does, such as
+    /// `(translation_unit (primitive_type) (identifier))`.
+    pub fn to_sexp(&self) -> String {
+        let mut text = String::new();
+        self.write_sexp(0, &mut text);
+        text
+    }
+
+    /// Converts the tree to JSON: an array of every node, in the order of `nodes`, as
+    /// `{"id", "kind", "named", "start_byte", "end_byte", "start_point", "end_point", "parent", "children"}`, where
+    /// points are `{"row", "column"}`.
+    pub fn to_json(&self) -> Value {
+        let point = |point: Point| json!({"row": point.row, "column": point.column});
+        Value::Array(
+            self.nodes
+                .iter()
+                .map(|node| {
+                    json!({
+                        "id": node.id,
+                        "kind": node.kind,
+                        "named": node.named,
+                        "start_byte": node.span.start,
+                        "end_byte": node.span.end,
+                        "start_point": point(node.start_point),
+                        "end_point": point(node.end_point),
+                        "parent": node.parent,
+                        "children": node.children,
+                    })
+                })
+                .collect(),
+        )
+    }
+
+    /// Adds a node as the last child of `parent`, returning its index
+    fn add(&mut self, index: &LineIndex, parent: Option<usize>, kind: &str, named: bool, span: Span) -> usize {
+        let id = self.nodes.len();
+        let point = |offset: usize| {
+            let (line, column) = index.line_col(offset);
+            Point { row: line - 1, column: column - 1 }
+        };
+        self.nodes.push(Node {
+            id,
+            kind: kind.to_string(),
+            named,
+            span,
+            start_point: point(span.start),
+            end_point: point(span.end),
+            parent,
+            children: Vec::new(),
+        });
+        if let Some(parent) = parent {
+            self.nodes[parent].children.push(id);
+        }
+        id
+    }
+
+    /// Appends the S-expression of a named node to `text`
+    fn write_sexp(&self, id: usize, text: &mut String) {
+        let node = &self.nodes[id];
+        text.push('(');
+        text.push_str(&node.kind);
+        for &child in &node.children {
+            if self.nodes[child].named {
+                text.push(' ');
+                self.write_sexp(child, text);
+            }
+        }
+        text.push(')');
+    }
+}
+
+/// Gives the kind of a leaf node and whether it is named
+fn leaf_kind<'a>(token: &'a Token, lexeme: &'a str) -> (&'a str, bool) {
+    match token {
+        Token::IDENTIFIER(_) => ("identifier", true),
+        Token::NUMBER(_) => ("number_literal", true),
+        Token::STRINGLITERAL(_) => ("string_literal", true),
+        Token::CHAR(_) => ("char_literal", true),
+        Token::HASHLINE(_) => ("preproc_line", true),
+        Token::TYPENAME(_) => ("type_identifier", true),
+        Token::CUSTOM(name, _) => (name.as_str(), true),
+        Token::UNKNOWN(_) => ("ERROR", true),
+        token if token.category() == TokenCategory::Type => ("primitive_type", true),
+        _ => (lexeme, false),
+    }
+}
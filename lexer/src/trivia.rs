@@ -0,0 +1,191 @@
+//! This file lexes losslessly, attaching the whitespace and comments between tokens to the tokens around them, as
+//! Roslyn does, so that tools such as documentation generators can find the comment written above a declaration
+//! without scanning the source text backwards.
+//!
+//! The trivia after a token up to the end of its line is its trailing trivia. Everything else between two tokens,
+//! from the start of the next line on, is the leading trivia of the second:
+//!
+//! ```c
+//! int x; // trailing trivia of `;`
+//!
+//! /* leading trivia of `struct`,
+//!    together with the blank line above */
+//! struct point;
+//! ```
+//!
+//! The trivia of every token, together with the tokens themselves, covers the source exactly.
+
+use crate::{
+    config::LexerConfig,
+    core::Lexer,
+    error::LexError,
+    span::{Span, SpannedToken},
+};
+
+/// The kinds of trivia.
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Hash)]
+pub enum TriviaKind {
+    /// Spaces, tabs, and control characters the lexer was configured to skip, within one line.
+    Whitespace,
+    /// One line break, `\n` or `\r\n`.
+    Newline,
+    /// A `//` comment, up to but not including the end of its line.
+    LineComment,
+    /// A `/* */` comment.
+    BlockComment,
+}
+
+/// One piece of trivia.
+///
+/// # Fields
+/// * `kind` - What the trivia is.
+/// * `span` - Where the trivia appears.
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Hash)]
+pub struct Trivia {
+    pub kind: TriviaKind,
+    pub span: Span,
+}
+
+impl Trivia {
+    /// Returns true if the trivia is a comment.
+    pub fn is_comment(&self) -> bool {
+        matches!(self.kind, TriviaKind::LineComment | TriviaKind::BlockComment)
+    }
+}
+
+/// A token with the trivia attached to it.
+///
+/// # Fields
+/// * `token` - The token and its span.
+/// * `leading` - The trivia before the token, from the start of the line after the previous token.
+/// * `trailing` - The trivia after the token, up to but not including the end of its line.
+#[derive(PartialEq, Debug, Clone)]
+pub struct LosslessToken {
+    pub token: SpannedToken,
+    pub leading: Vec<Trivia>,
+    pub trailing: Vec<Trivia>,
+}
+
+impl LosslessToken {
+    /// Gives the trivia before the token.
+    pub fn leading_trivia(&self) -> &[Trivia] {
+        &self.leading
+    }
+
+    /// Gives the trivia after the token on its line.
+    pub fn trailing_trivia(&self) -> &[Trivia] {
+        &self.trailing
+    }
+
+    /// Gives the comments directly above the token: the last comments of its leading trivia, with no blank line
+    /// between them or between the last of them and the token.
+    pub fn attached_comments(&self) -> Vec<Trivia> {
+        let mut start = self.leading.len();
+        let mut newlines: usize = 0;
+        for (index, trivia) in self.leading.iter().enumerate().rev() {
+            match trivia.kind {
+                TriviaKind::Whitespace => {}
+                TriviaKind::Newline => {
+                    newlines += 1;
+                    if newlines > 1 {
+                        break;
+                    }
+                }
+                TriviaKind::LineComment | TriviaKind::BlockComment => {
+                    newlines = 0;
+                    start = index;
+                }
+            }
+        }
+        self.leading[start..].iter().filter(|trivia| trivia.is_comment()).copied().collect()
+    }
+
+    /// Gives the span of the token together with all of its trivia.
+    pub fn full_span(&self) -> Span {
+        let start = self.leading.first().map_or(self.token.span.start, |trivia| trivia.span.start);
+        let end = self.trailing.last().map_or(self.token.span.end, |trivia| trivia.span.end);
+        Span::new(start, end)
+    }
+}
+
+/// Lexes `input`, attaching the trivia between tokens to the tokens around it.
+///
+/// # Parameters
+/// * `input` - The source code to be lexed.
+/// * `config` - The settings to lex with, as built by a `LexerBuilder`.
+///
+/// # Returns
+/// * `Ok(Vec<LosslessToken>)` - The tokens, ending with `Token::EOF`, which holds the trivia at the end of the
+///   input as its leading trivia.
+/// * `Err(Vec<LexError>)` - Every error and the span of source it was found at.
+pub fn lex_lossless(input: &str, config: &LexerConfig) -> Result<Vec<LosslessToken>, Vec<LexError>> {
+    let mut config = config.clone();
+    config.omit_eof = false;
+    let mut tokens = Lexer::lex_located(input, &config)?;
+    let mut lossless: Vec<LosslessToken> = Vec::with_capacity(tokens.len());
+    let mut offset: usize = 0;
+
+    for token in tokens.drain(..) {
+        let pieces = split_trivia(input, Span::new(offset, token.span.start), config.nested_comments());
+        let first_newline = pieces.iter().position(|trivia| trivia.kind == TriviaKind::Newline);
+        let leading = match lossless.last_mut() {
+            Some(previous) => {
+                let split = first_newline.unwrap_or(pieces.len());
+                previous.trailing = pieces[..split].to_vec();
+                pieces[split..].to_vec()
+            }
+            None => pieces,
+        };
+        offset = token.span.end;
+        lossless.push(LosslessToken { token, leading, trailing: Vec::new() });
+    }
+    Ok(lossless)
+}
+
+/// Splits the text between two tokens into pieces of trivia
+pub(crate) fn split_trivia(input: &str, span: Span, nested_comments: bool) -> Vec<Trivia> {
+    let text = &input[span.start..span.end];
+    let mut pieces: Vec<Trivia> = Vec::new();
+    let mut position: usize = 0;
+    while position < text.len() {
+        let rest = &text[position..];
+        let (kind, len) = if rest.starts_with("\r\n") {
+            (TriviaKind::Newline, 2)
+        } else if rest.starts_with('\n') {
+            (TriviaKind::Newline, 1)
+        } else if rest.starts_with("//") {
+            (TriviaKind::LineComment, rest.find(['\r', '\n']).unwrap_or(rest.len()))
+        } else if rest.starts_with("/*") {
+            (TriviaKind::BlockComment, block_comment_len(rest, nested_comments))
+        } else {
+            let end = rest.find(['\n', '/']).filter(|end| *end > 0).unwrap_or(rest.len());
+            let end = if rest[..end].ends_with('\r') && rest[end..].starts_with('\n') { end - 1 } else { end };
+            (TriviaKind::Whitespace, end.max(1))
+        };
+        pieces.push(Trivia { kind, span: Span::new(span.start + position, span.start + position + len) });
+        position += len;
+    }
+    pieces
+}
+
+/// Gives the length of the block comment at the start of `text`, running to the end of `text` if it is unclosed
+fn block_comment_len(text: &str, nested_comments: bool) -> usize {
+    let mut depth: usize = 0;
+    let mut position: usize = 0;
+    while position < text.len() {
+        let rest = &text[position..];
+        if rest.starts_with("/*") && (depth == 0 || nested_comments) {
+            depth += 1;
+            position += 2;
+        } else if rest.starts_with("*/") {
+            depth -= 1;
+            position += 2;
+            if depth == 0 {
+                return position;
+            }
+        } else {
+            position += rest.chars().next().map_or(1, char::len_utf8);
+        }
+    }
+    text.len()
+}
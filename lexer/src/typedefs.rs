@@ -0,0 +1,118 @@
+//! This file tracks the names a program declares as types, so that identifiers naming them can be tagged as
+//! `Token::TYPENAME` after lexing.
+//!
+//! C cannot be parsed from its tokens alone: `foo_t * x;` declares a pointer if `foo_t` names a type and multiplies
+//! otherwise. The usual fix, the "lexer hack", is for the lexer to know which names are types. `TypedefTable`
+//! holds those names, registered by hand or learned from `typedef` declarations, and `classify` retags matching
+//! identifiers in a token stream so a parser can tell declarations from expressions.
+//!
+//! Learning is a heuristic over tokens rather than a parse. In each declarator of a `typedef`, the declared name is
+//! the last identifier outside braces and parameter lists, so `typedef struct point { int x; } point_t;`,
+//! `typedef int (*compare_t)(int a, int b);`, and `typedef char name_t[16], *name_ptr;` are all understood. Scopes
+//! are ignored, so a type name stays registered for the rest of the stream.
+
+use std::collections::HashSet;
+
+use crate::{span::SpannedToken, token::Token};
+
+/// The set of names registered as types.
+///
+/// # Fields
+/// * `names` - Every registered type name.
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub struct TypedefTable {
+    names: HashSet<String>,
+}
+
+impl TypedefTable {
+    /// Creates an empty table.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a type name, returning true if it was not already registered.
+    pub fn insert(&mut self, name: &str) -> bool {
+        self.names.insert(name.to_string())
+    }
+
+    /// Unregisters a type name, returning true if it was registered.
+    pub fn remove(&mut self, name: &str) -> bool {
+        self.names.remove(name)
+    }
+
+    /// Returns true if a name is registered as a type.
+    pub fn contains(&self, name: &str) -> bool {
+        self.names.contains(name)
+    }
+
+    /// Registers every name declared by a `typedef` in a stream of tokens.
+    ///
+    /// # Parameters
+    /// * `tokens` - The tokens to search, as produced by `Lexer::lex_spanned`.
+    ///
+    /// # Returns
+    /// * `Vec<String>` - The names declared, in source order, including any that were already registered.
+    pub fn learn(&mut self, tokens: &[SpannedToken]) -> Vec<String> {
+        let mut declared: Vec<String> = Vec::new();
+        for (index, spanned) in tokens.iter().enumerate() {
+            if matches!(&spanned.token, Token::IDENTIFIER(name) if name.iter().copied().eq("typedef".chars())) {
+                declared.extend(declared_names(&tokens[index + 1..]));
+            }
+        }
+        for name in &declared {
+            self.insert(name);
+        }
+        declared
+    }
+
+    /// Retags every identifier whose name is registered as `Token::TYPENAME`, keeping its span and flags.
+    ///
+    /// # Returns
+    /// * `usize` - How many tokens were retagged.
+    pub fn classify(&self, tokens: &mut [SpannedToken]) -> usize {
+        let mut retagged: usize = 0;
+        for spanned in tokens.iter_mut() {
+            let Token::IDENTIFIER(name) = &mut spanned.token else {
+                continue;
+            };
+            if self.names.contains(&name.iter().collect::<String>()) {
+                spanned.token = Token::TYPENAME(std::mem::take(name));
+                retagged += 1;
+            }
+        }
+        retagged
+    }
+}
+
+/// Finds the names declared by the declarators of a `typedef`, given the tokens after the keyword
+fn declared_names(tokens: &[SpannedToken]) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    let mut candidate: Option<String> = None;
+    let (mut braces, mut parameters): (usize, usize) = (0, 0);
+    let mut previous: Option<&Token> = None;
+
+    for spanned in tokens {
+        match &spanned.token {
+            Token::LBRACKET => braces += 1,
+            Token::RBRACKET => braces = braces.saturating_sub(1),
+            // A parenthesis after a name or another list opens a parameter list rather than grouping a declarator
+            Token::LPAREN
+                if parameters > 0
+                    || matches!(previous, Some(Token::IDENTIFIER(_) | Token::TYPENAME(_) | Token::RPAREN)) =>
+            {
+                parameters += 1
+            }
+            Token::RPAREN if parameters > 0 => parameters -= 1,
+            Token::IDENTIFIER(name) | Token::TYPENAME(name) if braces == 0 && parameters == 0 => {
+                candidate = Some(name.iter().collect());
+            }
+            Token::COMMA if braces == 0 && parameters == 0 => names.extend(candidate.take()),
+            Token::SEMICOLON if braces == 0 => break,
+            Token::EOF => break,
+            _ => {}
+        }
+        previous = Some(&spanned.token);
+    }
+    names.extend(candidate);
+    names
+}
@@ -0,0 +1,185 @@
+//! This file watches files and directories and re-checks files as they change, for a quick feedback loop while
+//! developing dialect configurations or lexer rules.
+//!
+//! A `WatchSession` remembers the content hash of every file it has checked, so a change notification for a file
+//! whose contents did not actually change, as editors often cause when saving, does not re-lex it. `watch` drives
+//! a session from file system notifications.
+//!
+//! Under a watched directory, only the files the project driver would analyze are checked: those with one of the
+//! `DriverConfig` extensions that are not hidden or ignored. A file watched directly is always checked.
+
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::mpsc,
+    time::{Duration, Instant},
+};
+
+use common::error::ErrorType;
+use notify::{EventKind, RecursiveMode, Watcher};
+
+use crate::{
+    check::FileReport,
+    driver::{discover_files, is_source_file, DriverConfig},
+    fingerprint::content_hash,
+};
+
+/// The result of checking one file again.
+///
+/// # Fields
+/// * `report` - The file's diagnostics.
+/// * `bytes` - The size of the file.
+/// * `elapsed` - How long checking the file took.
+#[derive(PartialEq, Debug, Clone)]
+pub struct FileUpdate {
+    pub report: FileReport,
+    pub bytes: usize,
+    pub elapsed: Duration,
+}
+
+/// The files being watched and the hash of the contents each was last checked with.
+///
+/// # Fields
+/// * `hashes` - The content hash of every file checked.
+/// * `roots` - The files and directories passed to `scan`.
+/// * `config` - The settings files are checked with, and which files under a directory are checked.
+#[derive(Default)]
+pub struct WatchSession {
+    hashes: HashMap<PathBuf, u64>,
+    roots: Vec<PathBuf>,
+    config: DriverConfig,
+}
+
+impl std::fmt::Debug for WatchSession {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WatchSession")
+            .field("hashes", &self.hashes)
+            .field("roots", &self.roots)
+            .finish_non_exhaustive()
+    }
+}
+
+impl WatchSession {
+    /// Creates a session that has not checked any files.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a session that checks files with the given settings, such as those of a project's `lexer.toml`.
+    pub fn with_config(config: DriverConfig) -> Self {
+        Self { config, ..Self::default() }
+    }
+
+    /// Starts watching `root` and checks every file under it, recursively, as `driver::discover_files` finds them,
+    /// or `root` itself if it is a file. Hidden and ignored files, such as the swap files editors write, and files
+    /// without one of the configured extensions are skipped.
+    ///
+    /// # Returns
+    /// * `Ok(Vec<FileUpdate>)` - An update for every file, in path order.
+    /// * `Err(ErrorType)` - `ErrorType::IoError` if a directory cannot be read.
+    pub fn scan(&mut self, root: &Path) -> Result<Vec<FileUpdate>, ErrorType> {
+        if !self.roots.iter().any(|watched| watched == root) {
+            self.roots.push(root.to_path_buf());
+        }
+        let paths: Vec<PathBuf> = if root.is_dir() {
+            discover_files(root, &self.config)?
+        } else {
+            vec![root.to_path_buf()]
+        };
+        Ok(paths.iter().filter_map(|path| self.refresh(path)).collect())
+    }
+
+    /// Checks a file again if it is being watched and its contents changed since it was last checked.
+    ///
+    /// # Returns
+    /// * `Some(FileUpdate)` - The new diagnostics, if the file is new or changed.
+    /// * `None` - If the file is unchanged, not under a root passed to `scan`, skipped as `scan` skips files, or
+    ///   can no longer be read, in which case it is forgotten.
+    pub fn refresh(&mut self, path: &Path) -> Option<FileUpdate> {
+        if !self.watches(path) {
+            return None;
+        }
+        let Ok(source) = fs::read_to_string(path) else {
+            self.hashes.remove(path);
+            return None;
+        };
+        let hash = content_hash(&source);
+        if self.hashes.insert(path.to_path_buf(), hash) == Some(hash) {
+            return None;
+        }
+
+        let start = Instant::now();
+        let bytes = source.len();
+        let name = path.display().to_string();
+        let report = FileReport::check_with_config(&name, source, &self.config.lexer, &self.config.lints);
+        Some(FileUpdate { report, bytes, elapsed: start.elapsed() })
+    }
+
+    /// Gives the number of files the session is tracking.
+    pub fn len(&self) -> usize {
+        self.hashes.len()
+    }
+
+    /// Returns true if the session is not tracking any files.
+    pub fn is_empty(&self) -> bool {
+        self.hashes.is_empty()
+    }
+
+    /// Checks whether a file is a root itself or one `scan` would find under a root
+    fn watches(&self, path: &Path) -> bool {
+        self.roots.iter().any(|root| root == path || (root.is_dir() && is_source_file(root, path, &self.config)))
+    }
+}
+
+/// Checks every file under `paths`, then watches them and checks each file again whenever it changes. Runs until
+/// the file system watcher stops.
+///
+/// # Parameters
+/// * `paths` - The files and directories to watch. Directories are watched recursively.
+/// * `on_update` - Called with every update, first for the initial check of every file and then for every change.
+///
+/// # Errors
+/// * `ErrorType::IoError` if a path cannot be read or watched, or the watcher fails.
+pub fn watch<F: FnMut(&FileUpdate)>(paths: &[PathBuf], on_update: F) -> Result<(), ErrorType> {
+    watch_with_session(paths, WatchSession::new(), on_update)
+}
+
+/// Watches files as `watch` does, checking them with a session's settings, such as one made by
+/// `WatchSession::with_config`, and skipping the files they skip.
+///
+/// # Errors
+/// * `ErrorType::IoError` if a path cannot be read or watched, or the watcher fails.
+pub fn watch_with_session<F: FnMut(&FileUpdate)>(
+    paths: &[PathBuf],
+    mut session: WatchSession,
+    mut on_update: F,
+) -> Result<(), ErrorType> {
+    let (sender, receiver) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(sender).map_err(|error| watch_error(Path::new("."), error))?;
+
+    for path in paths {
+        watcher.watch(path, RecursiveMode::Recursive).map_err(|error| watch_error(path, error))?;
+        for update in session.scan(path)? {
+            on_update(&update);
+        }
+    }
+
+    for event in receiver {
+        let event = event.map_err(|error| watch_error(Path::new("."), error))?;
+        if matches!(event.kind, EventKind::Access(_)) {
+            continue;
+        }
+        for path in event.paths {
+            if let Some(update) = session.refresh(&path) {
+                on_update(&update);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Helper function to create an error for a failure of the file system watcher
+fn watch_error(path: &Path, error: notify::Error) -> ErrorType {
+    ErrorType::IoError { path: path.display().to_string(), message: error.to_string() }
+}
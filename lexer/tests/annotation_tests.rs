@@ -0,0 +1,59 @@
+use lexer::{
+    diagnostic::{Annotations, Diagnostic, LabelStyle},
+    span::Span,
+};
+
+/// cargo test --test annotation_tests
+/// Tests attaching labels to spans and rendering multi-label reports.
+
+#[test]
+fn test_labels_keep_order_and_style() {
+    let mut annotations = Annotations::new();
+    annotations.label(Span::new(4, 5), "used here").secondary(Span::new(0, 1), "declared here");
+    let styles: Vec<LabelStyle> = annotations.labels().iter().map(|label| label.style).collect();
+    assert_eq!(styles, vec![LabelStyle::Primary, LabelStyle::Secondary]);
+}
+
+#[test]
+fn test_render_labels_on_one_line() {
+    let source = "x = y + z;";
+    let diagnostic = Diagnostic::warning("mixed", "mismatched operands".to_string(), Span::new(4, 5));
+    let mut annotations = Annotations::new();
+    annotations.secondary(Span::new(8, 9), "this is a float").label(Span::new(4, 5), "this is an int");
+    assert_eq!(diagnostic.render_annotated(source, 4, &annotations), concat!(
+        "warning[mixed]: mismatched operands\n",
+        " --> 1:5\n",
+        "  |\n",
+        "1 | x = y + z;\n",
+        "  |     ^ this is an int\n",
+        "  |         - this is a float\n",
+    ));
+}
+
+#[test]
+fn test_render_labels_across_lines() {
+    let source = "int count;\n\n\n\n\n\n\n\n\ncount = 1;\ncount();";
+    let declared = Span::new(4, 9);
+    let called = Span::new(source.rfind("count").unwrap(), source.len() - 3);
+    let diagnostic = Diagnostic::warning("not-callable", "`count` is not a function".to_string(), called);
+    let mut annotations = Annotations::new();
+    annotations.label(called, "called here").secondary(declared, "declared as an int here");
+    assert_eq!(diagnostic.render_annotated(source, 4, &annotations), concat!(
+        "warning[not-callable]: `count` is not a function\n",
+        "  --> 11:1\n",
+        "   |\n",
+        " 1 | int count;\n",
+        "   |     ----- declared as an int here\n",
+        "11 | count();\n",
+        "   | ^^^^^ called here\n",
+    ));
+}
+
+#[test]
+fn test_render_matches_single_unlabeled_span() {
+    let source = "a $ b";
+    let diagnostic = Diagnostic::warning("odd", "odd character".to_string(), Span::new(2, 3));
+    let mut annotations = Annotations::new();
+    annotations.label(Span::new(2, 3), "");
+    assert_eq!(diagnostic.render_annotated(source, 4, &annotations), diagnostic.render(source, 4));
+}
@@ -0,0 +1,80 @@
+use common::error::ErrorType;
+use lexer::{
+    arena::{ArenaToken, LexSession},
+    config::LexerBuilder,
+    core::Lexer,
+    rule::{Cursor, TokenRule},
+    token::Token,
+};
+
+/// cargo test --test arena_tests
+/// Tests lexing into arena-allocated tokens.
+
+#[test]
+fn test_matches_owned_tokens() {
+    let input = "int count = 42; while (count) { count--; } /* é */ name_2";
+    let session = LexSession::new();
+    let tokens = session.lex(input).unwrap();
+    let owned = Lexer::lex_spanned(input).unwrap();
+
+    assert_eq!(tokens.len(), owned.len());
+    for (arena, owned) in tokens.iter().zip(owned.iter()) {
+        assert_eq!(arena.token.to_token(), owned.token);
+        assert_eq!(arena.span, owned.span);
+    }
+}
+
+#[test]
+fn test_payloads_borrow_source_text() {
+    let session = LexSession::new();
+    let tokens = session.lex("x1 = 250;").unwrap();
+    assert_eq!(tokens[0].token, ArenaToken::Identifier("x1"));
+    assert_eq!(tokens[1].token, ArenaToken::Token(Token::EQUAL));
+    assert_eq!(tokens[2].token, ArenaToken::Number("250"));
+}
+
+#[test]
+fn test_tokens_from_several_lexes_coexist() {
+    let session = LexSession::new();
+    let first = session.lex("alpha").unwrap();
+    let large = "b".repeat(100_000);
+    let second = session.lex(&large).unwrap();
+    let third = session.lex("gamma").unwrap();
+
+    assert_eq!(first[0].token, ArenaToken::Identifier("alpha"));
+    assert_eq!(second[0].token, ArenaToken::Identifier(large.as_str()));
+    assert_eq!(third[0].token, ArenaToken::Identifier("gamma"));
+    assert_eq!(session.allocated_bytes(), 100_010);
+}
+
+#[test]
+fn test_reset() {
+    let mut session = LexSession::new();
+    session.lex("abc").unwrap();
+    session.reset();
+    assert_eq!(session.allocated_bytes(), 0);
+}
+
+#[test]
+fn test_custom_tokens() {
+    let config = LexerBuilder::new().rule(0, Arrow).build();
+    let session = LexSession::with_config(config);
+    let tokens = session.lex("a => b").unwrap();
+    assert_eq!(tokens[1].token, ArenaToken::Custom("FATARROW", "=>"));
+}
+
+#[test]
+fn test_errors() {
+    let session = LexSession::new();
+    let errors = session.lex("a $").unwrap_err();
+    assert_eq!(errors[0].error(), &ErrorType::UnrecognizedToken { token: "$".to_string() });
+}
+
+/// A rule for the `=>` operator
+struct Arrow;
+
+impl TokenRule for Arrow {
+    fn try_lex(&self, cursor: &mut Cursor) -> Option<Result<Token, ErrorType>> {
+        cursor.eat("=>").then(|| Ok(Token::CUSTOM("FATARROW".to_string(), vec!['=', '>'])))
+    }
+}
@@ -0,0 +1,49 @@
+mod support;
+
+use std::fs;
+
+use common::error::ErrorType;
+use lexer::bench::{run_corpus, CountingAllocator};
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+use support::ScratchDir;
+
+/// cargo test --test bench_tests
+/// Tests measuring the lexer over a corpus of files.
+
+#[test]
+fn test_run_corpus() {
+    let dir = ScratchDir::new("totals");
+    fs::write(dir.join("a.c"), "int x;").unwrap();
+    fs::create_dir_all(dir.join("nested")).unwrap();
+    fs::write(dir.join("nested").join("b.c"), "x = 1 $ #").unwrap();
+    fs::write(dir.join("README.md"), "# $$$").unwrap();
+    fs::create_dir_all(dir.join(".git")).unwrap();
+    fs::write(dir.join(".git").join("index.c"), [0xff, 0xfe, 0x00]).unwrap();
+
+    let report = run_corpus(&dir).unwrap();
+    assert_eq!(report.files, 2);
+    assert_eq!(report.unreadable, 0);
+    assert_eq!(report.bytes, 15);
+    assert_eq!(report.tokens, 4);
+    assert_eq!(report.files_with_errors, 1);
+    assert_eq!(report.errors, 2);
+    assert_eq!(report.error_rate(), 0.5);
+    assert!(report.allocations.is_some_and(|allocations| allocations > 0));
+    assert!(report.throughput_mb_per_s() >= 0.0);
+}
+
+#[test]
+fn test_empty_corpus() {
+    let report = run_corpus(&ScratchDir::new("empty")).unwrap();
+    assert_eq!(report.files, 0);
+    assert_eq!(report.error_rate(), 0.0);
+    assert_eq!(report.allocations, None);
+}
+
+#[test]
+fn test_missing_corpus() {
+    let dir = ScratchDir::new("missing").join("absent");
+    assert!(matches!(run_corpus(&dir), Err(ErrorType::IoError { .. })));
+}
@@ -0,0 +1,74 @@
+mod support;
+
+use lexer::{
+    block::{extract_blocks, Block, BlockKind},
+    core::Lexer,
+    span::Span,
+    token::Token,
+};
+use support::ident;
+
+/// cargo test --test block_tests
+/// Tests finding brace-delimited blocks in a token stream.
+
+#[test]
+fn test_blocks_and_levels() {
+    let source = concat!(
+        "struct point { int x; };\n",
+        "int main(void) {\n",
+        "    if (x) { y; } else { z; }\n",
+        "    { int t = 1; }\n",
+        "}\n",
+    );
+    let blocks = extract_blocks(&Lexer::lex_spanned(source).unwrap());
+    let summary: Vec<(BlockKind, usize, Option<Token>)> = blocks
+        .iter()
+        .map(|block| (block.kind, block.level, block.introducer.as_ref().map(|spanned| spanned.token.clone())))
+        .collect();
+    assert_eq!(summary, vec![
+        (BlockKind::Struct, 0, Some(ident("point"))),
+        (BlockKind::Function, 0, Some(ident("main"))),
+        (BlockKind::Control, 1, Some(Token::IF)),
+        (BlockKind::Control, 1, Some(Token::ELSE)),
+        (BlockKind::Other, 1, None),
+    ]);
+    assert_eq!(&source[blocks[2].span.start..blocks[2].span.end], "{ y; }");
+}
+
+#[test]
+fn test_anonymous_struct_and_enum() {
+    let blocks = extract_blocks(&Lexer::lex_spanned("struct { int x; } a; enum color { RED };").unwrap());
+    assert_eq!(blocks[0].kind, BlockKind::Struct);
+    assert_eq!(blocks[0].introducer.as_ref().map(|spanned| &spanned.token), Some(&Token::STRUCT));
+    assert_eq!(blocks[1].kind, BlockKind::Enum);
+}
+
+#[test]
+fn test_calls_inside_functions_are_not_functions() {
+    let blocks = extract_blocks(&Lexer::lex_spanned("void f() { g(1) { } }").unwrap());
+    assert_eq!(blocks[0].kind, BlockKind::Function);
+    assert_eq!(blocks[1].kind, BlockKind::Other);
+    assert_eq!(blocks[1].introducer.as_ref().map(|spanned| &spanned.token), Some(&ident("g")));
+}
+
+#[test]
+fn test_token_ranges() {
+    let tokens = Lexer::lex_spanned("x = { 1, 2 };").unwrap();
+    assert_eq!(extract_blocks(&tokens), vec![Block {
+        tokens: 2..7,
+        span: Span::new(4, 12),
+        level: 0,
+        kind: BlockKind::Other,
+        introducer: None,
+    }]);
+}
+
+#[test]
+fn test_unclosed_and_stray_braces() {
+    let tokens = Lexer::lex_spanned("} void f() { while (1) { x;").unwrap();
+    let blocks = extract_blocks(&tokens);
+    assert_eq!(blocks.len(), 2);
+    assert_eq!(blocks[0].tokens, 5..tokens.len() - 1);
+    assert_eq!(blocks[0].span, Span::new(11, 27));
+    assert_eq!(blocks[1].level, 1);
+}
@@ -0,0 +1,97 @@
+use lexer::{
+    budget::{check_budgets, Budgets},
+    check::{check_lexed, FileReport},
+    core::Lexer,
+    diagnostic::{Diagnostic, Severity},
+    lint::LintConfig,
+    project::ProjectConfig,
+    span::Span,
+};
+
+/// cargo test --test budget_tests
+/// Tests checking files and their blocks against size budgets.
+
+#[test]
+fn test_no_budgets_by_default() {
+    assert!(budgets(SOURCE, Budgets::default()).is_empty());
+}
+
+#[test]
+fn test_file_token_budget() {
+    let diagnostics = budgets("int x = 1;", Budgets { max_file_tokens: Some(3), ..Budgets::default() });
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].code, "file-token-budget");
+    assert_eq!(diagnostics[0].severity, Severity::Warning);
+    assert_eq!(diagnostics[0].message, "file has 5 tokens, the budget is 3");
+    assert_eq!(diagnostics[0].span, Span::new(8, 9));
+    assert!(budgets("int x = 1;", Budgets { max_file_tokens: Some(5), ..Budgets::default() }).is_empty());
+}
+
+#[test]
+fn test_block_token_budget() {
+    let diagnostics = budgets(SOURCE, Budgets { max_block_tokens: Some(12), ..Budgets::default() });
+    let messages: Vec<&str> = diagnostics.iter().map(|diagnostic| diagnostic.message.as_str()).collect();
+    assert_eq!(messages, vec!["`long_one` has 24 tokens, the budget is 12"]);
+    assert_eq!(diagnostics[0].code, "block-token-budget");
+    assert_eq!(&SOURCE[diagnostics[0].span.start..diagnostics[0].span.end], "long_one");
+}
+
+#[test]
+fn test_block_line_budget() {
+    let diagnostics = budgets(SOURCE, Budgets { max_block_lines: Some(3), ..Budgets::default() });
+    let messages: Vec<&str> = diagnostics.iter().map(|diagnostic| diagnostic.message.as_str()).collect();
+    assert_eq!(messages, vec!["`long_one` spans 7 lines, the budget is 3"]);
+}
+
+#[test]
+fn test_anonymous_blocks() {
+    let diagnostics = budgets("int a[] = {1, 2, 3};", Budgets { max_block_tokens: Some(4), ..Budgets::default() });
+    assert_eq!(diagnostics[0].message, "block has 7 tokens, the budget is 4");
+    assert_eq!(diagnostics[0].span, Span::new(10, 11));
+}
+
+#[test]
+fn test_check_reports_budgets_as_warnings() {
+    let tokens = Lexer::lex_spanned(SOURCE).unwrap();
+    let diagnostics = check_lexed(SOURCE, &tokens, &[], &line_budget());
+    let report = FileReport { path: "a.c".to_string(), source: SOURCE.to_string(), diagnostics };
+    assert!(report.has_warnings());
+    assert!(!report.has_errors());
+}
+
+#[test]
+fn test_budgets_can_be_suppressed() {
+    let source = SOURCE.replacen("\nint long_one", "\n// lexer: ignore-next-line\nint long_one", 1);
+    let tokens = Lexer::lex_spanned(&source).unwrap();
+    let diagnostics = check_lexed(&source, &tokens, &[], &line_budget());
+    assert_eq!(diagnostics.len(), 1);
+    assert!(diagnostics[0].suppressed);
+}
+
+#[test]
+fn test_project_budgets() {
+    let project = ProjectConfig::from_toml("[budgets]\nmax_file_tokens = 100\nmax_block_lines = 50\n").unwrap();
+    let budgets = project.lint_config().budgets;
+    assert_eq!(budgets, Budgets { max_file_tokens: Some(100), max_block_tokens: None, max_block_lines: Some(50) });
+    assert!(ProjectConfig::from_toml("[budgets]\nmax_lines = 1\n").is_err());
+}
+
+/// Lexes source code and checks it against budgets
+fn budgets(source: &str, budgets: Budgets) -> Vec<Diagnostic> {
+    check_budgets(source, &Lexer::lex_spanned(source).unwrap(), &budgets)
+}
+
+/// Builds lint settings that allow blocks of up to three lines
+fn line_budget() -> LintConfig {
+    LintConfig { budgets: Budgets { max_block_lines: Some(3), ..Budgets::default() }, ..LintConfig::default() }
+}
+
+/// A short function followed by a long one, each with a nested block
+const SOURCE: &str = "int short_one(void) { if (x) { y(); } }
+int long_one(int n) {
+    int total = 0;
+    while (n > 0) {
+        total += n--;
+    }
+    return total;
+}";
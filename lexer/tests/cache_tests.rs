@@ -0,0 +1,103 @@
+mod support;
+
+use std::fs;
+
+use common::error::ErrorType;
+use lexer::{
+    cache::{CacheStorage, DirectoryStorage, LexCache, MemoryStorage},
+    core::Lexer,
+};
+use support::ScratchDir;
+
+/// cargo test --test cache_tests
+/// Tests caching lex results by content hash.
+
+#[test]
+fn test_memory_cache_hits_unchanged_source() {
+    let mut cache = LexCache::new(MemoryStorage::new());
+    let first = cache.lex_or_load_source("int x = 1;");
+    let second = cache.lex_or_load_source("int x = 1;");
+    assert_eq!(first, second);
+    assert_eq!(first, Lexer::lex_spanned("int x = 1;"));
+    assert_eq!((cache.hits(), cache.misses()), (1, 1));
+
+    let _ = cache.lex_or_load_source("int x = 2;");
+    assert_eq!((cache.hits(), cache.misses()), (1, 2));
+}
+
+#[test]
+fn test_errors_are_not_cached() {
+    let mut cache = LexCache::new(MemoryStorage::new());
+    assert!(cache.lex_or_load_source("$").is_err());
+    assert!(cache.lex_or_load_source("$").is_err());
+    assert_eq!(cache.hits(), 0);
+}
+
+#[test]
+fn test_directory_cache_persists() {
+    let dir = ScratchDir::new("persist");
+    let source = dir.join("main.c");
+    fs::write(&source, "return 0;").unwrap();
+
+    let mut cache = LexCache::new(DirectoryStorage::new(dir.join("cache")));
+    let first = cache.lex_or_load(&source).unwrap();
+
+    let mut reopened = LexCache::new(DirectoryStorage::new(dir.join("cache")));
+    assert_eq!(reopened.lex_or_load(&source), Ok(first));
+    assert_eq!(reopened.hits(), 1);
+}
+
+#[test]
+fn test_colliding_keys_are_not_confused() {
+    let mut cache = LexCache::new(SingleSlot::default());
+    assert_eq!(cache.lex_or_load_source("int x;"), Lexer::lex_spanned("int x;"));
+    assert_eq!(cache.lex_or_load_source("int y;"), Lexer::lex_spanned("int y;"));
+    assert_eq!(cache.lex_or_load_source("int y;"), Lexer::lex_spanned("int y;"));
+    assert_eq!((cache.hits(), cache.misses()), (1, 2));
+}
+
+#[test]
+fn test_corrupt_entries_are_lexed_again() {
+    let dir = ScratchDir::new("corrupt");
+    let source = dir.join("main.c");
+    fs::write(&source, "return 0;").unwrap();
+    let mut cache = LexCache::new(DirectoryStorage::new(dir.join("cache")));
+    let first = cache.lex_or_load(&source).unwrap();
+
+    for entry in fs::read_dir(dir.join("cache")).unwrap() {
+        let path = entry.unwrap().path();
+        let mut bytes = fs::read(&path).unwrap();
+        bytes.truncate(bytes.len() - 2);
+        bytes.extend_from_slice(&[0xFF; 12]);
+        fs::write(&path, bytes).unwrap();
+    }
+    let mut reopened = LexCache::new(DirectoryStorage::new(dir.join("cache")));
+    assert_eq!(reopened.lex_or_load(&source), Ok(first));
+    assert_eq!(reopened.misses(), 1);
+}
+
+#[test]
+fn test_missing_file() {
+    let mut cache = LexCache::new(MemoryStorage::new());
+    let result = cache.lex_or_load(&ScratchDir::new("missing").join("nope.c"));
+    match result {
+        Err(errors) => assert!(matches!(errors.as_slice(), [ErrorType::IoError { .. }])),
+        Ok(tokens) => panic!("expected an error, found {:?}", tokens),
+    }
+}
+
+/// A storage that keeps one entry under every key, as if every key collided
+#[derive(Default)]
+struct SingleSlot {
+    entry: Option<Vec<u8>>,
+}
+
+impl CacheStorage for SingleSlot {
+    fn load(&self, _key: u64) -> Option<Vec<u8>> {
+        self.entry.clone()
+    }
+
+    fn store(&mut self, _key: u64, bytes: Vec<u8>) {
+        self.entry = Some(bytes);
+    }
+}
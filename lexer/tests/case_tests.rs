@@ -0,0 +1,67 @@
+use lexer::{
+    case::{normalize_keyword_case, KeywordCase, KeywordEdit},
+    config::{LexerBuilder, LexerConfig},
+    core::Lexer,
+    span::Span,
+    token::Token,
+};
+
+/// cargo test --test case_tests
+/// Tests rewriting keywords to be spelled in one case.
+
+#[test]
+fn test_lowercase_keywords() {
+    let source = "INT main(Void) {\n    If (x) RETURN 1; /* IF in a comment */\n}\n";
+    let (rewritten, edits) = normalize_keyword_case(source, &LexerConfig::default(), KeywordCase::Lower).unwrap();
+    assert_eq!(rewritten, "int main(void) {\n    if (x) return 1; /* IF in a comment */\n}\n");
+    assert_eq!(edits.len(), 4);
+    assert_eq!(
+        edits[0],
+        KeywordEdit { span: Span::new(0, 3), original: "INT".to_string(), replacement: "int".to_string() }
+    );
+    assert_eq!(Lexer::lex(&rewritten).unwrap()[0], Token::TINTEGER);
+}
+
+#[test]
+fn test_uppercase_keywords() {
+    let source = "while (n) { n = n - 1; }";
+    let (rewritten, edits) = normalize_keyword_case(source, &LexerConfig::default(), KeywordCase::Upper).unwrap();
+    assert_eq!(rewritten, "WHILE (n) { n = n - 1; }");
+    assert_eq!(edits, vec![KeywordEdit {
+        span: Span::new(0, 5),
+        original: "while".to_string(),
+        replacement: "WHILE".to_string(),
+    }]);
+}
+
+#[test]
+fn test_spans_are_preserved() {
+    let source = "Const char *s = \"IF\";\tReturn";
+    let (rewritten, edits) = normalize_keyword_case(source, &LexerConfig::default(), KeywordCase::Lower).unwrap();
+    assert_eq!(rewritten.len(), source.len());
+    for edit in &edits {
+        assert_eq!(&source[edit.span.start..edit.span.end], edit.original);
+        assert_eq!(&rewritten[edit.span.start..edit.span.end], edit.replacement);
+    }
+    assert!(rewritten.contains("\"IF\""));
+}
+
+#[test]
+fn test_already_normalized_source_is_unchanged() {
+    let source = "int x = 1; // INT\n";
+    let (rewritten, edits) = normalize_keyword_case(source, &LexerConfig::default(), KeywordCase::Lower).unwrap();
+    assert_eq!(rewritten, source);
+    assert!(edits.is_empty());
+}
+
+#[test]
+fn test_contextual_keywords() {
+    let config = LexerBuilder::new().contextual_keyword("defer").build();
+    let (rewritten, _) = normalize_keyword_case("DEFER Deferred;", &config, KeywordCase::Lower).unwrap();
+    assert_eq!(rewritten, "defer Deferred;");
+}
+
+#[test]
+fn test_lex_errors() {
+    assert!(normalize_keyword_case("IF @", &LexerConfig::default(), KeywordCase::Lower).is_err());
+}
@@ -0,0 +1,267 @@
+mod support;
+
+use std::{
+    io::Write,
+    process::{Command, Output, Stdio},
+};
+
+use support::ScratchDir;
+
+/// cargo test --test cli_tests
+/// Tests the `lex` command line interface.
+
+#[test]
+fn test_tokens_subcommand() {
+    let dir = ScratchDir::new("tokens");
+    let file = dir.write("tokens.c", "int x;");
+    let output = lex(&["tokens", file.to_str().unwrap()]);
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), concat!(
+        "0..3\tTINTEGER\n",
+        "4..5\tIDENTIFIER(['x'])\n",
+        "5..6\tSEMICOLON\n",
+        "6..6\tEOF\n",
+    ));
+}
+
+#[test]
+fn test_tokens_subcommand_error() {
+    let dir = ScratchDir::new("error");
+    let file = dir.write("error.c", "int $;");
+    let output = lex(&["tokens", file.to_str().unwrap()]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stderr).unwrap().contains("UnrecognizedToken"));
+}
+
+#[test]
+fn test_gen_subcommand() {
+    let dir = ScratchDir::new("spec");
+    let spec = dir.write("spec.toml", "[keywords]\nlet = \"LET\"\n[operators]\n\"=\" = \"EQ\"\n");
+    let file = dir.write("input.toy", "let a = 1");
+    let output = lex(&["gen", spec.to_str().unwrap(), file.to_str().unwrap()]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.starts_with("0..3\tCUSTOM(\"LET\", ['l', 'e', 't'])\n"));
+    assert!(stdout.contains("6..7\tCUSTOM(\"EQ\", ['='])\n"));
+}
+
+#[test]
+fn test_csv_format() {
+    let dir = ScratchDir::new("format");
+    let file = dir.write("format.c", "int x;");
+    let output = lex(&["tokens", file.to_str().unwrap(), "--format", "csv"]);
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), concat!(
+        "kind,lexeme,line,column,length\n",
+        "TINTEGER,int,1,1,3\n",
+        "IDENTIFIER,x,1,5,1\n",
+        "SEMICOLON,;,1,6,1\n",
+        "EOF,,1,7,0\n",
+    ));
+}
+
+#[test]
+fn test_explain_subcommand() {
+    let dir = ScratchDir::new("explain");
+    let file = dir.write("explain.c", "a+++b");
+    let output = lex(&["explain", file.to_str().unwrap(), "--at", "1"]);
+    assert!(output.status.success());
+    assert!(String::from_utf8(output.stdout).unwrap().contains("were lexed as PLUSPLUS"));
+}
+
+#[test]
+fn test_explain_from_saved_trace() {
+    let dir = ScratchDir::new("traced");
+    let file = dir.write("traced.c", "a+++b");
+    let trace = file.with_extension("trace");
+    let output = lex(&["tokens", file.to_str().unwrap(), "--trace", trace.to_str().unwrap()]);
+    assert!(output.status.success());
+
+    let output = lex(&["explain", file.to_str().unwrap(), "--at", "3", "--trace", trace.to_str().unwrap()]);
+    assert!(output.status.success());
+    assert!(String::from_utf8(output.stdout).unwrap().contains("were lexed as PLUS by"));
+}
+
+#[test]
+fn test_check_subcommand_json() {
+    let dir = ScratchDir::new("clean");
+    let clean = dir.write("clean.c", "int x;");
+    let broken = dir.write("broken.c", "int x;\nx = $;");
+    let output = lex(&["check", clean.to_str().unwrap(), broken.to_str().unwrap(), "--format", "json"]);
+    assert!(!output.status.success());
+
+    let document: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(document["version"], 1);
+    let diagnostics = document["diagnostics"].as_array().unwrap();
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0], serde_json::json!({
+        "file": broken.to_str().unwrap(),
+        "range": {
+            "start": {"line": 2, "column": 5, "offset": 11},
+            "end": {"line": 2, "column": 6, "offset": 12},
+        },
+        "severity": "error",
+        "code": "unrecognized-token",
+        "message": "unrecognized token `$`",
+        "suppressed": false,
+    }));
+}
+
+#[test]
+fn test_check_subcommand_text() {
+    let dir = ScratchDir::new("warned");
+    let file = dir.write("warned.c", "#include <stdio.h>\nint __x;");
+    let output = lex(&["check", file.to_str().unwrap()]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let path = file.to_str().unwrap();
+    assert!(stdout.starts_with(&format!("{}:1:1: info[preprocessor-directive]: ", path)));
+    assert!(stdout.contains(&format!("{}:2:5: warning[", path)));
+}
+
+#[test]
+fn test_check_subcommand_config() {
+    let dir = ScratchDir::new("configured");
+    let file = dir.write("configured.c", "int $total;");
+    let config = dir.write("lexer.toml", "[identifiers]\nstart = \"$\"");
+    assert!(!lex(&["check", file.to_str().unwrap()]).status.success());
+    assert!(lex(&["check", file.to_str().unwrap(), "--config", config.to_str().unwrap()]).status.success());
+}
+
+#[test]
+fn test_tokens_subcommand_discovers_config() {
+    let dir = ScratchDir::new("aliases");
+    let file = dir.write("aliases.c", "a and b");
+    let plain = String::from_utf8(lex(&["tokens", file.to_str().unwrap()]).stdout).unwrap();
+    assert!(plain.contains("2..5\tIDENTIFIER"));
+
+    let project = dir.join("aliases");
+    dir.write("aliases/lexer.toml", "[keywords]\noperator_aliases = true\n");
+    let output = Command::new(env!("CARGO_BIN_EXE_lex"))
+        .args(["tokens", file.to_str().unwrap()])
+        .current_dir(&project)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(String::from_utf8(output.stdout).unwrap().contains("2..5\tANDAND"));
+}
+
+#[test]
+fn test_check_subcommand_budgets() {
+    let dir = ScratchDir::new("budgeted");
+    let file = dir.write("budgeted.c", "int f(void) {\n    return 1;\n}\n");
+    let path = file.to_str().unwrap();
+    let output = lex(&["check", path, "--max-block-lines", "2"]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(stdout, format!("{}:1:5: warning[block-line-budget]: `f` spans 3 lines, the budget is 2\n", path));
+    assert!(!lex(&["check", path, "--max-block-lines", "2", "--deny-warnings"]).status.success());
+    assert!(lex(&["check", path, "--max-block-lines", "3", "--deny-warnings"]).status.success());
+}
+
+#[test]
+fn test_check_subcommand_sarif() {
+    let dir = ScratchDir::new("scanned");
+    let file = dir.write("scanned.c", "x = $;");
+    let output = lex(&["check", file.to_str().unwrap(), "--format", "sarif"]);
+    assert!(!output.status.success());
+
+    let log: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(log["version"], "2.1.0");
+    assert_eq!(log["runs"][0]["results"][0]["ruleId"], "unrecognized-token");
+}
+
+#[test]
+fn test_stdin_stream() {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_lex"))
+        .args(["--stdin", "--stream"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(b"int x;\n").unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+
+    let lines: Vec<serde_json::Value> =
+        String::from_utf8(output.stdout).unwrap().lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+    assert_eq!(lines.len(), 4);
+    assert_eq!(lines[1], serde_json::json!({"kind": "IDENTIFIER", "lexeme": "x", "start": 4, "end": 5}));
+    assert_eq!(lines[3]["kind"], "EOF");
+}
+
+#[test]
+fn test_stream_requires_stdin() {
+    assert!(!lex(&["--stream"]).status.success());
+    assert!(!lex(&[]).status.success());
+}
+
+#[test]
+fn test_tree_subcommand() {
+    let dir = ScratchDir::new("tree");
+    let file = dir.write("tree.c", "int f() { return 1; }");
+    let output = lex(&["tree", file.to_str().unwrap()]);
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "(translation_unit (primitive_type) (identifier) (compound_statement (number_literal)))\n",
+    );
+
+    let output = lex(&["tree", file.to_str().unwrap(), "--format", "json"]);
+    let nodes: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(nodes[0]["children"], serde_json::json!([1, 2, 3, 4, 5]));
+}
+
+#[test]
+fn test_repl_subcommand() {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_lex"))
+        .arg("repl")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(b"f(a,\nb)\n$\n").unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), concat!(
+        "lex> ...> 0..1\tIDENTIFIER\tf\n",
+        "1..2\tLPAREN\t(\n",
+        "2..3\tIDENTIFIER\ta\n",
+        "3..4\tCOMMA\t,\n",
+        "5..6\tIDENTIFIER\tb\n",
+        "6..7\tRPAREN\t)\n",
+        "lex> 0..1\terror[unrecognized-token]: unrecognized token `$`\n",
+        "lex> ",
+    ));
+}
+
+#[test]
+fn test_teach_subcommand() {
+    let dir = ScratchDir::new("teach");
+    let file = dir.write("teach.c", "x = 1;");
+    let output = lex(&["teach", file.to_str().unwrap()]);
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "1 | x = 1;\n  | i o np\n\nlegend: i identifier  n number  o operator  p punctuation\n",
+    );
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_lex"))
+        .args(["teach", file.to_str().unwrap(), "--step"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(b"\n").unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), concat!(
+        "step 1 of 4: IDENTIFIER `x` at 1:1 (identifier)\n1 | x = 1;\n  | ^\n",
+        "step 2 of 4: EQUAL `=` at 1:3 (operator)\n1 | x = 1;\n  |   ^\n",
+    ));
+}
+
+/// Runs the `lex` binary with the given arguments
+fn lex(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_lex")).args(args).output().unwrap()
+}
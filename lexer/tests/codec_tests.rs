@@ -0,0 +1,101 @@
+use common::error::ErrorType;
+use lexer::{
+    codec::{decode, encode, FORMAT_VERSION},
+    core::Lexer,
+    span::{Span, SpannedToken},
+    token::{Token, TokenKind, TRIVIA_ID},
+};
+
+/// cargo test --test codec_tests
+/// Tests encoding token streams to bytes and back.
+
+#[test]
+fn test_round_trip() {
+    let input = "int main() {\n    int x = 10;\n    x = x + 1; /* comment */\n    return x;\n}";
+    let tokens = Lexer::lex_spanned(input).unwrap();
+    let bytes = encode(&tokens);
+    assert_eq!(&bytes[..4], &[b'L', b'X', b'T', FORMAT_VERSION]);
+    assert_eq!(decode(&bytes), Ok(tokens));
+}
+
+#[test]
+fn test_payloads_round_trip() {
+    let tokens = vec![
+        SpannedToken::new(Token::STRINGLITERAL(vec!['h', 'i']), Span::new(0, 4)),
+        SpannedToken::new(Token::CHAR('\u{00E9}'), Span::new(5, 9)),
+        SpannedToken::new(Token::IDENTIFIER(vec!['h', 'i']), Span::new(300, 302)),
+        SpannedToken::new(Token::EOF, Span::new(302, 302)),
+    ];
+    assert_eq!(decode(&encode(&tokens)), Ok(tokens));
+}
+
+#[test]
+fn test_flags_round_trip() {
+    let mut synthetic = SpannedToken::new(Token::STRINGLITERAL(vec!['a']), Span::new(0, 2));
+    synthetic.synthetic = true;
+    let mut contextual = SpannedToken::new(Token::IDENTIFIER(vec!['i', 'n', 'l', 'i', 'n', 'e']), Span::new(3, 9));
+    contextual.contextual_keyword = true;
+    let tokens = vec![synthetic, contextual, SpannedToken::new(Token::EOF, Span::new(9, 9))];
+    assert_eq!(decode(&encode(&tokens)), Ok(tokens));
+}
+
+#[test]
+fn test_unordered_spans_round_trip() {
+    // Tokens built by hand or reordered by a tool need not be in source order
+    let tokens = vec![
+        SpannedToken::new(Token::IDENTIFIER(vec!['b']), Span::new(10, 11)),
+        SpannedToken::new(Token::IDENTIFIER(vec!['a']), Span::new(2, 3)),
+        SpannedToken::new(Token::PLUS, Span::new(2, 3)),
+        SpannedToken::new(Token::EOF, Span::new(11, 11)),
+    ];
+    assert_eq!(decode(&encode(&tokens)), Ok(tokens));
+}
+
+#[test]
+fn test_repeated_symbols_stored_once() {
+    let tokens = Lexer::lex_spanned("counter counter counter counter").unwrap();
+    let bytes = encode(&tokens);
+    let occurrences = bytes.windows(7).filter(|window| *window == b"counter").count();
+    assert_eq!(occurrences, 1);
+}
+
+#[test]
+fn test_kinds_are_written_as_their_ids() {
+    for (index, token) in Token::ALL_KINDS.iter().enumerate() {
+        let bytes = encode(&[SpannedToken::new(token.clone(), Span::new(index, index + 1))]);
+        // The payloads of `ALL_KINDS` are empty, so each symbol takes the one byte of its length
+        let kind_at = 6 + bytes[4] as usize;
+        assert_eq!(bytes[kind_at], token.kind().id());
+        assert_eq!(decode(&bytes).unwrap()[0].token, *token);
+    }
+}
+
+#[test]
+fn test_unknown_kinds_are_malformed() {
+    for id in [Token::ALL_KINDS.len() as u8, TRIVIA_ID] {
+        let bytes = [b'L', b'X', b'T', FORMAT_VERSION, 0, 1, id, 0, 0, 0];
+        assert!(matches!(decode(&bytes), Err(ErrorType::MalformedTokenStream { .. })));
+    }
+    assert_eq!(TokenKind::from_id(TRIVIA_ID), Some(TokenKind::TRIVIA));
+}
+
+#[test]
+fn test_malformed_input() {
+    assert!(matches!(decode(b"XYZ\x01"), Err(ErrorType::MalformedTokenStream { .. })));
+    assert!(matches!(decode(b"LXT\x63"), Err(ErrorType::MalformedTokenStream { .. })));
+
+    let bytes = encode(&Lexer::lex_spanned("a + b").unwrap());
+    assert!(matches!(decode(&bytes[..bytes.len() - 1]), Err(ErrorType::MalformedTokenStream { .. })));
+}
+
+#[test]
+fn test_overflowing_span_is_malformed() {
+    // Two `EOF` tokens, each starting `u64::MAX` bytes after the end of the one before
+    let mut bytes = vec![b'L', b'X', b'T', FORMAT_VERSION, 0, 2];
+    for _ in 0..2 {
+        bytes.extend_from_slice(&[1, 0]);
+        bytes.extend_from_slice(&[0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x01]);
+        bytes.push(0);
+    }
+    assert_eq!(decode(&bytes), Err(ErrorType::MalformedTokenStream { message: "span out of range".to_string() }));
+}
@@ -0,0 +1,48 @@
+use lexer::{
+    column::{char_width, display_width},
+    diagnostic::Diagnostic,
+    span::{Location, Span},
+};
+
+/// cargo test --test column_tests
+/// Tests line and column reporting for tabs, wide characters, and combining marks.
+
+#[test]
+fn test_char_widths() {
+    assert_eq!(char_width('a'), 1);
+    assert_eq!(char_width('\u{4E2D}'), 2);
+    assert_eq!(char_width('\u{0301}'), 0);
+    assert_eq!(char_width('\u{200B}'), 0);
+}
+
+#[test]
+fn test_tab_stops() {
+    assert_eq!(display_width("\tx", 4), 5);
+    assert_eq!(display_width("ab\tx", 4), 5);
+    assert_eq!(display_width("ab\tx", 8), 9);
+}
+
+#[test]
+fn test_locations() {
+    let source = "int x;\n\t\u{4E2D}e\u{0301} = 1;";
+    let offset = source.find('=').unwrap();
+    let location = Span::new(offset, offset + 1).start_location(source, 4);
+    let expected = Location { line: 2, byte_column: offset - 7 + 1, display_column: 4 + 2 + 1 + 1 + 1 };
+    assert_eq!(location, expected);
+}
+
+#[test]
+fn test_render_aligns_carets_after_tabs_and_wide_characters() {
+    let source = "a = 1;\n\t\u{4E2D} bad;";
+    let start = source.find("bad").unwrap();
+    let diagnostic = Diagnostic::warning("test", "bad name".to_string(), Span::new(start, start + 3));
+    let expected = "warning[test]: bad name\n --> 2:8\n  |\n2 |     \u{4E2D} bad;\n  |        ^^^\n";
+    assert_eq!(diagnostic.render(source, 4), expected);
+}
+
+#[test]
+fn test_render_empty_span() {
+    let source = "x";
+    let diagnostic = Diagnostic::warning("eof", "at end".to_string(), Span::new(1, 1));
+    assert_eq!(diagnostic.render(source, 4), "warning[eof]: at end\n --> 1:2\n  |\n1 | x\n  |  ^\n");
+}
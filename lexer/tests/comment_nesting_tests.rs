@@ -0,0 +1,43 @@
+mod support;
+
+use lexer::{
+    config::{LexerBuilder, LexerConfig},
+    core::Lexer,
+    project::Dialect,
+    token::Token,
+};
+use support::ident;
+
+/// cargo test --test comment_nesting_tests
+/// Tests that block comments nest only when nesting is turned on.
+
+#[test]
+fn test_comments_do_not_nest_by_default() {
+    assert!(!LexerConfig::default().nested_comments());
+    let result = Lexer::lex_with_config("/* /* */ int x; */", &LexerConfig::default());
+    let expected = vec![Token::TINTEGER, ident("x"), Token::SEMICOLON, Token::ASTERISK, Token::FSLASH, Token::EOF];
+    assert_eq!(result, Ok(expected));
+}
+
+#[test]
+fn test_nested_comments() {
+    let config = LexerBuilder::new().nested_comments(true).build();
+    assert!(config.nested_comments());
+    assert_eq!(Lexer::lex_with_config("/* /* */ int x; */", &config), Ok(vec![Token::EOF]));
+}
+
+#[test]
+fn test_unclosed_nested_comment() {
+    let config = LexerBuilder::new().nested_comments(true).build();
+    assert_eq!(Lexer::lex_with_config("/* /* */ int x;", &config), Ok(vec![Token::EOF]));
+    assert_eq!(
+        Lexer::lex_with_config("/* /* */ int x;", &LexerConfig::default()),
+        Ok(vec![Token::TINTEGER, ident("x"), Token::SEMICOLON, Token::EOF])
+    );
+}
+
+#[test]
+fn test_dialects_do_not_nest() {
+    assert!(!Dialect::C89.nested_comments());
+    assert!(!Dialect::C99.nested_comments());
+}
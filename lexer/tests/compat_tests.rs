@@ -0,0 +1,77 @@
+mod support;
+
+use lexer::{
+    compat::{compare_corpus, compare_with_reference, reference_tokens, ClassifiedToken, TokenClass},
+    span::Span,
+};
+use support::ScratchDir;
+
+/// cargo test --test compat_tests
+/// Tests comparing the lexer against the reference C tokenizer.
+
+#[test]
+fn test_agrees_on_supported_c() {
+    let source = "#include <stdio.h>\nint main(void) {\n    // entry\n    return x->y + 'a' == 1 ? \"s\" : 0;\n}\n";
+    assert_eq!(compare_with_reference(source), vec![]);
+}
+
+#[test]
+fn test_reference_tokens() {
+    assert_eq!(reference_tokens("x <<= 0x1F; /* c */ u8\"s\""), vec![
+        token(TokenClass::Identifier, 0, 1),
+        token(TokenClass::Punctuator, 2, 5),
+        token(TokenClass::Number, 6, 10),
+        token(TokenClass::Punctuator, 10, 11),
+        token(TokenClass::String, 20, 25),
+    ]);
+}
+
+#[test]
+fn test_reference_keywords_and_directives() {
+    assert_eq!(reference_tokens("  #define A \\\n  1\nsizeof"), vec![
+        token(TokenClass::Directive, 2, 17),
+        token(TokenClass::Keyword, 18, 24),
+    ]);
+}
+
+#[test]
+fn test_boundary_divergence() {
+    let divergences = compare_with_reference("x += 1;");
+    assert_eq!(divergences.len(), 1);
+    assert_eq!(divergences[0].expected, vec![token(TokenClass::Punctuator, 2, 4)]);
+    assert_eq!(divergences[0].actual, vec![token(TokenClass::Punctuator, 2, 3), token(TokenClass::Punctuator, 3, 4)]);
+    assert_eq!(divergences[0].span(), Span::new(2, 4));
+}
+
+#[test]
+fn test_class_divergence() {
+    let divergences = compare_with_reference("n = sizeof x;");
+    assert_eq!(divergences.len(), 1);
+    assert_eq!(divergences[0].expected, vec![token(TokenClass::Keyword, 4, 10)]);
+    assert_eq!(divergences[0].actual, vec![token(TokenClass::Identifier, 4, 10)]);
+}
+
+#[test]
+fn test_errors_match_invalid_characters() {
+    assert_eq!(compare_with_reference("a $ b"), vec![]);
+}
+
+#[test]
+fn test_compare_corpus() {
+    let dir = ScratchDir::new("corpus");
+    dir.write("clean.c", "int x = 1;");
+    let ellipsis = dir.write("nested/ellipsis.c", "void f(int n, ...);");
+    dir.write("notes.txt", "x ... y");
+    dir.write("binary.c", [0xff, 0xfe, 0x00]);
+    dir.write(".git/stash.c", "void f(int n, ...);");
+
+    let results = compare_corpus(&dir).unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].0, ellipsis);
+    assert_eq!(results[0].1[0].expected, vec![token(TokenClass::Punctuator, 14, 17)]);
+}
+
+/// Builds a classified token
+fn token(class: TokenClass, start: usize, end: usize) -> ClassifiedToken {
+    ClassifiedToken { class, span: Span::new(start, end) }
+}
@@ -0,0 +1,74 @@
+use lexer::{
+    check::check_source,
+    config::{LexerBuilder, LexerConfig},
+    confusable::check_confusables,
+    core::Lexer,
+    diagnostic::Diagnostic,
+    span::Span,
+};
+
+/// cargo test --test confusable_tests
+/// Tests detection of lookalike and invisible characters.
+
+#[test]
+fn test_ascii_is_clean() {
+    let input = "int main() { return 0; }";
+    assert!(check(input, &LexerConfig::default()).is_empty());
+}
+
+#[test]
+fn test_cyrillic_lookalike_in_identifier() {
+    // The second letter is CYRILLIC SMALL LETTER A.
+    let input = "int v\u{0430}r = 1;";
+    let diagnostics = check(input, &cyrillic());
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].code, "confusable-character");
+    assert_eq!(diagnostics[0].span, Span::new(5, 7));
+}
+
+#[test]
+fn test_identifier_in_another_script_is_clean() {
+    // Cyrillic words that cannot pass for ASCII, though some of their letters can
+    let input = "int \u{0441}\u{0443}\u{043C}\u{043C}\u{0430} = 1;";
+    assert!(check(input, &cyrillic()).is_empty());
+
+    // Every letter of this one has an ASCII lookalike, so it reads as `cox`
+    let diagnostics = check("int \u{0441}\u{043E}\u{0445} = 1;", &cyrillic());
+    assert_eq!(diagnostics.len(), 3);
+}
+
+#[test]
+fn test_comments_and_strings_in_another_script_are_clean() {
+    let input = concat!(
+        "// \u{043F}\u{0440}\u{043E}\u{0432}\u{0435}\u{0440}\u{043A}\u{0430}\n",
+        "char *message = \"\u{041E}\u{0448}\u{0438}\u{0431}\u{043A}\u{0430}\";\n",
+    );
+    assert!(check_source(input).is_empty());
+}
+
+#[test]
+fn test_invisible_characters() {
+    let input = "x\u{200B} = 1; /* \u{202E} */";
+    let diagnostics = check(input, &LexerConfig::default());
+    let codes: Vec<&str> = diagnostics.iter().map(|d| d.code).collect();
+    assert_eq!(codes, vec!["invisible-character", "invisible-character"]);
+    assert_eq!(diagnostics[0].span, Span::new(1, 4));
+}
+
+#[test]
+fn test_leading_byte_order_mark_allowed() {
+    let input = "\u{FEFF}int x;";
+    assert!(check(input, &LexerConfig::default()).is_empty());
+}
+
+/// Lexes source code, recovering from errors, and checks it for confusable characters
+fn check(input: &str, config: &LexerConfig) -> Vec<Diagnostic> {
+    let (tokens, _) = Lexer::lex_recovering(input, config);
+    check_confusables(input, &tokens)
+}
+
+/// Settings that let identifiers hold Cyrillic letters
+fn cyrillic() -> LexerConfig {
+    let letters: String = ('\u{0410}'..='\u{044F}').chain(['\u{0455}', '\u{0456}', '\u{0458}']).collect();
+    LexerBuilder::new().identifier_start(&letters).unwrap().build()
+}
@@ -0,0 +1,35 @@
+use lexer::{
+    config::LexerBuilder,
+    core::Lexer,
+    token::Token,
+};
+
+/// cargo test --test contextual_keyword_tests
+/// Tests flagging identifiers that are contextual keywords.
+
+#[test]
+fn test_contextual_keywords_stay_identifiers() {
+    let config = LexerBuilder::new().c99_contextual_keywords().build();
+    let tokens = Lexer::lex_spanned_with_config("inline int restrict_ = restrict;", &config).unwrap();
+
+    assert_eq!(tokens[0].token, Token::IDENTIFIER("inline".chars().collect()));
+    assert!(tokens[0].contextual_keyword);
+    assert!(!tokens[1].contextual_keyword);
+    assert!(!tokens[2].contextual_keyword);
+    assert_eq!(tokens[4].token, Token::IDENTIFIER("restrict".chars().collect()));
+    assert!(tokens[4].contextual_keyword);
+}
+
+#[test]
+fn test_user_registered_keywords() {
+    let config = LexerBuilder::new().contextual_keyword("async").contextual_keyword("async").build();
+    let tokens = Lexer::lex_spanned_with_config("async asyncx", &config).unwrap();
+    assert!(tokens[0].contextual_keyword);
+    assert!(!tokens[1].contextual_keyword);
+}
+
+#[test]
+fn test_no_flags_by_default() {
+    let tokens = Lexer::lex_spanned("inline restrict").unwrap();
+    assert!(tokens.iter().all(|spanned| !spanned.contextual_keyword));
+}
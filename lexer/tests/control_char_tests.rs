@@ -0,0 +1,76 @@
+mod support;
+
+use common::error::ErrorType;
+use lexer::{
+    check::error_diagnostic,
+    config::{ControlCharPolicy, LexerBuilder, LexerConfig},
+    core::Lexer,
+    span::Span,
+    token::Token,
+};
+use support::ident;
+
+/// cargo test --test control_char_tests
+/// Tests the policies for control characters outside comments and literals.
+
+#[test]
+fn test_error_by_default() {
+    assert_eq!(LexerConfig::default().control_chars(), ControlCharPolicy::Error);
+    let errors = Lexer::lex_located("x\0\0\x01 y\x07", &LexerConfig::default()).unwrap_err();
+    assert_eq!(errors.len(), 2);
+    assert_eq!(errors[0].error(), &ErrorType::ControlCharacter { character: '\0', count: 3 });
+    assert_eq!(errors[0].span(), Span::new(1, 4));
+    assert_eq!(errors[1].error(), &ErrorType::ControlCharacter { character: '\x07', count: 1 });
+    assert_eq!(errors[1].span(), Span::new(6, 7));
+}
+
+#[test]
+fn test_error_diagnostic() {
+    let errors = Lexer::lex_located("\x1b[0m", &LexerConfig::default()).unwrap_err();
+    let diagnostic = error_diagnostic(&errors[0]);
+    assert_eq!(diagnostic.code, "control-character");
+    assert_eq!(diagnostic.message, "unexpected control character U+001B");
+}
+
+#[test]
+fn test_recovering_continues_after_control_chars() {
+    let (tokens, errors) = Lexer::lex_recovering("a\x01b", &LexerConfig::default());
+    assert_eq!(errors.len(), 1);
+    assert_eq!(tokens.into_iter().map(|spanned| spanned.token).collect::<Vec<Token>>(), vec![
+        ident("a"),
+        ident("b"),
+        Token::EOF,
+    ]);
+}
+
+#[test]
+fn test_warn() {
+    let config = LexerBuilder::new().control_chars(ControlCharPolicy::Warn).build();
+    let (tokens, errors, diagnostics) = Lexer::lex_with_diagnostics("x;\x0e\x0f y;", &config);
+    assert!(errors.is_empty());
+    assert_eq!(tokens.len(), 5);
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].code, "control-character");
+    assert_eq!(diagnostics[0].message, "ignored 2 control characters starting with U+000E");
+    assert_eq!(diagnostics[0].span, Span::new(2, 4));
+}
+
+#[test]
+fn test_skip() {
+    let config = LexerBuilder::new().control_chars(ControlCharPolicy::Skip).build();
+    let (tokens, errors, diagnostics) = Lexer::lex_with_diagnostics("\x0cint\x00x;", &config);
+    assert!(errors.is_empty());
+    assert!(diagnostics.is_empty());
+    assert_eq!(tokens.into_iter().map(|spanned| spanned.token).collect::<Vec<Token>>(), vec![
+        Token::TINTEGER,
+        ident("x"),
+        Token::SEMICOLON,
+        Token::EOF,
+    ]);
+}
+
+#[test]
+fn test_whitespace_and_literals_are_unaffected() {
+    assert!(Lexer::lex("int\tx;\r\n\x0b").is_ok());
+    assert!(Lexer::lex("// \x01\n/* \x02 */ \"\x03\"").is_ok());
+}
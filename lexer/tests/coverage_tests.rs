@@ -0,0 +1,60 @@
+use lexer::{
+    config::LexerBuilder,
+    coverage::{coverage, Handler},
+    token::TokenKind,
+};
+
+/// cargo test --test coverage_tests
+/// Tests reporting which kinds of token and lexer handlers a corpus exercises.
+
+#[test]
+fn test_counts_kinds_and_handlers() {
+    let config = LexerBuilder::new().build();
+    let report = coverage(["int x = 1; // one", "/* two */ x++;"], &config);
+    assert_eq!(report.inputs, 2);
+    assert_eq!(report.kinds[&TokenKind::IDENTIFIER], 2);
+    assert_eq!(report.kinds[&TokenKind::EOF], 2);
+    assert_eq!(report.kinds[&TokenKind::PLUSPLUS], 1);
+    assert_eq!(report.handlers[&Handler::LineComment], 1);
+    assert_eq!(report.handlers[&Handler::BlockComment], 1);
+    assert_eq!(report.handlers[&Handler::Plus], 1);
+    assert_eq!(report.handlers[&Handler::Comparison], 1);
+    assert_eq!(report.handlers[&Handler::EndOfInput], 2);
+}
+
+#[test]
+fn test_missing_kinds_and_handlers() {
+    let config = LexerBuilder::new().build();
+    let report = coverage(["while (x) { }"], &config);
+    let missing = report.missing_kinds();
+    assert!(missing.contains(&TokenKind::IF));
+    assert!(!missing.contains(&TokenKind::WHILE));
+    assert!(!missing.contains(&TokenKind::DEFAULT));
+    assert!(!missing.contains(&TokenKind::TYPENAME));
+    assert!(!missing.contains(&TokenKind::CUSTOM));
+    let handlers = report.missing_handlers();
+    assert!(handlers.contains(&Handler::Number));
+    assert!(!handlers.contains(&Handler::Word));
+    assert!(!handlers.contains(&Handler::CustomRule));
+    assert!(!handlers.contains(&Handler::RegexRule));
+}
+
+#[test]
+fn test_failing_inputs_still_count() {
+    let config = LexerBuilder::new().build();
+    let report = coverage(["x @", "\"open"], &config);
+    assert_eq!(report.kinds[&TokenKind::IDENTIFIER], 1);
+    assert_eq!(report.handlers[&Handler::Unrecognized], 1);
+    assert_eq!(report.handlers[&Handler::StringLiteral], 1);
+}
+
+#[test]
+fn test_regex_rules_are_reachable() {
+    let config = LexerBuilder::new().token_regex("HEX", "0x[0-9a-f]+").unwrap().build();
+    let report = coverage(vec![String::from("1")], &config);
+    assert!(report.missing_kinds().contains(&TokenKind::CUSTOM));
+    assert!(report.missing_handlers().contains(&Handler::RegexRule));
+    let report = coverage(["0xff"], &config);
+    assert_eq!(report.kinds[&TokenKind::CUSTOM], 1);
+    assert_eq!(report.handlers[&Handler::RegexRule], 1);
+}
@@ -0,0 +1,101 @@
+mod support;
+
+use lexer::{
+    core::Lexer,
+    cursor::TokenStream,
+    token::Token,
+};
+use support::ident;
+
+/// cargo test --test cursor_tests
+/// Tests the token stream cursor and its bracket helpers.
+
+#[test]
+fn test_peek_and_advance() {
+    let mut stream = stream("a = 1;");
+    assert_eq!(stream.peek().map(|spanned| &spanned.token), Some(&ident("a")));
+    assert_eq!(stream.peek_nth(1).map(|spanned| &spanned.token), Some(&Token::EQUAL));
+    assert_eq!(stream.advance().map(|spanned| spanned.token.clone()), Some(ident("a")));
+    assert_eq!(stream.position(), 1);
+    while !stream.is_at_end() {
+        stream.advance();
+    }
+    assert_eq!(stream.peek().map(|spanned| &spanned.token), Some(&Token::EOF));
+    assert!(stream.advance().is_some());
+    assert!(stream.advance().is_none());
+}
+
+#[test]
+fn test_find_matching() {
+    let stream = stream("f(a, (b), c[1]) { g(); }");
+    assert_eq!(stream.find_matching(1), Some(12));
+    assert_eq!(stream.find_matching(4), Some(6));
+    assert_eq!(stream.find_matching(9), Some(11));
+    assert_eq!(stream.find_matching(13), Some(18));
+    assert_eq!(stream.find_matching(0), None);
+    assert_eq!(stream.find_matching(12), None);
+    assert_eq!(stream.find_matching(100), None);
+}
+
+#[test]
+fn test_mismatched_brackets() {
+    // The `[` is never closed, so the `)` still closes the `(`
+    let stream = stream("( [ x ) ]");
+    assert_eq!(stream.find_matching(0), Some(3));
+    assert_eq!(stream.find_matching(1), None);
+}
+
+#[test]
+fn test_skip_balanced() {
+    let mut stream = stream("(a, (b)) c");
+    assert!(stream.skip_balanced());
+    assert_eq!(stream.peek().map(|spanned| &spanned.token), Some(&ident("c")));
+    assert!(!stream.skip_balanced());
+    assert_eq!(stream.position(), 7);
+}
+
+#[test]
+fn test_skip_unclosed_group() {
+    let mut stream = stream("x { a; ( b");
+    stream.advance();
+    assert!(stream.skip_balanced());
+    assert!(stream.is_at_end());
+    assert_eq!(stream.peek().map(|spanned| &spanned.token), Some(&Token::EOF));
+}
+
+#[test]
+fn test_prev_and_peek_back() {
+    let mut stream = stream("(int) x");
+    assert!(stream.prev().is_none());
+    assert!(stream.peek_back(1).is_none());
+    assert_eq!(stream.peek_back(0).map(|spanned| &spanned.token), Some(&Token::LPAREN));
+    stream.advance();
+    stream.advance();
+    stream.advance();
+    // A cast is a type name closed in parentheses before the current token
+    assert_eq!(stream.peek_back(1).map(|spanned| &spanned.token), Some(&Token::RPAREN));
+    assert_eq!(stream.peek_back(2).map(|spanned| &spanned.token), Some(&Token::TINTEGER));
+    assert!(stream.peek_back(4).is_none());
+    assert_eq!(stream.prev().map(|spanned| &spanned.token), Some(&Token::RPAREN));
+    assert_eq!(stream.position(), 2);
+    assert_eq!(stream.advance().map(|spanned| spanned.token.clone()), Some(Token::RPAREN));
+}
+
+#[test]
+fn test_seek() {
+    let mut stream = stream("a + b;");
+    stream.seek(2);
+    assert_eq!(stream.peek().map(|spanned| &spanned.token), Some(&ident("b")));
+    assert_eq!(stream.peek_back(1).map(|spanned| &spanned.token), Some(&Token::PLUS));
+    stream.seek(0);
+    assert_eq!(stream.peek().map(|spanned| &spanned.token), Some(&ident("a")));
+    stream.seek(100);
+    assert_eq!(stream.position(), 5);
+    assert!(stream.peek().is_none());
+    assert_eq!(stream.prev().map(|spanned| &spanned.token), Some(&Token::EOF));
+}
+
+/// Lexes a source into a token stream
+fn stream(source: &str) -> TokenStream {
+    TokenStream::new(Lexer::lex_spanned(source).unwrap())
+}
@@ -0,0 +1,82 @@
+use lexer::{
+    core::Lexer,
+    detect::{detect_dialect, Indentation, Language},
+    project::Dialect,
+    token::Token,
+};
+
+/// cargo test --test detect_tests
+/// Tests guessing the language and dialect of a file from its contents.
+
+#[test]
+fn test_c89() {
+    let source = "#include <stdio.h>\n\nint main(void) {\n    /* http://example.com */\n    return 0;\n}\n";
+    let guess = detect_dialect(source);
+    assert_eq!(guess.language, Language::C);
+    assert_eq!(guess.dialect, Dialect::C89);
+    assert_eq!(guess.confidence, 1.0);
+    assert!(guess.evidence.is_empty());
+}
+
+#[test]
+fn test_c99() {
+    let guess = detect_dialect("#include <stdint.h>\n// count up\nvoid f(void) { for (int i = 0; i < 3; i++) {} }\n");
+    assert_eq!(guess.language, Language::C);
+    assert_eq!(guess.dialect, Dialect::C99);
+    assert_eq!(
+        guess.evidence,
+        vec![
+            "`stdint.h` is a C99 header".to_string(),
+            "declaring a variable in a `for` loop is C99".to_string(),
+            "`//` comments are C99".to_string(),
+        ]
+    );
+}
+
+#[test]
+fn test_cpp() {
+    let guess = detect_dialect("#include <iostream>\nclass Point {};\nint main() { std::cout; return 0; }\n");
+    assert_eq!(guess.language, Language::Cpp);
+    assert_eq!(guess.confidence, 1.0);
+    assert!(guess.evidence.contains(&"`iostream` is a C++ standard header".to_string()));
+    assert!(guess.evidence.contains(&"`::` qualifies names in C++".to_string()));
+    assert!(guess.evidence.contains(&"`class` is a C++ keyword".to_string()));
+}
+
+#[test]
+fn test_other_languages() {
+    let guess = detect_dialect("fn main() {\n    let add = |a, b| a + b;\n    let f = x => x;\n}\n");
+    assert_eq!(guess.language, Language::Other);
+    assert!(guess.confidence > 0.5);
+    assert!(guess.evidence.contains(&"`=>` is not C".to_string()));
+}
+
+#[test]
+fn test_empty_input() {
+    let guess = detect_dialect("");
+    assert_eq!(guess.language, Language::C);
+    assert_eq!(guess.dialect, Dialect::C89);
+    assert_eq!(guess.confidence, 0.0);
+    assert_eq!(guess.indentation, Indentation::None);
+}
+
+#[test]
+fn test_include_of_a_macro_is_not_cpp() {
+    assert_eq!(detect_dialect("#include HEADER\nint x;").language, Language::C);
+}
+
+#[test]
+fn test_indentation() {
+    assert_eq!(detect_dialect("int x;\nint y;\n").indentation, Indentation::None);
+    assert_eq!(detect_dialect("{\n\tx;\n\t\ty;\n}\n").indentation, Indentation::Tabs);
+    assert_eq!(detect_dialect("{\n  x;\n    y;\n\n}\n").indentation, Indentation::Spaces(2));
+    assert_eq!(detect_dialect("{\n\tx;\n    y;\n}\n").indentation, Indentation::Mixed);
+}
+
+#[test]
+fn test_lexer_builder_uses_the_guessed_profile() {
+    let guess = detect_dialect("#include <vector>\nint f(int a, int b) { return a and b; }");
+    let config = guess.lexer_builder().build();
+    let tokens = Lexer::lex_with_config("a and b", &config).unwrap();
+    assert_eq!(tokens[1], Token::ANDAND);
+}
@@ -0,0 +1,62 @@
+use common::error::ErrorType;
+use lexer::{
+    codec,
+    core::Lexer,
+    diagnostic::Severity,
+    directive::{check_directives, directive_name},
+    span::Span,
+    token::Token,
+};
+
+/// cargo test --test directive_tests
+/// Tests passing preprocessor lines through the lexer and reporting them.
+
+#[test]
+fn test_include_lexes_as_hash_line() {
+    let tokens = Lexer::lex("#include <stdio.h>\nint x;").unwrap();
+    assert_eq!(tokens, vec![
+        Token::HASHLINE("#include <stdio.h>".chars().collect()),
+        Token::TINTEGER,
+        Token::IDENTIFIER(vec!['x']),
+        Token::SEMICOLON,
+        Token::EOF,
+    ]);
+}
+
+#[test]
+fn test_continued_and_indented_lines() {
+    let source = "  #  define MAX(a, b) \\\n    ((a) > (b))\nMAX";
+    let tokens = Lexer::lex_spanned(source).unwrap();
+    let line_end = source.rfind('\n').unwrap();
+    assert_eq!(tokens[0].token, Token::HASHLINE(source[2..line_end].chars().collect()));
+    assert_eq!(tokens[0].span, Span::new(2, line_end));
+    assert_eq!(tokens[1].token, Token::IDENTIFIER(vec!['M', 'A', 'X']));
+    assert_eq!(directive_name(&tokens[0].token), Some("define".to_string()));
+}
+
+#[test]
+fn test_stray_hash_is_an_error() {
+    let errors = Lexer::lex("x = #y;").unwrap_err();
+    assert_eq!(errors, vec![ErrorType::UnrecognizedToken { token: "#".to_string() }]);
+}
+
+#[test]
+fn test_check_directives() {
+    let tokens = Lexer::lex_spanned("#include <stdio.h>\n#\n#frobnicate\nint x;").unwrap();
+    let diagnostics = check_directives(&tokens);
+
+    let codes: Vec<(Severity, &str)> = diagnostics.iter().map(|d| (d.severity, d.code)).collect();
+    assert_eq!(codes, vec![
+        (Severity::Info, "preprocessor-directive"),
+        (Severity::Info, "null-directive"),
+        (Severity::Warning, "unknown-directive"),
+    ]);
+    assert_eq!(diagnostics[0].message, "`#include` directive was passed through without preprocessing");
+    assert_eq!(diagnostics[2].span, Span::new(21, 32));
+}
+
+#[test]
+fn test_hash_line_round_trips_through_codec() {
+    let tokens = Lexer::lex_spanned("#pragma once\n").unwrap();
+    assert_eq!(codec::decode(&codec::encode(&tokens)).unwrap(), tokens);
+}
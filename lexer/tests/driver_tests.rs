@@ -0,0 +1,83 @@
+mod support;
+
+use std::path::PathBuf;
+
+use lexer::{
+    config::LexerBuilder,
+    driver::{analyze_project, discover_files, DriverConfig},
+    lint::LintConfig,
+};
+use support::ScratchDir;
+
+/// cargo test --test driver_tests
+/// Tests analyzing every source file in a project.
+
+#[test]
+fn test_discover_files() {
+    let dir = scratch_dir("discover");
+    let paths = discover_files(&dir, &DriverConfig::default()).unwrap();
+    assert_eq!(paths, vec![dir.join("a.c"), dir.join("include").join("b.h"), dir.join("src").join("c.c")]);
+}
+
+#[test]
+fn test_discover_files_with_extensions() {
+    let dir = scratch_dir("extensions");
+    let config = DriverConfig { extensions: vec!["h".to_string()], ..DriverConfig::default() };
+    assert_eq!(discover_files(&dir, &config).unwrap(), vec![dir.join("include").join("b.h")]);
+}
+
+#[test]
+fn test_analyze_project() {
+    let dir = scratch_dir("analyze");
+    let analysis = analyze_project(&dir, &DriverConfig { threads: Some(2), ..DriverConfig::default() }).unwrap();
+
+    let paths: Vec<PathBuf> = analysis.files.iter().map(|file| PathBuf::from(&file.report.path)).collect();
+    assert_eq!(paths, vec![PathBuf::from("a.c"), PathBuf::from("include/b.h"), PathBuf::from("src/c.c")]);
+    assert_eq!(analysis.metrics.files, 3);
+    assert_eq!(analysis.metrics.bytes, 36);
+    assert_eq!(analysis.metrics.tokens, analysis.files.iter().map(|file| file.tokens).sum::<usize>());
+    assert_eq!(analysis.metrics.errors, 1);
+    assert_eq!(analysis.metrics.infos, 1);
+    assert!(analysis.has_errors());
+    assert_eq!(analysis.reports()[2].diagnostics[0].code, "unrecognized-token");
+}
+
+#[test]
+fn test_analyze_project_with_config() {
+    let dir = scratch_dir("config");
+    let config = DriverConfig {
+        lexer: LexerBuilder::new().identifier_start("$").unwrap().build(),
+        lints: LintConfig { max_identifier_length: Some(3), ..LintConfig::default() },
+        ..DriverConfig::default()
+    };
+    let analysis = analyze_project(&dir, &config).unwrap();
+    assert_eq!(analysis.metrics.errors, 0);
+    assert_eq!(analysis.metrics.warnings, 1);
+}
+
+#[test]
+fn test_for_project() {
+    let dir = scratch_dir("project");
+    dir.write("lexer.toml", "ignore = [\"src\"]\n[lints]\nmax_identifier_length = 3");
+    let analysis = analyze_project(&dir, &DriverConfig::for_project(&dir).unwrap()).unwrap();
+    assert_eq!(analysis.metrics.files, 2);
+    assert_eq!(analysis.metrics.warnings, 1);
+
+    assert_eq!(DriverConfig::for_project(&scratch_dir("no-project")).unwrap().ignore, Vec::<String>::new());
+}
+
+#[test]
+fn test_missing_root() {
+    assert!(analyze_project(&ScratchDir::new("missing").join("absent"), &DriverConfig::default()).is_err());
+}
+
+/// Creates a small project in a directory private to this test
+fn scratch_dir(name: &str) -> ScratchDir {
+    let dir = ScratchDir::new(name);
+    dir.write("a.c", "int count = 1;");
+    dir.write("include/b.h", "#pragma once\n");
+    dir.write("src/c.c", "long = $;");
+    dir.write("notes.txt", "not code");
+    dir.write(".git/d.c", "hidden");
+    dir
+}
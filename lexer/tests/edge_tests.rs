@@ -1,6 +1,7 @@
 use common::error::ErrorType;
 
 use lexer::{
+    config::LexerBuilder,
     core::Lexer,
     token::Token,
 };
@@ -176,3 +177,34 @@ fn test_single_line_comment() {
     ];
     assert_eq!(result, Ok(expected));
 }
+
+#[test]
+fn test_at_sign_inside_comments() {
+    let input = "// mail@example\nx /* @ */ y";
+    let result = Lexer::lex(input);
+    let expected = vec![
+        Token::IDENTIFIER(vec!['x']), Token::IDENTIFIER(vec!['y']), Token::EOF,
+    ];
+    assert_eq!(result, Ok(expected));
+}
+
+#[test]
+fn test_nested_block_comments() {
+    let input = "a /* outer /* inner */ still outer */ b /*/ not closed */ c";
+    let config = LexerBuilder::new().nested_comments(true).build();
+    let result = Lexer::lex_with_config(input, &config);
+    let expected = vec![
+        Token::IDENTIFIER(vec!['a']), Token::IDENTIFIER(vec!['b']), Token::IDENTIFIER(vec!['c']), Token::EOF,
+    ];
+    assert_eq!(result, Ok(expected));
+}
+
+#[test]
+fn test_many_consecutive_comments() {
+    let input = "//\n".repeat(100_000) + "/* */".repeat(100_000).as_str() + "x";
+    let result = Lexer::lex(&input);
+    let expected = vec![
+        Token::IDENTIFIER(vec!['x']), Token::EOF,
+    ];
+    assert_eq!(result, Ok(expected));
+}
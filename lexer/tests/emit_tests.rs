@@ -0,0 +1,106 @@
+use lexer::{
+    core::Lexer,
+    emit::{emit, BraceStyle, EmitStyle},
+    splice::{Delimiter, TokenStreamBuilder},
+    token::Token,
+};
+
+/// cargo test --test emit_tests
+/// Tests laying token streams out as indented source.
+
+#[test]
+fn test_function_is_indented() {
+    let source = "int max(int a,int b){if(a>b){return a;}else{return b;}}";
+    let expected = "int max(int a, int b) {
+    if (a > b) {
+        return a;
+    } else {
+        return b;
+    }
+}
+";
+    assert_eq!(pretty(source, &EmitStyle::default()), expected);
+}
+
+#[test]
+fn test_next_line_braces() {
+    let style = EmitStyle { brace_style: BraceStyle::NextLine, indent: "\t".to_string(), ..EmitStyle::default() };
+    let expected = "void f(void)\n{\n\tif (x)\n\t{\n\t\ty();\n\t}\n\telse\n\t{\n\t\tz();\n\t}\n}\n";
+    assert_eq!(pretty("void f(void){if(x){y();}else{z();}}", &style), expected);
+}
+
+#[test]
+fn test_compact_spacing() {
+    let style = EmitStyle {
+        space_around_binary: false,
+        space_after_comma: false,
+        space_after_keyword: false,
+        ..EmitStyle::default()
+    };
+    assert_eq!(pretty("while (a + b > c) f(a, b);", &style), "while(a+b>c) f(a,b);\n");
+}
+
+#[test]
+fn test_loops_statements_and_labels() {
+    let source = "for(i=0;i<n;i++){switch(a[i]){case 1:x=-x;break;default:x++;}}do{n--;}while(n);";
+    let expected = "for (i = 0; i < n; i++) {
+    switch (a[i]) {
+        case 1:
+        x = -x;
+        break;
+        default:
+        x++;
+    }
+}
+do {
+    n--;
+} while (n);
+";
+    assert_eq!(pretty(source, &EmitStyle::default()), expected);
+}
+
+#[test]
+fn test_declarations_and_initializers() {
+    let source = "struct point{int x;int *next;};int a[2]={1,2};char *s=&c;";
+    let expected = "struct point {
+    int x;
+    int *next;
+};
+int a[2] = {1, 2};
+char *s = &c;
+";
+    assert_eq!(pretty(source, &EmitStyle::default()), expected);
+}
+
+#[test]
+fn test_preprocessor_lines_stay_at_column_zero() {
+    let source = "#include <stdio.h>\nvoid f(void){\n#ifdef DEBUG\nlog();\n#endif\n}";
+    let expected = "#include <stdio.h>\nvoid f(void) {\n#ifdef DEBUG\n    log();\n#endif\n}\n";
+    assert_eq!(pretty(source, &EmitStyle::default()), expected);
+}
+
+#[test]
+fn test_output_relexes_to_the_same_tokens() {
+    let source = "int f(int *p){return - -*p+ ++p[0]-(p->x).y*a?b:c;}";
+    let tokens = Lexer::lex(source).unwrap();
+    for style in [EmitStyle::default(), EmitStyle { space_around_binary: false, ..EmitStyle::default() }] {
+        assert_eq!(Lexer::lex(&emit(&tokens, &style)).unwrap(), tokens);
+    }
+}
+
+#[test]
+fn test_builder_pretty_source() {
+    let body = Lexer::lex_spanned("total += 1;").unwrap();
+    let builder = TokenStreamBuilder::new()
+        .keyword("while")
+        .unwrap()
+        .group(Delimiter::Parenthesis, TokenStreamBuilder::new().ident("running"))
+        .group(Delimiter::Brace, TokenStreamBuilder::new().splice(&body));
+    assert_eq!(builder.to_pretty_source(&EmitStyle::default()), "while (running) {\n    total += 1;\n}\n");
+    assert_eq!(emit(&[Token::EOF], &EmitStyle::default()), "");
+}
+
+/// Lexes source code and lays it out again
+fn pretty(source: &str, style: &EmitStyle) -> String {
+    emit(&Lexer::lex(source).unwrap(), style)
+}
@@ -0,0 +1,90 @@
+use common::error::ErrorType;
+use lexer::{
+    core::Lexer,
+    encoding::{detect, transcode, Encoding},
+    span::Span,
+    token::Token,
+};
+
+/// cargo test --test encoding_tests
+/// Tests lexing source bytes that are not plain UTF-8.
+
+#[test]
+fn test_detection() {
+    assert_eq!(detect(b"int x;"), Encoding::Utf8);
+    assert_eq!(detect(&[0xEF, 0xBB, 0xBF, b'x']), Encoding::Utf8);
+    assert_eq!(detect(&utf16le("x", true)), Encoding::Utf16Le);
+    assert_eq!(detect(&[0xFE, 0xFF, 0x00, b'x']), Encoding::Utf16Be);
+    assert_eq!(detect(&[b'x', 0xE9]), Encoding::Latin1);
+}
+
+#[test]
+fn test_utf16_spans_refer_to_original_bytes() {
+    let bytes = utf16le("int x;", true);
+    let tokens = Lexer::lex_bytes_with_encoding(&bytes, Encoding::Auto).unwrap();
+    let kinds: Vec<Token> = tokens.iter().map(|t| t.token.clone()).collect();
+    assert_eq!(kinds, vec![Token::TINTEGER, Token::IDENTIFIER(vec!['x']), Token::SEMICOLON, Token::EOF]);
+    assert_eq!(tokens[0].span, Span::new(2, 8));
+    assert_eq!(tokens[1].span, Span::new(10, 12));
+    assert_eq!(tokens[3].span, Span::new(14, 14));
+}
+
+#[test]
+fn test_latin1_comment() {
+    // A Latin-1 e-acute inside a comment, which is not valid UTF-8.
+    let bytes = b"/* caf\xE9 */ y";
+    let tokens = Lexer::lex_bytes_with_encoding(bytes, Encoding::Auto).unwrap();
+    assert_eq!(tokens[0].token, Token::IDENTIFIER(vec!['y']));
+    assert_eq!(tokens[0].span, Span::new(11, 12));
+}
+
+#[test]
+fn test_utf8_bom_is_skipped() {
+    let tokens = Lexer::lex_bytes_with_encoding(b"\xEF\xBB\xBFx", Encoding::Auto).unwrap();
+    assert_eq!(tokens[0].span, Span::new(3, 4));
+}
+
+#[test]
+fn test_invalid_bytes_in_explicit_encoding() {
+    let expected = ErrorType::InvalidEncoding { encoding: "UTF-8".to_string(), offset: 1 };
+    assert_eq!(Lexer::lex_bytes_with_encoding(b"x\xFF", Encoding::Utf8), Err(vec![expected]));
+    assert!(transcode(&[0x00, 0xD8, 0x00], Encoding::Utf16Le).is_err());
+    assert!(transcode(&[0x00, 0xD8, 0x78, 0x00], Encoding::Utf16Le).is_err());
+}
+
+#[test]
+fn test_lossy_replaces_invalid_utf8_in_comments() {
+    let bytes = b"x /* \xFF\xFE bad */ y";
+    let (result, diagnostics) = Lexer::lex_bytes_lossy(bytes);
+    let tokens = result.unwrap();
+    assert_eq!(tokens[1].token, Token::IDENTIFIER(vec!['y']));
+    assert_eq!(tokens[1].span, Span::new(15, 16));
+    let spans: Vec<Span> = diagnostics.iter().map(|d| d.span).collect();
+    assert_eq!(spans, vec![Span::new(5, 6), Span::new(6, 7)]);
+    assert_eq!(diagnostics[0].code, "invalid-utf8");
+}
+
+#[test]
+fn test_lossy_reports_replacement_in_code() {
+    let (result, diagnostics) = Lexer::lex_bytes_lossy(b"a \xC3 b");
+    let expected = ErrorType::UnrecognizedToken { token: char::REPLACEMENT_CHARACTER.to_string() };
+    assert_eq!(result, Err(vec![expected]));
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].span, Span::new(2, 3));
+}
+
+#[test]
+fn test_lossy_valid_input_has_no_diagnostics() {
+    let (result, diagnostics) = Lexer::lex_bytes_lossy("int \u{e9};".as_bytes());
+    assert!(result.is_err());
+    assert!(diagnostics.is_empty());
+    let (result, diagnostics) = Lexer::lex_bytes_lossy(b"int x;");
+    assert_eq!(result, Lexer::lex_spanned("int x;"));
+    assert!(diagnostics.is_empty());
+}
+
+fn utf16le(text: &str, bom: bool) -> Vec<u8> {
+    let mut bytes = if bom { vec![0xFF, 0xFE] } else { Vec::new() };
+    bytes.extend(text.encode_utf16().flat_map(|unit| unit.to_le_bytes()));
+    bytes
+}
@@ -0,0 +1,84 @@
+mod support;
+
+use lexer::{
+    config::{LexerBuilder, LexerConfig},
+    core::Lexer,
+    span::Span,
+    token::Token,
+};
+use support::ident;
+
+/// cargo test --test eof_tests
+/// Tests the options for how the end of the input is handled.
+
+#[test]
+fn test_eof_emitted_by_default() {
+    assert!(LexerConfig::default().emits_eof());
+    assert_eq!(Lexer::lex("x"), Ok(vec![ident("x"), Token::EOF]));
+}
+
+#[test]
+fn test_omit_eof() {
+    let config = LexerBuilder::new().emit_eof(false).build();
+    assert!(!config.emits_eof());
+    assert_eq!(Lexer::lex_with_config("x;", &config), Ok(vec![ident("x"), Token::SEMICOLON]));
+    assert_eq!(Lexer::lex_with_config("", &config), Ok(vec![]));
+
+    let (tokens, _) = Lexer::lex_recovering("x @", &config);
+    assert_eq!(tokens.last().map(|spanned| &spanned.token), Some(&ident("x")));
+
+    // A caller driving the lexer still sees where the input ends
+    let mut lexer = Lexer::new("x", config);
+    lexer.next_token().unwrap();
+    assert_eq!(lexer.next_token().unwrap().token, Token::EOF);
+}
+
+#[test]
+fn test_final_newline_lint_alone() {
+    let config = LexerBuilder::new().final_newline_lint(true).build();
+    assert!(config.reports_missing_final_newline());
+    let (_, _, diagnostics) = Lexer::lex_with_diagnostics("x; \ny;", &config);
+    let codes: Vec<&str> = diagnostics.iter().map(|diagnostic| diagnostic.code).collect();
+    assert_eq!(codes, vec!["missing-final-newline"]);
+    assert_eq!(diagnostics[0].span, Span::new(6, 6));
+
+    let (_, _, diagnostics) = Lexer::lex_with_diagnostics("x;\n", &config);
+    assert!(diagnostics.is_empty());
+}
+
+#[test]
+fn test_final_newline_lint_follows_whitespace_lints() {
+    assert!(!LexerConfig::default().reports_missing_final_newline());
+    assert!(LexerBuilder::new().whitespace_lints(true).build().reports_missing_final_newline());
+
+    let config = LexerBuilder::new().whitespace_lints(true).final_newline_lint(false).build();
+    let (_, _, diagnostics) = Lexer::lex_with_diagnostics("x; \ny;", &config);
+    let codes: Vec<&str> = diagnostics.iter().map(|diagnostic| diagnostic.code).collect();
+    assert_eq!(codes, vec!["trailing-whitespace"]);
+}
+
+#[test]
+fn test_nul_is_unrecognized_by_default() {
+    assert!(Lexer::lex("x;\0y;").is_err());
+}
+
+#[test]
+fn test_nul_ends_input() {
+    let config = LexerBuilder::new().nul_ends_input(true).build();
+    let (tokens, errors, diagnostics) = Lexer::lex_with_diagnostics("x;\0garbage @", &config);
+    assert!(errors.is_empty());
+    let kinds: Vec<Token> = tokens.iter().map(|spanned| spanned.token.clone()).collect();
+    assert_eq!(kinds, vec![ident("x"), Token::SEMICOLON, Token::EOF]);
+    assert_eq!(tokens[2].span, Span::new(2, 2));
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].code, "trailing-garbage");
+    assert_eq!(diagnostics[0].span, Span::new(2, 12));
+}
+
+#[test]
+fn test_nul_padding_is_not_garbage() {
+    let config = LexerBuilder::new().nul_ends_input(true).build();
+    let (tokens, _, diagnostics) = Lexer::lex_with_diagnostics("x;\n\0\0\0", &config);
+    assert_eq!(tokens.len(), 3);
+    assert!(diagnostics.is_empty());
+}
@@ -0,0 +1,62 @@
+use common::error::ErrorType;
+use lexer::{
+    config::LexerConfig,
+    core::Lexer,
+    error::{sort_by_span, LexError},
+    span::Span,
+};
+
+/// cargo test --test error_order_tests
+/// Tests that errors are reported in a deterministic, source-ordered way.
+
+#[test]
+fn test_error_spans() {
+    let errors = Lexer::lex_located("int $ = 1; #", &LexerConfig::default()).unwrap_err();
+    let spans: Vec<Span> = errors.iter().map(|error| error.span()).collect();
+    assert_eq!(spans, vec![Span::new(4, 5), Span::new(11, 12)]);
+    assert_eq!(errors[0].error(), &ErrorType::UnrecognizedToken { token: "$".to_string() });
+}
+
+#[test]
+fn test_error_spans_after_multibyte_characters() {
+    let errors = Lexer::lex_located("/* é */ $", &LexerConfig::default()).unwrap_err();
+    assert_eq!(errors[0].span(), Span::new(9, 10));
+}
+
+#[test]
+fn test_errors_match_unspanned_lex() {
+    let input = "$ a # b ` c";
+    let located = Lexer::lex_located(input, &LexerConfig::default()).unwrap_err();
+    let plain = Lexer::lex(input).unwrap_err();
+    assert_eq!(located.into_iter().map(ErrorType::from).collect::<Vec<ErrorType>>(), plain);
+}
+
+#[test]
+fn test_repeated_lexes_are_identical() {
+    let input = "$ a # b ` c \\ d";
+    let first = Lexer::lex_located(input, &LexerConfig::default()).unwrap_err();
+    for _ in 0..10 {
+        assert_eq!(Lexer::lex_located(input, &LexerConfig::default()).unwrap_err(), first);
+    }
+}
+
+#[test]
+fn test_sort_is_stable() {
+    let mut errors = vec![
+        error("c", 5, 6),
+        error("a", 1, 2),
+        error("b", 1, 2),
+        error("d", 1, 1),
+    ];
+    sort_by_span(&mut errors);
+    let order: Vec<String> = errors.iter().map(|error| match error.error() {
+        ErrorType::UnrecognizedToken { token } => token.clone(),
+        _ => unreachable!(),
+    }).collect();
+    assert_eq!(order, vec!["d", "a", "b", "c"]);
+}
+
+/// Builds an error with the given span
+fn error(token: &str, start: usize, end: usize) -> LexError {
+    LexError::new(ErrorType::UnrecognizedToken { token: token.to_string() }, Span::new(start, end))
+}
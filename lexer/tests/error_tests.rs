@@ -1,5 +1,5 @@
 use common::error::ErrorType;
-use lexer::core::Lexer;
+use lexer::{core::Lexer, token::Token};
 
 /// cargo test --test error_tests
 /// Tests combinations of tokens that should flag an error. 
@@ -24,10 +24,10 @@ fn test_hashtag_in_statement() {
 
 #[test]
 fn test_hashtag_allone() {
+    // A `#` alone on its line is an empty preprocessor line, not an error
     let input = "#";
     let result = Lexer::lex(input);
-    let expected_error = ErrorType::UnrecognizedToken{token: "#".to_string()};
-    let expected = Err(vec![expected_error]);
+    let expected = Ok(vec![Token::HASHLINE(vec!['#']), Token::EOF]);
     assert_eq!(result, expected);
 }
 
@@ -0,0 +1,57 @@
+use lexer::{
+    core::Lexer,
+    export::{write_csv, write_tsv},
+    lexgen::{GeneratedLexer, LexerSpec},
+};
+
+/// cargo test --test export_tests
+/// Tests exporting tokens as CSV and TSV.
+
+#[test]
+fn test_csv() {
+    let source = "int x;\n  x = 10;";
+    let tokens = Lexer::lex_spanned(source).unwrap();
+    let mut csv: Vec<u8> = Vec::new();
+    write_csv(&mut csv, source, &tokens).unwrap();
+    assert_eq!(String::from_utf8(csv).unwrap(), concat!(
+        "kind,lexeme,line,column,length\n",
+        "TINTEGER,int,1,1,3\n",
+        "IDENTIFIER,x,1,5,1\n",
+        "SEMICOLON,;,1,6,1\n",
+        "IDENTIFIER,x,2,3,1\n",
+        "EQUAL,=,2,5,1\n",
+        "NUMBER,10,2,7,2\n",
+        "SEMICOLON,;,2,9,1\n",
+        "EOF,,2,10,0\n",
+    ));
+}
+
+#[test]
+fn test_csv_quoting() {
+    let source = "\"a,b\" \"say \"\"hi\"\"\"";
+    let tokens = string_lexer("\"").lex(source).unwrap();
+    let mut csv: Vec<u8> = Vec::new();
+    write_csv(&mut csv, source, &tokens).unwrap();
+    let csv = String::from_utf8(csv).unwrap();
+    assert!(csv.contains("STRINGLITERAL,\"\"\"a,b\"\"\",1,1,5\n"));
+}
+
+#[test]
+fn test_tsv_escaping() {
+    let source = "'a\tb' 'c\\d'";
+    let tokens = string_lexer("'").lex(source).unwrap();
+    let mut tsv: Vec<u8> = Vec::new();
+    write_tsv(&mut tsv, source, &tokens).unwrap();
+    assert_eq!(String::from_utf8(tsv).unwrap(), concat!(
+        "kind\tlexeme\tline\tcolumn\tlength\n",
+        "STRINGLITERAL\t'a\\tb'\t1\t1\t5\n",
+        "STRINGLITERAL\t'c\\\\d'\t1\t7\t5\n",
+        "EOF\t\t1\t12\t0\n",
+    ));
+}
+
+/// Generates a lexer for strings delimited by `quote`
+fn string_lexer(quote: &str) -> GeneratedLexer {
+    let spec = format!("{{ \"literals\": {{ \"strings\": [{:?}] }} }}", quote);
+    GeneratedLexer::new(LexerSpec::from_json(&spec).unwrap()).unwrap()
+}
@@ -0,0 +1,65 @@
+use lexer::{
+    config::LexerConfig,
+    core::Lexer,
+    filter::TokenFilters,
+    raw::{lex_raw, with_spans},
+    span::{Span, SpannedToken},
+    token::{Token, TokenKind},
+    trivia::lex_lossless,
+};
+
+/// cargo test --test filter_tests
+/// Tests the adapters that filter token streams by kind.
+
+#[test]
+fn test_without_directives_keeps_spans() {
+    let tokens = Lexer::lex_spanned("#include <stdio.h>\nint x;\n#endif").unwrap();
+    let kept: Vec<&SpannedToken> = tokens.iter().without_directives().collect();
+    let expected: Vec<&SpannedToken> = tokens[1..4].iter().chain(tokens.last()).collect();
+    assert_eq!(kept, expected);
+    assert_eq!(kept[0].span, Span::new(19, 22));
+}
+
+#[test]
+fn test_only_kind() {
+    let tokens = Lexer::lex("a = b + 1;").unwrap();
+    let identifiers: Vec<Token> = tokens.into_iter().only_kind(TokenKind::IDENTIFIER).collect();
+    assert_eq!(identifiers, vec![Token::IDENTIFIER(vec!['a']), Token::IDENTIFIER(vec!['b'])]);
+}
+
+#[test]
+fn test_without_trivia_on_raw_tokens() {
+    let source = "int  x; // done\n";
+    let raw = lex_raw(source).unwrap();
+    let spans: Vec<(TokenKind, Span)> =
+        with_spans(&raw).without_trivia().map(|(token, span)| (token.kind, span)).collect();
+    assert_eq!(spans, vec![
+        (TokenKind::TINTEGER, Span::new(0, 3)),
+        (TokenKind::IDENTIFIER, Span::new(5, 6)),
+        (TokenKind::SEMICOLON, Span::new(6, 7)),
+        (TokenKind::EOF, Span::new(16, 16)),
+    ]);
+}
+
+#[test]
+fn test_without_trivia_keeps_spanned_tokens() {
+    let tokens = Lexer::lex_spanned("x /* note */ y").unwrap();
+    assert_eq!(tokens.iter().without_trivia().count(), tokens.len());
+}
+
+#[test]
+fn test_filters_chain_lazily_from_both_ends() {
+    let tokens = Lexer::lex_spanned("#define A 1\nf(a, b);\n#undef A\ng(c);").unwrap();
+    let mut names = tokens.iter().without_trivia().without_directives().only_kind(TokenKind::IDENTIFIER);
+    assert_eq!(names.next().map(|spanned| spanned.span), Some(Span::new(12, 13)));
+    assert_eq!(names.next_back().map(|spanned| spanned.span), Some(Span::new(32, 33)));
+    let rest: Vec<Span> = names.map(|spanned| spanned.span).collect();
+    assert_eq!(rest, vec![Span::new(14, 15), Span::new(17, 18), Span::new(30, 31)]);
+}
+
+#[test]
+fn test_filters_on_lossless_tokens() {
+    let tokens = lex_lossless("// lead\nint x;", &LexerConfig::default()).unwrap();
+    let types: Vec<Span> = tokens.iter().only_kind(TokenKind::TINTEGER).map(|lossless| lossless.token.span).collect();
+    assert_eq!(types, vec![Span::new(8, 11)]);
+}
@@ -0,0 +1,141 @@
+mod support;
+
+use lexer::{
+    core::Lexer,
+    fingerprint::{content_hash, hash_tokens, Fingerprint, DEFAULT_K},
+    span::Span,
+    token::Token,
+};
+use support::ident;
+
+/// cargo test --test fingerprint_tests
+/// Tests winnowing fingerprints of token streams and comparing them, and hashing token streams.
+
+#[test]
+fn test_renaming_and_reformatting_keep_the_fingerprint() {
+    let copied = "int add_up(int *xs,int n){int acc=1;/* loop */for(int j=5;j<n;j++){acc+=xs[j];}return acc;}";
+    assert_eq!(fingerprint(ORIGINAL).similarity(&fingerprint(copied)), 1.0);
+}
+
+#[test]
+fn test_unrelated_code_is_dissimilar() {
+    let other = "struct point { float x; float y; };\nvoid move(struct point *p) { p->x = p->y * 2.0; }";
+    assert!(fingerprint(ORIGINAL).similarity(&fingerprint(other)) < 0.2);
+}
+
+#[test]
+fn test_containment_finds_code_copied_into_a_larger_file() {
+    let larger = format!("{}\nvoid other(void) {{ if (a) {{ b(); }} else {{ c(); }} while (d) {{ e--; }} }}", ORIGINAL);
+    let original = fingerprint(ORIGINAL);
+    let larger = fingerprint(&larger);
+    assert_eq!(original.containment(&larger), 1.0);
+    assert!(larger.containment(&original) < 1.0);
+    assert!(original.similarity(&larger) < 1.0);
+}
+
+#[test]
+fn test_matches_locate_shared_code() {
+    let prefixed = format!("char c;\n{}", ORIGINAL);
+    let matches = fingerprint(ORIGINAL).matches(&fingerprint(&prefixed));
+    assert!(!matches.is_empty());
+    for (ours, theirs) in matches {
+        assert_eq!(&ORIGINAL[ours.start..ours.end], &prefixed[theirs.start..theirs.end]);
+    }
+}
+
+#[test]
+fn test_short_inputs() {
+    let short = fingerprint("x = 1;");
+    assert!(short.hashes.is_empty());
+    assert_eq!(short.similarity(&short), 0.0);
+    assert_eq!(short.containment(&fingerprint(ORIGINAL)), 0.0);
+
+    let one_gram = fingerprint("a = b + c * d;");
+    assert_eq!(one_gram.hashes.len(), 1);
+    assert_eq!(one_gram.hashes[0].span, Span::new(0, 14));
+}
+
+#[test]
+fn test_winnowing_selects_fewer_hashes_than_kgrams() {
+    let tokens = Lexer::lex_spanned(ORIGINAL).unwrap();
+    let kgrams = tokens.len() - 1 - DEFAULT_K + 1;
+    let every = Fingerprint::with_params(&tokens, DEFAULT_K, 1);
+    assert_eq!(every.hashes.len(), kgrams);
+    assert!(Fingerprint::new(&tokens).hashes.len() < kgrams);
+}
+
+#[test]
+#[should_panic]
+fn test_zero_window_panics() {
+    Fingerprint::with_params(&Lexer::lex_spanned("x").unwrap(), 3, 0);
+}
+
+#[test]
+fn test_token_hash_ignores_formatting_and_comments() {
+    let original = "int add(int a, int b) {\n    return a + b; /* sum */\n}\n";
+    let reformatted = "// adds\nint add(int a,int b){return a+b;}";
+    assert_eq!(hash_tokens(&lex(original)), hash_tokens(&lex(reformatted)));
+}
+
+#[test]
+fn test_token_hash_sees_changed_tokens() {
+    let original = hash_tokens(&lex("x = 1;"));
+    assert_ne!(original, hash_tokens(&lex("x = 2;")));
+    assert_ne!(original, hash_tokens(&lex("y = 1;")));
+    assert_ne!(original, hash_tokens(&lex("x == 1;")));
+    assert_ne!(hash_tokens(&lex("s = \"a\";")), hash_tokens(&lex("s = \"b\";")));
+    assert_ne!(hash_tokens(&lex("c = 'a';")), hash_tokens(&lex("c = 'b';")));
+}
+
+#[test]
+fn test_token_hash_separates_payloads() {
+    let split = [ident("ab"), ident("c")];
+    let joined = [ident("a"), ident("bc")];
+    assert_ne!(hash_tokens(&split), hash_tokens(&joined));
+}
+
+#[test]
+fn test_token_hash_ignores_directive_formatting_and_comments() {
+    let original = hash_tokens(&lex("#include <a.h> // old\n#define X 1\n"));
+    assert_eq!(original, hash_tokens(&lex("#include <a.h> // new\n#define X  1\n")));
+    assert_eq!(original, hash_tokens(&lex("#  include <a.h>\n#define X /* one */ 1 \\\n\n")));
+    assert_ne!(original, hash_tokens(&lex("#include <b.h>\n#define X 1\n")));
+    assert_ne!(original, hash_tokens(&lex("#include <a.h>\n#define X 2\n")));
+    assert_ne!(hash_tokens(&lex("#define S \"a  b\"")), hash_tokens(&lex("#define S \"a b\"")));
+    assert_ne!(hash_tokens(&lex("#define S \"//\"")), hash_tokens(&lex("#define S \"\"")));
+}
+
+#[test]
+fn test_token_hash_is_stable() {
+    // The hash is stored between builds, so it must not change between runs or versions
+    assert_eq!(hash_tokens(&[]), 0xcbf29ce484222325);
+    assert_eq!(hash_tokens(&[Token::EOF]), hash_tokens(&[]));
+    assert_eq!(hash_tokens(&lex("int x;")), hash_tokens(&lex("int x;")));
+}
+
+#[test]
+fn test_content_hash_is_stable() {
+    // Recordings store the hash, so it must not change between runs or versions
+    assert_eq!(content_hash(""), 0xcbf29ce484222325);
+    assert_eq!(content_hash("int x;"), content_hash("int x;"));
+    assert_ne!(content_hash("int x;"), content_hash("int y;"));
+}
+
+/// Lexes and fingerprints source code with the default parameters
+fn fingerprint(source: &str) -> Fingerprint {
+    Fingerprint::new(&Lexer::lex_spanned(source).unwrap())
+}
+
+/// A function to copy from
+const ORIGINAL: &str = "int sum(int *values, int count) {
+    int total = 0;
+    for (int i = 0; i < count; i++) {
+        total += values[i];
+    }
+    return total;
+}";
+
+/// Lexes source code that is known to be valid
+fn lex(source: &str) -> Vec<Token> {
+    Lexer::lex(source).unwrap()
+}
@@ -0,0 +1,67 @@
+use lexer::{
+    core::Lexer,
+    highlight::{semantic_tokens, HighlightKind, Modifiers, SemanticToken},
+};
+
+/// cargo test --test highlight_tests
+/// Tests classifying tokens for highlighting.
+
+#[test]
+fn test_declarations() {
+    let highlighted = highlight("int count; const char *const name; struct Point p;");
+    assert_eq!(at(&highlighted, "count").modifiers, Modifiers::DECLARATION);
+    assert_eq!(at(&highlighted, "name").modifiers, Modifiers::DECLARATION);
+    assert_eq!(at(&highlighted, "Point").modifiers, Modifiers::DECLARATION);
+    assert_eq!(at(&highlighted, "p").modifiers, Modifiers::NONE);
+}
+
+#[test]
+fn test_function_calls_and_definitions() {
+    let highlighted = highlight("void run(int n) { print(n); }");
+    assert_eq!(at(&highlighted, "run").kind, HighlightKind::Function);
+    assert!(at(&highlighted, "run").modifiers.contains(Modifiers::DECLARATION));
+    assert_eq!(at(&highlighted, "print").kind, HighlightKind::Function);
+    assert_eq!(at(&highlighted, "print").modifiers, Modifiers::NONE);
+    assert_eq!(at(&highlighted, "n").kind, HighlightKind::Variable);
+}
+
+#[test]
+fn test_constants() {
+    let highlighted = highlight("x = MAX_SIZE + Max + X2;");
+    assert_eq!(at(&highlighted, "MAX_SIZE").modifiers, Modifiers::CONSTANT);
+    assert_eq!(at(&highlighted, "Max").modifiers, Modifiers::NONE);
+    assert_eq!(at(&highlighted, "X2").modifiers.bits(), 2);
+}
+
+#[test]
+fn test_other_kinds() {
+    let kinds: Vec<HighlightKind> = highlight("#include <a.h>\nif (x) return \"s\" + 1;")
+        .iter()
+        .map(|(_, token)| token.kind)
+        .collect();
+    assert_eq!(kinds, vec![
+        HighlightKind::Preprocessor,
+        HighlightKind::Keyword,
+        HighlightKind::Punctuation,
+        HighlightKind::Variable,
+        HighlightKind::Punctuation,
+        HighlightKind::Keyword,
+        HighlightKind::String,
+        HighlightKind::Operator,
+        HighlightKind::Number,
+        HighlightKind::Punctuation,
+    ]);
+}
+
+/// Lexes and classifies source code, keeping the source for lookups
+fn highlight(source: &str) -> Vec<(String, SemanticToken)> {
+    semantic_tokens(&Lexer::lex_spanned(source).unwrap())
+        .into_iter()
+        .map(|token| (source[token.span.start..token.span.end].to_string(), token))
+        .collect()
+}
+
+/// Finds the first classified token with the given text
+fn at(highlighted: &[(String, SemanticToken)], text: &str) -> SemanticToken {
+    highlighted.iter().find(|(lexeme, _)| lexeme == text).unwrap().1
+}
@@ -0,0 +1,68 @@
+use common::error::ErrorType;
+use lexer::{
+    config::LexerBuilder,
+    core::Lexer,
+    token::Token,
+};
+
+/// cargo test --test identifier_chars_tests
+/// Tests configuring which characters may start and continue identifiers.
+
+#[test]
+fn test_dollar_identifiers() {
+    let config = LexerBuilder::new().identifier_start("$").unwrap().build();
+    assert_eq!(Lexer::lex_with_config("$el = a$b;", &config).unwrap(), vec![
+        Token::IDENTIFIER("$el".chars().collect()),
+        Token::EQUAL,
+        Token::IDENTIFIER("a$b".chars().collect()),
+        Token::SEMICOLON,
+        Token::EOF,
+    ]);
+    assert!(Lexer::lex("$el").is_err());
+}
+
+#[test]
+fn test_primes_continue_but_do_not_start() {
+    let config = LexerBuilder::new().identifier_continue("'").unwrap().build();
+    assert_eq!(Lexer::lex_with_config("x' = x'' + 'a';", &config).unwrap(), vec![
+        Token::IDENTIFIER("x'".chars().collect()),
+        Token::EQUAL,
+        Token::IDENTIFIER("x''".chars().collect()),
+        Token::PLUS,
+        Token::CHAR('a'),
+        Token::SEMICOLON,
+        Token::EOF,
+    ]);
+}
+
+#[test]
+fn test_at_sign_identifiers_end_at_input_end() {
+    let config = LexerBuilder::new().identifier_start("@").unwrap().build();
+    assert_eq!(Lexer::lex_with_config("@a@", &config).unwrap(), vec![
+        Token::IDENTIFIER("@a@".chars().collect()),
+        Token::EOF,
+    ]);
+}
+
+#[test]
+fn test_keywords_still_match() {
+    let config = LexerBuilder::new().identifier_continue("$").unwrap().build();
+    assert_eq!(Lexer::lex_with_config("int int$", &config).unwrap(), vec![
+        Token::TINTEGER,
+        Token::IDENTIFIER("int$".chars().collect()),
+        Token::EOF,
+    ]);
+}
+
+#[test]
+fn test_conflicting_characters_are_rejected() {
+    for (start, chars) in [(true, "-"), (true, "7"), (true, "\""), (true, "#"), (false, " "), (false, "/"), (false, ".")] {
+        let builder = LexerBuilder::new();
+        let result = if start { builder.identifier_start(chars) } else { builder.identifier_continue(chars) };
+        assert!(
+            matches!(result, Err(ErrorType::InvalidIdentifierCharacter { character, .. }) if character == chars.chars().next().unwrap()),
+            "{:?} was accepted",
+            chars,
+        );
+    }
+}
@@ -0,0 +1,82 @@
+use std::{sync::Arc, thread};
+
+use lexer::{
+    core::Lexer,
+    interner::{GlobalInterner, InternerSnapshot},
+    token::Token,
+};
+
+/// cargo test --test interner_tests
+/// Tests interning identifiers and paths across files and threads.
+
+#[test]
+fn test_identical_strings_share_a_symbol() {
+    let interner = GlobalInterner::new();
+    let count = interner.intern("count");
+    assert_eq!(interner.intern("count"), count);
+    assert_ne!(interner.intern("src/main.c"), count);
+    assert_eq!(interner.resolve(count), "count");
+    assert_eq!(interner.get("missing"), None);
+    assert_eq!(interner.len(), 2);
+}
+
+#[test]
+fn test_identifiers_across_files() {
+    let interner = GlobalInterner::new();
+    let mut symbols = Vec::new();
+    for source in ["int count = 0;", "count = count + 1;"] {
+        for token in Lexer::lex(source).unwrap() {
+            if let Token::IDENTIFIER(chars) = token {
+                symbols.push(interner.intern_chars(&chars));
+            }
+        }
+    }
+    assert_eq!(symbols.len(), 3);
+    assert!(symbols.iter().all(|symbol| *symbol == symbols[0]));
+    assert_eq!(interner.len(), 1);
+}
+
+#[test]
+fn test_interning_from_many_threads() {
+    let interner = Arc::new(GlobalInterner::new());
+    let handles: Vec<_> = (0..8)
+        .map(|_| {
+            let interner = Arc::clone(&interner);
+            thread::spawn(move || (0..100).map(|i| interner.intern(&format!("name{}", i))).collect::<Vec<_>>())
+        })
+        .collect();
+    let results: Vec<_> = handles.into_iter().map(|handle| handle.join().unwrap()).collect();
+
+    assert!(results.iter().all(|symbols| *symbols == results[0]));
+    assert_eq!(interner.len(), 100);
+    assert_eq!(interner.resolve(results[0][42]), "name42");
+}
+
+#[test]
+fn test_snapshot_and_restore() {
+    let interner = GlobalInterner::new();
+    let symbols: Vec<_> = ["a", "b", "c"].iter().map(|text| interner.intern(text)).collect();
+    let snapshot = interner.snapshot();
+
+    let restored = GlobalInterner::new();
+    restored.intern("a");
+    assert!(restored.restore(&snapshot));
+    for symbol in symbols {
+        assert_eq!(restored.resolve(symbol), interner.resolve(symbol));
+    }
+}
+
+#[test]
+fn test_restore_rejects_conflicts() {
+    let interner = GlobalInterner::new();
+    interner.intern("x");
+    assert!(!interner.restore(&InternerSnapshot { strings: vec!["y".to_string()] }));
+    assert!(!interner.restore(&InternerSnapshot { strings: vec!["x".to_string(), "z".to_string(), "z".to_string()] }));
+    assert_eq!(interner.len(), 1);
+}
+
+#[test]
+fn test_global_is_shared() {
+    let symbol = GlobalInterner::global().intern("shared_across_calls");
+    assert_eq!(GlobalInterner::global().get("shared_across_calls"), Some(symbol));
+}
@@ -0,0 +1,105 @@
+use common::error::ErrorType;
+use lexer::{
+    lexgen::{GeneratedLexer, LexerSpec},
+    span::Span,
+    token::Token,
+};
+
+/// cargo test --test lexgen_tests
+/// Tests lexers generated from TOML and JSON specifications.
+
+#[test]
+fn test_toml_spec() {
+    let lexer = GeneratedLexer::new(LexerSpec::from_toml(SPEC).unwrap()).unwrap();
+    let tokens = lexer.lex("let x := 1 + y # done\nprint 'hi' (* note *)").unwrap();
+    let kinds: Vec<Token> = tokens.into_iter().map(|spanned| spanned.token).collect();
+    assert_eq!(kinds, vec![
+        custom("LET", "let"),
+        Token::IDENTIFIER(chars("x")),
+        custom("ASSIGN", ":="),
+        Token::NUMBER(chars("1")),
+        custom("PLUS", "+"),
+        Token::IDENTIFIER(chars("y")),
+        custom("PRINT", "print"),
+        Token::STRINGLITERAL(chars("hi")),
+        Token::EOF,
+    ]);
+}
+
+#[test]
+fn test_json_spec_matches_toml() {
+    let json = r##"{
+        "keywords": { "let": "LET", "print": "PRINT" },
+        "operators": { "+": "PLUS", ":": "COLON", ":=": "ASSIGN" },
+        "comments": { "line": ["#"], "block": [["(*", "*)"]] },
+        "literals": { "strings": ["'"] }
+    }"##;
+    assert_eq!(LexerSpec::from_json(json).unwrap(), LexerSpec::from_toml(SPEC).unwrap());
+}
+
+#[test]
+fn test_spans() {
+    let lexer = GeneratedLexer::new(LexerSpec::from_toml(SPEC).unwrap()).unwrap();
+    let tokens = lexer.lex("x : y").unwrap();
+    let spans: Vec<Span> = tokens.iter().map(|spanned| spanned.span).collect();
+    assert_eq!(spans, vec![Span::new(0, 1), Span::new(2, 3), Span::new(4, 5), Span::new(5, 5)]);
+}
+
+#[test]
+fn test_disabled_literals() {
+    let spec = LexerSpec::from_toml("[operators]\n\"1\" = \"ONE\"\n[literals]\nnumbers = false\n").unwrap();
+    let tokens = GeneratedLexer::new(spec).unwrap().lex("11").unwrap();
+    assert_eq!(tokens[0].token, custom("ONE", "1"));
+    assert_eq!(tokens[1].token, custom("ONE", "1"));
+}
+
+#[test]
+fn test_lex_errors() {
+    let lexer = GeneratedLexer::new(LexerSpec::from_toml(SPEC).unwrap()).unwrap();
+    let errors = lexer.lex("x @ 'open").unwrap_err();
+    assert_eq!(errors, vec![
+        ErrorType::UnrecognizedToken { token: "@".to_string() },
+        ErrorType::SyntaxError { message: "unterminated string literal".to_string() },
+    ]);
+}
+
+#[test]
+fn test_invalid_specs() {
+    assert!(matches!(LexerSpec::from_toml("[unknown]\n"), Err(ErrorType::InvalidLexerSpec { .. })));
+    assert!(matches!(LexerSpec::from_json("{"), Err(ErrorType::InvalidLexerSpec { .. })));
+
+    let bad_keyword = LexerSpec::from_toml("[keywords]\n\"not-ident\" = \"BAD\"\n").unwrap();
+    assert!(matches!(GeneratedLexer::new(bad_keyword), Err(ErrorType::InvalidLexerSpec { .. })));
+
+    let empty_operator = LexerSpec::from_toml("[operators]\n\"\" = \"EMPTY\"\n").unwrap();
+    assert!(matches!(GeneratedLexer::new(empty_operator), Err(ErrorType::InvalidLexerSpec { .. })));
+}
+
+/// Builds a custom token
+fn custom(kind: &str, lexeme: &str) -> Token {
+    Token::CUSTOM(kind.to_string(), chars(lexeme))
+}
+
+/// Converts a string to the character vector tokens store
+fn chars(text: &str) -> Vec<char> {
+    text.chars().collect()
+}
+
+/// The specification most tests use
+const SPEC: &str = r##"
+[keywords]
+let = "LET"
+print = "PRINT"
+
+[operators]
+"+" = "PLUS"
+":" = "COLON"
+":=" = "ASSIGN"
+
+[comments]
+line = ["#"]
+block = [["(*", "*)"]]
+
+[literals]
+strings = ["'"]
+"##;
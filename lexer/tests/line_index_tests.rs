@@ -0,0 +1,50 @@
+use lexer::{
+    line_index::LineIndex,
+    span::{Location, Span},
+};
+
+/// cargo test --test line_index_tests
+/// Tests converting between byte offsets and lines and columns.
+
+#[test]
+fn test_line_col() {
+    let index = LineIndex::new("ab\ncd\n\nef");
+    assert_eq!(index.line_count(), 4);
+    assert_eq!(index.line_col(0), (1, 1));
+    assert_eq!(index.line_col(2), (1, 3));
+    assert_eq!(index.line_col(3), (2, 1));
+    assert_eq!(index.line_col(6), (3, 1));
+    assert_eq!(index.line_col(8), (4, 2));
+    assert_eq!(index.line_col(100), (4, 3));
+}
+
+#[test]
+fn test_offset_round_trip() {
+    let source = "int x;\n\tx = 1;\n\n// end\n";
+    let index = LineIndex::new(source);
+    for offset in 0..=source.len() {
+        let (line, column) = index.line_col(offset);
+        assert_eq!(index.offset(line, column), Some(offset));
+    }
+    assert_eq!(index.offset(1, 8), None);
+    assert_eq!(index.offset(0, 1), None);
+    assert_eq!(index.offset(2, 0), None);
+    assert_eq!(index.offset(9, 1), None);
+}
+
+#[test]
+fn test_line_spans() {
+    let index = LineIndex::new("ab\r\ncd");
+    assert_eq!(index.line_span(1), Some(Span::new(0, 3)));
+    assert_eq!(index.line_span(2), Some(Span::new(4, 6)));
+    assert_eq!(index.line_span(3), None);
+}
+
+#[test]
+fn test_matches_location_of() {
+    let source = "a\n\t\u{4E2D}e\u{0301} = 1;\nlast";
+    let index = LineIndex::new(source);
+    for offset in 0..=source.len() + 2 {
+        assert_eq!(index.location(source, offset, 4), Location::of(source, offset, 4), "offset {}", offset);
+    }
+}
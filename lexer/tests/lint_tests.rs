@@ -0,0 +1,85 @@
+use lexer::{
+    config::LexerBuilder,
+    core::Lexer,
+    diagnostic::Severity,
+    lint::{find_normalization_duplicates, lint_identifiers, LintConfig, NamingStyle, NormalizationForm},
+    span::Span,
+};
+
+/// cargo test --test lint_tests
+/// Tests the optional identifier lint pass.
+
+#[test]
+fn test_spans_of_tokens() {
+    let input = "int  x = 42;";
+    let tokens = Lexer::lex_spanned(input).unwrap();
+    let spans: Vec<Span> = tokens.iter().map(|t| t.span).collect();
+    let expected = vec![
+        Span::new(0, 3), Span::new(5, 6), Span::new(7, 8), Span::new(9, 11), Span::new(11, 12), Span::new(12, 12),
+    ];
+    assert_eq!(spans, expected);
+}
+
+#[test]
+fn test_clean_identifiers() {
+    let tokens = Lexer::lex_spanned("int count = total + 1;").unwrap();
+    let diagnostics = lint_identifiers(&tokens, &LintConfig::default());
+    assert!(diagnostics.is_empty());
+}
+
+#[test]
+fn test_identifier_too_long() {
+    let input = "int a_really_long_name = 0;";
+    let tokens = Lexer::lex_spanned(input).unwrap();
+    let config = LintConfig { max_identifier_length: Some(10), ..LintConfig::default() };
+    let diagnostics = lint_identifiers(&tokens, &config);
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].code, "identifier-too-long");
+    assert_eq!(diagnostics[0].severity, Severity::Warning);
+    assert_eq!(diagnostics[0].span, Span::new(4, 22));
+}
+
+#[test]
+fn test_reserved_identifiers() {
+    let tokens = Lexer::lex_spanned("__x _Y _z").unwrap();
+    let diagnostics = lint_identifiers(&tokens, &LintConfig::default());
+    let spans: Vec<Span> = diagnostics.iter().map(|d| d.span).collect();
+    assert_eq!(spans, vec![Span::new(0, 3), Span::new(4, 6)]);
+}
+
+#[test]
+fn test_naming_style() {
+    let tokens = Lexer::lex_spanned("my_var myVar MAX_SIZE").unwrap();
+
+    let snake = LintConfig { naming_style: Some(NamingStyle::SnakeCase), ..LintConfig::default() };
+    let diagnostics = lint_identifiers(&tokens, &snake);
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].span, Span::new(7, 12));
+
+    let camel = LintConfig { naming_style: Some(NamingStyle::CamelCase), ..LintConfig::default() };
+    let diagnostics = lint_identifiers(&tokens, &camel);
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].span, Span::new(0, 6));
+}
+
+#[test]
+fn test_normalization_duplicates() {
+    let config = LexerBuilder::new().identifier_start("éﬁ").unwrap().identifier_continue("\u{301}").unwrap().build();
+    let source = "caf\u{e9} = cafe\u{301} + caf\u{e9} + cafe\u{301} + \u{fb01}le + file;";
+    let tokens = Lexer::lex_spanned_with_config(source, &config).unwrap();
+
+    let nfc = find_normalization_duplicates(&tokens, NormalizationForm::Nfc);
+    assert_eq!(nfc.len(), 1);
+    assert_eq!(nfc[0].code, "normalization-duplicate");
+    assert_eq!(nfc[0].span, Span::new(8, 14));
+    assert_eq!(nfc[0].message, "identifier `cafe\u{301}` is the same name as `caf\u{e9}` after NFC normalization");
+
+    let nfkc = find_normalization_duplicates(&tokens, NormalizationForm::Nfkc);
+    let names: Vec<&str> = nfkc.iter().map(|d| &source[d.span.start..d.span.end]).collect();
+    assert_eq!(names, vec!["cafe\u{301}", "file"]);
+
+    let diagnostics = lint_identifiers(&tokens, &LintConfig::default());
+    assert_eq!(diagnostics.len(), 2);
+    let off = LintConfig { normalization: None, ..LintConfig::default() };
+    assert!(lint_identifiers(&tokens, &off).is_empty());
+}
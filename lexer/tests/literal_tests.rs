@@ -0,0 +1,95 @@
+use common::error::ErrorType;
+use lexer::{
+    core::Lexer,
+    diagnostic::Severity,
+    literal::{check_literal_suffixes, parse_char, parse_int},
+    span::Span,
+    token::Token,
+};
+
+/// cargo test --test literal_tests
+/// Tests the values computed for integer and character literals.
+
+#[test]
+fn test_integer_radixes() {
+    assert_eq!(parse_int("0"), Ok(0));
+    assert_eq!(parse_int("42"), Ok(42));
+    assert_eq!(parse_int("010"), Ok(8));
+    assert_eq!(parse_int("0x1F"), Ok(31));
+    assert_eq!(parse_int("0XfF"), Ok(255));
+}
+
+#[test]
+fn test_lexed_number_value() {
+    let result = Lexer::lex("010").unwrap();
+    let value = match &result[0] {
+        Token::NUMBER(digits) => parse_int(&digits.iter().collect::<String>()),
+        other => panic!("expected a number, found {}", other),
+    };
+    assert_eq!(value, Ok(8));
+}
+
+#[test]
+fn test_invalid_integers() {
+    let expected = Err(ErrorType::InvalidLiteral {
+        literal: "09".to_string(),
+        reason: "`9` is not a base 8 digit".to_string(),
+    });
+    assert_eq!(parse_int("09"), expected);
+    assert!(parse_int("0x").is_err());
+    assert!(parse_int("99999999999999999999").is_err());
+}
+
+#[test]
+fn test_character_constants() {
+    assert_eq!(parse_char("'a'"), Ok(97));
+    assert_eq!(parse_char("'\\n'"), Ok(10));
+    assert_eq!(parse_char("'\\101'"), Ok(65));
+    assert_eq!(parse_char("'\\0'"), Ok(0));
+    assert_eq!(parse_char("'\\x41'"), Ok(65));
+    assert_eq!(parse_char("'\\''"), Ok(39));
+}
+
+#[test]
+fn test_invalid_character_constants() {
+    assert!(parse_char("''").is_err());
+    assert!(parse_char("'ab'").is_err());
+    assert!(parse_char("'\\q'").is_err());
+    assert!(parse_char("'\\1011'").is_err());
+    assert!(parse_char("a").is_err());
+}
+
+#[test]
+fn test_number_followed_by_word() {
+    let source = "x = 123abc + 4 y;";
+    let diagnostics = check_literal_suffixes(source, &Lexer::lex_spanned(source).unwrap());
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].code, "invalid-literal-suffix");
+    assert_eq!(diagnostics[0].severity, Severity::Error);
+    assert_eq!(diagnostics[0].span, Span::new(4, 10));
+    assert_eq!(
+        diagnostics[0].message,
+        "`abc` is not a valid suffix for the number `123`; add a space if they are separate tokens",
+    );
+}
+
+#[test]
+fn test_valid_c_literals_are_unsupported_not_invalid() {
+    for source in ["10UL", "10ull", "0x1F", "0xffu", "0b101", "1e10", "1.5f", "2.0L", "3E5f"] {
+        let diagnostics = check_literal_suffixes(source, &Lexer::lex_spanned(source).unwrap());
+        assert_eq!(diagnostics.len(), 1, "{}", source);
+        assert_eq!(diagnostics[0].code, "unsupported-literal", "{}", source);
+        assert_eq!(diagnostics[0].severity, Severity::Warning);
+    }
+    let diagnostics = check_literal_suffixes("0x1F", &Lexer::lex_spanned("0x1F").unwrap());
+    assert_eq!(diagnostics[0].message, "`0x1F` is valid C, but is lexed as `0` followed by `x1F`");
+}
+
+#[test]
+fn test_invalid_suffixes() {
+    for source in ["10lL", "10uu", "0x", "0xg", "1.5u", "7int", "10_"] {
+        let diagnostics = check_literal_suffixes(source, &Lexer::lex_spanned(source).unwrap());
+        assert_eq!(diagnostics.len(), 1, "{}", source);
+        assert_eq!(diagnostics[0].code, "invalid-literal-suffix", "{}", source);
+    }
+}
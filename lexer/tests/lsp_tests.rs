@@ -0,0 +1,130 @@
+#![cfg(feature = "lsp")]
+
+use std::io::{BufReader, Cursor};
+
+use lexer::{
+    config::LexerConfig,
+    lsp::{read_message, write_message, Server},
+};
+use serde_json::{json, Value};
+
+/// cargo test --test lsp_tests --features lsp
+/// Tests the language server.
+
+#[test]
+fn test_message_framing() {
+    let mut bytes: Vec<u8> = Vec::new();
+    write_message(&mut bytes, &json!({"id": 1})).unwrap();
+    write_message(&mut bytes, &json!({"id": 2})).unwrap();
+    assert!(bytes.starts_with(b"Content-Length: 8\r\n\r\n{\"id\":1}"));
+
+    let mut reader = BufReader::new(Cursor::new(bytes));
+    assert_eq!(read_message(&mut reader).unwrap(), Some(json!({"id": 1})));
+    assert_eq!(read_message(&mut reader).unwrap(), Some(json!({"id": 2})));
+    assert_eq!(read_message(&mut reader).unwrap(), None);
+
+    let mut reader = BufReader::new(Cursor::new(b"Content-Type: x\r\n\r\n{}".to_vec()));
+    assert!(read_message(&mut reader).is_err());
+}
+
+#[test]
+fn test_initialize() {
+    let mut server = Server::new(LexerConfig::default());
+    let replies = server.handle(&request(1, "initialize", json!({})));
+    let capabilities = &replies[0]["result"]["capabilities"];
+    assert_eq!(replies[0]["id"], 1);
+    assert_eq!(capabilities["textDocumentSync"], 1);
+    assert_eq!(capabilities["documentSymbolProvider"], true);
+    assert_eq!(capabilities["semanticTokensProvider"]["legend"]["tokenTypes"][3], "function");
+}
+
+#[test]
+fn test_publish_diagnostics() {
+    let mut server = Server::new(LexerConfig::default());
+    let replies = server.handle(&open("int x = $;\n"));
+    assert_eq!(replies.len(), 1);
+    assert_eq!(replies[0]["method"], "textDocument/publishDiagnostics");
+    assert_eq!(replies[0]["params"]["diagnostics"][0]["code"], "unrecognized-token");
+    assert_eq!(replies[0]["params"]["diagnostics"][0]["severity"], 1);
+    assert_eq!(replies[0]["params"]["diagnostics"][0]["range"], json!({
+        "start": {"line": 0, "character": 8},
+        "end": {"line": 0, "character": 9},
+    }));
+
+    let change = json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/didChange",
+        "params": {"textDocument": {"uri": URI, "version": 2}, "contentChanges": [{"text": "int x = 1;\n"}]},
+    });
+    let replies = server.handle(&change);
+    assert_eq!(replies[0]["params"]["diagnostics"], json!([]));
+}
+
+#[test]
+fn test_utf16_positions() {
+    let mut server = Server::new(LexerConfig::default());
+    let replies = server.handle(&open("\"\u{1F600}\" $"));
+    assert_eq!(replies[0]["params"]["diagnostics"][0]["range"]["start"], json!({"line": 0, "character": 5}));
+}
+
+#[test]
+fn test_semantic_tokens() {
+    let mut server = Server::new(LexerConfig::default());
+    server.handle(&open("int main() {\n  return 0;\n}"));
+    let replies = server.handle(&request(2, "textDocument/semanticTokens/full", document()));
+    assert_eq!(replies[0]["result"]["data"], json!([
+        0, 0, 3, 1, 0,
+        0, 4, 4, 3, 1,
+        1, 2, 6, 0, 0,
+        0, 7, 1, 4, 0,
+    ]));
+}
+
+#[test]
+fn test_document_symbols_and_folding() {
+    let mut server = Server::new(LexerConfig::default());
+    server.handle(&open("struct s { int a; };\nint f() {\n  return 1;\n}\n"));
+
+    let replies = server.handle(&request(3, "textDocument/documentSymbol", document()));
+    let symbols = replies[0]["result"].as_array().unwrap();
+    assert_eq!(symbols.len(), 2);
+    assert_eq!((symbols[0]["name"].clone(), symbols[0]["kind"].clone()), (json!("s"), json!(23)));
+    assert_eq!(symbols[1]["selectionRange"]["start"], json!({"line": 1, "character": 4}));
+
+    let replies = server.handle(&request(4, "textDocument/foldingRange", document()));
+    assert_eq!(replies[0]["result"], json!([{"startLine": 1, "endLine": 3}]));
+}
+
+#[test]
+fn test_unknown_request_and_exit() {
+    let mut server = Server::new(LexerConfig::default());
+    let replies = server.handle(&request(5, "textDocument/hover", document()));
+    assert_eq!(replies[0]["error"]["code"], -32601);
+    assert!(server.handle(&json!({"jsonrpc": "2.0", "method": "$/cancelRequest"})).is_empty());
+
+    assert_eq!(server.handle(&request(6, "shutdown", Value::Null))[0]["result"], Value::Null);
+    server.handle(&json!({"jsonrpc": "2.0", "method": "exit"}));
+    assert_eq!(server.exit_code(), Some(0));
+}
+
+/// The URI of the document every test opens
+const URI: &str = "file:///test.c";
+
+/// Builds a request
+fn request(id: u64, method: &str, params: Value) -> Value {
+    json!({"jsonrpc": "2.0", "id": id, "method": method, "params": params})
+}
+
+/// Builds a `didOpen` notification for the test document
+fn open(text: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/didOpen",
+        "params": {"textDocument": {"uri": URI, "languageId": "c", "version": 1, "text": text}},
+    })
+}
+
+/// Builds the parameters naming the test document
+fn document() -> Value {
+    json!({"textDocument": {"uri": URI}})
+}
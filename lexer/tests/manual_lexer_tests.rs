@@ -0,0 +1,82 @@
+use lexer::{
+    config::LexerConfig,
+    core::Lexer,
+    span::Span,
+    token::Token,
+};
+
+/// cargo test --test manual_lexer_tests
+/// Tests driving the lexer one token at a time.
+
+#[test]
+fn test_next_token_matches_lex() {
+    let source = "int x = y >= 10; // done";
+    let mut lexer = Lexer::new(source, LexerConfig::default());
+    let mut tokens: Vec<Token> = Vec::new();
+    loop {
+        let token = lexer.next_token().unwrap().token;
+        tokens.push(token.clone());
+        if token == Token::EOF {
+            break;
+        }
+    }
+
+    assert_eq!(tokens, Lexer::lex(source).unwrap());
+    assert!(lexer.is_at_end());
+    assert_eq!(lexer.next_token().unwrap().token, Token::EOF);
+}
+
+#[test]
+fn test_empty_input() {
+    let mut lexer = Lexer::new("", LexerConfig::default());
+    assert!(lexer.is_at_end());
+    assert_eq!(lexer.next_token().unwrap().span, Span::new(0, 0));
+    assert_eq!(Lexer::lex("").unwrap(), vec![Token::EOF]);
+}
+
+#[test]
+fn test_offset_tracks_consumed_input() {
+    let mut lexer = Lexer::new("ab  + c", LexerConfig::default());
+    assert_eq!(lexer.offset(), 0);
+    assert_eq!(lexer.next_token().unwrap().span, Span::new(0, 2));
+    assert_eq!(lexer.offset(), 2);
+    assert_eq!(lexer.next_token().unwrap().span, Span::new(4, 5));
+    assert_eq!(lexer.offset(), 5);
+}
+
+#[test]
+fn test_seek_lexes_embedded_snippet() {
+    let buffer = "<% x + 1 %> ignored";
+    let mut lexer = Lexer::new(buffer, LexerConfig::default());
+    lexer.seek(3);
+
+    let mut tokens: Vec<(Token, Span)> = Vec::new();
+    while lexer.offset() < 8 {
+        let spanned = lexer.next_token().unwrap();
+        tokens.push((spanned.token, spanned.span));
+    }
+    assert_eq!(tokens, vec![
+        (Token::IDENTIFIER(vec!['x']), Span::new(3, 4)),
+        (Token::PLUS, Span::new(5, 6)),
+        (Token::NUMBER(vec!['1']), Span::new(7, 8)),
+    ]);
+}
+
+#[test]
+fn test_seek_into_multibyte_character() {
+    let mut lexer = Lexer::new("é x", LexerConfig::default());
+    lexer.seek(1);
+    assert_eq!(lexer.offset(), 0);
+    lexer.seek(100);
+    assert!(lexer.is_at_end());
+    assert_eq!(lexer.next_token().unwrap().span, Span::new(4, 4));
+}
+
+#[test]
+fn test_errors_advance_one_character() {
+    let mut lexer = Lexer::new("$$x", LexerConfig::default());
+    assert_eq!(lexer.next_token().unwrap_err().span(), Span::new(0, 1));
+    assert_eq!(lexer.next_token().unwrap_err().span(), Span::new(1, 2));
+    assert_eq!(lexer.next_token().unwrap().token, Token::IDENTIFIER(vec!['x']));
+    assert_eq!(Lexer::lex("$$x").unwrap_err().len(), 2);
+}
@@ -0,0 +1,68 @@
+use common::error::ErrorType;
+use lexer::{
+    check::{error_annotations, error_diagnostic},
+    config::{LexerBuilder, LexerConfig},
+    core::Lexer,
+    diagnostic::LabelStyle,
+    span::Span,
+    token::Token,
+};
+
+/// cargo test --test multiline_string_tests
+/// Tests strings that run over newlines, and the diagnostics for strings a newline ends.
+
+#[test]
+fn test_newline_ends_string_by_default() {
+    let errors = Lexer::lex_located("x = \"open\ny;", &LexerConfig::default()).unwrap_err();
+    assert_eq!(errors[0].error(), &ErrorType::SyntaxError { message: "unterminated string literal".to_string() });
+    assert_eq!(errors[0].span(), Span::new(4, 9));
+}
+
+#[test]
+fn test_multiline_strings() {
+    let config = LexerBuilder::new().multiline_strings(true).build();
+    let tokens = Lexer::lex_spanned_with_config("x = \"one\ntwo\";", &config).unwrap();
+    assert_eq!(tokens[2].token, Token::STRINGLITERAL("one\ntwo".chars().collect()));
+    assert_eq!(tokens[2].span, Span::new(4, 13));
+    assert!(Lexer::lex_with_config("c = 'a\n';", &config).is_err());
+}
+
+#[test]
+fn test_multiline_string_to_end_of_input() {
+    let config = LexerBuilder::new().multiline_strings(true).build();
+    let source = "x = \"one\ntwo";
+    let errors = Lexer::lex_located(source, &config).unwrap_err();
+    assert_eq!(errors[0].span(), Span::new(4, 12));
+    let labels = error_annotations(&errors[0], source);
+    assert_eq!(labels.labels()[1].span, Span::new(12, 12));
+    assert_eq!(labels.labels()[1].message, "the input ends before a closing quote");
+}
+
+#[test]
+fn test_labels_point_at_quote_and_newline() {
+    let source = "x = \"open\ny;";
+    let errors = Lexer::lex_located(source, &LexerConfig::default()).unwrap_err();
+    let annotations = error_annotations(&errors[0], source);
+    let labels = annotations.labels();
+    assert_eq!(labels.len(), 2);
+    assert_eq!((labels[0].span, labels[0].style), (Span::new(4, 5), LabelStyle::Primary));
+    assert_eq!((labels[1].span, labels[1].style), (Span::new(9, 10), LabelStyle::Secondary));
+    let rendered = error_diagnostic(&errors[0]).render_annotated(source, 4, &annotations);
+    assert_eq!(rendered, concat!(
+        "error[syntax-error]: unterminated string literal\n",
+        " --> 1:5\n",
+        "  |\n",
+        "1 | x = \"open\n",
+        "  |     ^ this string is never closed\n",
+        "  |          - the line ends before a closing quote\n",
+    ));
+}
+
+#[test]
+fn test_other_errors_have_one_label() {
+    let source = "x @";
+    let errors = Lexer::lex_located(source, &LexerConfig::default()).unwrap_err();
+    let annotations = error_annotations(&errors[0], source);
+    assert_eq!(annotations.labels().len(), 1);
+    assert_eq!(annotations.labels()[0].span, Span::new(2, 3));
+}
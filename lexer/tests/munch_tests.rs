@@ -0,0 +1,62 @@
+use lexer::{
+    config::LexerBuilder,
+    core::Lexer,
+    span::{split_token_at, Span},
+    token::Token,
+};
+
+/// cargo test --test munch_tests
+/// Tests how adjacent operators are grouped, and splitting them apart again.
+
+#[test]
+fn test_maximal_munch() {
+    assert_eq!(lex("a+++b"), vec![ident('a'), Token::PLUSPLUS, Token::PLUS, ident('b'), Token::EOF]);
+    assert_eq!(lex("a---b"), vec![ident('a'), Token::MINUSMINUS, Token::DASH, ident('b'), Token::EOF]);
+    assert_eq!(lex("a&&&b"), vec![ident('a'), Token::ANDAND, Token::AMPERSAND, ident('b'), Token::EOF]);
+    assert_eq!(lex("a-->b"), vec![ident('a'), Token::MINUSMINUS, Token::GREATERTHAN, ident('b'), Token::EOF]);
+    assert_eq!(lex("a>>=b"), vec![ident('a'), Token::GREATERTHAN, Token::GREATERTHANEQUAL, ident('b'), Token::EOF]);
+    assert_eq!(lex("a===b"), vec![ident('a'), Token::EQUALEQUAL, Token::EQUAL, ident('b'), Token::EOF]);
+}
+
+#[test]
+fn test_split_first() {
+    assert_eq!(Token::GREATERTHANEQUAL.split_first(), Some((Token::GREATERTHAN, Token::EQUAL)));
+    assert_eq!(Token::POINTER.split_first(), Some((Token::DASH, Token::GREATERTHAN)));
+    assert_eq!(Token::PLUS.split_first(), None);
+    assert_eq!(Token::GREATERTHANEQUAL.split_greater(), Some((Token::GREATERTHAN, Token::EQUAL)));
+    assert_eq!(Token::LESSTHANEQUAL.split_greater(), None);
+}
+
+#[test]
+fn test_split_token_at() {
+    let mut tokens = Lexer::lex_spanned("List<int>=x").unwrap();
+    assert_eq!(tokens[3].token, Token::GREATERTHANEQUAL);
+
+    assert!(split_token_at(&mut tokens, 3));
+    assert_eq!(tokens[3].token, Token::GREATERTHAN);
+    assert_eq!(tokens[3].span, Span::new(8, 9));
+    assert_eq!(tokens[4].token, Token::EQUAL);
+    assert_eq!(tokens[4].span, Span::new(9, 10));
+
+    assert!(!split_token_at(&mut tokens, 0));
+    assert!(!split_token_at(&mut tokens, 100));
+}
+
+#[test]
+fn test_split_angle_brackets_flag() {
+    let config = LexerBuilder::new().split_angle_brackets(true).build();
+    let tokens = Lexer::lex_with_config("List<int>=x; a <= b", &config).unwrap();
+    assert_eq!(tokens[3], Token::GREATERTHAN);
+    assert_eq!(tokens[4], Token::EQUAL);
+    assert_eq!(tokens[8], Token::LESSTHANEQUAL);
+}
+
+/// Lexes input that must lex successfully
+fn lex(input: &str) -> Vec<Token> {
+    Lexer::lex(input).unwrap()
+}
+
+/// Builds a single-character identifier
+fn ident(c: char) -> Token {
+    Token::IDENTIFIER(vec![c])
+}
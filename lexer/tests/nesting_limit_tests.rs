@@ -0,0 +1,63 @@
+mod support;
+
+use common::error::ErrorType;
+use lexer::{
+    config::{LexerBuilder, LexerConfig, DEFAULT_MAX_DEPTH},
+    core::Lexer,
+    span::Span,
+    token::Token,
+};
+use support::ident;
+
+/// cargo test --test nesting_limit_tests
+/// Tests the limits on how deeply comments and brackets may nest.
+
+#[test]
+fn test_default_limits() {
+    let config = LexerConfig::default();
+    assert_eq!(config.max_comment_depth(), DEFAULT_MAX_DEPTH);
+    assert_eq!(config.max_bracket_depth(), DEFAULT_MAX_DEPTH);
+}
+
+#[test]
+fn test_brackets_within_limit() {
+    let config = LexerBuilder::new().max_bracket_depth(2).build();
+    let tokens = Lexer::lex_with_config("f((x)); g[{1}];", &config).unwrap();
+    assert_eq!(tokens.len(), 15);
+}
+
+#[test]
+fn test_bracket_beyond_limit() {
+    let config = LexerBuilder::new().max_bracket_depth(2).build();
+    let errors = Lexer::lex_located("((( x )))", &config).unwrap_err();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].error(), &ErrorType::NestingTooDeep { construct: "bracket".to_string(), limit: 2 });
+    assert_eq!(errors[0].span(), Span::new(2, 3));
+}
+
+#[test]
+fn test_deep_brackets_recover() {
+    let source = format!("{}x{}", "(".repeat(10_000), ")".repeat(10_000));
+    let (tokens, errors) = Lexer::lex_recovering(&source, &LexerConfig::default());
+    assert_eq!(errors.len(), 10_000 - DEFAULT_MAX_DEPTH);
+    assert_eq!(tokens.iter().filter(|spanned| spanned.token == Token::LPAREN).count(), DEFAULT_MAX_DEPTH);
+}
+
+#[test]
+fn test_comment_beyond_limit() {
+    let config = LexerBuilder::new().nested_comments(true).max_comment_depth(2).build();
+    assert_eq!(Lexer::lex_with_config("/* a /* b */ c */ x", &config), Ok(vec![ident("x"), Token::EOF]));
+
+    let errors = Lexer::lex_located("/* a /* b /* c /* d */ */ */ */ x", &config).unwrap_err();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].error(), &ErrorType::NestingTooDeep { construct: "block comment".to_string(), limit: 2 });
+    assert_eq!(errors[0].span(), Span::new(10, 12));
+}
+
+#[test]
+fn test_comment_beyond_limit_is_still_skipped() {
+    let config = LexerBuilder::new().nested_comments(true).max_comment_depth(1).build();
+    let (tokens, errors) = Lexer::lex_recovering("/* /* /* */ */ */ x", &config);
+    assert_eq!(errors.len(), 1);
+    assert_eq!(tokens.into_iter().map(|spanned| spanned.token).collect::<Vec<Token>>(), vec![ident("x"), Token::EOF]);
+}
@@ -0,0 +1,93 @@
+use common::error::ErrorType;
+use lexer::{
+    check::error_diagnostic,
+    config::{LeadingZeros, LexerBuilder, LexerConfig},
+    core::Lexer,
+    span::Span,
+    token::Token,
+};
+
+/// cargo test --test number_policy_tests
+/// Tests the configurable checks on numeric literals.
+
+#[test]
+fn test_allowed_by_default() {
+    assert_eq!(LexerConfig::default().leading_zeros(), LeadingZeros::Allow);
+    let (tokens, errors, diagnostics) = Lexer::lex_with_diagnostics("0089 123456789012", &LexerConfig::default());
+    assert_eq!(tokens[0].token, number("0089"));
+    assert!(errors.is_empty());
+    assert!(diagnostics.is_empty());
+}
+
+#[test]
+fn test_octal_rejects_invalid_digits() {
+    let config = LexerBuilder::new().leading_zeros(LeadingZeros::Octal).build();
+    assert_eq!(Lexer::lex_with_config("0127 0 9", &config).unwrap()[0], number("0127"));
+    let errors = Lexer::lex_located("x = 0129;", &config).unwrap_err();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].span(), Span::new(4, 8));
+    assert_eq!(
+        errors[0].error(),
+        &ErrorType::InvalidLiteral { literal: "0129".to_string(), reason: "`9` is not an octal digit".to_string() }
+    );
+    assert_eq!(error_diagnostic(&errors[0]).code, "invalid-literal");
+}
+
+#[test]
+fn test_decimal_with_warning() {
+    let config = LexerBuilder::new().leading_zeros(LeadingZeros::DecimalWithWarning).build();
+    let (tokens, errors, diagnostics) = Lexer::lex_with_diagnostics("0 10 0123", &config);
+    assert!(errors.is_empty());
+    assert_eq!(tokens[2].token, number("0123"));
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].code, "leading-zero");
+    assert_eq!(diagnostics[0].span, Span::new(5, 9));
+    assert_eq!(diagnostics[0].message, "`0123` has a leading zero, which C reads as octal");
+}
+
+#[test]
+fn test_no_warning_for_numbers_claimed_by_regex_rules() {
+    let config = LexerBuilder::new()
+        .leading_zeros(LeadingZeros::DecimalWithWarning)
+        .token_regex("UNIT", r"0[0-9]+px")
+        .unwrap()
+        .build();
+    let (tokens, errors, diagnostics) = Lexer::lex_with_diagnostics("007px 007", &config);
+    assert!(errors.is_empty());
+    assert_eq!(tokens[0].token, Token::CUSTOM("UNIT".to_string(), "007px".chars().collect()));
+    assert_eq!(tokens[1].token, number("007"));
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].span, Span::new(6, 9));
+}
+
+#[test]
+fn test_max_digits() {
+    let config = LexerBuilder::new().max_number_digits(4).build();
+    assert!(Lexer::lex_with_config("1234", &config).is_ok());
+    let errors = Lexer::lex_located("1 12345", &config).unwrap_err();
+    assert_eq!(errors[0].span(), Span::new(2, 7));
+    assert_eq!(
+        errors[0].error(),
+        &ErrorType::InvalidLiteral {
+            literal: "12345".to_string(),
+            reason: "has 5 digits, more than the limit of 4".to_string(),
+        }
+    );
+}
+
+#[test]
+fn test_repeated_zeros() {
+    let config = LexerBuilder::new().reject_repeated_zeros(true).build();
+    assert!(Lexer::lex_with_config("0 01 100", &config).is_ok());
+    for source in ["00", "007"] {
+        let errors = Lexer::lex_located(source, &config).unwrap_err();
+        assert!(matches!(
+            errors[0].error(),
+            ErrorType::InvalidLiteral { reason, .. } if reason == "starts with more than one zero"
+        ));
+    }
+}
+
+fn number(digits: &str) -> Token {
+    Token::NUMBER(digits.chars().collect())
+}
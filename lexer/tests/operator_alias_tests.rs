@@ -0,0 +1,83 @@
+mod support;
+
+use lexer::{
+    config::{LexerBuilder, LexerConfig},
+    core::Lexer,
+    span::{Span, SpannedToken},
+    token::Token,
+};
+use support::ident;
+
+/// cargo test --test operator_alias_tests
+/// Tests lexing the C++ alternative operator spellings as operators.
+
+#[test]
+fn test_aliases_lex_to_operator_tokens() {
+    let tokens = Lexer::lex_with_config("a and b or not c", &aliases()).unwrap();
+    assert_eq!(
+        tokens,
+        vec![
+            ident("a"),
+            Token::ANDAND,
+            ident("b"),
+            Token::BARBAR,
+            Token::EXCLAMATIONPOINT,
+            ident("c"),
+            Token::EOF,
+        ]
+    );
+    let tokens = Lexer::lex_with_config("x bitand y bitor compl z xor w not_eq v", &aliases()).unwrap();
+    let operators: Vec<Token> = tokens.into_iter().filter(|token| !matches!(token, Token::IDENTIFIER(_))).collect();
+    assert_eq!(
+        operators,
+        vec![Token::AMPERSAND, Token::BAR, Token::TILDE, Token::CARET, Token::NOTEQUAL, Token::EOF]
+    );
+}
+
+#[test]
+fn test_aliases_are_identifiers_by_default() {
+    let tokens = Lexer::lex("a and b").unwrap();
+    assert_eq!(tokens[1], ident("and"));
+}
+
+#[test]
+fn test_aliases_only_match_whole_words() {
+    let tokens = Lexer::lex_with_config("android order nothing and_eq", &aliases()).unwrap();
+    assert!(tokens[..4].iter().all(|token| matches!(token, Token::IDENTIFIER(_))));
+}
+
+#[test]
+fn test_original_lexeme_is_kept() {
+    let source = "if (a and b) {}";
+    let tokens = Lexer::lex_spanned_with_config(source, &aliases()).unwrap();
+    assert_eq!(tokens[3].token, Token::ANDAND);
+    assert_eq!(tokens[3].lexeme(source), "and");
+}
+
+#[test]
+fn test_alias_tokens_cannot_be_split() {
+    let spanned = SpannedToken::new(Token::ANDAND, Span::new(0, 3));
+    assert_eq!(spanned.split_first(), None);
+    assert!(SpannedToken::new(Token::ANDAND, Span::new(0, 2)).split_first().is_some());
+}
+
+#[test]
+fn test_aliases_coexist_with_contextual_keywords() {
+    let config = LexerBuilder::new().contextual_keyword("defer").operator_aliases(true).build();
+    let tokens = Lexer::lex_spanned_with_config("defer not x", &config).unwrap();
+    assert!(tokens[0].contextual_keyword);
+    assert_eq!(tokens[1].token, Token::EXCLAMATIONPOINT);
+}
+
+#[test]
+fn test_trace_explains_aliases() {
+    let source = "a or b";
+    let (_, trace) = Lexer::lex_traced(source, &aliases());
+    let explanation = trace.explain(source, 2).unwrap();
+    assert!(explanation.contains("alternative spelling of the BARBAR operator"), "{}", explanation);
+}
+
+/// Builds a configuration with operator aliases
+fn aliases() -> LexerConfig {
+    LexerBuilder::new().operator_aliases(true).build()
+}
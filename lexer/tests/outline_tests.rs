@@ -0,0 +1,56 @@
+use lexer::{
+    core::Lexer,
+    outline::{outline, OutlineItem, OutlineKind},
+    span::Span,
+};
+
+/// cargo test --test outline_tests
+/// Tests extracting a file outline from tokens.
+
+#[test]
+fn test_functions_and_types() {
+    let source = concat!(
+        "#include <stdio.h>\n",
+        "struct point { int x; int y; };\n",
+        "enum color { RED };\n",
+        "static int add(int a, int b) { return a + b; }\n",
+        "int main(void) { if (add(1, 2)) { return 0; } }\n",
+    );
+    let items = outline(&Lexer::lex_spanned(source).unwrap());
+    let summary: Vec<(OutlineKind, &str)> = items.iter().map(|item| (item.kind, item.name.as_str())).collect();
+    assert_eq!(summary, vec![
+        (OutlineKind::Struct, "point"),
+        (OutlineKind::Enum, "color"),
+        (OutlineKind::Function, "add"),
+        (OutlineKind::Function, "main"),
+    ]);
+
+    let add = &items[2];
+    assert_eq!(&source[add.span.start..add.span.end], "static int add(int a, int b) { return a + b; }");
+    assert_eq!(&source[add.name_span.start..add.name_span.end], "add");
+    assert_eq!(&source[items[0].span.start..items[0].span.end], "struct point { int x; int y; }");
+}
+
+#[test]
+fn test_prototypes_and_references_are_skipped() {
+    let source = "int f(int x);\nstruct point p;\nstruct { int x; } anonymous;\nint g = f(1);";
+    assert_eq!(outline(&Lexer::lex_spanned(source).unwrap()), vec![]);
+}
+
+#[test]
+fn test_nested_type() {
+    let source = "struct outer { struct inner { int x; } in; };";
+    let names: Vec<String> = outline(&Lexer::lex_spanned(source).unwrap()).into_iter().map(|item| item.name).collect();
+    assert_eq!(names, vec!["outer", "inner"]);
+}
+
+#[test]
+fn test_unclosed_function() {
+    let source = "void run() { while (1) {";
+    assert_eq!(outline(&Lexer::lex_spanned(source).unwrap()), vec![OutlineItem {
+        kind: OutlineKind::Function,
+        name: "run".to_string(),
+        name_span: Span::new(5, 8),
+        span: Span::new(0, 24),
+    }]);
+}
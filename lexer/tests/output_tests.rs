@@ -0,0 +1,55 @@
+mod support;
+
+use common::error::ErrorType;
+use lexer::{
+    config::{LexerBuilder, LexerConfig},
+    core::Lexer,
+    interner::GlobalInterner,
+    span::Span,
+    token::Token,
+};
+use support::ident;
+
+/// cargo test --test output_tests
+/// Tests bundling the results of a lex into a `LexOutput`.
+
+#[test]
+fn test_successful_lex() {
+    let source = "int x;\nx = 1;\n";
+    let output = Lexer::lex_output(source, &LexerConfig::default());
+    assert!(output.is_ok());
+    assert_eq!(output.tokens.len(), 8);
+    assert_eq!(output.tokens[7].token, Token::EOF);
+    assert_eq!(output.stats.bytes, source.len());
+    assert_eq!(output.stats.lines, 3);
+    assert_eq!(output.stats.tokens, 8);
+    assert_eq!(output.stats.errors, 0);
+    assert_eq!(output.line_index.line_col(7), (2, 1));
+    assert!(std::ptr::eq(output.interner, GlobalInterner::global()));
+    assert_eq!(output.into_result(), Lexer::lex_located(source, &LexerConfig::default()));
+}
+
+#[test]
+fn test_tokens_kept_with_errors() {
+    let output = Lexer::lex_output("x @ y", &LexerConfig::default());
+    assert!(!output.is_ok());
+    assert_eq!(output.tokens.iter().map(|spanned| spanned.token.clone()).collect::<Vec<Token>>(), vec![
+        ident("x"),
+        ident("y"),
+        Token::EOF,
+    ]);
+    assert_eq!(output.stats.errors, 1);
+    assert_eq!(output.errors[0].error(), &ErrorType::UnrecognizedToken { token: "@".to_string() });
+    assert_eq!(output.errors[0].span(), Span::new(2, 3));
+    assert!(output.into_result().is_err());
+}
+
+#[test]
+fn test_diagnostics_and_warnings() {
+    let config = LexerBuilder::new().whitespace_lints(true).build();
+    let output = Lexer::lex_output("x; \ny", &config);
+    assert!(output.is_ok());
+    let codes: Vec<&str> = output.diagnostics.iter().map(|diagnostic| diagnostic.code).collect();
+    assert_eq!(codes, vec!["trailing-whitespace", "missing-final-newline"]);
+    assert_eq!(output.stats.warnings, 2);
+}
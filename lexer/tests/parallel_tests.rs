@@ -0,0 +1,64 @@
+mod support;
+
+use std::{fs, path::PathBuf};
+
+use common::error::ErrorType;
+use lexer::{
+    core::Lexer,
+    parallel::{lex_files_parallel, lex_files_with_threads},
+    span::{Span, SpannedToken},
+    token::Token,
+};
+use support::ScratchDir;
+
+/// cargo test --test parallel_tests
+/// Tests lexing many files concurrently.
+
+#[test]
+fn test_types_are_thread_safe() {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<Token>();
+    assert_send_sync::<Span>();
+    assert_send_sync::<SpannedToken>();
+    assert_send_sync::<ErrorType>();
+}
+
+#[test]
+fn test_results_keep_input_order() {
+    let dir = ScratchDir::new("order");
+    let paths: Vec<PathBuf> = (0..20)
+        .map(|i| {
+            let path = dir.join(format!("file{}.c", i));
+            fs::write(&path, format!("int x{} = {};", i, i)).unwrap();
+            path
+        })
+        .collect();
+
+    let results = lex_files_with_threads(&paths, 4);
+    assert_eq!(results.len(), 20);
+    for (i, file) in results.iter().enumerate() {
+        assert_eq!(file.path, paths[i]);
+        assert_eq!(file.result, Lexer::lex_spanned(&format!("int x{} = {};", i, i)));
+    }
+}
+
+#[test]
+fn test_errors_are_per_file() {
+    let dir = ScratchDir::new("errors");
+    let good = dir.join("good.c");
+    let bad = dir.join("bad.c");
+    fs::write(&good, "x;").unwrap();
+    fs::write(&bad, "$").unwrap();
+    let missing = dir.join("missing.c");
+
+    let results = lex_files_parallel(&[good, bad, missing]);
+    assert!(results[0].result.is_ok());
+    assert_eq!(results[1].result, Err(vec![ErrorType::UnrecognizedToken { token: "$".to_string() }]));
+    assert!(matches!(results[2].result.as_ref().unwrap_err().as_slice(), [ErrorType::IoError { .. }]));
+}
+
+#[test]
+fn test_no_files() {
+    let paths: Vec<PathBuf> = Vec::new();
+    assert!(lex_files_parallel(&paths).is_empty());
+}
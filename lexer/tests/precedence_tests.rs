@@ -0,0 +1,61 @@
+use lexer::{
+    precedence::{is_prefix_operator, precedence, Assoc, POSTFIX_PRECEDENCE, UNARY_PRECEDENCE},
+    token::{Token, TokenCategory, TokenKind},
+};
+
+/// cargo test --test precedence_tests
+/// Tests the operator precedence table.
+
+#[test]
+fn test_c_ordering() {
+    let order = [
+        TokenKind::EQUAL,
+        TokenKind::CTRUE,
+        TokenKind::BARBAR,
+        TokenKind::ANDAND,
+        TokenKind::BAR,
+        TokenKind::CARET,
+        TokenKind::AMPERSAND,
+        TokenKind::EQUALEQUAL,
+        TokenKind::LESSTHAN,
+        TokenKind::PLUS,
+        TokenKind::ASTERISK,
+    ];
+    for pair in order.windows(2) {
+        assert!(level(pair[0]) < level(pair[1]), "{:?} should bind looser than {:?}", pair[0], pair[1]);
+    }
+    assert_eq!(UNARY_PRECEDENCE, level(TokenKind::ASTERISK) + 1);
+    assert_eq!(POSTFIX_PRECEDENCE, UNARY_PRECEDENCE + 1);
+}
+
+#[test]
+fn test_same_level_operators() {
+    assert_eq!(precedence(TokenKind::DASH), precedence(TokenKind::PLUS));
+    assert_eq!(precedence(TokenKind::PERCENT), precedence(TokenKind::FSLASH));
+    assert_eq!(precedence(TokenKind::NOTEQUAL), precedence(TokenKind::EQUALEQUAL));
+    assert_eq!(precedence(TokenKind::GREATERTHANEQUAL), precedence(TokenKind::LESSTHAN));
+}
+
+#[test]
+fn test_associativity() {
+    assert_eq!(precedence(TokenKind::EQUAL).unwrap().1, Assoc::Right);
+    assert_eq!(precedence(TokenKind::CTRUE).unwrap().1, Assoc::Right);
+    assert_eq!(precedence(TokenKind::DASH).unwrap().1, Assoc::Left);
+}
+
+#[test]
+fn test_non_operators() {
+    for token in Token::ALL_KINDS.iter() {
+        if token.category() != TokenCategory::Operator {
+            assert_eq!(precedence(token.kind()), None, "{:?}", token);
+        }
+    }
+    assert_eq!(precedence(TokenKind::EXCLAMATIONPOINT), None);
+    assert!(is_prefix_operator(TokenKind::EXCLAMATIONPOINT));
+    assert!(!is_prefix_operator(TokenKind::PLUS));
+}
+
+/// Gives the precedence level of an operator that must have one
+fn level(kind: TokenKind) -> u8 {
+    precedence(kind).unwrap().0
+}
@@ -0,0 +1,29 @@
+use lexer::prelude::*;
+
+/// cargo test --test prelude_tests
+/// Tests that the stable API is reachable through the prelude.
+
+#[test]
+fn test_lex_through_prelude() {
+    let config: LexerConfig = LexerBuilder::new().build();
+    let tokens: Vec<SpannedToken> = Lexer::lex_located("int x;", &config).unwrap();
+    assert_eq!(tokens[0].token.kind(), TokenKind::TINTEGER);
+    assert_eq!(tokens[0].token.kind().category(), TokenCategory::Type);
+    assert_eq!(tokens[0].span, Span::new(0, 3));
+    assert_eq!(hash_tokens(&[tokens[0].token.clone()]), hash_tokens(&Lexer::lex("int").unwrap()));
+}
+
+#[test]
+fn test_errors_and_diagnostics_through_prelude() {
+    let output: LexOutput = Lexer::lex_output("x @", &LexerConfig::default());
+    let error: &LexError = &output.errors[0];
+    assert_eq!(error.error(), &ErrorType::UnrecognizedToken { token: "@".to_string() });
+    let diagnostic = Diagnostic::warning("example", "message".to_string(), error.span());
+    assert_eq!(diagnostic.severity, Severity::Warning);
+    let file = SourceFile::new("main.c", "x @");
+    assert_eq!(file.snippet(error.span()), Some("@"));
+    assert_eq!(file.line_index().location("x @", 2, 4), Location { line: 1, byte_column: 3, display_column: 3 });
+    let stats: &LexStats = &output.stats;
+    assert_eq!(stats.errors, 1);
+    assert_eq!(LineIndex::new("a\nb").line_count(), 2);
+}
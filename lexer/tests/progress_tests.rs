@@ -0,0 +1,107 @@
+mod support;
+
+use std::{
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use lexer::{
+    config::LexerConfig,
+    core::Lexer,
+    driver::{analyze_project, analyze_project_with_progress, DriverConfig, ProjectMetrics},
+    progress::Progress,
+};
+use support::ScratchDir;
+
+/// cargo test --test progress_tests
+/// Tests reporting the progress of long lexes.
+
+#[test]
+fn test_lex_reports_progress_by_interval() {
+    let input = "int x;\n".repeat(100);
+    let reports: Mutex<Vec<usize>> = Mutex::new(Vec::new());
+    let reporter = |progress: &Progress| {
+        assert_eq!(progress.bytes_total, input.len());
+        assert_eq!(progress.current_file, None);
+        reports.lock().unwrap().push(progress.bytes_processed);
+    };
+    let (tokens, errors) = Lexer::lex_with_progress(&input, &LexerConfig::default(), 100, &reporter);
+    assert_eq!(tokens.len(), 301);
+    assert!(errors.is_empty());
+
+    let reports = reports.into_inner().unwrap();
+    assert_eq!(reports.len(), 7);
+    assert_eq!(reports.last(), Some(&input.len()));
+    assert!(reports.windows(2).all(|pair| pair[0] < pair[1]));
+    assert!(reports[..6].iter().enumerate().all(|(index, bytes)| *bytes >= (index + 1) * 100));
+}
+
+#[test]
+fn test_lex_with_progress_matches_lex_recovering() {
+    let input = "x = \"open\ny = $;";
+    let config = LexerConfig::default();
+    let reports: Mutex<Vec<bool>> = Mutex::new(Vec::new());
+    let lexed = Lexer::lex_with_progress(input, &config, 1, &|progress: &Progress| {
+        reports.lock().unwrap().push(progress.is_done());
+    });
+    assert_eq!(lexed, Lexer::lex_recovering(input, &config));
+    assert_eq!(reports.into_inner().unwrap().last(), Some(&true));
+}
+
+#[test]
+fn test_empty_input_is_done() {
+    let reports: Mutex<Vec<f64>> = Mutex::new(Vec::new());
+    Lexer::lex_with_progress("", &LexerConfig::default(), 10, &|progress: &Progress| {
+        reports.lock().unwrap().push(progress.fraction());
+    });
+    assert_eq!(reports.into_inner().unwrap(), vec![1.0]);
+}
+
+#[test]
+#[should_panic]
+fn test_zero_interval_panics() {
+    Lexer::lex_with_progress("x", &LexerConfig::default(), 0, &|_: &Progress| {});
+}
+
+#[test]
+fn test_project_progress() {
+    let dir = scratch_dir("project");
+    let reports: Mutex<Vec<(usize, usize, Option<PathBuf>)>> = Mutex::new(Vec::new());
+    let reporter = |progress: &Progress| {
+        assert_eq!((progress.bytes_total, progress.files_total), (20, 3));
+        let current = progress.current_file.map(Path::to_path_buf);
+        reports.lock().unwrap().push((progress.bytes_processed, progress.files_completed, current));
+    };
+    let config = DriverConfig { threads: Some(2), ..DriverConfig::default() };
+    let analysis = analyze_project_with_progress(&dir, &config, &reporter).unwrap();
+    let unreported = analyze_project(&dir, &config).unwrap().metrics;
+    assert_eq!(analysis.metrics, ProjectMetrics { elapsed: analysis.metrics.elapsed, ..unreported });
+
+    let reports = reports.into_inner().unwrap();
+    assert_eq!(reports.len(), 6);
+    assert!(reports.windows(2).all(|pair| pair[0].0 <= pair[1].0 && pair[0].1 <= pair[1].1));
+    let mut started: Vec<PathBuf> = reports.iter().filter_map(|report| report.2.clone()).collect();
+    started.sort();
+    assert_eq!(started, vec![dir.join("a.c"), dir.join("b.c"), dir.join("c.h")]);
+    assert_eq!(reports.last().unwrap(), &(20, 3, None));
+}
+
+#[test]
+fn test_empty_project_reports_once() {
+    let dir = ScratchDir::new("empty");
+    let reports: Mutex<Vec<bool>> = Mutex::new(Vec::new());
+    analyze_project_with_progress(&dir, &DriverConfig::default(), &|progress: &Progress| {
+        reports.lock().unwrap().push(progress.is_done());
+    })
+    .unwrap();
+    assert_eq!(reports.into_inner().unwrap(), vec![true]);
+}
+
+/// Creates a project of three files in a directory private to this test
+fn scratch_dir(name: &str) -> ScratchDir {
+    let dir = ScratchDir::new(name);
+    dir.write("a.c", "int a;");
+    dir.write("b.c", "int bb;");
+    dir.write("c.h", "int ccc");
+    dir
+}
@@ -0,0 +1,118 @@
+mod support;
+
+use std::{fs, path::Path};
+
+use common::error::ErrorType;
+use lexer::{
+    core::Lexer,
+    lint::{NamingStyle, NormalizationForm},
+    project::{is_ignored, Dialect, ProjectConfig, CONFIG_FILE_NAME},
+    token::Token,
+};
+use support::ScratchDir;
+
+/// cargo test --test project_tests
+/// Tests loading project settings from `lexer.toml`.
+
+#[test]
+fn test_from_toml() {
+    let config = ProjectConfig::from_toml(concat!(
+        "dialect = \"c99\"\n",
+        "ignore = [\"build\"]\n",
+        "[keywords]\ncontextual = [\"defer\"]\n",
+        "[identifiers]\nstart = \"$\"\n",
+        "[defines]\nDEBUG = \"1\"\n",
+        "[lints]\nmax_identifier_length = 0\nnaming_style = \"snake_case\"\nnormalization = \"nfc\"\n",
+    ))
+    .unwrap();
+    assert_eq!(config.dialect, Dialect::C99);
+    assert_eq!(config.ignore, vec!["build"]);
+    assert_eq!(config.defines["DEBUG"], "1");
+
+    let lints = config.lint_config();
+    assert_eq!(lints.max_identifier_length, None);
+    assert!(lints.reserved_names);
+    assert_eq!(lints.naming_style, Some(NamingStyle::SnakeCase));
+    assert_eq!(lints.normalization, Some(NormalizationForm::Nfc));
+}
+
+#[test]
+fn test_empty_config_is_default() {
+    assert_eq!(ProjectConfig::from_toml("").unwrap(), ProjectConfig::default());
+    assert_eq!(ProjectConfig::default().lint_config(), lexer::lint::LintConfig::default());
+}
+
+#[test]
+fn test_invalid_config() {
+    assert!(matches!(ProjectConfig::from_toml("colour = 1"), Err(ErrorType::InvalidConfigFile { .. })));
+    assert!(matches!(ProjectConfig::from_toml("dialect = \"c11\""), Err(ErrorType::InvalidConfigFile { .. })));
+}
+
+#[test]
+fn test_lexer_builder_applies_settings() {
+    let text = "dialect = \"c99\"\n[keywords]\ncontextual = [\"defer\"]\n[identifiers]\nstart = \"$\"";
+    let config = ProjectConfig::from_toml(text).unwrap();
+    let tokens = Lexer::lex_spanned_with_config("$a inline defer x", &config.lexer_builder().unwrap().build()).unwrap();
+    let flags: Vec<bool> = tokens.iter().map(|spanned| spanned.contextual_keyword).collect();
+    assert_eq!(flags, vec![false, true, true, false, false]);
+}
+
+#[test]
+fn test_operator_aliases_toggle() {
+    let config = ProjectConfig::from_toml("[keywords]\noperator_aliases = true").unwrap();
+    let tokens = Lexer::lex_with_config("a and b", &config.lexer_builder().unwrap().build()).unwrap();
+    assert_eq!(tokens[1], Token::ANDAND);
+    let tokens = Lexer::lex_with_config("a and b", &ProjectConfig::default().lexer_builder().unwrap().build()).unwrap();
+    assert_eq!(tokens[1], Token::IDENTIFIER("and".chars().collect()));
+}
+
+#[test]
+fn test_whitespace_lints_toggle() {
+    let config = ProjectConfig::from_toml("[lints]\nwhitespace = true").unwrap();
+    let (_, _, diagnostics) = Lexer::lex_with_diagnostics("x; \n", &config.lexer_builder().unwrap().build());
+    assert_eq!(diagnostics[0].code, "trailing-whitespace");
+}
+
+#[test]
+fn test_nested_comments_override() {
+    assert!(!ProjectConfig::default().lexer_builder().unwrap().build().nested_comments());
+    let config = ProjectConfig::from_toml("dialect = \"c99\"\nnested_comments = true").unwrap();
+    assert!(config.lexer_builder().unwrap().build().nested_comments());
+}
+
+#[test]
+fn test_builder_overrides_settings() {
+    let config = ProjectConfig::default();
+    let lexer_config = config.lexer_builder().unwrap().max_bracket_depth(1).build();
+    assert!(Lexer::lex_with_config("((x))", &lexer_config).is_err());
+}
+
+#[test]
+fn test_invalid_identifier_chars() {
+    let config = ProjectConfig::from_toml("[identifiers]\nstart = \"+\"").unwrap();
+    assert!(matches!(config.lexer_builder(), Err(ErrorType::InvalidIdentifierCharacter { character: '+', .. })));
+}
+
+#[test]
+fn test_load_and_discover() {
+    let dir = ScratchDir::new("discover");
+    fs::create_dir_all(dir.join("src").join("deep")).unwrap();
+    dir.write(CONFIG_FILE_NAME, "include_paths = [\"include\"]");
+
+    let (path, config) = ProjectConfig::discover(&dir.join("src").join("deep")).unwrap().unwrap();
+    assert_eq!(path, dir.join(CONFIG_FILE_NAME));
+    assert_eq!(config.include_paths, vec![dir.join("include")]);
+}
+
+#[test]
+fn test_is_ignored() {
+    let patterns = vec!["build".to_string(), "*.gen.c".to_string(), "vendor/**/*.h".to_string()];
+    assert!(is_ignored(Path::new("build"), &patterns));
+    assert!(is_ignored(Path::new("src/build/a.c"), &patterns));
+    assert!(is_ignored(Path::new("src/parser.gen.c"), &patterns));
+    assert!(is_ignored(Path::new("vendor/a.h"), &patterns));
+    assert!(is_ignored(Path::new("vendor/lib/b.h"), &patterns));
+    assert!(!is_ignored(Path::new("vendor/lib/b.c"), &patterns));
+    assert!(!is_ignored(Path::new("src/builder.c"), &patterns));
+    assert!(!is_ignored(Path::new("src/vendor/a.h"), &patterns));
+}
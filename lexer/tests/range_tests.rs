@@ -0,0 +1,56 @@
+use lexer::{
+    config::LexerConfig,
+    core::Lexer,
+    span::Span,
+    token::Token,
+};
+
+/// cargo test --test range_tests
+/// Tests lexing a byte range of a file with spans relative to the whole file.
+
+#[test]
+fn test_range_spans_are_absolute() {
+    let source = "int f() { return x + 1; }";
+    let body = source.find('{').unwrap() + 1..source.rfind('}').unwrap();
+    let tokens = Lexer::lex_range(source, body.clone()).unwrap();
+
+    let expected = Lexer::lex_spanned(source).unwrap();
+    let inside: Vec<_> = expected.into_iter().filter(|t| t.span.start >= body.start && t.span.end <= body.end).collect();
+    assert_eq!(&tokens[..tokens.len() - 1], &inside[..]);
+    assert_eq!(tokens.last().unwrap().token, Token::EOF);
+    assert_eq!(tokens.last().unwrap().span, Span::new(body.end, body.end));
+}
+
+#[test]
+fn test_range_ignores_errors_outside() {
+    let source = "$ a = b; $";
+    let tokens = Lexer::lex_range(source, 2..8).unwrap();
+    assert_eq!(tokens.len(), 5);
+    assert_eq!(tokens[0].span, Span::new(2, 3));
+}
+
+#[test]
+fn test_range_reports_errors_with_absolute_spans() {
+    let errors = Lexer::lex_range("a = b; c $ d;", 7..13).unwrap_err();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].span(), Span::new(9, 10));
+}
+
+#[test]
+fn test_range_cuts_tokens_at_end() {
+    let tokens = Lexer::lex_range_with_config("counter", 0..4, &LexerConfig::default()).unwrap();
+    assert_eq!(tokens[0].token, Token::IDENTIFIER("coun".chars().collect()));
+}
+
+#[test]
+fn test_empty_range() {
+    let tokens = Lexer::lex_range("a b", 1..1).unwrap();
+    assert_eq!(tokens.len(), 1);
+    assert_eq!(tokens[0].span, Span::new(1, 1));
+}
+
+#[test]
+#[should_panic]
+fn test_range_inside_character_panics() {
+    let _ = Lexer::lex_range("é", 1..2);
+}
@@ -0,0 +1,54 @@
+use lexer::{
+    core::Lexer,
+    raw::{lex_raw, recover, with_spans, RawToken},
+    span::Span,
+    token::{Token, TokenKind},
+};
+
+/// cargo test --test raw_tests
+/// Tests lexing into compact raw tokens.
+
+#[test]
+fn test_size() {
+    assert_eq!(std::mem::size_of::<RawToken>(), 8);
+}
+
+#[test]
+fn test_raw_tokens_tile_source() {
+    let input = "int x = 10; // done\n";
+    let raw = lex_raw(input).unwrap();
+    assert_eq!(raw, vec![
+        RawToken::new(TokenKind::TINTEGER, 3),
+        RawToken::new(TokenKind::TRIVIA, 1),
+        RawToken::new(TokenKind::IDENTIFIER, 1),
+        RawToken::new(TokenKind::TRIVIA, 1),
+        RawToken::new(TokenKind::EQUAL, 1),
+        RawToken::new(TokenKind::TRIVIA, 1),
+        RawToken::new(TokenKind::NUMBER, 2),
+        RawToken::new(TokenKind::SEMICOLON, 1),
+        RawToken::new(TokenKind::TRIVIA, 9),
+        RawToken::new(TokenKind::EOF, 0),
+    ]);
+    assert_eq!(raw.iter().map(|token| token.len as usize).sum::<usize>(), input.len());
+}
+
+#[test]
+fn test_recovers_full_tokens() {
+    let input = "while (count_1 >= 42) { /* é */ count_1--; }";
+    let raw = lex_raw(input).unwrap();
+    let recovered: Vec<(Token, Span)> = with_spans(&raw)
+        .filter_map(|(token, span)| recover(input, token.kind, span).map(|token| (token, span)))
+        .collect();
+    let expected: Vec<(Token, Span)> = Lexer::lex_spanned(input)
+        .unwrap()
+        .into_iter()
+        .map(|spanned| (spanned.token, spanned.span))
+        .collect();
+    assert_eq!(recovered, expected);
+}
+
+#[test]
+fn test_errors() {
+    let errors = lex_raw("a $ b").unwrap_err();
+    assert_eq!(errors[0].span(), Span::new(2, 3));
+}
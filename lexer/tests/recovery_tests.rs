@@ -0,0 +1,80 @@
+use common::error::ErrorType;
+use lexer::{
+    config::LexerConfig,
+    core::Lexer,
+    span::Span,
+    token::Token,
+};
+
+/// cargo test --test recovery_tests
+/// Tests string and character literals, and recovering from unterminated ones.
+
+#[test]
+fn test_string_literals() {
+    assert_eq!(Lexer::lex(r#"s = "hi \"there\"";"#).unwrap(), vec![
+        Token::IDENTIFIER(vec!['s']),
+        Token::EQUAL,
+        Token::STRINGLITERAL(r#"hi \"there\""#.chars().collect()),
+        Token::SEMICOLON,
+        Token::EOF,
+    ]);
+    assert_eq!(Lexer::lex(r#""""#).unwrap(), vec![Token::STRINGLITERAL(Vec::new()), Token::EOF]);
+}
+
+#[test]
+fn test_char_literals() {
+    assert_eq!(Lexer::lex(r"'a' '\n' '\'' '\x41' '\101'").unwrap(), vec![
+        Token::CHAR('a'),
+        Token::CHAR('\n'),
+        Token::CHAR('\''),
+        Token::CHAR('A'),
+        Token::CHAR('A'),
+        Token::EOF,
+    ]);
+    assert!(matches!(Lexer::lex("'ab'").unwrap_err()[0], ErrorType::InvalidLiteral { .. }));
+    assert!(matches!(Lexer::lex("''").unwrap_err()[0], ErrorType::InvalidLiteral { .. }));
+}
+
+#[test]
+fn test_unterminated_literals_fail_without_recovery() {
+    let unterminated = ErrorType::SyntaxError { message: "unterminated string literal".to_string() };
+    assert_eq!(Lexer::lex("x = \"open\ny;").unwrap_err(), vec![unterminated]);
+    assert!(Lexer::lex("x = 'a").is_err());
+}
+
+#[test]
+fn test_recovery_closes_string_at_end_of_line() {
+    let source = "x = \"open;\ny = 1;";
+    let (tokens, errors) = Lexer::lex_recovering(source, &LexerConfig::default());
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].error(), &ErrorType::SyntaxError { message: "unterminated string literal".to_string() });
+    assert_eq!(errors[0].span(), Span::new(4, 10));
+
+    let string = &tokens[2];
+    assert_eq!(string.token, Token::STRINGLITERAL("open;".chars().collect()));
+    assert_eq!(string.span, Span::new(4, 10));
+    assert!(string.synthetic);
+    assert_eq!(tokens.iter().filter(|spanned| spanned.synthetic).count(), 1);
+
+    let rest: Vec<Token> = tokens[3..].iter().map(|spanned| spanned.token.clone()).collect();
+    assert_eq!(rest, vec![Token::IDENTIFIER(vec!['y']), Token::EQUAL, Token::NUMBER(vec!['1']), Token::SEMICOLON, Token::EOF]);
+}
+
+#[test]
+fn test_recovery_at_end_of_input() {
+    let (tokens, errors) = Lexer::lex_recovering("c = 'x", &LexerConfig::default());
+    assert_eq!(errors[0].error(), &ErrorType::SyntaxError { message: "unterminated character literal".to_string() });
+    assert_eq!(tokens[2].token, Token::CHAR('x'));
+    assert!(tokens[2].synthetic);
+    assert_eq!(tokens[3].token, Token::EOF);
+}
+
+#[test]
+fn test_recovery_skips_unrecognized_characters() {
+    let (tokens, errors) = Lexer::lex_recovering("a $ \"b", &LexerConfig::default());
+    let spans: Vec<Span> = errors.iter().map(|error| error.span()).collect();
+    assert_eq!(spans, vec![Span::new(2, 3), Span::new(4, 6)]);
+    let kinds: Vec<Token> = tokens.into_iter().map(|spanned| spanned.token).collect();
+    assert_eq!(kinds, vec![Token::IDENTIFIER(vec!['a']), Token::STRINGLITERAL(vec!['b']), Token::EOF]);
+}
@@ -0,0 +1,72 @@
+mod support;
+
+use lexer::{
+    core::Lexer,
+    redact::{redact_tokens, Redactor},
+    span::SpannedToken,
+    token::Token,
+};
+use support::ident;
+
+/// cargo test --test redact_tests
+/// Tests replacing identifier and literal payloads with stable placeholders.
+
+#[test]
+fn test_identifiers_get_stable_placeholders() {
+    let tokens = redacted("int total = count + total;");
+    assert_eq!(tokens[0].token, Token::TINTEGER);
+    assert_eq!(tokens[1].token, ident("id1"));
+    assert_eq!(tokens[3].token, ident("id2"));
+    assert_eq!(tokens[5].token, ident("id1"));
+}
+
+#[test]
+fn test_literals_get_placeholders() {
+    let tokens = redacted("f(\"secret\", 42, 'q', \"secret\", 7);");
+    let payloads: Vec<Token> = tokens.into_iter().map(|spanned| spanned.token).collect();
+    assert_eq!(payloads[2], Token::STRINGLITERAL("str1".chars().collect()));
+    assert_eq!(payloads[4], Token::NUMBER(vec!['1']));
+    assert_eq!(payloads[6], Token::CHAR('a'));
+    assert_eq!(payloads[8], Token::STRINGLITERAL("str1".chars().collect()));
+    assert_eq!(payloads[10], Token::NUMBER(vec!['2']));
+}
+
+#[test]
+fn test_preprocessor_lines_keep_their_directive() {
+    let tokens = redacted("#include \"internal/keys.h\"\n#  define TOKEN 1\n");
+    assert_eq!(tokens[0].token, Token::HASHLINE("#include line1".chars().collect()));
+    assert_eq!(tokens[1].token, Token::HASHLINE("#define line2".chars().collect()));
+}
+
+#[test]
+fn test_redaction_keeps_spans_and_structure() {
+    let source = "while (x < limit) { x++; }";
+    let tokens = Lexer::lex_spanned(source).unwrap();
+    let redacted = redact_tokens(&tokens);
+    assert_eq!(redacted.len(), tokens.len());
+    for (original, redacted) in tokens.iter().zip(redacted.iter()) {
+        assert_eq!(original.span, redacted.span);
+        assert_eq!(original.token.kind(), redacted.token.kind());
+    }
+}
+
+#[test]
+fn test_redaction_is_deterministic_per_file() {
+    let tokens = |source: &str| redacted(source).into_iter().map(|spanned| spanned.token).collect::<Vec<Token>>();
+    assert_eq!(tokens("a = b; c = a;"), tokens("a = b; c = a;"));
+    assert_eq!(tokens("a = b; c = a;"), tokens("x = y; z = x;"));
+}
+
+#[test]
+fn test_redactor_numbers_across_calls() {
+    let mut redactor = Redactor::new();
+    assert_eq!(redactor.identifier(&['a']), "id1");
+    assert_eq!(redactor.redact(&ident("b")), ident("id2"));
+    assert_eq!(redactor.identifier(&['a']), "id1");
+    assert_eq!(redactor.redact(&Token::SEMICOLON), Token::SEMICOLON);
+}
+
+/// Lexes and redacts source code
+fn redacted(source: &str) -> Vec<SpannedToken> {
+    redact_tokens(&Lexer::lex_spanned(source).unwrap())
+}
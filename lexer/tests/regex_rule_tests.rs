@@ -0,0 +1,86 @@
+use common::error::ErrorType;
+use lexer::{
+    config::LexerBuilder,
+    core::Lexer,
+    token::Token,
+};
+
+/// cargo test --test regex_rule_tests
+/// Tests token kinds defined by regular expressions.
+
+#[test]
+fn test_date_regex() {
+    let config = LexerBuilder::new().token_regex("DATE", r"\d{4}-\d{2}-\d{2}").unwrap().build();
+    let result = Lexer::lex_with_config("when = 2024-01-31 - 7;", &config);
+    let expected = vec![
+        Token::IDENTIFIER("when".chars().collect()),
+        Token::EQUAL,
+        Token::CUSTOM("DATE".to_string(), "2024-01-31".chars().collect()),
+        Token::DASH,
+        Token::NUMBER(vec!['7']),
+        Token::SEMICOLON,
+        Token::EOF,
+    ];
+    assert_eq!(result, Ok(expected));
+}
+
+#[test]
+fn test_builtin_wins_ties() {
+    let config = LexerBuilder::new().token_regex("WORD", r"[a-z]+").unwrap().build();
+    let result = Lexer::lex_with_config("while x", &config);
+    let expected = vec![Token::WHILE, Token::IDENTIFIER(vec!['x']), Token::EOF];
+    assert_eq!(result, Ok(expected));
+}
+
+#[test]
+fn test_longest_match_against_builtin() {
+    let config = LexerBuilder::new().token_regex("ARROW", r"=>|-->").unwrap().build();
+    let result = Lexer::lex_with_config("a => b --> c -> d", &config);
+    let expected = vec![
+        Token::IDENTIFIER(vec!['a']),
+        Token::CUSTOM("ARROW".to_string(), vec!['=', '>']),
+        Token::IDENTIFIER(vec!['b']),
+        Token::CUSTOM("ARROW".to_string(), vec!['-', '-', '>']),
+        Token::IDENTIFIER(vec!['c']),
+        Token::POINTER,
+        Token::IDENTIFIER(vec!['d']),
+        Token::EOF,
+    ];
+    assert_eq!(result, Ok(expected));
+}
+
+#[test]
+fn test_regex_covers_unrecognized_characters() {
+    let config = LexerBuilder::new().token_regex("VAR", r"\$[a-z]+").unwrap().build();
+    let result = Lexer::lex_with_config("$name + 1", &config).unwrap();
+    assert_eq!(result[0], Token::CUSTOM("VAR".to_string(), "$name".chars().collect()));
+}
+
+#[test]
+fn test_longest_regex_wins() {
+    let config = LexerBuilder::new()
+        .token_regex("SHORT", r"\$\w")
+        .unwrap()
+        .token_regex("LONG", r"\$\w+")
+        .unwrap()
+        .build();
+    let result = Lexer::lex_with_config("$abc $a", &config).unwrap();
+    assert_eq!(result[0], Token::CUSTOM("LONG".to_string(), "$abc".chars().collect()));
+    assert_eq!(result[1], Token::CUSTOM("SHORT".to_string(), "$a".chars().collect()));
+}
+
+#[test]
+fn test_regex_win_drops_builtin_errors() {
+    let config = LexerBuilder::new().token_regex("RAWSTR", r#""[^"\n]*\n"#).unwrap().build();
+    let (tokens, errors) = Lexer::lex_recovering("\"abc\nint x;\n", &config);
+    assert!(errors.is_empty());
+    assert_eq!(tokens[0].token, Token::CUSTOM("RAWSTR".to_string(), "\"abc\n".chars().collect()));
+    assert!(!tokens[0].synthetic);
+    assert_eq!(tokens[1].token, Token::TINTEGER);
+}
+
+#[test]
+fn test_invalid_pattern() {
+    let result = LexerBuilder::new().token_regex("BAD", "(");
+    assert!(matches!(result, Err(ErrorType::InvalidTokenPattern { .. })));
+}
@@ -0,0 +1,73 @@
+mod support;
+
+use lexer::{
+    config::LexerConfig,
+    repl::{Repl, ReplEntry, CONTINUATION_PROMPT, PROMPT},
+    token::Token,
+};
+use support::ident;
+
+/// cargo test --test repl_tests
+/// Tests lexing entries typed in an interactive session.
+
+#[test]
+fn test_single_line_entry() {
+    let mut repl = Repl::new(LexerConfig::default());
+    assert_eq!(repl.prompt(), PROMPT);
+    assert_eq!(kinds(repl.feed("int x;\n")), vec![Token::TINTEGER, ident("x"), Token::SEMICOLON, Token::EOF]);
+    assert!(!repl.is_continuing());
+}
+
+#[test]
+fn test_open_brackets_continue() {
+    let mut repl = Repl::new(LexerConfig::default());
+    assert_eq!(repl.feed("int main() {\n"), ReplEntry::Incomplete);
+    assert_eq!(repl.prompt(), CONTINUATION_PROMPT);
+    assert_eq!(repl.feed("  a[(1\n"), ReplEntry::Incomplete);
+    let ReplEntry::Complete { source, tokens, errors } = repl.feed(")];}\n") else {
+        panic!("entry should be complete");
+    };
+    assert_eq!(source, "int main() {\n  a[(1\n)];}");
+    assert_eq!(tokens.len(), 14);
+    assert!(errors.is_empty());
+    assert_eq!(repl.prompt(), PROMPT);
+}
+
+#[test]
+fn test_open_comment_continues() {
+    let mut repl = Repl::new(LexerConfig::default());
+    assert_eq!(repl.feed("x /* note\n"), ReplEntry::Incomplete);
+    assert_eq!(kinds(repl.feed("end */ y\n")), vec![ident("x"), ident("y"), Token::EOF]);
+}
+
+#[test]
+fn test_backslash_splices_lines() {
+    let mut repl = Repl::new(LexerConfig::default());
+    assert_eq!(repl.feed("\"abc\\\n"), ReplEntry::Incomplete);
+    assert_eq!(kinds(repl.feed("def\"\n")), vec![Token::STRINGLITERAL("abcdef".chars().collect()), Token::EOF]);
+}
+
+#[test]
+fn test_unterminated_string_is_complete() {
+    let mut repl = Repl::new(LexerConfig::default());
+    let ReplEntry::Complete { errors, .. } = repl.feed("\"abc\n") else {
+        panic!("entry should be complete");
+    };
+    assert_eq!(errors.len(), 1);
+}
+
+#[test]
+fn test_finish_partial_entry() {
+    let mut repl = Repl::new(LexerConfig::default());
+    assert_eq!(repl.feed("f(\n"), ReplEntry::Incomplete);
+    assert_eq!(kinds(repl.finish()), vec![ident("f"), Token::LPAREN, Token::EOF]);
+    assert!(!repl.is_continuing());
+}
+
+/// Gives the tokens of a complete entry
+fn kinds(entry: ReplEntry) -> Vec<Token> {
+    match entry {
+        ReplEntry::Complete { tokens, .. } => tokens.into_iter().map(|spanned| spanned.token).collect(),
+        ReplEntry::Incomplete => panic!("entry should be complete"),
+    }
+}
@@ -0,0 +1,84 @@
+use common::error::ErrorType;
+use lexer::{
+    config::{LexerBuilder, LexerConfig},
+    replay::Recording,
+    span::Span,
+    trace::{Decision, Rule},
+};
+
+/// cargo test --test replay_tests
+/// Tests recording lexer sessions, saving them, and replaying them step by step.
+
+#[test]
+fn test_replay_follows_the_session() {
+    let source = "int x = 1;";
+    let recording = Recording::record(source, &LexerConfig::default(), false);
+    let steps: Vec<(Span, &str)> = recording.replay().map(|step| (step.span(), step.lexeme)).collect();
+    assert_eq!(steps[0], (Span::new(0, 3), "int"));
+    assert_eq!(steps[1], (Span::new(3, 4), " "));
+    assert_eq!(steps[2], (Span::new(4, 5), "x"));
+    assert_eq!(steps.last().unwrap().1, "");
+    assert!(recording.replay().enumerate().all(|(index, step)| step.index == index));
+    assert_eq!(recording.source(), source);
+    assert!(recording.matches(source));
+    assert!(!recording.matches("int y = 1;"));
+}
+
+#[test]
+fn test_recording_round_trips() {
+    let config = LexerBuilder::new().nested_comments(true).build();
+    let recording = Recording::record("/* a /* b */ */ x @ y", &config, false);
+    let decoded = Recording::decode(&recording.encode()).unwrap();
+    assert_eq!(decoded, recording);
+    assert!(decoded.settings.contains(&("nested_comments".to_string(), "true".to_string())));
+}
+
+#[test]
+fn test_recording_keeps_errors() {
+    let recording = Recording::record("a @ b", &LexerConfig::default(), false);
+    let error = recording.replay().find(|step| matches!(step.step.decision, Decision::Error { .. })).unwrap();
+    assert_eq!(error.step.decision, Decision::Error { rule: Rule::Builtin });
+    assert_eq!(error.lexeme, "@");
+}
+
+#[test]
+fn test_redaction_renames_identifiers_consistently() {
+    let recording = Recording::record("int secret = other + secret;", &LexerConfig::default(), true);
+    assert!(recording.redacted);
+    assert_eq!(recording.source(), "int id1 = id2 + id1;");
+}
+
+#[test]
+fn test_redaction_numbers_numbers() {
+    let recording = Recording::record("int secret = 31337 + 42 * 31337;", &LexerConfig::default(), true);
+    assert_eq!(recording.source(), "int id1 = 1 + 2 * 1;");
+}
+
+#[test]
+fn test_redaction_masks_comments_and_literals() {
+    let source = "// key\nchar *p = \"hunter two\"; /* why */ char c = 'k';\n#define KEY 1\n";
+    let recording = Recording::record(source, &LexerConfig::default(), true);
+    assert_eq!(
+        recording.source(),
+        "// xxx\nchar *id1 = \"xxxxxx xxx\"; /* xxx */ char id2 = 'x';\n#xxxxxx xxx x\n"
+    );
+}
+
+#[test]
+fn test_redaction_keeps_spans_and_kinds() {
+    let source = "count = name;";
+    let plain = Recording::record(source, &LexerConfig::default(), false);
+    let redacted = Recording::record(source, &LexerConfig::default(), true);
+    assert_eq!(plain.trace, redacted.trace);
+    assert_eq!(redacted.input_hash, plain.input_hash);
+}
+
+#[test]
+fn test_decode_rejects_other_files() {
+    assert!(matches!(Recording::decode(b"LXTR\x01"), Err(ErrorType::MalformedTokenStream { .. })));
+    let mut bytes = Recording::record("x", &LexerConfig::default(), false).encode();
+    bytes.push(0);
+    assert!(matches!(Recording::decode(&bytes), Err(ErrorType::MalformedTokenStream { .. })));
+    let bytes = Recording::record("x", &LexerConfig::default(), false).encode();
+    assert!(matches!(Recording::decode(&bytes[..bytes.len() - 1]), Err(ErrorType::MalformedTokenStream { .. })));
+}
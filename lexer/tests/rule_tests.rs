@@ -0,0 +1,118 @@
+use common::error::ErrorType;
+use lexer::{
+    codec::{decode, encode},
+    config::LexerBuilder,
+    core::Lexer,
+    rule::{Cursor, TokenRule},
+    token::Token,
+};
+
+/// cargo test --test rule_tests
+/// Tests extending the lexer with custom token rules.
+
+#[test]
+fn test_date_literal_rule() {
+    let config = LexerBuilder::new().rule(0, DateRule).build();
+    let result = Lexer::lex_with_config("d = 2024-01-31;", &config);
+    let expected = vec![
+        Token::IDENTIFIER(vec!['d']),
+        Token::EQUAL,
+        Token::CUSTOM("DATE".to_string(), "2024-01-31".chars().collect()),
+        Token::SEMICOLON,
+        Token::EOF,
+    ];
+    assert_eq!(result, Ok(expected));
+}
+
+#[test]
+fn test_rule_falls_back_to_builtin() {
+    let config = LexerBuilder::new().rule(0, DateRule).build();
+    let result = Lexer::lex_with_config("2024 - 1", &config);
+    assert_eq!(result, Lexer::lex("2024 - 1"));
+}
+
+#[test]
+fn test_rule_errors() {
+    let config = LexerBuilder::new().rule(0, SqlStringRule).build();
+    let expected = ErrorType::SyntaxError { message: "unterminated SQL string".to_string() };
+    assert_eq!(Lexer::lex_with_config("sql`select", &config), Err(vec![expected]));
+}
+
+#[test]
+fn test_rule_priority() {
+    let config = LexerBuilder::new()
+        .rule(0, SqlStringRule)
+        .rule(10, KeywordOverride)
+        .build();
+    let result = Lexer::lex_with_config("sql`x`", &config);
+    let expected = vec![
+        Token::CUSTOM("SQLKEYWORD".to_string(), vec!['s', 'q', 'l']),
+        Token::CUSTOM("TICK".to_string(), vec!['`']),
+        Token::IDENTIFIER(vec!['x']),
+        Token::CUSTOM("TICK".to_string(), vec!['`']),
+        Token::EOF,
+    ];
+    assert_eq!(result, Ok(expected));
+
+    let config = LexerBuilder::new().rule(0, KeywordOverride).rule(10, SqlStringRule).build();
+    let result = Lexer::lex_with_config("sql`x`", &config).unwrap();
+    assert_eq!(result[0], Token::CUSTOM("SQL".to_string(), vec!['x']));
+}
+
+#[test]
+fn test_custom_tokens_round_trip() {
+    let config = LexerBuilder::new().rule(0, DateRule).build();
+    let tokens = Lexer::lex_spanned_with_config("2024-01-31 2024-02-01", &config).unwrap();
+    assert_eq!(decode(&encode(&tokens)), Ok(tokens));
+}
+
+/// Lexes dates written as `YYYY-MM-DD`.
+struct DateRule;
+
+impl TokenRule for DateRule {
+    fn try_lex(&self, cursor: &mut Cursor) -> Option<Result<Token, ErrorType>> {
+        for digits in [4, 2, 2] {
+            if cursor.consumed() > 0 && !cursor.eat("-") {
+                return None;
+            }
+            if cursor.eat_while(|c| c.is_ascii_digit()) != digits {
+                return None;
+            }
+        }
+        Some(Ok(Token::CUSTOM("DATE".to_string(), cursor.lexeme().to_vec())))
+    }
+}
+
+/// Lexes SQL strings written as sql`...`, keeping only their contents.
+struct SqlStringRule;
+
+impl TokenRule for SqlStringRule {
+    fn try_lex(&self, cursor: &mut Cursor) -> Option<Result<Token, ErrorType>> {
+        if !cursor.eat("sql`") {
+            return None;
+        }
+        let mut contents = Vec::new();
+        loop {
+            match cursor.bump() {
+                Some('`') => return Some(Ok(Token::CUSTOM("SQL".to_string(), contents))),
+                Some(c) => contents.push(c),
+                None => return Some(Err(ErrorType::SyntaxError { message: "unterminated SQL string".to_string() })),
+            }
+        }
+    }
+}
+
+/// Lexes the word `sql` and backticks on their own, and consumes nothing elsewhere.
+struct KeywordOverride;
+
+impl TokenRule for KeywordOverride {
+    fn try_lex(&self, cursor: &mut Cursor) -> Option<Result<Token, ErrorType>> {
+        if cursor.eat("sql") {
+            Some(Ok(Token::CUSTOM("SQLKEYWORD".to_string(), cursor.lexeme().to_vec())))
+        } else if cursor.eat("`") {
+            Some(Ok(Token::CUSTOM("TICK".to_string(), cursor.lexeme().to_vec())))
+        } else {
+            Some(Ok(Token::DEFAULT))
+        }
+    }
+}
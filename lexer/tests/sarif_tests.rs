@@ -0,0 +1,56 @@
+use lexer::{
+    check::FileReport,
+    sarif::{to_sarif, SARIF_SCHEMA},
+};
+use serde_json::json;
+
+/// cargo test --test sarif_tests
+/// Tests exporting diagnostics as SARIF.
+
+#[test]
+fn test_log_structure() {
+    let reports = vec![
+        FileReport::check("src/a.c", "int __a;".to_string()),
+        FileReport::check("src\\b.c", "int __b;\nx = $;".to_string()),
+    ];
+    let log = to_sarif(&reports);
+
+    assert_eq!(log["$schema"], SARIF_SCHEMA);
+    let run = &log["runs"][0];
+    assert_eq!(run["tool"]["driver"]["name"], "lex");
+    assert_eq!(run["columnKind"], "unicodeCodePoints");
+
+    let rules: Vec<&str> = run["tool"]["driver"]["rules"].as_array().unwrap()
+        .iter()
+        .map(|rule| rule["id"].as_str().unwrap())
+        .collect();
+    assert_eq!(rules, vec!["reserved-identifier", "unrecognized-token"]);
+
+    let results = run["results"].as_array().unwrap();
+    assert_eq!(results.len(), 3);
+    assert_eq!(results[1]["ruleIndex"], 0);
+    assert_eq!(results[1]["locations"][0]["physicalLocation"]["artifactLocation"]["uri"], "src/b.c");
+    assert_eq!(results[2]["level"], "error");
+    assert_eq!(results[2]["ruleIndex"], 1);
+}
+
+#[test]
+fn test_regions_count_code_points() {
+    let log = to_sarif(&[FileReport::check("u.c", "s = \"é\"; $".to_string())]);
+    let result = &log["runs"][0]["results"][0];
+    assert_eq!(result["level"], "error");
+    assert_eq!(result["locations"][0]["physicalLocation"]["region"], json!({
+        "startLine": 1,
+        "startColumn": 10,
+        "endLine": 1,
+        "endColumn": 11,
+        "byteOffset": 10,
+        "byteLength": 1,
+    }));
+}
+
+#[test]
+fn test_info_becomes_note() {
+    let log = to_sarif(&[FileReport::check("d.c", "#pragma once".to_string())]);
+    assert_eq!(log["runs"][0]["results"][0]["level"], "note");
+}
@@ -0,0 +1,62 @@
+use common::error::ErrorType;
+use lexer::{
+    config::LexerBuilder,
+    rule::{Cursor, TokenRule},
+    shrink::{fails_with, panics_on, shrink_failing_input},
+    token::Token,
+};
+
+/// cargo test --test shrink_tests
+/// Tests minimizing inputs that make the lexer fail.
+
+#[test]
+fn test_shrinks_to_failing_character() {
+    let config = LexerBuilder::new().build();
+    let unrecognized = fails_with(&config, |error| matches!(error, ErrorType::UnrecognizedToken { .. }));
+    let input = "int main() {\n    return 0 @ 1;\n}\n";
+    assert_eq!(shrink_failing_input(input, unrecognized), "@");
+}
+
+#[test]
+fn test_shrinks_to_panicking_pair() {
+    let config = LexerBuilder::new().rule(0, PanicsOnPair).build();
+    let input = "int x = 1;\n// note\nx = x %2 + 3;\n";
+    let shrunk = shrink_failing_input(input, panics_on(&config));
+    assert_eq!(shrunk, "%2");
+}
+
+#[test]
+fn test_result_is_minimal() {
+    let input = "a1b2c3d4e5";
+    let predicate = |candidate: &str| candidate.contains('3') && candidate.contains('a');
+    let shrunk = shrink_failing_input(input, predicate);
+    assert_eq!(shrunk, "a3");
+    for index in 0..shrunk.len() {
+        let mut smaller = shrunk.clone();
+        smaller.remove(index);
+        assert!(!predicate(&smaller));
+    }
+}
+
+#[test]
+fn test_multibyte_input_stays_valid() {
+    assert_eq!(shrink_failing_input("héllo wörld", |candidate| candidate.contains('ö')), "ö");
+}
+
+#[test]
+fn test_passing_input_is_unchanged() {
+    let config = LexerBuilder::new().build();
+    assert_eq!(shrink_failing_input("int x;", panics_on(&config)), "int x;");
+}
+
+/// A rule that panics on a `%` followed by a digit, standing in for a lexer bug
+struct PanicsOnPair;
+
+impl TokenRule for PanicsOnPair {
+    fn try_lex(&self, cursor: &mut Cursor) -> Option<Result<Token, ErrorType>> {
+        if cursor.eat("%") && cursor.eat_while(|c| c.is_ascii_digit()) > 0 {
+            panic!("digit after `%`");
+        }
+        None
+    }
+}
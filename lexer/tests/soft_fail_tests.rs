@@ -0,0 +1,82 @@
+use common::error::ErrorType;
+use lexer::{
+    codec,
+    config::{LexerBuilder, LexerConfig},
+    core::Lexer,
+    project::ProjectConfig,
+    span::Span,
+    token::{Token, TokenCategory, TokenKind},
+};
+
+/// cargo test --test soft_fail_tests
+/// Tests keeping text the lexer does not support as unknown tokens.
+
+#[test]
+fn test_unrecognized_characters_become_unknown_tokens() {
+    let (tokens, errors, diagnostics) = Lexer::lex_with_diagnostics("x = @y;", &soft_fail());
+    assert!(errors.is_empty());
+    let kinds: Vec<TokenKind> = tokens.iter().map(|spanned| spanned.token.kind()).collect();
+    assert_eq!(
+        kinds,
+        vec![
+            TokenKind::IDENTIFIER,
+            TokenKind::EQUAL,
+            TokenKind::UNKNOWN,
+            TokenKind::IDENTIFIER,
+            TokenKind::SEMICOLON,
+            TokenKind::EOF,
+        ]
+    );
+    assert_eq!(tokens[2].token, Token::UNKNOWN(vec!['@']));
+    assert_eq!(tokens[2].span, Span::new(4, 5));
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].code, "unknown-token");
+    assert_eq!(diagnostics[0].message, "`@` is not supported and was kept as an unknown token");
+    assert_eq!(diagnostics[0].span, Span::new(4, 5));
+}
+
+#[test]
+fn test_invalid_literals_become_unknown_tokens() {
+    let tokens = Lexer::lex_with_config("c = 'ab';", &soft_fail()).unwrap();
+    assert_eq!(tokens[2], Token::UNKNOWN("'ab'".chars().collect()));
+}
+
+#[test]
+fn test_errors_by_default() {
+    let errors = Lexer::lex("x = @y;").unwrap_err();
+    assert!(matches!(errors[0], ErrorType::UnrecognizedToken { .. }));
+}
+
+#[test]
+fn test_unterminated_strings_are_still_errors() {
+    assert!(Lexer::lex_with_config("s = \"open\n", &soft_fail()).is_err());
+}
+
+#[test]
+fn test_unknown_token_kind() {
+    let token = Token::UNKNOWN(vec!['@']);
+    assert_eq!(token.name(), "UNKNOWN");
+    assert_eq!(token.category(), TokenCategory::Special);
+    assert_eq!(token.text(), Some(&['@'][..]));
+    assert_eq!(TokenKind::from_name("UNKNOWN"), Some(TokenKind::UNKNOWN));
+    let (kind, data) = token.clone().into_parts();
+    assert_eq!(Token::from_parts(kind, data), Some(token));
+}
+
+#[test]
+fn test_unknown_tokens_round_trip_through_the_codec() {
+    let tokens = Lexer::lex_spanned_with_config("a @ b", &soft_fail()).unwrap();
+    assert_eq!(codec::decode(&codec::encode(&tokens)).unwrap(), tokens);
+}
+
+#[test]
+fn test_project_soft_fail() {
+    let project = ProjectConfig::from_toml("soft_fail = true").unwrap();
+    let config = project.lexer_builder().unwrap().build();
+    assert_eq!(Lexer::lex_with_config("@", &config).unwrap()[0], Token::UNKNOWN(vec!['@']));
+}
+
+/// Builds a configuration in soft-fail mode
+fn soft_fail() -> LexerConfig {
+    LexerBuilder::new().soft_fail(true).build()
+}
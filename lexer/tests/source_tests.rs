@@ -0,0 +1,41 @@
+use lexer::{
+    source::{SourceFile, SourceLine},
+    span::Span,
+};
+
+/// cargo test --test source_tests
+/// Tests extracting excerpts of source files.
+
+#[test]
+fn test_snippet() {
+    let file = SourceFile::new("main.c", "int x;\nint é;\n");
+    assert_eq!(file.name(), "main.c");
+    assert_eq!(file.snippet(Span::new(0, 3)), Some("int"));
+    assert_eq!(file.snippet(Span::new(11, 13)), Some("é"));
+    assert_eq!(file.snippet(Span::new(11, 12)), None);
+    assert_eq!(file.snippet(Span::new(0, 100)), None);
+}
+
+#[test]
+fn test_line_containing() {
+    let file = SourceFile::new("main.c", "int x;\r\nx = 1;\n");
+    assert_eq!(file.line_containing(0), SourceLine { number: 1, span: Span::new(0, 7), text: "int x;\r" });
+    assert_eq!(file.line_containing(8), SourceLine { number: 2, span: Span::new(8, 14), text: "x = 1;" });
+    assert_eq!(file.line_containing(14).number, 2);
+    assert_eq!(file.line_containing(100), SourceLine { number: 3, span: Span::new(15, 15), text: "" });
+}
+
+#[test]
+fn test_context_lines() {
+    let file = SourceFile::new("main.c", "a\nb\nc\nd\ne");
+    let numbers = |span: Span, n: usize| -> Vec<usize> {
+        file.context_lines(span, n).iter().map(|line| line.number).collect()
+    };
+    assert_eq!(numbers(Span::new(4, 5), 0), vec![3]);
+    assert_eq!(numbers(Span::new(4, 5), 1), vec![2, 3, 4]);
+    assert_eq!(numbers(Span::new(0, 1), 2), vec![1, 2, 3]);
+    assert_eq!(numbers(Span::new(2, 6), 0), vec![2, 3]);
+    assert_eq!(numbers(Span::new(6, 8), 5), vec![1, 2, 3, 4, 5]);
+    assert_eq!(numbers(Span::new(9, 9), 1), vec![4, 5]);
+    assert_eq!(file.context_lines(Span::new(4, 5), 1)[0].text, "b");
+}
@@ -0,0 +1,53 @@
+use lexer::{
+    core::Lexer,
+    diagnostic::Diagnostic,
+    span::Span,
+};
+
+/// cargo test --test span_tests
+/// Tests spans of multi-character tokens and the span utilities.
+
+#[test]
+fn test_multi_character_token_spans() {
+    let source = "while (count <= 10) x++ && \"done\";";
+    let lexemes: Vec<&str> = Lexer::lex_spanned(source)
+        .unwrap()
+        .iter()
+        .map(|spanned| &source[spanned.span.start..spanned.span.end])
+        .collect();
+    assert_eq!(lexemes, vec!["while", "(", "count", "<=", "10", ")", "x", "++", "&&", "\"done\"", ";", ""]);
+}
+
+#[test]
+fn test_render_underlines_whole_token() {
+    let source = "if (a <= b) return;";
+    let tokens = Lexer::lex_spanned(source).unwrap();
+    let diagnostic = Diagnostic::warning("test", "comparison".to_string(), tokens[3].span);
+    assert_eq!(
+        diagnostic.render(source, 4),
+        "warning[test]: comparison\n --> 1:7\n  |\n1 | if (a <= b) return;\n  |       ^^\n",
+    );
+
+    let diagnostic = Diagnostic::warning("test", "keyword".to_string(), tokens[6].span);
+    assert!(diagnostic.render(source, 4).ends_with("  |             ^^^^^^\n"));
+}
+
+#[test]
+fn test_merge() {
+    assert_eq!(Span::new(4, 6).merge(Span::new(10, 12)), Span::new(4, 12));
+    assert_eq!(Span::new(10, 12).merge(Span::new(4, 6)), Span::new(4, 12));
+    assert_eq!(Span::new(2, 9).merge(Span::new(3, 4)), Span::new(2, 9));
+    assert_eq!(Span::new(5, 5).merge(Span::new(5, 5)), Span::new(5, 5));
+}
+
+#[test]
+fn test_contains() {
+    let span = Span::new(4, 10);
+    assert!(span.contains(Span::new(4, 10)));
+    assert!(span.contains(Span::new(5, 7)));
+    assert!(span.contains(Span::new(4, 4)));
+    assert!(span.contains(Span::new(10, 10)));
+    assert!(!span.contains(Span::new(3, 5)));
+    assert!(!span.contains(Span::new(9, 11)));
+    assert!(!span.contains(Span::new(11, 11)));
+}
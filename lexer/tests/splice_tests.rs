@@ -0,0 +1,91 @@
+use common::error::ErrorType;
+use lexer::{
+    core::Lexer,
+    splice::{detokenize, token_text, Delimiter, TokenStreamBuilder},
+    span::Span,
+    token::Token,
+};
+
+/// cargo test --test splice_tests
+/// Tests building token streams in code, splicing in lexed ones, and turning them back into source.
+
+#[test]
+fn test_builder_lays_out_minimal_source() {
+    let call = TokenStreamBuilder::new()
+        .ident("step")
+        .group(Delimiter::Parenthesis, TokenStreamBuilder::new())
+        .punct(";")
+        .unwrap();
+    let builder = TokenStreamBuilder::new()
+        .keyword("while")
+        .unwrap()
+        .group(Delimiter::Parenthesis, TokenStreamBuilder::new().ident("n").punct("--").unwrap())
+        .group(Delimiter::Brace, call);
+    assert_eq!(builder.to_source(), "while(n--){step();}");
+}
+
+#[test]
+fn test_splicing_lexed_tokens() {
+    let template = Lexer::lex_spanned("x = x * 2;").unwrap();
+    let builder = TokenStreamBuilder::new()
+        .keyword("if")
+        .unwrap()
+        .group(Delimiter::Parenthesis, TokenStreamBuilder::new().ident("ready"))
+        .group(Delimiter::Brace, TokenStreamBuilder::new().splice(&template));
+    assert_eq!(builder.to_source(), "if(ready){x=x*2;}");
+    assert!(!builder.tokens().contains(&Token::EOF));
+}
+
+#[test]
+fn test_built_source_relexes_to_the_same_tokens() {
+    let (source, stream) = TokenStreamBuilder::new()
+        .token(Token::DASH)
+        .token(Token::DASH)
+        .token(Token::FSLASH)
+        .token(Token::ASTERISK)
+        .number("1")
+        .token(Token::DOT)
+        .ident("field")
+        .token(Token::LESSTHAN)
+        .token(Token::EQUAL)
+        .keyword("return")
+        .unwrap()
+        .string("say \"hi\"\n\u{1}2")
+        .char('\'')
+        .char('\n')
+        .build();
+    let relexed = Lexer::lex_spanned(&source).unwrap();
+    assert_eq!(relexed, stream.tokens());
+}
+
+#[test]
+fn test_spans_point_into_the_source() {
+    let (source, stream) = TokenStreamBuilder::new().keyword("int").unwrap().ident("count").punct(";").unwrap().build();
+    assert_eq!(source, "int count;");
+    let spans: Vec<Span> = stream.tokens().iter().map(|spanned| spanned.span).collect();
+    assert_eq!(spans, vec![Span::new(0, 3), Span::new(4, 9), Span::new(9, 10), Span::new(10, 10)]);
+    assert_eq!(stream.tokens().last().unwrap().token, Token::EOF);
+}
+
+#[test]
+fn test_preprocessor_lines_get_their_own_line() {
+    let tokens = Lexer::lex("#include <stdio.h>\nint x;").unwrap();
+    assert_eq!(detokenize(&tokens), "#include <stdio.h>\nint x;");
+    let builder = TokenStreamBuilder::new().ident("a").token(Token::HASHLINE("#define B".chars().collect())).ident("c");
+    assert_eq!(builder.to_source(), "a\n#define B\nc");
+}
+
+#[test]
+fn test_token_text() {
+    assert_eq!(token_text(&Token::IDENTIFIER(vec!['x'])), "x");
+    assert_eq!(token_text(&Token::STRINGLITERAL("a\\n".chars().collect())), "\"a\\n\"");
+    assert_eq!(token_text(&Token::CHAR('\\')), "'\\\\'");
+    assert_eq!(token_text(&Token::POINTER), "->");
+    assert_eq!(token_text(&Token::EOF), "");
+}
+
+#[test]
+fn test_unknown_spellings_are_rejected() {
+    assert!(matches!(TokenStreamBuilder::new().keyword("let"), Err(ErrorType::UnrecognizedToken { .. })));
+    assert!(matches!(TokenStreamBuilder::new().punct("::"), Err(ErrorType::UnrecognizedToken { .. })));
+}
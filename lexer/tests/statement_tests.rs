@@ -0,0 +1,49 @@
+use lexer::{
+    core::Lexer,
+    statement::statements,
+};
+
+/// cargo test --test statement_tests
+/// Tests splitting token streams into statements.
+
+#[test]
+fn test_simple_statements() {
+    let source = "int x = 1; x = x + 2;\nreturn x;";
+    assert_eq!(texts(source), vec!["int x = 1;", "x = x + 2;", "return x;"]);
+}
+
+#[test]
+fn test_blocks() {
+    let source = "int main() { if (x) { y; } else { z; } return 0; } int y;";
+    assert_eq!(texts(source), vec!["int main() { if (x) { y; } else { z; } return 0; }", "int y;"]);
+}
+
+#[test]
+fn test_continuations() {
+    let source = "if (a) { b; } else { c; } do { d; } while (e); struct S { int f; }; g;";
+    assert_eq!(texts(source), vec![
+        "if (a) { b; } else { c; }",
+        "do { d; } while (e);",
+        "struct S { int f; };",
+        "g;",
+    ]);
+}
+
+#[test]
+fn test_semicolons_inside_parentheses() {
+    let source = "for (i = 0; i < 3; i++) { f(i); } done;";
+    assert_eq!(texts(source), vec!["for (i = 0; i < 3; i++) { f(i); }", "done;"]);
+}
+
+#[test]
+fn test_unterminated_and_empty() {
+    assert_eq!(texts("a; b = 2"), vec!["a;", "b = 2"]);
+    assert!(texts("  // nothing\n").is_empty());
+    assert_eq!(statements(&Lexer::lex_spanned("a; b;").unwrap())[1].tokens, 2..4);
+}
+
+/// Gives the source text of each statement
+fn texts(source: &str) -> Vec<&str> {
+    let tokens = Lexer::lex_spanned(source).unwrap();
+    statements(&tokens).into_iter().map(|statement| &source[statement.span.start..statement.span.end]).collect()
+}
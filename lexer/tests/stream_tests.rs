@@ -0,0 +1,87 @@
+use lexer::{
+    config::LexerConfig,
+    span::{Span, SpannedToken},
+    stream::{StreamItem, StreamLexer},
+    token::Token,
+};
+
+/// cargo test --test stream_tests
+/// Tests lexing input that arrives a piece at a time.
+
+#[test]
+fn test_emits_complete_lines() {
+    let mut stream = StreamLexer::new(LexerConfig::default());
+    assert_eq!(kinds(&stream.push("int x;\n")), vec!["TINTEGER", "IDENTIFIER", "SEMICOLON"]);
+    assert_eq!(kinds(&stream.push("x = 1;\n")), vec!["IDENTIFIER", "EQUAL", "NUMBER", "SEMICOLON"]);
+    assert_eq!(kinds(&stream.finish()), vec!["EOF"]);
+}
+
+#[test]
+fn test_holds_partial_line() {
+    let mut stream = StreamLexer::new(LexerConfig::default());
+    assert!(stream.push("int cou").is_empty());
+    assert_eq!(kinds(&stream.push("nt;\n")), vec!["TINTEGER", "IDENTIFIER", "SEMICOLON"]);
+}
+
+#[test]
+fn test_holds_open_block_comment() {
+    let mut stream = StreamLexer::new(LexerConfig::default());
+    assert!(stream.push("x /* still\n").is_empty());
+    assert!(stream.push("open\n").is_empty());
+    assert_eq!(kinds(&stream.push("*/ y;\n")), vec!["IDENTIFIER", "IDENTIFIER", "SEMICOLON"]);
+}
+
+#[test]
+fn test_holds_continued_preprocessor_line() {
+    let mut stream = StreamLexer::new(LexerConfig::default());
+    assert_eq!(kinds(&stream.push("x;\n#define A \\\n")), vec!["IDENTIFIER", "SEMICOLON"]);
+    let items = stream.push("1\n");
+    assert_eq!(items, vec![token(Token::HASHLINE("#define A \\\n1".chars().collect()), "#define A \\\n1", 3, 16)]);
+}
+
+#[test]
+fn test_spans_are_absolute() {
+    let mut stream = StreamLexer::new(LexerConfig::default());
+    stream.push("a;\n");
+    let items = stream.push("bc;\n");
+    assert_eq!(items[0], token(Token::IDENTIFIER(vec!['b', 'c']), "bc", 3, 5));
+    assert_eq!(stream.finish(), vec![token(Token::EOF, "", 7, 7)]);
+}
+
+#[test]
+fn test_finish_lexes_unterminated_line() {
+    let mut stream = StreamLexer::new(LexerConfig::default());
+    stream.push("x");
+    assert_eq!(kinds(&stream.finish()), vec!["IDENTIFIER", "EOF"]);
+}
+
+#[test]
+fn test_errors_are_emitted() {
+    let mut stream = StreamLexer::new(LexerConfig::default());
+    let items = stream.push("x $ y\n");
+    assert!(matches!(items[1], StreamItem::Error(_)));
+    assert_eq!(items[1].to_json()["error"], "unrecognized-token");
+    assert_eq!(items[1].to_json()["start"], 2);
+}
+
+#[test]
+fn test_to_json() {
+    let json = token(Token::IDENTIFIER(vec!['x']), "x", 4, 5).to_json();
+    assert_eq!(json, serde_json::json!({"kind": "IDENTIFIER", "lexeme": "x", "start": 4, "end": 5}));
+}
+
+/// Gives the kind of every item, or `error` for errors
+fn kinds(items: &[StreamItem]) -> Vec<&'static str> {
+    items
+        .iter()
+        .map(|item| match item {
+            StreamItem::Token { token, .. } => token.token.name(),
+            StreamItem::Error(_) => "error",
+        })
+        .collect()
+}
+
+/// Builds a token item
+fn token(token: Token, lexeme: &str, start: usize, end: usize) -> StreamItem {
+    StreamItem::Token { token: SpannedToken::new(token, Span::new(start, end)), lexeme: lexeme.to_string() }
+}
@@ -0,0 +1,61 @@
+//! Helpers shared by the integration tests. Every test binary that needs them declares `mod support;`, so a
+//! binary may leave some of them unused.
+#![allow(dead_code)]
+
+use std::{
+    fs,
+    ops::Deref,
+    path::{Path, PathBuf},
+};
+
+use lexer::token::Token;
+
+/// An empty directory private to one test, removed with everything in it when dropped.
+pub struct ScratchDir {
+    path: PathBuf,
+}
+
+impl ScratchDir {
+    /// Creates the directory under the system's temporary directory, named after the test binary, the process, and
+    /// `name`, clearing out anything an earlier run left there.
+    pub fn new(name: &str) -> Self {
+        let dir = format!("lex-{}-{}-{}", env!("CARGO_CRATE_NAME"), std::process::id(), name);
+        let path = std::env::temp_dir().join(dir);
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(&path).unwrap();
+        Self { path }
+    }
+
+    /// Writes a file into the directory, creating any directories in `name`, and returns its path.
+    pub fn write(&self, name: &str, contents: impl AsRef<[u8]>) -> PathBuf {
+        let path = self.path.join(name);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, contents).unwrap();
+        path
+    }
+}
+
+impl Deref for ScratchDir {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl AsRef<Path> for ScratchDir {
+    fn as_ref(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for ScratchDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
+
+/// Builds an identifier token
+pub fn ident(name: &str) -> Token {
+    Token::IDENTIFIER(name.chars().collect())
+}
@@ -0,0 +1,68 @@
+use lexer::{
+    check::{check_source, FileReport},
+    config::LexerConfig,
+    core::Lexer,
+    sarif::to_sarif,
+    span::Span,
+    suppress::{find_suppressions, SuppressionScope},
+};
+
+/// cargo test --test suppress_tests
+/// Tests suppressing diagnostics with comments.
+
+#[test]
+fn test_find_suppressions() {
+    let source = "// lexer: ignore-next-line\nx = 1; /* lexer: allow(unrecognized, too-long) */ y;\n// plain";
+    let (tokens, errors) = Lexer::lex_recovering(source, &LexerConfig::default());
+    let (suppressions, invalid) = find_suppressions(source, &tokens, &errors);
+
+    assert!(invalid.is_empty());
+    assert_eq!(suppressions.len(), 2);
+    assert_eq!(suppressions[0].scope, SuppressionScope::NextLine);
+    assert_eq!(suppressions[0].span, Span::new(0, 26));
+    assert!(suppressions[0].codes.is_empty());
+    assert_eq!(suppressions[1].scope, SuppressionScope::NextToken);
+    assert_eq!(suppressions[1].codes, vec!["unrecognized", "too-long"]);
+}
+
+#[test]
+fn test_ignore_next_line() {
+    let diagnostics = check_source("// lexer: ignore-next-line\nx = $;\ny = $;");
+    let suppressed: Vec<bool> = diagnostics.iter().map(|diagnostic| diagnostic.suppressed).collect();
+    assert_eq!(suppressed, vec![true, false]);
+}
+
+#[test]
+fn test_ignore_next_line_with_codes() {
+    let diagnostics = check_source("// lexer: ignore-next-line(reserved-identifier)\nint __x = $;");
+    assert!(diagnostics.iter().any(|diagnostic| diagnostic.code == "unrecognized-token" && !diagnostic.suppressed));
+}
+
+#[test]
+fn test_allow_next_token() {
+    let diagnostics = check_source("x = /* lexer: allow(unrecognized) */ $ + $;");
+    let suppressed: Vec<bool> = diagnostics.iter().map(|diagnostic| diagnostic.suppressed).collect();
+    assert_eq!(suppressed, vec![true, false]);
+}
+
+#[test]
+fn test_comments_inside_strings_are_ignored() {
+    let diagnostics = check_source("s = \"// lexer: ignore-next-line\";\nx = $;");
+    assert!(!diagnostics[0].suppressed);
+}
+
+#[test]
+fn test_invalid_suppressions() {
+    let diagnostics = check_source("// lexer: ignore-everything\n/* lexer: allow */\n// lexer: allow(x\n");
+    let codes: Vec<&str> = diagnostics.iter().map(|diagnostic| diagnostic.code).collect();
+    assert_eq!(codes, vec!["invalid-suppression"; 3]);
+}
+
+#[test]
+fn test_suppressed_errors_do_not_fail() {
+    let report = FileReport::check("a.c", "// lexer: ignore-next-line\nx = $;".to_string());
+    assert!(!report.has_errors());
+
+    let log = to_sarif(&[report]);
+    assert_eq!(log["runs"][0]["results"][0]["suppressions"][0]["kind"], "inSource");
+}
@@ -0,0 +1,53 @@
+use std::{ptr, thread};
+
+use lexer::{config::LexerBuilder, core::Lexer, tables::KeywordTable, token::Token};
+
+/// cargo test --test tables_tests
+/// Tests building keyword tables once and sharing them between lexers.
+
+#[test]
+fn test_standard_table_is_shared() {
+    let config = LexerBuilder::new().build();
+    assert!(ptr::eq(config.keyword_table(), &**KeywordTable::standard()));
+    assert!(ptr::eq(LexerBuilder::new().build().keyword_table(), config.keyword_table()));
+    assert!(!KeywordTable::standard().is_contextual(&chars("inline")));
+}
+
+#[test]
+fn test_clones_share_a_table() {
+    let config = LexerBuilder::new().c99_contextual_keywords().build();
+    let clone = config.clone();
+    assert!(ptr::eq(config.keyword_table(), clone.keyword_table()));
+    assert!(!ptr::eq(config.keyword_table(), &**KeywordTable::standard()));
+    assert!(clone.keyword_table().is_contextual(&chars("restrict")));
+}
+
+#[test]
+fn test_keyword_lookup() {
+    let table = KeywordTable::new(&["defer".to_string()]);
+    assert_eq!(table.keyword(&chars("while")), Some(&Token::WHILE));
+    assert_eq!(table.keyword(&chars("int")), Some(&Token::TINTEGER));
+    assert_eq!(table.keyword(&chars("whilst")), None);
+    assert!(table.is_contextual(&chars("defer")));
+    assert_eq!(table.contextual_keywords().collect::<Vec<String>>(), vec!["defer"]);
+}
+
+#[test]
+fn test_many_lexers_from_one_config() {
+    let config = LexerBuilder::new().contextual_keyword("defer").build();
+    let handles: Vec<_> = (0..4)
+        .map(|_| {
+            let config = config.clone();
+            thread::spawn(move || Lexer::lex_spanned_with_config("defer x; while", &config).unwrap())
+        })
+        .collect();
+    for handle in handles {
+        let tokens = handle.join().unwrap();
+        assert!(tokens[0].contextual_keyword);
+        assert_eq!(tokens[3].token, Token::WHILE);
+    }
+}
+
+fn chars(word: &str) -> Vec<char> {
+    word.chars().collect()
+}
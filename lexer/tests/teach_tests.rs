@@ -0,0 +1,96 @@
+use lexer::{
+    config::LexerConfig,
+    core::Lexer,
+    span::Span,
+    teach::{annotate, teach_steps, AnnotationKind},
+    token::Token,
+};
+
+/// cargo test --test teach_tests
+/// Tests annotating source with its tokens for teaching.
+
+#[test]
+fn test_annotate_marks_tokens_under_each_line() {
+    let source = "int count = 42;\nreturn \"hi\";\n";
+    assert_eq!(annotate_plain(source), concat!(
+        "1 | int count = 42;\n",
+        "  | ttt iiiii o nnp\n",
+        "2 | return \"hi\";\n",
+        "  | kkkkkk ssssp\n",
+        "\n",
+        "legend: k keyword  t type  i identifier  n number  s string  o operator  p punctuation\n",
+    ));
+}
+
+#[test]
+fn test_annotate_keeps_tabs_aligned() {
+    assert_eq!(annotate_plain("\tx;"), "1 | \tx;\n  | \tip\n\nlegend: i identifier  p punctuation\n");
+}
+
+#[test]
+fn test_annotate_marks_errors() {
+    assert_eq!(annotate_plain("a $ b"), "1 | a $ b\n  | i ! i\n\nlegend: i identifier  ! error\n");
+}
+
+#[test]
+fn test_annotate_colors_tokens_and_markers() {
+    let source = "x";
+    let (tokens, errors) = Lexer::lex_recovering(source, &LexerConfig::default());
+    let color = AnnotationKind::Identifier.color();
+    assert_eq!(
+        annotate(source, &tokens, &errors, true),
+        format!("1 | {c}x\x1b[0m\n  | {c}i\x1b[0m\n\nlegend: {c}i\x1b[0m identifier\n", c = color),
+    );
+}
+
+#[test]
+fn test_annotate_empty_source() {
+    assert_eq!(annotate_plain(""), "1 |\n  |\n");
+}
+
+#[test]
+fn test_annotation_kinds() {
+    assert_eq!(AnnotationKind::of(&Token::WHILE), AnnotationKind::Keyword);
+    assert_eq!(AnnotationKind::of(&Token::TINTEGER), AnnotationKind::Type);
+    assert_eq!(AnnotationKind::of(&Token::TYPENAME("size_t".chars().collect())), AnnotationKind::Type);
+    assert_eq!(AnnotationKind::of(&Token::CHAR('a')), AnnotationKind::String);
+    assert_eq!(AnnotationKind::of(&Token::LBRACKET), AnnotationKind::Punctuation);
+    assert_eq!(AnnotationKind::of(&Token::HASHLINE("#define X".chars().collect())), AnnotationKind::Preprocessor);
+    assert_eq!(AnnotationKind::of(&Token::UNKNOWN(vec!['$'])), AnnotationKind::Unknown);
+}
+
+#[test]
+fn test_teach_steps_go_in_source_order() {
+    let source = "a $ 1";
+    let (tokens, errors) = Lexer::lex_recovering(source, &LexerConfig::default());
+    let steps = teach_steps(&tokens, &errors);
+    let described: Vec<(Span, &str)> = steps.iter().map(|step| (step.span, step.description.as_str())).collect();
+    assert_eq!(described, vec![
+        (Span::new(0, 1), "IDENTIFIER `a`"),
+        (Span::new(2, 3), "error: unrecognized token `$`"),
+        (Span::new(4, 5), "NUMBER `1`"),
+    ]);
+    assert_eq!(steps[1].kind, AnnotationKind::Error);
+}
+
+#[test]
+fn test_teach_step_renders_carets_under_token() {
+    let source = "int x;\n\tcount++;";
+    let (tokens, errors) = Lexer::lex_recovering(source, &LexerConfig::default());
+    let steps = teach_steps(&tokens, &errors);
+    assert_eq!(steps.len(), 6);
+    assert_eq!(
+        steps[3].render(source, 4, steps.len(), false),
+        "step 4 of 6: IDENTIFIER `count` at 2:2 (identifier)\n2 | \tcount++;\n  | \t^^^^^\n",
+    );
+    assert_eq!(
+        steps[4].render(source, 5, steps.len(), false),
+        "step 5 of 6: PLUSPLUS `++` at 2:7 (operator)\n2 | \tcount++;\n  | \t     ^^\n",
+    );
+}
+
+/// Annotates source lexed with the default settings, without color
+fn annotate_plain(source: &str) -> String {
+    let (tokens, errors) = Lexer::lex_recovering(source, &LexerConfig::default());
+    annotate(source, &tokens, &errors, false)
+}
@@ -0,0 +1,75 @@
+use lexer::{
+    case::{normalize_keyword_case, KeywordCase},
+    config::LexerConfig,
+    span::Span,
+    text::{apply_edits, TextEdit},
+};
+
+/// cargo test --test text_tests
+/// Tests applying edits to source code and remapping spans.
+
+#[test]
+fn test_apply_edits_in_any_order() {
+    let source = "int count = 0;";
+    let edits = vec![TextEdit::new(Span::new(12, 13), "10"), TextEdit::new(Span::new(4, 9), "n")];
+    let (edited, _) = apply_edits(source, &edits);
+    assert_eq!(edited, "int n = 10;");
+}
+
+#[test]
+fn test_insert_and_delete() {
+    let source = "x = y;";
+    let edits = vec![TextEdit::insert(0, "int "), TextEdit::delete(Span::new(4, 5)), TextEdit::insert(0, "const ")];
+    let (edited, _) = apply_edits(source, &edits);
+    assert_eq!(edited, "int const x = ;");
+}
+
+#[test]
+fn test_no_edits() {
+    let (edited, remapper) = apply_edits("a b", &[]);
+    assert_eq!(edited, "a b");
+    assert_eq!(remapper.span(Span::new(2, 3)), Some(Span::new(2, 3)));
+}
+
+#[test]
+fn test_remap_spans() {
+    let source = "int count = 0; return count;";
+    let edits = vec![TextEdit::new(Span::new(4, 9), "n"), TextEdit::new(Span::new(22, 27), "n")];
+    let (edited, remapper) = apply_edits(source, &edits);
+    assert_eq!(edited, "int n = 0; return n;");
+
+    // Spans before, after, and covering an edit
+    assert_eq!(remapper.span(Span::new(0, 3)), Some(Span::new(0, 3)));
+    assert_eq!(remapper.span(Span::new(15, 21)), Some(Span::new(11, 17)));
+    assert_eq!(remapper.span(Span::new(4, 9)), Some(Span::new(4, 5)));
+    assert_eq!(remapper.span(Span::new(0, 14)), Some(Span::new(0, 10)));
+    assert_eq!(remapper.offset(source.len()), Some(edited.len()));
+
+    // Text an edit replaced no longer exists
+    assert_eq!(remapper.offset(6), None);
+    assert_eq!(remapper.span(Span::new(6, 14)), None);
+}
+
+#[test]
+fn test_remap_around_insertion() {
+    let (edited, remapper) = apply_edits("ab", &[TextEdit::insert(1, "xyz")]);
+    assert_eq!(edited, "axyzb");
+    assert_eq!(remapper.offset(1), Some(1));
+    assert_eq!(remapper.span(Span::new(1, 2)), Some(Span::new(1, 5)));
+    assert_eq!(remapper.span(Span::new(0, 1)), Some(Span::new(0, 1)));
+}
+
+#[test]
+#[should_panic(expected = "overlaps another edit")]
+fn test_overlapping_edits() {
+    apply_edits("abcdef", &[TextEdit::new(Span::new(0, 3), "x"), TextEdit::new(Span::new(2, 4), "y")]);
+}
+
+#[test]
+fn test_apply_keyword_edits() {
+    let source = "IF (x) RETURN;";
+    let (normalized, keyword_edits) =
+        normalize_keyword_case(source, &LexerConfig::default(), KeywordCase::Lower).unwrap();
+    let edits: Vec<TextEdit> = keyword_edits.into_iter().map(TextEdit::from).collect();
+    assert_eq!(apply_edits(source, &edits).0, normalized);
+}
@@ -0,0 +1,43 @@
+use lexer::{
+    core::Lexer,
+    token::{Token, TokenCategory},
+};
+
+/// cargo test --test token_info_tests
+/// Tests enumerating the lexer's vocabulary.
+
+#[test]
+fn test_all_kinds_have_distinct_names() {
+    let mut names: Vec<&str> = Token::ALL_KINDS.iter().map(|token| token.name()).collect();
+    names.sort();
+    names.dedup();
+    assert_eq!(names.len(), Token::ALL_KINDS.len());
+}
+
+#[test]
+fn test_metadata() {
+    assert_eq!(Token::NUMBER(vec!['7']).name(), "NUMBER");
+    assert_eq!(Token::NUMBER(vec!['7']).category(), TokenCategory::Literal);
+    assert_eq!(Token::WHILE.example(), "while");
+    assert_eq!(Token::WHILE.category(), TokenCategory::Keyword);
+    assert_eq!(Token::TLONG.category(), TokenCategory::Type);
+    assert_eq!(Token::GREATERTHANEQUAL.example(), ">=");
+    assert_eq!(Token::SEMICOLON.category(), TokenCategory::Punctuation);
+    assert_eq!(Token::EOF.category(), TokenCategory::Special);
+}
+
+#[test]
+fn test_examples_lex_to_their_kind() {
+    for kind in Token::ALL_KINDS.iter().filter(|kind| !kind.example().is_empty()) {
+        let tokens = Lexer::lex(kind.example()).unwrap();
+        assert_eq!(tokens[0].name(), kind.name(), "example {:?}", kind.example());
+        assert_eq!(tokens.len(), 2, "example {:?}", kind.example());
+    }
+}
+
+#[test]
+fn test_tables_lex_to_their_tokens() {
+    for (spelling, token) in Token::keywords().chain(Token::operators()) {
+        assert_eq!(Lexer::lex(spelling).unwrap(), vec![token, Token::EOF], "spelling {:?}", spelling);
+    }
+}
@@ -0,0 +1,93 @@
+use lexer::{
+    core::Lexer,
+    token::{Token, TokenCategory, TokenData, TokenKind, TRIVIA_ID},
+};
+
+/// cargo test --test token_kind_tests
+/// Tests separating tokens into kinds and payloads.
+
+#[test]
+fn test_kind_is_small_and_copy() {
+    assert_eq!(std::mem::size_of::<TokenKind>(), 1);
+    let kind = Token::WHILE.kind();
+    let copy = kind;
+    assert_eq!(kind, copy);
+}
+
+#[test]
+fn test_kinds_match_all_kinds() {
+    for (index, token) in Token::ALL_KINDS.iter().enumerate() {
+        assert_eq!(token.kind() as usize, index);
+        assert_eq!(token.kind().name(), token.name());
+        assert_eq!(token.kind().category(), token.category());
+    }
+    assert_eq!(TokenKind::TRIVIA.name(), "TRIVIA");
+    assert_eq!(TokenKind::TRIVIA.category(), TokenCategory::Special);
+}
+
+#[test]
+fn test_parts_round_trip() {
+    let tokens = vec![
+        Token::IDENTIFIER(vec!['x']),
+        Token::NUMBER(vec!['4', '2']),
+        Token::STRINGLITERAL(vec!['h', 'i']),
+        Token::CHAR('a'),
+        Token::CUSTOM("ARROW".to_string(), vec!['=', '>']),
+        Token::PLUSPLUS,
+        Token::EOF,
+    ];
+    for token in tokens {
+        let (kind, data) = token.clone().into_parts();
+        assert_eq!(Token::from_parts(kind, data), Some(token));
+    }
+}
+
+#[test]
+fn test_mismatched_parts() {
+    assert_eq!(Token::from_parts(TokenKind::IDENTIFIER, TokenData::None), None);
+    assert_eq!(Token::from_parts(TokenKind::PLUS, TokenData::Char('+')), None);
+    assert_eq!(Token::from_parts(TokenKind::TRIVIA, TokenData::None), None);
+}
+
+#[test]
+fn test_match_on_kind() {
+    let tokens = Lexer::lex_spanned("count = count + 1;").unwrap();
+    let operators = tokens
+        .iter()
+        .filter(|spanned| matches!(spanned.kind(), TokenKind::EQUAL | TokenKind::PLUS))
+        .count();
+    assert_eq!(operators, 2);
+    assert_eq!(tokens[0].token.text(), Some(&['c', 'o', 'u', 'n', 't'][..]));
+    assert_eq!(Token::SEMICOLON.text(), None);
+}
+
+#[test]
+fn test_ids_are_stable() {
+    // Ids are part of the serialized formats consumers depend on: this list may only ever be appended to
+    let names = [
+        "DEFAULT", "EOF", "NUMBER", "IDENTIFIER", "STRINGLITERAL", "CHAR", "PLUSPLUS", "MINUSMINUS", "FSLASH", "DASH",
+        "PLUS", "EQUAL", "PERCENT", "ASTERISK", "STRUCT", "ENUM", "IF", "ELSE", "RETURN", "FOR", "WHILE", "DO",
+        "BREAK", "CONTINUE", "SWITCH", "CASE", "RBRACKET", "LBRACKET", "LPAREN", "RPAREN", "LBRACE", "RBRACE",
+        "SEMICOLON", "COMMA", "COLON", "DOT", "ANDAND", "BARBAR", "EXCLAMATIONPOINT", "LESSTHAN", "GREATERTHAN",
+        "NOTEQUAL", "EQUALEQUAL", "LESSTHANEQUAL", "GREATERTHANEQUAL", "TINTEGER", "TBOOLEAN", "TDOUBLE", "TFLOAT",
+        "TCHAR", "TVOID", "TSIGNINT", "TUSIGN", "TLONG", "AMPERSAND", "BAR", "CARET", "TILDE", "POINTER", "CONST",
+        "CTRUE", "CUSTOM", "HASHLINE", "TYPENAME", "UNKNOWN",
+    ];
+    assert_eq!(names.len(), Token::ALL_KINDS.len());
+    for (id, name) in names.iter().enumerate() {
+        let kind = TokenKind::from_id(id as u8).unwrap();
+        assert_eq!(kind.name(), *name);
+        assert_eq!(kind.id(), id as u8);
+        assert_eq!(TokenKind::from_name(name), Some(kind));
+    }
+    assert_eq!(TokenKind::TRIVIA.id(), TRIVIA_ID);
+    assert_eq!(TokenKind::from_id(TRIVIA_ID), Some(TokenKind::TRIVIA));
+}
+
+#[test]
+fn test_unknown_ids_and_names() {
+    assert_eq!(TokenKind::from_id(Token::ALL_KINDS.len() as u8), None);
+    assert_eq!(TokenKind::from_id(200), None);
+    assert_eq!(TokenKind::from_name("NOPE"), None);
+    assert_eq!(TokenKind::from_name("TRIVIA"), Some(TokenKind::TRIVIA));
+}
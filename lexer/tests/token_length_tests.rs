@@ -0,0 +1,91 @@
+use common::error::ErrorType;
+use lexer::{
+    config::{LexerBuilder, LongTokenPolicy},
+    core::Lexer,
+    span::Span,
+    token::Token,
+};
+
+/// cargo test --test token_length_tests
+/// Tests the limit on how many characters a token may have.
+
+#[test]
+fn test_tokens_within_limit() {
+    let config = LexerBuilder::new().max_token_length(5).build();
+    let tokens = Lexer::lex_with_config("abcde 12345 \"abc\"", &config).unwrap();
+    assert_eq!(tokens, vec![
+        Token::IDENTIFIER(chars("abcde")),
+        Token::NUMBER(chars("12345")),
+        Token::STRINGLITERAL(chars("abc")),
+        Token::EOF,
+    ]);
+}
+
+#[test]
+fn test_long_token_is_an_error() {
+    let config = LexerBuilder::new().max_token_length(4).build();
+    let errors = Lexer::lex_located("x = abcdefgh;", &config).unwrap_err();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].error(), &ErrorType::TokenTooLong { kind: "IDENTIFIER".to_string(), length: 8, limit: 4 });
+    assert_eq!(errors[0].span(), Span::new(4, 12));
+}
+
+#[test]
+fn test_long_string_counts_its_quotes() {
+    let config = LexerBuilder::new().max_token_length(4).build();
+    let errors = Lexer::lex_located("\"abc\"", &config).unwrap_err();
+    assert_eq!(errors[0].error(), &ErrorType::TokenTooLong { kind: "STRINGLITERAL".to_string(), length: 5, limit: 4 });
+}
+
+#[test]
+fn test_lexing_recovers_after_long_token() {
+    let config = LexerBuilder::new().max_token_length(3).build();
+    let (tokens, errors) = Lexer::lex_recovering("1234567 + x", &config);
+    assert_eq!(errors.len(), 1);
+    let tokens: Vec<Token> = tokens.into_iter().map(|spanned| spanned.token).collect();
+    assert_eq!(tokens, vec![Token::PLUS, Token::IDENTIFIER(chars("x")), Token::EOF]);
+}
+
+#[test]
+fn test_operators_and_keywords_are_not_limited() {
+    let config = LexerBuilder::new().max_token_length(1).build();
+    let tokens = Lexer::lex_with_config("while (x >= y)", &config).unwrap();
+    assert_eq!(tokens.len(), 7);
+}
+
+#[test]
+fn test_truncate_keeps_long_token_cut_to_limit() {
+    let config = LexerBuilder::new().max_token_length(4).long_tokens(LongTokenPolicy::Truncate).build();
+    let (tokens, errors, diagnostics) = Lexer::lex_with_diagnostics("abcdefgh 1;", &config);
+    assert!(errors.is_empty());
+    assert_eq!(tokens[0].token, Token::IDENTIFIER(chars("abcd")));
+    assert_eq!(tokens[0].span, Span::new(0, 8));
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].code, "token-too-long");
+    assert_eq!(diagnostics[0].message, "IDENTIFIER of 8 characters was cut to the limit of 4");
+    assert_eq!(diagnostics[0].span, Span::new(0, 8));
+}
+
+#[test]
+fn test_truncate_cuts_string_contents() {
+    let config = LexerBuilder::new().max_token_length(4).long_tokens(LongTokenPolicy::Truncate).build();
+    let tokens = Lexer::lex_with_config("\"abcdef\"", &config).unwrap();
+    assert_eq!(tokens[0], Token::STRINGLITERAL(chars("abcd")));
+}
+
+#[test]
+fn test_pathological_identifier() {
+    let input = "a".repeat(1_000_000);
+    let config = LexerBuilder::new().max_token_length(256).long_tokens(LongTokenPolicy::Truncate).build();
+    let tokens = Lexer::lex_with_config(&input, &config).unwrap();
+    assert_eq!(tokens[0].text().map(<[char]>::len), Some(256));
+
+    let config = LexerBuilder::new().max_token_length(256).build();
+    let errors = Lexer::lex_with_config(&input, &config).unwrap_err();
+    assert_eq!(errors, vec![ErrorType::TokenTooLong { kind: "IDENTIFIER".to_string(), length: 1_000_000, limit: 256 }]);
+}
+
+/// Splits a string into the characters of a token's payload
+fn chars(text: &str) -> Vec<char> {
+    text.chars().collect()
+}
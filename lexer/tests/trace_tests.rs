@@ -0,0 +1,80 @@
+use common::error::ErrorType;
+use lexer::{
+    config::{LexerBuilder, LexerConfig},
+    core::Lexer,
+    mode::LexMode,
+    span::Span,
+    trace::{Decision, Rule, Trace},
+};
+
+/// cargo test --test trace_tests
+/// Tests recording and explaining the lexer's decisions.
+
+#[test]
+fn test_trace_steps() {
+    let (result, trace) = Lexer::lex_traced("a /* c */ b", &LexerConfig::default());
+    assert!(result.is_ok());
+    let decisions: Vec<(Span, Decision)> = trace.steps.into_iter().map(|step| (step.span, step.decision)).collect();
+    assert_eq!(decisions, vec![
+        (Span::new(0, 1), token(Rule::Builtin, "IDENTIFIER")),
+        (Span::new(1, 10), Decision::Trivia),
+        (Span::new(10, 11), token(Rule::Builtin, "IDENTIFIER")),
+        (Span::new(11, 11), token(Rule::Builtin, "EOF")),
+    ]);
+}
+
+#[test]
+fn test_trace_records_rules_and_errors() {
+    let config = LexerBuilder::new().token_regex("FLOAT", r"[0-9]+\.[0-9]+").unwrap().build();
+    let (result, trace) = Lexer::lex_traced("1.5 $", &config);
+    assert!(result.is_err());
+    assert_eq!(trace.steps[0].decision, token(Rule::Regex, "FLOAT"));
+    assert_eq!(trace.steps[2].decision, Decision::Error { rule: Rule::Builtin });
+    assert!(trace.steps.iter().all(|step| step.mode == LexMode::Normal));
+}
+
+#[test]
+fn test_round_trip() {
+    let config = LexerBuilder::new().token_regex("FLOAT", r"[0-9]+\.[0-9]+").unwrap().build();
+    let (_, trace) = Lexer::lex_traced("x = 1.5; // done\n$", &config);
+    assert_eq!(Trace::decode(&trace.encode()).unwrap(), trace);
+}
+
+#[test]
+fn test_decode_rejects_corrupt_traces() {
+    let (_, trace) = Lexer::lex_traced("a + b", &LexerConfig::default());
+    let bytes = trace.encode();
+    assert!(matches!(Trace::decode(&bytes[..bytes.len() - 1]), Err(ErrorType::MalformedTokenStream { .. })));
+    assert!(matches!(Trace::decode(b"LXT\x01"), Err(ErrorType::MalformedTokenStream { .. })));
+
+    let mut trailing = bytes.clone();
+    trailing.push(0);
+    assert!(matches!(Trace::decode(&trailing), Err(ErrorType::MalformedTokenStream { .. })));
+}
+
+#[test]
+fn test_explain_maximal_munch() {
+    let source = "a+++b";
+    let (_, trace) = Lexer::lex_traced(source, &LexerConfig::default());
+
+    let first = trace.explain(source, 2).unwrap();
+    assert!(first.starts_with("bytes 1..3 (`++`) were lexed as PLUSPLUS by the built-in rules\n"));
+    assert!(first.contains("operators matching at byte 1: `++` (PLUSPLUS), `+` (PLUS)"));
+    assert!(first.contains("maximal munch"));
+
+    let second = trace.explain(source, 3).unwrap();
+    assert!(second.starts_with("bytes 3..4 (`+`) were lexed as PLUS by the built-in rules\n"));
+    assert!(second.contains("the previous step ended at byte 3"));
+}
+
+#[test]
+fn test_explain_out_of_range() {
+    let (_, trace) = Lexer::lex_traced("a", &LexerConfig::default());
+    assert!(trace.explain("a", 1).unwrap().contains("EOF"));
+    assert_eq!(trace.explain("a", 5), None);
+}
+
+/// Builds a token decision
+fn token(rule: Rule, kind: &str) -> Decision {
+    Decision::Token { rule, kind: kind.to_string() }
+}
@@ -0,0 +1,85 @@
+use lexer::{
+    core::Lexer,
+    span::Span,
+    tree::{Point, Tree},
+};
+
+/// cargo test --test tree_tests
+/// Tests arranging tokens into a tree-sitter-style tree.
+
+#[test]
+fn test_sexp() {
+    let source = concat!(
+        "#include <stdio.h>\n",
+        "struct point { int x; };\n",
+        "enum color { RED };\n",
+        "int main() {\n",
+        "    int a[] = { 1, 2 };\n",
+        "    if (a) { puts(\"hi\"); }\n",
+        "}\n",
+    );
+    assert_eq!(build(source).to_sexp(), concat!(
+        "(translation_unit (preproc_line) (identifier) (field_declaration_list (primitive_type) (identifier)) ",
+        "(identifier) (enumerator_list (identifier)) (primitive_type) (identifier) ",
+        "(compound_statement (primitive_type) (identifier) (initializer_list (number_literal) (number_literal)) ",
+        "(identifier) (compound_statement (identifier) (string_literal))))",
+    ));
+}
+
+#[test]
+fn test_nodes_and_relations() {
+    let tree = build("if (x) {\n  y;\n}");
+    let root = tree.root();
+    assert_eq!(root.kind, "translation_unit");
+    assert_eq!(root.parent, None);
+    assert_eq!(root.children.len(), 5);
+
+    let keyword = tree.node(root.children[0]).unwrap();
+    assert_eq!((keyword.kind.as_str(), keyword.named), ("if", false));
+
+    let block = tree.node(root.children[4]).unwrap();
+    assert_eq!(block.kind, "compound_statement");
+    assert_eq!(block.span, Span::new(7, 15));
+    assert_eq!(block.start_point, Point { row: 0, column: 7 });
+    assert_eq!(block.end_point, Point { row: 2, column: 1 });
+    let kinds: Vec<&str> = block.children.iter().map(|&child| tree.node(child).unwrap().kind.as_str()).collect();
+    assert_eq!(kinds, vec!["{", "identifier", ";", "}"]);
+    assert!(block.children.iter().all(|&child| tree.node(child).unwrap().parent == Some(block.id)));
+}
+
+#[test]
+fn test_descendant_for_byte_range() {
+    let tree = build("void f() { g(); }");
+    assert_eq!(tree.descendant_for_byte_range(11, 12).kind, "identifier");
+    assert_eq!(tree.descendant_for_byte_range(10, 15).kind, "compound_statement");
+    assert_eq!(tree.descendant_for_byte_range(0, 17).kind, "translation_unit");
+}
+
+#[test]
+fn test_unclosed_and_stray_braces() {
+    let tree = build("} { x");
+    assert_eq!(tree.to_sexp(), "(translation_unit (compound_statement (identifier)))");
+    assert_eq!(tree.node(1).unwrap().kind, "}");
+    assert_eq!(tree.node(2).unwrap().span, Span::new(2, 5));
+}
+
+#[test]
+fn test_json() {
+    let json = build("x;").to_json();
+    assert_eq!(json[1], serde_json::json!({
+        "id": 1,
+        "kind": "identifier",
+        "named": true,
+        "start_byte": 0,
+        "end_byte": 1,
+        "start_point": {"row": 0, "column": 0},
+        "end_point": {"row": 0, "column": 1},
+        "parent": 0,
+        "children": [],
+    }));
+}
+
+/// Lexes a source and builds its tree
+fn build(source: &str) -> Tree {
+    Tree::build(source, &Lexer::lex_spanned(source).unwrap())
+}
@@ -0,0 +1,90 @@
+mod support;
+
+use lexer::{
+    config::LexerBuilder,
+    span::Span,
+    token::Token,
+    trivia::{lex_lossless, LosslessToken, TriviaKind},
+};
+use support::ident;
+
+/// cargo test --test trivia_tests
+/// Tests attaching whitespace and comments to the tokens around them.
+
+#[test]
+fn test_doc_comment_attaches_to_struct() {
+    let source = "int x; // count\n\n/* A point. */\nstruct point;";
+    let tokens = lex(source);
+    let keyword = tokens.iter().find(|lossless| lossless.token.token == Token::STRUCT).unwrap();
+    let comments = keyword.attached_comments();
+    assert_eq!(comments.len(), 1);
+    assert_eq!(&source[comments[0].span.start..comments[0].span.end], "/* A point. */");
+    assert_eq!(comments[0].kind, TriviaKind::BlockComment);
+}
+
+#[test]
+fn test_trailing_comment_stays_on_its_line() {
+    let source = "x; // note\ny";
+    let tokens = lex(source);
+    let semicolon = &tokens[1];
+    assert_eq!(semicolon.token.token, Token::SEMICOLON);
+    let kinds: Vec<TriviaKind> = semicolon.trailing_trivia().iter().map(|trivia| trivia.kind).collect();
+    assert_eq!(kinds, vec![TriviaKind::Whitespace, TriviaKind::LineComment]);
+    let kinds: Vec<TriviaKind> = tokens[2].leading_trivia().iter().map(|trivia| trivia.kind).collect();
+    assert_eq!(kinds, vec![TriviaKind::Newline]);
+    assert!(tokens[2].attached_comments().is_empty());
+}
+
+#[test]
+fn test_blank_line_detaches_comments() {
+    let source = "// one\n// two\n\n// three\n  // four\nint";
+    let tokens = lex(source);
+    let texts: Vec<&str> = tokens[0]
+        .attached_comments()
+        .iter()
+        .map(|trivia| &source[trivia.span.start..trivia.span.end])
+        .collect();
+    assert_eq!(texts, vec!["// three", "// four"]);
+}
+
+#[test]
+fn test_trivia_covers_source() {
+    let source = "  /* a */ int\r\n\tx = 1; // end\r\n\n";
+    let tokens = lex(source);
+    assert_eq!(tokens.last().unwrap().token.token, Token::EOF);
+    let mut offset: usize = 0;
+    for lossless in &tokens {
+        assert_eq!(lossless.full_span().start, offset);
+        offset = lossless.full_span().end;
+    }
+    assert_eq!(offset, source.len());
+    let eof = tokens.last().unwrap();
+    let kinds: Vec<TriviaKind> = eof.leading_trivia().iter().map(|trivia| trivia.kind).collect();
+    assert_eq!(kinds, vec![TriviaKind::Newline, TriviaKind::Newline]);
+}
+
+#[test]
+fn test_nested_comments_follow_config() {
+    let source = "/* a /* b */ c */ x";
+    let config = LexerBuilder::new().nested_comments(true).build();
+    let tokens = lex_lossless(source, &config).unwrap();
+    assert_eq!(tokens[0].leading_trivia()[0].span, Span::new(0, 17));
+    assert_eq!(tokens[0].token.token, ident("x"));
+}
+
+#[test]
+fn test_eof_kept_when_omitted_by_config() {
+    let config = LexerBuilder::new().emit_eof(false).build();
+    let tokens = lex_lossless("x // end", &config).unwrap();
+    assert_eq!(tokens.len(), 2);
+    assert_eq!(tokens[1].token.token, Token::EOF);
+}
+
+#[test]
+fn test_errors_are_reported() {
+    assert!(lex_lossless("int @", &LexerBuilder::new().build()).is_err());
+}
+
+fn lex(source: &str) -> Vec<LosslessToken> {
+    lex_lossless(source, &LexerBuilder::new().build()).unwrap()
+}
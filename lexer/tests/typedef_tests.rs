@@ -0,0 +1,82 @@
+mod support;
+
+use lexer::{
+    codec::{decode, encode},
+    core::Lexer,
+    span::{Span, SpannedToken},
+    token::{Token, TokenCategory, TokenKind},
+    typedefs::TypedefTable,
+};
+use support::ident;
+
+/// cargo test --test typedef_tests
+/// Tests registering type names and tagging identifiers that name them.
+
+#[test]
+fn test_classify_registered_names() {
+    let mut table = TypedefTable::new();
+    assert!(table.insert("foo_t"));
+    assert!(!table.insert("foo_t"));
+    let mut tokens = Lexer::lex_spanned("foo_t * x; y * z;").unwrap();
+    assert_eq!(table.classify(&mut tokens), 1);
+    assert_eq!(tokens[0].token, Token::TYPENAME("foo_t".chars().collect()));
+    assert_eq!(tokens[0].span, Span::new(0, 5));
+    assert_eq!(tokens[4].token, ident("y"));
+}
+
+#[test]
+fn test_remove() {
+    let mut table = TypedefTable::new();
+    table.insert("foo_t");
+    assert!(table.remove("foo_t"));
+    assert!(!table.contains("foo_t"));
+    assert!(!table.remove("foo_t"));
+}
+
+#[test]
+fn test_learn_typedefs() {
+    let source = concat!(
+        "typedef unsigned long size_t;\n",
+        "typedef struct point { int x; int y; } point_t, *point_ptr;\n",
+        "typedef int (*compare_t)(int a, int b);\n",
+        "typedef char name_t[16];\n",
+        "typedef size_t count_t;\n",
+        "int a; int b;\n",
+    );
+    let mut table = TypedefTable::new();
+    let declared = table.learn(&Lexer::lex_spanned(source).unwrap());
+    assert_eq!(declared, vec!["size_t", "point_t", "point_ptr", "compare_t", "name_t", "count_t"]);
+    assert!(table.contains("compare_t"));
+    assert!(!table.contains("point"));
+    assert!(!table.contains("a"));
+}
+
+#[test]
+fn test_lexer_hack() {
+    let mut tokens = Lexer::lex_spanned("typedef int foo_t; foo_t x; int foo;").unwrap();
+    let mut table = TypedefTable::new();
+    table.learn(&tokens);
+    table.classify(&mut tokens);
+    let kinds: Vec<TokenKind> = tokens.iter().map(SpannedToken::kind).collect();
+    assert_eq!(&kinds[4..7], &[TokenKind::TYPENAME, TokenKind::IDENTIFIER, TokenKind::SEMICOLON]);
+    assert_eq!(kinds[8], TokenKind::IDENTIFIER);
+}
+
+#[test]
+fn test_typename_token() {
+    let token = Token::TYPENAME("size_t".chars().collect());
+    assert_eq!(token.name(), "TYPENAME");
+    assert_eq!(token.category(), TokenCategory::Type);
+    assert_eq!(token.text(), Some(&['s', 'i', 'z', 'e', '_', 't'][..]));
+    let (kind, data) = token.clone().into_parts();
+    assert_eq!(Token::from_parts(kind, data), Some(token));
+}
+
+#[test]
+fn test_typename_round_trips_through_codec() {
+    let mut tokens = Lexer::lex_spanned("foo_t x;").unwrap();
+    let mut table = TypedefTable::new();
+    table.insert("foo_t");
+    table.classify(&mut tokens);
+    assert_eq!(decode(&encode(&tokens)).unwrap(), tokens);
+}
@@ -0,0 +1,82 @@
+mod support;
+
+use std::fs;
+
+use lexer::{config::LexerBuilder, diagnostic::Severity, driver::DriverConfig, watch::WatchSession};
+use support::ScratchDir;
+
+/// cargo test --test watch_tests
+/// Tests re-checking watched files only when they change.
+
+#[test]
+fn test_scan_checks_every_visible_file() {
+    let dir = ScratchDir::new("scan");
+    fs::create_dir_all(dir.join("nested")).unwrap();
+    fs::write(dir.join("a.c"), "int a;").unwrap();
+    fs::write(dir.join("nested/b.c"), "b = $;").unwrap();
+    fs::write(dir.join(".a.c.swp"), "$$$").unwrap();
+    fs::write(dir.join("README.md"), "# $$$").unwrap();
+
+    let mut session = WatchSession::new();
+    let updates = session.scan(&dir).unwrap();
+    let paths: Vec<String> = updates.iter().map(|update| update.report.path.clone()).collect();
+    assert_eq!(paths, vec![dir.join("a.c").display().to_string(), dir.join("nested/b.c").display().to_string()]);
+    assert!(!updates[0].report.has_errors());
+    assert_eq!(updates[1].report.diagnostics[0].severity, Severity::Error);
+    assert_eq!(updates[1].bytes, 6);
+    assert_eq!(session.len(), 2);
+}
+
+#[test]
+fn test_refresh_skips_unchanged_files() {
+    let dir = ScratchDir::new("refresh");
+    let file = dir.join("main.c");
+    fs::write(&file, "int x;").unwrap();
+
+    let mut session = WatchSession::new();
+    assert_eq!(session.scan(&dir).unwrap().len(), 1);
+    assert!(session.refresh(&file).is_none());
+
+    fs::write(&file, "int x = $;").unwrap();
+    assert!(session.refresh(&file).unwrap().report.has_errors());
+
+    fs::remove_file(&file).unwrap();
+    assert!(session.refresh(&file).is_none());
+    assert!(session.is_empty());
+}
+
+#[test]
+fn test_session_checks_with_config() {
+    let dir = ScratchDir::new("config");
+    let file = dir.join("main.c");
+    fs::write(&file, "int $total;").unwrap();
+
+    assert!(WatchSession::new().scan(&file).unwrap()[0].report.has_errors());
+    let lexer = LexerBuilder::new().identifier_start("$").unwrap().build();
+    let mut session = WatchSession::with_config(DriverConfig { lexer, ..DriverConfig::default() });
+    assert!(!session.scan(&file).unwrap()[0].report.has_errors());
+}
+
+#[test]
+fn test_refresh_skips_files_the_driver_skips() {
+    let dir = ScratchDir::new("skip");
+    fs::create_dir_all(dir.join("build")).unwrap();
+    for name in ["main.c", "Cargo.toml", "build/out.c", "gen.c"] {
+        fs::write(dir.join(name), "$").unwrap();
+    }
+
+    let config = DriverConfig { ignore: vec!["build".to_string(), "gen.c".to_string()], ..DriverConfig::default() };
+    let mut session = WatchSession::with_config(config);
+    assert_eq!(session.scan(&dir).unwrap().len(), 1);
+    for name in ["main.c", "Cargo.toml", "build/out.c", "gen.c"] {
+        fs::write(dir.join(name), "$$").unwrap();
+    }
+    assert!(session.refresh(&dir.join("main.c")).is_some());
+    assert!(session.refresh(&dir.join("Cargo.toml")).is_none());
+    assert!(session.refresh(&dir.join("build/out.c")).is_none());
+    assert!(session.refresh(&dir.join("gen.c")).is_none());
+    let other = ScratchDir::new("elsewhere");
+    let elsewhere = other.write("main.c", "$");
+    assert!(session.refresh(&elsewhere).is_none());
+    assert_eq!(session.len(), 1);
+}
@@ -0,0 +1,75 @@
+use lexer::{
+    check::FileReport,
+    config::{LexerBuilder, LexerConfig},
+    core::Lexer,
+    diagnostic::Diagnostic,
+    lint::LintConfig,
+    span::Span,
+};
+
+/// cargo test --test whitespace_lint_tests
+/// Tests the optional lints for trailing whitespace, mixed indentation, and a missing final newline.
+
+#[test]
+fn test_off_by_default() {
+    let (_, _, diagnostics) = Lexer::lex_with_diagnostics("int x;  \n \tx", &LexerConfig::default());
+    assert!(diagnostics.is_empty());
+}
+
+#[test]
+fn test_trailing_whitespace() {
+    let source = "int x; \t\nint y;\n   \nz;\r\n";
+    assert_eq!(lint(source), vec![
+        ("trailing-whitespace", Span::new(6, 8)),
+        ("trailing-whitespace", Span::new(16, 19)),
+    ]);
+}
+
+#[test]
+fn test_trailing_whitespace_after_line_comment() {
+    assert_eq!(lint("x; // note  \ny;\n"), vec![("trailing-whitespace", Span::new(10, 12))]);
+}
+
+#[test]
+fn test_mixed_indentation() {
+    let source = "{\n \tx;\n\t y;\n\t\tz;\n}\n";
+    assert_eq!(lint(source), vec![("mixed-indentation", Span::new(2, 4))]);
+}
+
+#[test]
+fn test_missing_final_newline() {
+    assert_eq!(lint("x;"), vec![("missing-final-newline", Span::new(2, 2))]);
+    let expected = vec![("trailing-whitespace", Span::new(2, 4)), ("missing-final-newline", Span::new(4, 4))];
+    assert_eq!(lint("x;  "), expected);
+    assert_eq!(lint("x; // end"), vec![("missing-final-newline", Span::new(9, 9))]);
+    assert!(lint("x;\n").is_empty());
+    assert!(lint("").is_empty());
+}
+
+#[test]
+fn test_next_token_reports_once() {
+    let config = LexerBuilder::new().whitespace_lints(true).build();
+    let mut lexer = Lexer::new("x", config);
+    for _ in 0..3 {
+        lexer.next_token().unwrap();
+    }
+    assert_eq!(lexer.take_diagnostics().len(), 1);
+    assert!(lexer.take_diagnostics().is_empty());
+}
+
+#[test]
+fn test_reported_and_suppressed_by_check() {
+    let config = LexerBuilder::new().whitespace_lints(true).build();
+    let source = "int x; \n// lexer: ignore-next-line\nint y; \n".to_string();
+    let report = FileReport::check_with_config("a.c", source, &config, &LintConfig::default());
+    let codes: Vec<(&str, bool)> =
+        report.diagnostics.iter().map(|diagnostic| (diagnostic.code, diagnostic.suppressed)).collect();
+    assert_eq!(codes, vec![("trailing-whitespace", false), ("trailing-whitespace", true)]);
+}
+
+/// Lexes a source with the whitespace lints on and gives the code and span of each warning
+fn lint(source: &str) -> Vec<(&'static str, Span)> {
+    let config = LexerBuilder::new().whitespace_lints(true).build();
+    let (_, _, diagnostics) = Lexer::lex_with_diagnostics(source, &config);
+    diagnostics.iter().map(|diagnostic: &Diagnostic| (diagnostic.code, diagnostic.span)).collect()
+}